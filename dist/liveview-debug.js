@@ -4903,9 +4903,27 @@ within:
 
     var topbar = topbar_min.exports;
 
-    const csrfToken = document
-      .querySelector("meta[name='csrf-token']")
-      .getAttribute("content");
+    // The CSRF token is read from the meta tag, or from the double-submit cookie
+    // when the handler uses cookie based CSRF protection.
+    function csrfToken() {
+      const meta = document.querySelector("meta[name='csrf-token']");
+      if (meta) {
+        return meta.getAttribute("content");
+      }
+      const cookie = document.cookie
+        .split("; ")
+        .find((cookie) => cookie.startsWith("_live_view_csrf_token="));
+      return cookie ? decodeURIComponent(cookie.slice(cookie.indexOf("=") + 1)) : null;
+    }
+
+    // Short-lived auth tokens are kept in session storage, sent when joining, and
+    // replaced when the server pushes a refreshed token.
+    const AUTH_TOKEN_KEY = "live_view_auth_token";
+
+    // The browser's timezone is sent when joining, and kept in a cookie so page
+    // loads can render times in it too.
+    const TIMEZONE = Intl.DateTimeFormat().resolvedOptions().timeZone;
+    document.cookie = `_live_view_timezone=${encodeURIComponent(TIMEZONE)}; Path=/; SameSite=Lax`;
 
     class SocketWrapper {
       constructor(endPoint, opts) {
@@ -4915,8 +4933,20 @@ within:
       }
     }
 
+    // Statics of the render each LiveView was last joined with, by element id.
+    const joinedStatics = {};
+
+    // Hooks bound with `phx-hook` are registered on `window.liveViewHooks` before
+    // this script runs, named by the hook's Rust type name.
     const liveSocket = new LiveSocket("/", SocketWrapper, {
-      params: { _csrf_token: csrfToken },
+      hooks: window.liveViewHooks || {},
+      params: (el) => ({
+        _csrf_token: csrfToken(),
+        _auth_token: sessionStorage.getItem(AUTH_TOKEN_KEY),
+        _timezone: TIMEZONE,
+        _statics: joinedStatics[el.id] && joinedStatics[el.id].fingerprint,
+        _serializer: "msgpack",
+      }),
       metadata: {
         click: (e, t) => ({
           detail: e.detail,
@@ -4924,6 +4954,560 @@ within:
       },
     });
 
+    // Servers built with the `msgpack` feature send frames encoded with
+    // MessagePack, as binary frames starting with the header of a five item array.
+    // Binary frames of the upload protocol start with their kind instead.
+    const MSGPACK_FRAME = 0x95;
+
+    function decodeMsgpack(bytes) {
+      const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+      const text = new TextDecoder();
+      let offset = 0;
+      const skip = (length) => {
+        offset += length;
+        return offset - length;
+      };
+      const str = (length) => text.decode(bytes.subarray(skip(length), offset));
+      const array = (length) => Array.from({ length }, () => value());
+      const map = (length) => {
+        const obj = {};
+        for (let i = 0; i < length; i++) {
+          const key = value();
+          obj[key] = value();
+        }
+        return obj;
+      };
+      function value() {
+        const type = view.getUint8(skip(1));
+        if (type < 0x80) return type;
+        if (type < 0x90) return map(type & 0x0f);
+        if (type < 0xa0) return array(type & 0x0f);
+        if (type < 0xc0) return str(type & 0x1f);
+        if (type >= 0xe0) return type - 0x100;
+        switch (type) {
+          case 0xc0: return null;
+          case 0xc2: return false;
+          case 0xc3: return true;
+          case 0xc4: return bytes.slice(skip(view.getUint8(skip(1))), offset);
+          case 0xc5: return bytes.slice(skip(view.getUint16(skip(2))), offset);
+          case 0xc6: return bytes.slice(skip(view.getUint32(skip(4))), offset);
+          case 0xca: return view.getFloat32(skip(4));
+          case 0xcb: return view.getFloat64(skip(8));
+          case 0xcc: return view.getUint8(skip(1));
+          case 0xcd: return view.getUint16(skip(2));
+          case 0xce: return view.getUint32(skip(4));
+          case 0xcf: return Number(view.getBigUint64(skip(8)));
+          case 0xd0: return view.getInt8(skip(1));
+          case 0xd1: return view.getInt16(skip(2));
+          case 0xd2: return view.getInt32(skip(4));
+          case 0xd3: return Number(view.getBigInt64(skip(8)));
+          case 0xd9: return str(view.getUint8(skip(1)));
+          case 0xda: return str(view.getUint16(skip(2)));
+          case 0xdb: return str(view.getUint32(skip(4)));
+          case 0xdc: return array(view.getUint16(skip(2)));
+          case 0xdd: return array(view.getUint32(skip(4)));
+          case 0xde: return map(view.getUint16(skip(2)));
+          case 0xdf: return map(view.getUint32(skip(4)));
+          default: throw new Error(`unsupported MessagePack type ${type}`);
+        }
+      }
+      return value();
+    }
+
+    function decodeMsgpackFrames(socket) {
+      const decode = socket.decode;
+      socket.decode = (rawPayload, callback) => {
+        if (!(rawPayload instanceof ArrayBuffer) || new Uint8Array(rawPayload)[0] !== MSGPACK_FRAME) {
+          return decode(rawPayload, callback);
+        }
+        const [join_ref, ref, topic, event, payload] = decodeMsgpack(new Uint8Array(rawPayload));
+        return callback({ join_ref, ref, topic, event, payload });
+      };
+    }
+
+    // Strings repeated within a render are sent once in an `i` table on the root
+    // of the diff or join render, and referenced with `{i: index}` within it.
+    // Replies and pushed events, under `r` and `e`, are never interned, so they're
+    // left as sent even if they hold `i` keys of their own.
+    function expandInterned(value, table) {
+      if (Array.isArray(value)) {
+        return value.map((item) => expandInterned(item, table));
+      }
+      if (value === null || typeof value !== "object") {
+        return value;
+      }
+      if (typeof value.i === "number" && Object.keys(value).length === 1) {
+        return table[value.i];
+      }
+      for (const key in value) {
+        value[key] = expandInterned(value[key], table);
+      }
+      return value;
+    }
+
+    function expandInternedMessage(msg) {
+      const payload = msg.payload || {};
+      const response = payload.response || {};
+      const render = msg.event === "diff" ? payload : response.diff || response.rendered;
+      if (!render || !Array.isArray(render.i)) {
+        return msg;
+      }
+      const table = render.i;
+      delete render.i;
+      for (const key in render) {
+        if (key !== "r" && key !== "e") {
+          render[key] = expandInterned(render[key], table);
+        }
+      }
+      return msg;
+    }
+
+    function expandInternedStrings(socket) {
+      const decode = socket.decode;
+      socket.decode = (rawPayload, callback) =>
+        decode(rawPayload, (msg) => callback(expandInternedMessage(msg)));
+    }
+
+    // Encrypted state snapshots are sent under `st`, and stored in the LiveView's
+    // `data-phx-static` attribute, which is sent back when rejoining.
+    function storeStateToken(msg) {
+      const payload = msg.payload || {};
+      const response = payload.response || {};
+      const render = msg.event === "diff" ? payload : response.diff || response.rendered;
+      if (!render || typeof render.st !== "string") {
+        return msg;
+      }
+      const el = document.getElementById(msg.topic.replace(/^lv:/, ""));
+      if (el) {
+        el.setAttribute("data-phx-static", render.st);
+      }
+      delete render.st;
+      return msg;
+    }
+
+    function storeStateTokens(socket) {
+      const decode = socket.decode;
+      socket.decode = (rawPayload, callback) =>
+        decode(rawPayload, (msg) => callback(storeStateToken(msg)));
+    }
+
+    // Join replies carry the fingerprint of their statics under `sf`. The server
+    // leaves the statics out when rejoining with the same fingerprint, and they're
+    // restored from the render of the previous join.
+    function restoreStatics(render, cached) {
+      if (!render || !cached || typeof render !== "object" || typeof cached !== "object") {
+        return;
+      }
+      if (!Array.isArray(render)) {
+        ["s", "p"].forEach((key) => {
+          if (!(key in render) && key in cached) {
+            render[key] = cached[key];
+          }
+        });
+      }
+      for (const key in render) {
+        if (key !== "s" && key !== "p") {
+          restoreStatics(render[key], cached[key]);
+        }
+      }
+    }
+
+    function restoreJoinStatic(msg) {
+      const rendered = ((msg.payload || {}).response || {}).rendered;
+      if (!rendered || typeof rendered.sf !== "string") {
+        return msg;
+      }
+      const id = msg.topic.replace(/^lv:/, "");
+      const fingerprint = rendered.sf;
+      delete rendered.sf;
+      const cached = joinedStatics[id];
+      if (!("s" in rendered) && cached && cached.fingerprint === fingerprint) {
+        restoreStatics(rendered, cached.rendered);
+      }
+      joinedStatics[id] = { fingerprint, rendered: JSON.parse(JSON.stringify(rendered)) };
+      return msg;
+    }
+
+    function restoreJoinStatics(socket) {
+      const decode = socket.decode;
+      socket.decode = (rawPayload, callback) =>
+        decode(rawPayload, (msg) => callback(restoreJoinStatic(msg)));
+    }
+
+    // Events bound on elements with the `phx-no-reply` attribute are acknowledged
+    // locally, and the server only sends a diff if something changed. Each push is
+    // tagged by the element it's bound to, as the same event may be bound without
+    // the attribute elsewhere.
+    function isNoReplyPush(el, payload) {
+      if (!el) {
+        return false;
+      }
+      const form = payload.type === "form" ? el.form : null;
+      return el.hasAttribute("phx-no-reply") || (!!form && form.hasAttribute("phx-no-reply"));
+    }
+
+    function tagNoReplyPushes(liveSocket) {
+      const newRootView = liveSocket.newRootView.bind(liveSocket);
+      let tagged = false;
+      liveSocket.newRootView = (el, flash) => {
+        const view = newRootView(el, flash);
+        if (tagged) {
+          return view;
+        }
+        tagged = true;
+        // Child views share the prototype of root views.
+        const prototype = Object.getPrototypeOf(view);
+        const pushWithReply = prototype.pushWithReply;
+        prototype.pushWithReply = function (refGenerator, event, payload, onReply) {
+          // The bound element is the first one the ref is put on, right before
+          // the push is sent.
+          const putRef =
+            refGenerator &&
+            (() => {
+              const result = refGenerator();
+              if (event === "event" && isNoReplyPush(result[1][0], payload)) {
+                payload.no_reply = true;
+              }
+              return result;
+            });
+          return pushWithReply.call(this, putRef, event, payload, onReply);
+        };
+        return view;
+      };
+    }
+
+    function acknowledgeNoReplyEvents(socket) {
+      const channel = socket.channel.bind(socket);
+      socket.channel = (topic, params) => {
+        const chan = channel(topic, params);
+        const push = chan.push.bind(chan);
+        chan.push = (event, payload, timeout) => {
+          if (event !== "event" || !payload.no_reply) {
+            return push(event, payload, timeout);
+          }
+          const noReplyPush = push(event, payload, timeout);
+          noReplyPush.trigger("ok", {});
+          return noReplyPush;
+        };
+        return chan;
+      };
+    }
+
+    // Clicks on `phx-click` elements with `data-optimistic-class` or
+    // `data-optimistic-text` attributes apply the predicted change immediately.
+    // The event is sent with an optimistic ref, and the change is reverted unless
+    // the server's reply accepts it.
+    const OPTIMISTIC_SELECTOR =
+      "[phx-click][data-optimistic-class], [phx-click][data-optimistic-text]";
+    let optimisticRef = 0;
+    let pendingOptimistic = null;
+
+    function applyOptimistic(el) {
+      const className = el.className;
+      const text = el.textContent;
+      const classes = (el.dataset.optimisticClass || "").split(" ").filter((name) => name);
+      classes.forEach((name) => el.classList.toggle(name));
+      if (el.dataset.optimisticText != null) {
+        el.textContent = el.dataset.optimisticText;
+      }
+      return () => {
+        el.className = className;
+        if (el.dataset.optimisticText != null) {
+          el.textContent = text;
+        }
+      };
+    }
+
+    window.addEventListener(
+      "click",
+      (e) => {
+        const el = e.target.closest && e.target.closest(OPTIMISTIC_SELECTOR);
+        if (!el) {
+          return;
+        }
+        const pending = { ref: ++optimisticRef, revert: applyOptimistic(el) };
+        pendingOptimistic = pending;
+        // Revert the change if the click didn't push an event.
+        setTimeout(() => {
+          if (pendingOptimistic === pending) {
+            pendingOptimistic = null;
+            pending.revert();
+          }
+        }, 0);
+      },
+      true
+    );
+
+    function reconcileOptimisticEvents(socket) {
+      const channel = socket.channel.bind(socket);
+      socket.channel = (topic, params) => {
+        const chan = channel(topic, params);
+        const push = chan.push.bind(chan);
+        chan.push = (event, payload, timeout) => {
+          const pending = pendingOptimistic;
+          if (event !== "event" || !pending) {
+            return push(event, payload, timeout);
+          }
+          pendingOptimistic = null;
+          // No-reply events keep the change until the next diff replaces it.
+          if (payload.no_reply) {
+            return push(event, payload, timeout);
+          }
+          return push(event, { ...payload, optimistic_ref: pending.ref }, timeout)
+            .receive("ok", (resp) => {
+              const optimistic = resp.optimistic || {};
+              if (optimistic.ref !== pending.ref || !optimistic.accepted) {
+                pending.revert();
+              }
+            })
+            .receive("error", () => pending.revert())
+            .receive("timeout", () => pending.revert());
+        };
+        return chan;
+      };
+    }
+
+    tagNoReplyPushes(liveSocket);
+    acknowledgeNoReplyEvents(liveSocket.socket);
+    reconcileOptimisticEvents(liveSocket.socket);
+    decodeMsgpackFrames(liveSocket.socket);
+    expandInternedStrings(liveSocket.socket);
+    storeStateTokens(liveSocket.socket);
+    restoreJoinStatics(liveSocket.socket);
+
+    window.addEventListener("phx:live_view:auth_token", (e) => {
+      sessionStorage.setItem(AUTH_TOKEN_KEY, e.detail.token);
+      liveSocket.main.channel.push("auth_ack", {});
+    });
+
+    // Sessions rotated by the server replace the session and CSRF token used when
+    // rejoining. The double-submit cookie is kept, since rejoins over the same
+    // socket are checked against the cookie it was opened with.
+    window.addEventListener("phx:live_view:session", (e) => {
+      liveSocket.main.el.setAttribute("data-phx-session", e.detail.session);
+      const meta = document.querySelector("meta[name='csrf-token']");
+      if (meta) {
+        meta.setAttribute("content", e.detail.csrf_token);
+      }
+      // The cached page still embeds the revoked session, so refresh it for the
+      // next reload or back navigation.
+      fetch(window.location.href, { cache: "reload", credentials: "same-origin" }).catch(() => {});
+    });
+
+    // Notifications pushed with `Socket::notify` are shown in the
+    // `#live-view-toasts` element, which is created if the layout has none.
+    function toastContainer() {
+      let container = document.getElementById("live-view-toasts");
+      if (!container) {
+        container = document.createElement("div");
+        container.id = "live-view-toasts";
+        container.setAttribute("aria-live", "polite");
+        document.body.appendChild(container);
+      }
+      return container;
+    }
+
+    window.addEventListener("phx:live_view:notify", (e) => {
+      const { level, message, timeout } = e.detail;
+      const toast = document.createElement("div");
+      toast.className = `toast toast-${level}`;
+      toast.setAttribute("role", level === "error" ? "alert" : "status");
+      const text = document.createElement("span");
+      text.className = "toast-message";
+      text.textContent = message;
+      const close = document.createElement("button");
+      close.className = "toast-close";
+      close.type = "button";
+      close.setAttribute("aria-label", "Close");
+      close.textContent = "×";
+      close.addEventListener("click", () => toast.remove());
+      toast.append(text, close);
+      toastContainer().appendChild(toast);
+      if (timeout != null) {
+        setTimeout(() => toast.remove(), timeout);
+      }
+    });
+
+    // Downloads started with `Socket::download` are saved by clicking a temporary
+    // link, to a blob of the content if it was sent over the socket.
+    window.addEventListener("phx:live_view:download", (e) => {
+      const { url, content, content_type, filename } = e.detail;
+      let href = url;
+      if (content != null) {
+        const bytes = Uint8Array.from(atob(content), (c) => c.charCodeAt(0));
+        href = URL.createObjectURL(new Blob([bytes], { type: content_type }));
+      }
+      const link = document.createElement("a");
+      link.href = href;
+      link.download = filename || "";
+      document.body.appendChild(link);
+      link.click();
+      link.remove();
+      if (content != null) {
+        setTimeout(() => URL.revokeObjectURL(href), 0);
+      }
+    });
+
+    // Forms with a `data-autosave` key save their values to local storage as they
+    // change, and restore them when the LiveView is loaded again. Drafts are
+    // cleared when the form is submitted. Hidden and password inputs are never
+    // stored.
+    const DRAFT_KEY_PREFIX = "live_view_draft:";
+    const restoredDrafts = new WeakSet();
+
+    function draftInputs(form) {
+      return Array.from(form.elements).filter(
+        (input) => input.name && input.type !== "hidden" && input.type !== "password"
+      );
+    }
+
+    function saveDraft(form) {
+      const params = new URLSearchParams();
+      for (const input of draftInputs(form)) {
+        if ((input.type === "checkbox" || input.type === "radio") && !input.checked) {
+          continue;
+        }
+        params.append(input.name, input.value);
+      }
+      localStorage.setItem(DRAFT_KEY_PREFIX + form.dataset.autosave, params.toString());
+    }
+
+    function restoreDraft(form) {
+      restoredDrafts.add(form);
+      const draft = localStorage.getItem(DRAFT_KEY_PREFIX + form.dataset.autosave);
+      if (draft === null) {
+        return;
+      }
+      const params = new URLSearchParams(draft);
+      const inputs = draftInputs(form);
+      for (const input of inputs) {
+        if (input.type === "checkbox" || input.type === "radio") {
+          input.checked = params.getAll(input.name).includes(input.value);
+        } else if (params.has(input.name)) {
+          input.value = params.get(input.name);
+        }
+      }
+      // Send the restored values with the form's `phx-change` event.
+      if (inputs[0]) {
+        inputs[0].dispatchEvent(new Event("input", { bubbles: true }));
+      }
+    }
+
+    window.addEventListener("input", (e) => {
+      const form = e.target.form;
+      if (!form || !form.dataset.autosave) {
+        return;
+      }
+      clearTimeout(form.autosaveTimer);
+      form.autosaveTimer = setTimeout(() => saveDraft(form), 300);
+    });
+
+    window.addEventListener("submit", (e) => {
+      if (e.target.dataset.autosave) {
+        clearTimeout(e.target.autosaveTimer);
+        localStorage.removeItem(DRAFT_KEY_PREFIX + e.target.dataset.autosave);
+      }
+    });
+
+    window.addEventListener("phx:page-loading-stop", () => {
+      document
+        .querySelectorAll("form[data-autosave]")
+        .forEach((form) => restoredDrafts.has(form) || restoreDraft(form));
+    });
+
+    // Keyboard focus is kept within the last visible `data-focus-trap` element,
+    // such as an open modal, and moved into it when it appears.
+    const FOCUSABLE =
+      "a[href], button:not([disabled]), input:not([disabled]), select:not([disabled]), textarea:not([disabled]), [tabindex]:not([tabindex='-1'])";
+
+    function isVisible(el) {
+      return el.getClientRects().length > 0;
+    }
+
+    function activeFocusTrap() {
+      const traps = Array.from(document.querySelectorAll("[data-focus-trap]")).filter(isVisible);
+      return traps[traps.length - 1];
+    }
+
+    function focusableElements(trap) {
+      return Array.from(trap.querySelectorAll(FOCUSABLE)).filter(isVisible);
+    }
+
+    window.addEventListener("keydown", (e) => {
+      const trap = activeFocusTrap();
+      if (e.key !== "Tab" || !trap) {
+        return;
+      }
+      const elements = focusableElements(trap);
+      const first = elements[0];
+      const last = elements[elements.length - 1];
+      const outside = !trap.contains(document.activeElement);
+      if (!first) {
+        e.preventDefault();
+      } else if (e.shiftKey && (outside || document.activeElement === first)) {
+        e.preventDefault();
+        last.focus();
+      } else if (!e.shiftKey && (outside || document.activeElement === last)) {
+        e.preventDefault();
+        first.focus();
+      }
+    });
+
+    new MutationObserver(() => {
+      const trap = activeFocusTrap();
+      if (trap && !trap.contains(document.activeElement)) {
+        const [first] = focusableElements(trap);
+        if (first) {
+          first.focus();
+        }
+      }
+    }).observe(document.body, { childList: true, subtree: true });
+
+    // Items of a stream deleted by the server are listed in its container's
+    // `data-stream-deleted` attribute, and removed once the DOM is patched.
+    document.addEventListener("phx:update", () => {
+      document.querySelectorAll("[data-stream-deleted]").forEach((container) => {
+        container
+          .getAttribute("data-stream-deleted")
+          .split(" ")
+          .forEach((id) => {
+            const item = id && document.getElementById(id);
+            if (item && item.parentNode === container) {
+              item.remove();
+            }
+          });
+      });
+    });
+
+    // Inputs without their own `phx-debounce` or `phx-throttle` attribute use the
+    // defaults of their LiveView's container, set before the event is handled.
+    ["input", "change"].forEach((type) => {
+      document.addEventListener(
+        type,
+        (e) => {
+          const input = e.target;
+          if (
+            !(input instanceof Element) ||
+            input.hasAttribute("phx-debounce") ||
+            input.hasAttribute("phx-throttle")
+          ) {
+            return;
+          }
+          const container = input.closest("[data-default-debounce], [data-default-throttle]");
+          if (!container) {
+            return;
+          }
+          ["debounce", "throttle"].forEach((name) => {
+            const value = container.getAttribute(`data-default-${name}`);
+            if (value) {
+              input.setAttribute(`phx-${name}`, value);
+            }
+          });
+        },
+        true
+      );
+    });
+
     // Show progress bar on live navigation and form submits
     topbar.config({ barColors: { 0: "#29d" }, shadowColor: "rgba(0, 0, 0, .3)" });
     window.addEventListener("phx:page-loading-start", (info) => topbar.show());
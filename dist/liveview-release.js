@@ -1 +1 @@
-!function(e){"function"==typeof define&&define.amd?define(e):e()}((function(){"use strict";!function(){const e={NODE_ENV:"production"};try{if(process)return process.env=Object.assign({},process.env),void Object.assign(process.env,e)}catch(e){}globalThis.process={env:e}}();var e=e=>{if("function"==typeof e)return e;return function(){return e}},t="undefined"!=typeof self?self:null,i="undefined"!=typeof window?window:null,s=t||i||s,n=0,r=1,o=2,a=3,l="closed",h="errored",d="joined",c="joining",u="leaving",p="phx_close",f="phx_error",g="phx_join",m="phx_reply",v="phx_leave",b="longpoll",y="websocket",k=4,w=class{constructor(e,t,i,s){this.channel=e,this.event=t,this.payload=i||function(){return{}},this.receivedResp=null,this.timeout=s,this.timeoutTimer=null,this.recHooks=[],this.sent=!1}resend(e){this.timeout=e,this.reset(),this.send()}send(){this.hasReceived("timeout")||(this.startTimeout(),this.sent=!0,this.channel.socket.push({topic:this.channel.topic,event:this.event,payload:this.payload(),ref:this.ref,join_ref:this.channel.joinRef()}))}receive(e,t){return this.hasReceived(e)&&t(this.receivedResp.response),this.recHooks.push({status:e,callback:t}),this}reset(){this.cancelRefEvent(),this.ref=null,this.refEvent=null,this.receivedResp=null,this.sent=!1}matchReceive({status:e,response:t,_ref:i}){this.recHooks.filter((t=>t.status===e)).forEach((e=>e.callback(t)))}cancelRefEvent(){this.refEvent&&this.channel.off(this.refEvent)}cancelTimeout(){clearTimeout(this.timeoutTimer),this.timeoutTimer=null}startTimeout(){this.timeoutTimer&&this.cancelTimeout(),this.ref=this.channel.socket.makeRef(),this.refEvent=this.channel.replyEventName(this.ref),this.channel.on(this.refEvent,(e=>{this.cancelRefEvent(),this.cancelTimeout(),this.receivedResp=e,this.matchReceive(e)})),this.timeoutTimer=setTimeout((()=>{this.trigger("timeout",{})}),this.timeout)}hasReceived(e){return this.receivedResp&&this.receivedResp.status===e}trigger(e,t){this.channel.trigger(this.refEvent,{status:e,response:t})}},E=class{constructor(e,t){this.callback=e,this.timerCalc=t,this.timer=null,this.tries=0}reset(){this.tries=0,clearTimeout(this.timer)}scheduleTimeout(){clearTimeout(this.timer),this.timer=setTimeout((()=>{this.tries=this.tries+1,this.callback()}),this.timerCalc(this.tries+1))}},C=class{static request(e,t,i,n,r,o,a){if(s.XDomainRequest){let i=new s.XDomainRequest;return this.xdomainRequest(i,e,t,n,r,o,a)}{let l=new s.XMLHttpRequest;return this.xhrRequest(l,e,t,i,n,r,o,a)}}static xdomainRequest(e,t,i,s,n,r,o){return e.timeout=n,e.open(t,i),e.onload=()=>{let t=this.parseJSON(e.responseText);o&&o(t)},r&&(e.ontimeout=r),e.onprogress=()=>{},e.send(s),e}static xhrRequest(e,t,i,s,n,r,o,a){return e.open(t,i,!0),e.timeout=r,e.setRequestHeader("Content-Type",s),e.onerror=()=>a&&a(null),e.onreadystatechange=()=>{if(e.readyState===k&&a){let t=this.parseJSON(e.responseText);a(t)}},o&&(e.ontimeout=o),e.send(n),e}static parseJSON(e){if(!e||""===e)return null;try{return JSON.parse(e)}catch(t){return console&&console.log("failed to parse JSON response",e),null}}static serialize(e,t){let i=[];for(var s in e){if(!Object.prototype.hasOwnProperty.call(e,s))continue;let n=t?`${t}[${s}]`:s,r=e[s];"object"==typeof r?i.push(this.serialize(r,n)):i.push(encodeURIComponent(n)+"="+encodeURIComponent(r))}return i.join("&")}static appendParams(e,t){if(0===Object.keys(t).length)return e;let i=e.match(/\?/)?"&":"?";return`${e}${i}${this.serialize(t)}`}},A=class{constructor(e){this.endPoint=null,this.token=null,this.skipHeartbeat=!0,this.reqs=new Set,this.onopen=function(){},this.onerror=function(){},this.onmessage=function(){},this.onclose=function(){},this.pollEndpoint=this.normalizeEndpoint(e),this.readyState=n,this.poll()}normalizeEndpoint(e){return e.replace("ws://","http://").replace("wss://","https://").replace(new RegExp("(.*)/"+y),"$1/"+b)}endpointURL(){return C.appendParams(this.pollEndpoint,{token:this.token})}closeAndRetry(e,t,i){this.close(e,t,i),this.readyState=n}ontimeout(){this.onerror("timeout"),this.closeAndRetry(1005,"timeout",!1)}isActive(){return this.readyState===r||this.readyState===n}poll(){this.ajax("GET",null,(()=>this.ontimeout()),(e=>{if(e){var{status:t,token:i,messages:s}=e;this.token=i}else t=0;switch(t){case 200:s.forEach((e=>{setTimeout((()=>this.onmessage({data:e})),0)})),this.poll();break;case 204:this.poll();break;case 410:this.readyState=r,this.onopen({}),this.poll();break;case 403:this.onerror(403),this.close(1008,"forbidden",!1);break;case 0:case 500:this.onerror(500),this.closeAndRetry(1011,"internal server error",500);break;default:throw new Error(`unhandled poll status ${t}`)}}))}send(e){this.ajax("POST",e,(()=>this.onerror("timeout")),(e=>{e&&200===e.status||(this.onerror(e&&e.status),this.closeAndRetry(1011,"internal server error",!1))}))}close(e,t,i){for(let e of this.reqs)e.abort();this.readyState=a;let s=Object.assign({code:1e3,reason:void 0,wasClean:!0},{code:e,reason:t,wasClean:i});"undefined"!=typeof CloseEvent?this.onclose(new CloseEvent("close",s)):this.onclose(s)}ajax(e,t,i,s){let n;n=C.request(e,this.endpointURL(),"application/json",t,this.timeout,(()=>{this.reqs.delete(n),i()}),(e=>{this.reqs.delete(n),this.isActive()&&s(e)})),this.reqs.add(n)}},S={HEADER_LENGTH:1,META_LENGTH:4,KINDS:{push:0,reply:1,broadcast:2},encode(e,t){if(e.payload.constructor===ArrayBuffer)return t(this.binaryEncode(e));{let i=[e.join_ref,e.ref,e.topic,e.event,e.payload];return t(JSON.stringify(i))}},decode(e,t){if(e.constructor===ArrayBuffer)return t(this.binaryDecode(e));{let[i,s,n,r,o]=JSON.parse(e);return t({join_ref:i,ref:s,topic:n,event:r,payload:o})}},binaryEncode(e){let{join_ref:t,ref:i,event:s,topic:n,payload:r}=e,o=this.META_LENGTH+t.length+i.length+n.length+s.length,a=new ArrayBuffer(this.HEADER_LENGTH+o),l=new DataView(a),h=0;l.setUint8(h++,this.KINDS.push),l.setUint8(h++,t.length),l.setUint8(h++,i.length),l.setUint8(h++,n.length),l.setUint8(h++,s.length),Array.from(t,(e=>l.setUint8(h++,e.charCodeAt(0)))),Array.from(i,(e=>l.setUint8(h++,e.charCodeAt(0)))),Array.from(n,(e=>l.setUint8(h++,e.charCodeAt(0)))),Array.from(s,(e=>l.setUint8(h++,e.charCodeAt(0))));var d=new Uint8Array(a.byteLength+r.byteLength);return d.set(new Uint8Array(a),0),d.set(new Uint8Array(r),a.byteLength),d.buffer},binaryDecode(e){let t=new DataView(e),i=t.getUint8(0),s=new TextDecoder;switch(i){case this.KINDS.push:return this.decodePush(e,t,s);case this.KINDS.reply:return this.decodeReply(e,t,s);case this.KINDS.broadcast:return this.decodeBroadcast(e,t,s)}},decodePush(e,t,i){let s=t.getUint8(1),n=t.getUint8(2),r=t.getUint8(3),o=this.HEADER_LENGTH+this.META_LENGTH-1,a=i.decode(e.slice(o,o+s));o+=s;let l=i.decode(e.slice(o,o+n));o+=n;let h=i.decode(e.slice(o,o+r));return o+=r,{join_ref:a,ref:null,topic:l,event:h,payload:e.slice(o,e.byteLength)}},decodeReply(e,t,i){let s=t.getUint8(1),n=t.getUint8(2),r=t.getUint8(3),o=t.getUint8(4),a=this.HEADER_LENGTH+this.META_LENGTH,l=i.decode(e.slice(a,a+s));a+=s;let h=i.decode(e.slice(a,a+n));a+=n;let d=i.decode(e.slice(a,a+r));a+=r;let c=i.decode(e.slice(a,a+o));a+=o;let u=e.slice(a,e.byteLength);return{join_ref:l,ref:h,topic:d,event:m,payload:{status:c,response:u}}},decodeBroadcast(e,t,i){let s=t.getUint8(1),n=t.getUint8(2),r=this.HEADER_LENGTH+2,o=i.decode(e.slice(r,r+s));r+=s;let a=i.decode(e.slice(r,r+n));return r+=n,{join_ref:null,ref:null,topic:o,event:a,payload:e.slice(r,e.byteLength)}}},T="consecutive-reloads",x=["phx-click-loading","phx-change-loading","phx-submit-loading","phx-keydown-loading","phx-keyup-loading","phx-blur-loading","phx-focus-loading"],L="data-phx-component",P="data-phx-link",R="data-phx-ref",_="data-phx-ref-src",D="track-uploads",I="data-phx-upload-ref",O="data-phx-preflighted-refs",N="drop-target",$="data-phx-active-refs",M="phx:live-file:updated",j="data-phx-skip",U="data-phx-prune",H="page-loading",F="phx-connected",B="phx-loading",J="phx-no-feedback",q="phx-error",V="data-phx-parent-id",W="data-phx-main",z="data-phx-root-id",G="feedback-for",K="phx-has-focused",X=["text","textarea","number","email","password","search","tel","url","date","time","datetime-local","color","range"],Y=["checkbox","radio"],Q="phx-has-submitted",Z="data-phx-session",ee=`[${Z}]`,te="data-phx-sticky",ie="data-phx-static",se="data-phx-readonly",ne="data-phx-disabled",re="disable-with",oe="data-phx-disable-with-restore",ae="hook",le="update",he="auto-recover",de="phx:live-socket:debug",ce="phx:live-socket:profiling",ue="phx:live-socket:latency-sim",pe="debounce-trigger",fe="throttled",ge="debounce-prev-key",me={debounce:300,throttle:300},ve="d",be="s",ye="e",ke="r",we="t",Ee=(e,t)=>console.error&&console.error(e,t),Ce=e=>{let t=typeof e;return"number"===t||"string"===t&&/^(0|[1-9]\d*)$/.test(e)};var Ae,Se=e=>"function"==typeof e?e:function(){return e},Te=e=>JSON.parse(JSON.stringify(e)),xe=(e,t,i)=>{do{if(e.matches(`[${t}]`))return e;e=e.parentElement||e.parentNode}while(null!==e&&1===e.nodeType&&!(i&&i.isSameNode(e)||e.matches(ee)));return null},Le=e=>null!==e&&"object"==typeof e&&!(e instanceof Array),Pe=e=>{for(let t in e)return!1;return!0},Re=(e,t)=>e&&t(e),_e=function(e,t,i,s){e.forEach((e=>{let t=new class{constructor(e,t,i){this.liveSocket=i,this.entry=e,this.offset=0,this.chunkSize=t,this.chunkTimer=null,this.uploadChannel=i.channel(`lvu:${e.ref}`,{token:e.metadata()})}error(e){clearTimeout(this.chunkTimer),this.uploadChannel.leave(),this.entry.error(e)}upload(){this.uploadChannel.onError((e=>this.error(e))),this.uploadChannel.join().receive("ok",(e=>this.readNextChunk())).receive("error",(e=>this.error(e)))}isDone(){return this.offset>=this.entry.file.size}readNextChunk(){let e=new window.FileReader,t=this.entry.file.slice(this.offset,this.chunkSize+this.offset);e.onload=e=>{if(null!==e.target.error)return Ee("Read error: "+e.target.error);this.offset+=e.target.result.byteLength,this.pushChunk(e.target.result)},e.readAsArrayBuffer(t)}pushChunk(e){this.uploadChannel.isJoined()&&this.uploadChannel.push("chunk",e).receive("ok",(()=>{this.entry.progress(this.offset/this.entry.file.size*100),this.isDone()||(this.chunkTimer=setTimeout((()=>this.readNextChunk()),this.liveSocket.getLatencySim()||0))}))}}(e,i.config.chunk_size,s);t.upload()}))},De={canPushState:()=>void 0!==history.pushState,dropLocal(e,t,i){return e.removeItem(this.localKey(t,i))},updateLocal(e,t,i,s,n){let r=this.getLocal(e,t,i),o=this.localKey(t,i),a=null===r?s:n(r);return e.setItem(o,JSON.stringify(a)),a},getLocal(e,t,i){return JSON.parse(e.getItem(this.localKey(t,i)))},updateCurrentState(e){this.canPushState()&&history.replaceState(e(history.state||{}),"",window.location.href)},pushState(e,t,i){if(this.canPushState()){if(i!==window.location.href){if("redirect"==t.type&&t.scroll){let e=history.state||{};e.scroll=t.scroll,history.replaceState(e,"",window.location.href)}delete t.scroll,history[e+"State"](t,"",i||null);let s=this.getHashTargetEl(window.location.hash);s?s.scrollIntoView():"redirect"===t.type&&window.scroll(0,0)}}else this.redirect(i)},setCookie(e,t){document.cookie=`${e}=${t}`},getCookie:e=>document.cookie.replace(new RegExp(`(?:(?:^|.*;s*)${e}s*=s*([^;]*).*$)|^.*$`),"$1"),redirect(e,t){t&&De.setCookie("__phoenix_flash__",t+"; max-age=60000; path=/"),window.location=e},localKey:(e,t)=>`${e}-${t}`,getHashTargetEl(e){let t=e.toString().substring(1);if(""!==t)return document.getElementById(t)||document.querySelector(`a[name="${t}"]`)}},Ie=De,Oe={byId:e=>document.getElementById(e)||Ee(`no id found for ${e}`),removeClass(e,t){e.classList.remove(t),0===e.classList.length&&e.removeAttribute("class")},all(e,t,i){if(!e)return[];let s=Array.from(e.querySelectorAll(t));return i?s.forEach(i):s},childNodeLength(e){let t=document.createElement("template");return t.innerHTML=e,t.content.childElementCount},isUploadInput:e=>"file"===e.type&&null!==e.getAttribute(I),findUploadInputs(e){return this.all(e,`input[type="file"][${I}]`)},findComponentNodeList(e,t){return this.filterWithinSameLiveView(this.all(e,`[${L}="${t}"]`),e)},isPhxDestroyed:e=>!(!e.id||!Oe.private(e,"destroyed")),markPhxChildDestroyed(e){this.isPhxChild(e)&&e.setAttribute(Z,""),this.putPrivate(e,"destroyed",!0)},findPhxChildrenInFragment(e,t){let i=document.createElement("template");return i.innerHTML=e,this.findPhxChildren(i.content,t)},isIgnored:(e,t)=>"ignore"===(e.getAttribute(t)||e.getAttribute("data-phx-update")),isPhxUpdate:(e,t,i)=>e.getAttribute&&i.indexOf(e.getAttribute(t))>=0,findPhxSticky(e){return this.all(e,`[${te}]`)},findPhxChildren(e,t){return this.all(e,`${ee}[${V}="${t}"]`)},findParentCIDs(e,t){let i=new Set(t);return t.reduce(((t,i)=>{let s=`[${L}="${i}"] [${L}]`;return this.filterWithinSameLiveView(this.all(e,s),e).map((e=>parseInt(e.getAttribute(L)))).forEach((e=>t.delete(e))),t}),i)},filterWithinSameLiveView(e,t){return t.querySelector(ee)?e.filter((e=>this.withinSameLiveView(e,t))):e},withinSameLiveView(e,t){for(;e=e.parentNode;){if(e.isSameNode(t))return!0;if(null!==e.getAttribute(Z))return!1}},private:(e,t)=>e.phxPrivate&&e.phxPrivate[t],deletePrivate(e,t){e.phxPrivate&&delete e.phxPrivate[t]},putPrivate(e,t,i){e.phxPrivate||(e.phxPrivate={}),e.phxPrivate[t]=i},updatePrivate(e,t,i,s){let n=this.private(e,t);void 0===n?this.putPrivate(e,t,s(i)):this.putPrivate(e,t,s(n))},copyPrivates(e,t){t.phxPrivate&&(e.phxPrivate=t.phxPrivate)},putTitle(e){let t=document.querySelector("title"),{prefix:i,suffix:s}=t.dataset;document.title=`${i||""}${e}${s||""}`},debounce(e,t,i,s,n,r,o,a){let l=e.getAttribute(i),h=e.getAttribute(n);""===l&&(l=s),""===h&&(h=r);let d=l||h;switch(d){case null:return a();case"blur":return void(this.once(e,"debounce-blur")&&e.addEventListener("blur",(()=>a())));default:let i=parseInt(d),s=()=>h?this.deletePrivate(e,fe):a(),n=this.incCycle(e,pe,s);if(isNaN(i))return Ee(`invalid throttle/debounce value: ${d}`);if(h){let s=!1;if("keydown"===t.type){let i=this.private(e,ge);this.putPrivate(e,ge,t.key),s=i!==t.key}if(!s&&this.private(e,fe))return!1;a(),this.putPrivate(e,fe,!0),setTimeout((()=>{o()&&this.triggerCycle(e,pe)}),i)}else setTimeout((()=>{o()&&this.triggerCycle(e,pe,n)}),i);let r=e.form;r&&this.once(r,"bind-debounce")&&r.addEventListener("submit",(()=>{Array.from(new FormData(r).entries(),(([e])=>{let t=r.querySelector(`[name="${e}"]`);this.incCycle(t,pe),this.deletePrivate(t,fe)}))})),this.once(e,"bind-debounce")&&e.addEventListener("blur",(()=>this.triggerCycle(e,pe)))}},triggerCycle(e,t,i){let[s,n]=this.private(e,t);i||(i=s),i===s&&(this.incCycle(e,t),n())},once(e,t){return!0!==this.private(e,t)&&(this.putPrivate(e,t,!0),!0)},incCycle(e,t,i=function(){}){let[s]=this.private(e,t)||[0,i];return s++,this.putPrivate(e,t,[s,i]),s},discardError(e,t,i){let s=t.getAttribute&&t.getAttribute(i),n=s&&e.querySelector(`[id="${s}"], [name="${s}"]`);n&&(this.private(n,K)||this.private(n.form,Q)||t.classList.add(J))},showError(e,t){(e.id||e.name)&&this.all(e.form,`[${t}="${e.id}"], [${t}="${e.name}"]`,(e=>{this.removeClass(e,J)}))},isPhxChild:e=>e.getAttribute&&e.getAttribute(V),isPhxSticky:e=>e.getAttribute&&null!==e.getAttribute(te),firstPhxChild(e){return this.isPhxChild(e)?e:this.all(e,`[${V}]`)[0]},dispatchEvent(e,t,i={}){let s={bubbles:void 0===i.bubbles||!!i.bubbles,cancelable:!0,detail:i.detail||{}},n="click"===t?new MouseEvent("click",s):new CustomEvent(t,s);e.dispatchEvent(n)},cloneNode(e,t){if(void 0===t)return e.cloneNode(!0);{let i=e.cloneNode(!1);return i.innerHTML=t,i}},mergeAttrs(e,t,i={}){let s=i.exclude||[],n=i.isIgnored,r=t.attributes;for(let i=r.length-1;i>=0;i--){let n=r[i].name;s.indexOf(n)<0&&e.setAttribute(n,t.getAttribute(n))}let o=e.attributes;for(let i=o.length-1;i>=0;i--){let s=o[i].name;n?s.startsWith("data-")&&!t.hasAttribute(s)&&e.removeAttribute(s):t.hasAttribute(s)||e.removeAttribute(s)}},mergeFocusedInput(e,t){e instanceof HTMLSelectElement||Oe.mergeAttrs(e,t,{exclude:["value"]}),t.readOnly?e.setAttribute("readonly",!0):e.removeAttribute("readonly")},hasSelectionRange:e=>e.setSelectionRange&&("text"===e.type||"textarea"===e.type),restoreFocus(e,t,i){if(!Oe.isTextualInput(e))return;let s=e.matches(":focus");e.readOnly&&e.blur(),s||e.focus(),this.hasSelectionRange(e)&&e.setSelectionRange(t,i)},isFormInput:e=>/^(?:input|select|textarea)$/i.test(e.tagName)&&"button"!==e.type,syncAttrsToProps(e){e instanceof HTMLInputElement&&Y.indexOf(e.type.toLocaleLowerCase())>=0&&(e.checked=null!==e.getAttribute("checked"))},isTextualInput:e=>X.indexOf(e.type)>=0,isNowTriggerFormExternal:(e,t)=>e.getAttribute&&null!==e.getAttribute(t),syncPendingRef(e,t,i){let s=e.getAttribute(R);if(null===s)return!0;let n=e.getAttribute(_);return Oe.isFormInput(e)||null!==e.getAttribute(i)?(Oe.isUploadInput(e)&&Oe.mergeAttrs(e,t,{isIgnored:!0}),Oe.putPrivate(e,R,t),!1):(x.forEach((i=>{e.classList.contains(i)&&t.classList.add(i)})),t.setAttribute(R,s),t.setAttribute(_,n),!0)},cleanChildNodes(e,t){if(Oe.isPhxUpdate(e,t,["append","prepend"])){let t=[];e.childNodes.forEach((e=>{if(!e.id){e.nodeType===Node.TEXT_NODE&&""===e.nodeValue.trim()||Ee(`only HTML element tags with an id are allowed inside containers with phx-update.\n\nremoving illegal node: "${(e.outerHTML||e.nodeValue).trim()}"\n\n`),t.push(e)}})),t.forEach((e=>e.remove()))}},replaceRootContainer(e,t,i){let s=new Set(["id",Z,ie,W,z]);if(e.tagName.toLowerCase()===t.toLowerCase())return Array.from(e.attributes).filter((e=>!s.has(e.name.toLowerCase()))).forEach((t=>e.removeAttribute(t.name))),Object.keys(i).filter((e=>!s.has(e.toLowerCase()))).forEach((t=>e.setAttribute(t,i[t]))),e;{let n=document.createElement(t);return Object.keys(i).forEach((e=>n.setAttribute(e,i[e]))),s.forEach((t=>n.setAttribute(t,e.getAttribute(t)))),n.innerHTML=e.innerHTML,e.replaceWith(n),n}},getSticky(e,t,i){let s=(Oe.private(e,"sticky")||[]).find((([e])=>t===e));if(s){let[e,t,i]=s;return i}return"function"==typeof i?i():i},deleteSticky(e,t){this.updatePrivate(e,"sticky",[],(e=>e.filter((([e,i])=>e!==t))))},putSticky(e,t,i){let s=i(e);this.updatePrivate(e,"sticky",[],(e=>{let n=e.findIndex((([e])=>t===e));return n>=0?e[n]=[t,i,s]:e.push([t,i,s]),e}))},applyStickyOperations(e){let t=Oe.private(e,"sticky");t&&t.forEach((([t,i,s])=>this.putSticky(e,t,i)))}},Ne=Oe,$e=class{static isActive(e,t){let i=void 0===t._phxRef,s=e.getAttribute($).split(",").indexOf(je.genFileRef(t))>=0;return t.size>0&&(i||s)}static isPreflighted(e,t){return e.getAttribute(O).split(",").indexOf(je.genFileRef(t))>=0&&this.isActive(e,t)}constructor(e,t,i){this.ref=je.genFileRef(t),this.fileEl=e,this.file=t,this.view=i,this.meta=null,this._isCancelled=!1,this._isDone=!1,this._progress=0,this._lastProgressSent=-1,this._onDone=function(){},this._onElUpdated=this.onElUpdated.bind(this),this.fileEl.addEventListener(M,this._onElUpdated)}metadata(){return this.meta}progress(e){this._progress=Math.floor(e),this._progress>this._lastProgressSent&&(this._progress>=100?(this._progress=100,this._lastProgressSent=100,this._isDone=!0,this.view.pushFileProgress(this.fileEl,this.ref,100,(()=>{je.untrackFile(this.fileEl,this.file),this._onDone()}))):(this._lastProgressSent=this._progress,this.view.pushFileProgress(this.fileEl,this.ref,this._progress)))}cancel(){this._isCancelled=!0,this._isDone=!0,this._onDone()}isDone(){return this._isDone}error(e="failed"){this.view.pushFileProgress(this.fileEl,this.ref,{error:e}),je.clearFiles(this.fileEl)}onDone(e){this._onDone=()=>{this.fileEl.removeEventListener(M,this._onElUpdated),e()}}onElUpdated(){-1===this.fileEl.getAttribute($).split(",").indexOf(this.ref)&&this.cancel()}toPreflightPayload(){return{last_modified:this.file.lastModified,name:this.file.name,size:this.file.size,type:this.file.type,ref:this.ref}}uploader(e){if(this.meta.uploader){let t=e[this.meta.uploader]||Ee(`no uploader configured for ${this.meta.uploader}`);return{name:this.meta.uploader,callback:t}}return{name:"channel",callback:_e}}zipPostFlight(e){this.meta=e.entries[this.ref],this.meta||Ee(`no preflight upload response returned with ref ${this.ref}`,{input:this.fileEl,response:e})}},Me=0,je=class{static genFileRef(e){let t=e._phxRef;return void 0!==t?t:(e._phxRef=(Me++).toString(),e._phxRef)}static getEntryDataURL(e,t,i){let s=this.activeFiles(e).find((e=>this.genFileRef(e)===t));i(URL.createObjectURL(s))}static hasUploadsInProgress(e){let t=0;return Ne.findUploadInputs(e).forEach((e=>{e.getAttribute(O)!==e.getAttribute("data-phx-done-refs")&&t++})),t>0}static serializeUploads(e){let t=this.activeFiles(e),i={};return t.forEach((t=>{let s={path:e.name},n=e.getAttribute(I);i[n]=i[n]||[],s.ref=this.genFileRef(t),s.name=t.name||s.ref,s.type=t.type,s.size=t.size,i[n].push(s)})),i}static clearFiles(e){e.value=null,e.removeAttribute(I),Ne.putPrivate(e,"files",[])}static untrackFile(e,t){Ne.putPrivate(e,"files",Ne.private(e,"files").filter((e=>!Object.is(e,t))))}static trackFiles(e,t){if(null!==e.getAttribute("multiple")){let i=t.filter((t=>!this.activeFiles(e).find((e=>Object.is(e,t)))));Ne.putPrivate(e,"files",this.activeFiles(e).concat(i)),e.value=null}else Ne.putPrivate(e,"files",t)}static activeFileInputs(e){let t=Ne.findUploadInputs(e);return Array.from(t).filter((e=>e.files&&this.activeFiles(e).length>0))}static activeFiles(e){return(Ne.private(e,"files")||[]).filter((t=>$e.isActive(e,t)))}static inputsAwaitingPreflight(e){let t=Ne.findUploadInputs(e);return Array.from(t).filter((e=>this.filesAwaitingPreflight(e).length>0))}static filesAwaitingPreflight(e){return this.activeFiles(e).filter((t=>!$e.isPreflighted(e,t)))}constructor(e,t,i){this.view=t,this.onComplete=i,this._entries=Array.from(je.filesAwaitingPreflight(e)||[]).map((i=>new $e(e,i,t))),this.numEntriesInProgress=this._entries.length}entries(){return this._entries}initAdapterUpload(e,t,i){this._entries=this._entries.map((t=>(t.zipPostFlight(e),t.onDone((()=>{this.numEntriesInProgress--,0===this.numEntriesInProgress&&this.onComplete()})),t)));let s=this._entries.reduce(((e,t)=>{let{name:s,callback:n}=t.uploader(i.uploaders);return e[s]=e[s]||{callback:n,entries:[]},e[s].entries.push(t),e}),{});for(let n in s){let{callback:r,entries:o}=s[n];r(o,t,e,i)}}},Ue={LiveFileUpload:{activeRefs(){return this.el.getAttribute($)},preflightedRefs(){return this.el.getAttribute(O)},mounted(){this.preflightedWas=this.preflightedRefs()},updated(){let e=this.preflightedRefs();this.preflightedWas!==e&&(this.preflightedWas=e,""===e&&this.__view.cancelSubmit(this.el.form)),""===this.activeRefs()&&(this.el.value=null),this.el.dispatchEvent(new CustomEvent(M))}},LiveImgPreview:{mounted(){this.ref=this.el.getAttribute("data-phx-entry-ref"),this.inputEl=document.getElementById(this.el.getAttribute(I)),je.getEntryDataURL(this.inputEl,this.ref,(e=>{this.url=e,this.el.src=e}))},destroyed(){URL.revokeObjectURL(this.url)}}};var He="undefined"==typeof document?void 0:document,Fe=!!He&&"content"in He.createElement("template"),Be=!!He&&He.createRange&&"createContextualFragment"in He.createRange();function Je(e){return e=e.trim(),Fe?function(e){var t=He.createElement("template");return t.innerHTML=e,t.content.childNodes[0]}(e):Be?function(e){return Ae||(Ae=He.createRange()).selectNode(He.body),Ae.createContextualFragment(e).childNodes[0]}(e):function(e){var t=He.createElement("body");return t.innerHTML=e,t.childNodes[0]}(e)}function qe(e,t){var i,s,n=e.nodeName,r=t.nodeName;return n===r||(i=n.charCodeAt(0),s=r.charCodeAt(0),i<=90&&s>=97?n===r.toUpperCase():s<=90&&i>=97&&r===n.toUpperCase())}function Ve(e,t,i){e[i]!==t[i]&&(e[i]=t[i],e[i]?e.setAttribute(i,""):e.removeAttribute(i))}var We={OPTION:function(e,t){var i=e.parentNode;if(i){var s=i.nodeName.toUpperCase();"OPTGROUP"===s&&(s=(i=i.parentNode)&&i.nodeName.toUpperCase()),"SELECT"!==s||i.hasAttribute("multiple")||(e.hasAttribute("selected")&&!t.selected&&(e.setAttribute("selected","selected"),e.removeAttribute("selected")),i.selectedIndex=-1)}Ve(e,t,"selected")},INPUT:function(e,t){Ve(e,t,"checked"),Ve(e,t,"disabled"),e.value!==t.value&&(e.value=t.value),t.hasAttribute("value")||e.removeAttribute("value")},TEXTAREA:function(e,t){var i=t.value;e.value!==i&&(e.value=i);var s=e.firstChild;if(s){var n=s.nodeValue;if(n==i||!i&&n==e.placeholder)return;s.nodeValue=i}},SELECT:function(e,t){if(!t.hasAttribute("multiple")){for(var i,s,n=-1,r=0,o=e.firstChild;o;)if("OPTGROUP"===(s=o.nodeName&&o.nodeName.toUpperCase()))o=(i=o).firstChild;else{if("OPTION"===s){if(o.hasAttribute("selected")){n=r;break}r++}!(o=o.nextSibling)&&i&&(o=i.nextSibling,i=null)}e.selectedIndex=n}}};function ze(){}function Ge(e){if(e)return e.getAttribute&&e.getAttribute("id")||e.id}var Ke,Xe,Ye=(Ke=function(e,t){var i,s,n,r,o=t.attributes;if(11!==t.nodeType&&11!==e.nodeType){for(var a=o.length-1;a>=0;a--)s=(i=o[a]).name,n=i.namespaceURI,r=i.value,n?(s=i.localName||s,e.getAttributeNS(n,s)!==r&&("xmlns"===i.prefix&&(s=i.name),e.setAttributeNS(n,s,r))):e.getAttribute(s)!==r&&e.setAttribute(s,r);for(var l=e.attributes,h=l.length-1;h>=0;h--)s=(i=l[h]).name,(n=i.namespaceURI)?(s=i.localName||s,t.hasAttributeNS(n,s)||e.removeAttributeNS(n,s)):t.hasAttribute(s)||e.removeAttribute(s)}},function(e,t,i){if(i||(i={}),"string"==typeof t)if("#document"===e.nodeName||"HTML"===e.nodeName||"BODY"===e.nodeName){var s=t;(t=He.createElement("html")).innerHTML=s}else t=Je(t);var n=i.getNodeKey||Ge,r=i.onBeforeNodeAdded||ze,o=i.onNodeAdded||ze,a=i.onBeforeElUpdated||ze,l=i.onElUpdated||ze,h=i.onBeforeNodeDiscarded||ze,d=i.onNodeDiscarded||ze,c=i.onBeforeElChildrenUpdated||ze,u=!0===i.childrenOnly,p=Object.create(null),f=[];function g(e){f.push(e)}function m(e,t){if(1===e.nodeType)for(var i=e.firstChild;i;){var s=void 0;t&&(s=n(i))?g(s):(d(i),i.firstChild&&m(i,t)),i=i.nextSibling}}function v(e,t,i){!1!==h(e)&&(t&&t.removeChild(e),d(e),m(e,i))}function b(e){o(e);for(var t=e.firstChild;t;){var i=t.nextSibling,s=n(t);if(s){var r=p[s];r&&qe(t,r)?(t.parentNode.replaceChild(r,t),y(r,t)):b(t)}else b(t);t=i}}function y(e,t,i){var s=n(t);if(s&&delete p[s],!i){if(!1===a(e,t))return;if(Ke(e,t),l(e),!1===c(e,t))return}"TEXTAREA"!==e.nodeName?function(e,t){var i,s,o,a,l,h=t.firstChild,d=e.firstChild;e:for(;h;){for(a=h.nextSibling,i=n(h);d;){if(o=d.nextSibling,h.isSameNode&&h.isSameNode(d)){h=a,d=o;continue e}s=n(d);var c=d.nodeType,u=void 0;if(c===h.nodeType&&(1===c?(i?i!==s&&((l=p[i])?o===l?u=!1:(e.insertBefore(l,d),s?g(s):v(d,e,!0),d=l):u=!1):s&&(u=!1),(u=!1!==u&&qe(d,h))&&y(d,h)):3!==c&&8!=c||(u=!0,d.nodeValue!==h.nodeValue&&(d.nodeValue=h.nodeValue))),u){h=a,d=o;continue e}s?g(s):v(d,e,!0),d=o}if(i&&(l=p[i])&&qe(l,h))e.appendChild(l),y(l,h);else{var f=r(h);!1!==f&&(f&&(h=f),h.actualize&&(h=h.actualize(e.ownerDocument||He)),e.appendChild(h),b(h))}h=a,d=o}!function(e,t,i){for(;t;){var s=t.nextSibling;(i=n(t))?g(i):v(t,e,!0),t=s}}(e,d,s);var m=We[e.nodeName];m&&m(e,t)}(e,t):We.TEXTAREA(e,t)}!function e(t){if(1===t.nodeType||11===t.nodeType)for(var i=t.firstChild;i;){var s=n(i);s&&(p[s]=i),e(i),i=i.nextSibling}}(e);var k,w,E=e,C=E.nodeType,A=t.nodeType;if(!u)if(1===C)1===A?qe(e,t)||(d(e),E=function(e,t){for(var i=e.firstChild;i;){var s=i.nextSibling;t.appendChild(i),i=s}return t}(e,(k=t.nodeName,(w=t.namespaceURI)&&"http://www.w3.org/1999/xhtml"!==w?He.createElementNS(w,k):He.createElement(k)))):E=t;else if(3===C||8===C){if(A===C)return E.nodeValue!==t.nodeValue&&(E.nodeValue=t.nodeValue),E;E=t}if(E===t)d(e);else{if(t.isSameNode&&t.isSameNode(E))return;if(y(E,t,u),f)for(var S=0,T=f.length;S<T;S++){var x=p[f[S]];x&&v(x,x.parentNode,!1)}}return!u&&E!==e&&e.parentNode&&(E.actualize&&(E=E.actualize(e.ownerDocument||He)),e.parentNode.replaceChild(E,e)),E}),Qe=class{static patchEl(e,t,i){Ye(e,t,{childrenOnly:!1,onBeforeElUpdated:(e,t)=>{if(i&&i.isSameNode(e)&&Ne.isFormInput(e))return Ne.mergeFocusedInput(e,t),!1}})}constructor(e,t,i,s,n){this.view=e,this.liveSocket=e.liveSocket,this.container=t,this.id=i,this.rootID=e.root.id,this.html=s,this.targetCID=n,this.cidPatch=Ce(this.targetCID),this.callbacks={beforeadded:[],beforeupdated:[],beforephxChildAdded:[],afteradded:[],afterupdated:[],afterdiscarded:[],afterphxChildAdded:[],aftertransitionsDiscarded:[]}}before(e,t){this.callbacks[`before${e}`].push(t)}after(e,t){this.callbacks[`after${e}`].push(t)}trackBefore(e,...t){this.callbacks[`before${e}`].forEach((e=>e(...t)))}trackAfter(e,...t){this.callbacks[`after${e}`].forEach((e=>e(...t)))}markPrunableContentForRemoval(){Ne.all(this.container,"[phx-update=append] > *, [phx-update=prepend] > *",(e=>{e.setAttribute(U,"")}))}perform(){let{view:e,liveSocket:t,container:i,html:s}=this,n=this.isCIDPatch()?this.targetCIDContainer(s):i;if(this.isCIDPatch()&&!n)return;let r=t.getActiveElement(),{selectionStart:o,selectionEnd:a}=r&&Ne.hasSelectionRange(r)?r:{},l=t.binding(le),h=t.binding(G),d=t.binding(re),c=t.binding("trigger-action"),u=t.binding("remove"),p=[],f=[],g=[],m=[],v=null,b=t.time("premorph container prep",(()=>this.buildDiffHTML(i,s,l,n)));return this.trackBefore("added",i),this.trackBefore("updated",i,i),t.time("morphdom",(()=>{Ye(n,b,{childrenOnly:null===n.getAttribute(L),getNodeKey:e=>Ne.isPhxDestroyed(e)?null:e.id,onBeforeNodeAdded:e=>(this.trackBefore("added",e),e),onNodeAdded:t=>{t instanceof HTMLImageElement&&t.srcset?t.srcset=t.srcset:t instanceof HTMLVideoElement&&t.autoplay&&t.play(),Ne.isNowTriggerFormExternal(t,c)&&(v=t),Ne.discardError(n,t,h),(Ne.isPhxChild(t)&&e.ownsElement(t)||Ne.isPhxSticky(t)&&e.ownsElement(t.parentNode))&&this.trackAfter("phxChildAdded",t),p.push(t)},onNodeDiscarded:e=>{(Ne.isPhxChild(e)||Ne.isPhxSticky(e))&&t.destroyViewByEl(e),this.trackAfter("discarded",e)},onBeforeNodeDiscarded:e=>!(!e.getAttribute||null===e.getAttribute(U))||(null===e.parentNode||!Ne.isPhxUpdate(e.parentNode,l,["append","prepend"])||!e.id)&&(e.getAttribute&&e.getAttribute(u)?(m.push(e),!1):!this.skipCIDSibling(e)),onElUpdated:e=>{Ne.isNowTriggerFormExternal(e,c)&&(v=e),f.push(e)},onBeforeElUpdated:(e,t)=>{if(Ne.cleanChildNodes(t,l),this.skipCIDSibling(t))return!1;if(Ne.isPhxSticky(e))return!1;if(Ne.isIgnored(e,l))return this.trackBefore("updated",e,t),Ne.mergeAttrs(e,t,{isIgnored:!0}),f.push(e),Ne.applyStickyOperations(e),!1;if("number"===e.type&&e.validity&&e.validity.badInput)return!1;if(!Ne.syncPendingRef(e,t,d))return Ne.isUploadInput(e)&&(this.trackBefore("updated",e,t),f.push(e)),Ne.applyStickyOperations(e),!1;if(Ne.isPhxChild(t)){let i=e.getAttribute(Z);return Ne.mergeAttrs(e,t,{exclude:[ie]}),""!==i&&e.setAttribute(Z,i),e.setAttribute(z,this.rootID),Ne.applyStickyOperations(e),!1}return Ne.copyPrivates(t,e),Ne.discardError(n,t,h),r&&e.isSameNode(r)&&Ne.isFormInput(e)?(this.trackBefore("updated",e,t),Ne.mergeFocusedInput(e,t),Ne.syncAttrsToProps(e),f.push(e),Ne.applyStickyOperations(e),!1):(Ne.isPhxUpdate(t,l,["append","prepend"])&&g.push(new class{constructor(e,t,i){let s=new Set,n=new Set([...t.children].map((e=>e.id))),r=[];Array.from(e.children).forEach((e=>{if(e.id&&(s.add(e.id),n.has(e.id))){let t=e.previousElementSibling&&e.previousElementSibling.id;r.push({elementId:e.id,previousElementId:t})}})),this.containerId=t.id,this.updateType=i,this.elementsToModify=r,this.elementIdsToAdd=[...n].filter((e=>!s.has(e)))}perform(){let e=Ne.byId(this.containerId);this.elementsToModify.forEach((t=>{t.previousElementId?Re(document.getElementById(t.previousElementId),(e=>{Re(document.getElementById(t.elementId),(t=>{t.previousElementSibling&&t.previousElementSibling.id==e.id||e.insertAdjacentElement("afterend",t)}))})):Re(document.getElementById(t.elementId),(t=>{null==t.previousElementSibling||e.insertAdjacentElement("afterbegin",t)}))})),"prepend"==this.updateType&&this.elementIdsToAdd.reverse().forEach((t=>{Re(document.getElementById(t),(t=>e.insertAdjacentElement("afterbegin",t)))}))}}(e,t,t.getAttribute(l))),Ne.syncAttrsToProps(t),Ne.applyStickyOperations(t),this.trackBefore("updated",e,t),!0)}})})),t.isDebugEnabled()&&function(){let e=new Set,t=document.querySelectorAll("*[id]");for(let i=0,s=t.length;i<s;i++)e.has(t[i].id)?console.error(`Multiple IDs detected: ${t[i].id}. Ensure unique element ids.`):e.add(t[i].id)}(),g.length>0&&t.time("post-morph append/prepend restoration",(()=>{g.forEach((e=>e.perform()))})),t.silenceEvents((()=>Ne.restoreFocus(r,o,a))),Ne.dispatchEvent(document,"phx:update"),p.forEach((e=>this.trackAfter("added",e))),f.forEach((e=>this.trackAfter("updated",e))),m.length>0&&(t.transitionRemoves(m),t.requestDOMUpdate((()=>{m.forEach((e=>{let i=Ne.firstPhxChild(e);i&&t.destroyViewByEl(i),e.remove()})),this.trackAfter("transitionsDiscarded",m)}))),v&&(t.disconnect(),v.submit()),!0}isCIDPatch(){return this.cidPatch}skipCIDSibling(e){return e.nodeType===Node.ELEMENT_NODE&&null!==e.getAttribute(j)}targetCIDContainer(e){if(!this.isCIDPatch())return;let[t,...i]=Ne.findComponentNodeList(this.container,this.targetCID);return 0===i.length&&1===Ne.childNodeLength(e)?t:t&&t.parentNode}buildDiffHTML(e,t,i,s){let n=this.isCIDPatch(),r=n&&s.getAttribute(L)===this.targetCID.toString();if(!n||r)return t;{let e=null,i=document.createElement("template");e=Ne.cloneNode(s);let[n,...r]=Ne.findComponentNodeList(e,this.targetCID);return i.innerHTML=t,r.forEach((e=>e.remove())),Array.from(e.childNodes).forEach((e=>{e.id&&e.nodeType===Node.ELEMENT_NODE&&e.getAttribute(L)!==this.targetCID.toString()&&(e.setAttribute(j,""),e.innerHTML="")})),Array.from(i.content.childNodes).forEach((t=>e.insertBefore(t,n))),n.remove(),e.outerHTML}}},Ze=class{static extract(e){let{[ke]:t,[ye]:i,[we]:s}=e;return delete e[ke],delete e[ye],delete e[we],{diff:e,title:s,reply:t||null,events:i||[]}}constructor(e,t){this.viewId=e,this.rendered={},this.mergeDiff(t)}parentViewId(){return this.viewId}toString(e){return this.recursiveToString(this.rendered,this.rendered.c,e)}recursiveToString(e,t=e.c,i){let s={buffer:"",components:t,onlyCids:i=i?new Set(i):null};return this.toOutputBuffer(e,null,s),s.buffer}componentCIDs(e){return Object.keys(e.c||{}).map((e=>parseInt(e)))}isComponentOnlyDiff(e){return!!e.c&&1===Object.keys(e).length}getComponent(e,t){return e.c[t]}mergeDiff(e){let t=e.c,i={};if(delete e.c,this.rendered=this.mutableMerge(this.rendered,e),this.rendered.c=this.rendered.c||{},t){let s=this.rendered.c;for(let e in t)t[e]=this.cachedFindComponent(e,t[e],s,t,i);for(let e in t)s[e]=t[e];e.c=t}}cachedFindComponent(e,t,i,s,n){if(n[e])return n[e];{let r,o,a=t[be];if(Ce(a)){let e;e=a>0?this.cachedFindComponent(a,s[a],i,s,n):i[-a],o=e[be],r=this.cloneMerge(e,t),r[be]=o}else r=void 0!==t[be]?t:this.cloneMerge(i[e]||{},t);return n[e]=r,r}}mutableMerge(e,t){return void 0!==t[be]?t:(this.doMutableMerge(e,t),e)}doMutableMerge(e,t){for(let i in t){let s=t[i],n=e[i];Le(s)&&void 0===s[be]&&Le(n)?this.doMutableMerge(n,s):e[i]=s}}cloneMerge(e,t){let i={...e,...t};for(let s in i){let n=t[s],r=e[s];Le(n)&&void 0===n[be]&&Le(r)&&(i[s]=this.cloneMerge(r,n))}return i}componentToString(e){return this.recursiveCIDToString(this.rendered.c,e)}pruneCIDs(e){e.forEach((e=>delete this.rendered.c[e]))}get(){return this.rendered}isNewFingerprint(e={}){return!!e[be]}templateStatic(e,t){return"number"==typeof e?t[e]:e}toOutputBuffer(e,t,i){if(e[ve])return this.comprehensionToBuffer(e,t,i);let{[be]:s}=e;s=this.templateStatic(s,t),i.buffer+=s[0];for(let n=1;n<s.length;n++)this.dynamicToBuffer(e[n-1],t,i),i.buffer+=s[n]}comprehensionToBuffer(e,t,i){let{[ve]:s,[be]:n}=e;n=this.templateStatic(n,t);let r=t||e.p;for(let e=0;e<s.length;e++){let t=s[e];i.buffer+=n[0];for(let e=1;e<n.length;e++)this.dynamicToBuffer(t[e-1],r,i),i.buffer+=n[e]}}dynamicToBuffer(e,t,i){"number"==typeof e?i.buffer+=this.recursiveCIDToString(i.components,e,i.onlyCids):Le(e)?this.toOutputBuffer(e,t,i):i.buffer+=e}recursiveCIDToString(e,t,i){let s=e[t]||Ee(`no component for CID ${t}`,e),n=document.createElement("template");n.innerHTML=this.recursiveToString(s,e,i);let r=n.content,o=i&&!i.has(t),[a,l]=Array.from(r.childNodes).reduce((([e,i],s,r)=>s.nodeType===Node.ELEMENT_NODE?s.getAttribute(L)?[e,!0]:(s.setAttribute(L,t),s.id||(s.id=`${this.parentViewId()}-${t}-${r}`),o&&(s.setAttribute(j,""),s.innerHTML=""),[!0,i]):""!==s.nodeValue.trim()?(Ee(`only HTML element tags are allowed at the root of components.\n\ngot: "${s.nodeValue.trim()}"\n\nwithin:\n`,n.innerHTML.trim()),s.replaceWith(this.createSpan(s.nodeValue,t)),[!0,i]):(s.remove(),[e,i])),[!1,!1]);return a||l?!a&&l?(Ee("expected at least one HTML element tag directly inside a component, but only subcomponents were found. A component must render at least one HTML tag directly inside itself.",n.innerHTML.trim()),n.innerHTML):n.innerHTML:(Ee("expected at least one HTML element tag inside a component, but the component is empty:\n",n.innerHTML.trim()),this.createSpan("",t).outerHTML)}createSpan(e,t){let i=document.createElement("span");return i.innerText=e,i.setAttribute(L,t),i}},et=1,tt=class{static makeID(){return et++}static elementID(e){return e.phxHookId}constructor(e,t,i){this.__view=e,this.liveSocket=e.liveSocket,this.__callbacks=i,this.__listeners=new Set,this.__isDisconnected=!1,this.el=t,this.el.phxHookId=this.constructor.makeID();for(let e in this.__callbacks)this[e]=this.__callbacks[e]}__mounted(){this.mounted&&this.mounted()}__updated(){this.updated&&this.updated()}__beforeUpdate(){this.beforeUpdate&&this.beforeUpdate()}__destroyed(){this.destroyed&&this.destroyed()}__reconnected(){this.__isDisconnected&&(this.__isDisconnected=!1,this.reconnected&&this.reconnected())}__disconnected(){this.__isDisconnected=!0,this.disconnected&&this.disconnected()}pushEvent(e,t={},i=function(){}){return this.__view.pushHookEvent(null,e,t,i)}pushEventTo(e,t,i={},s=function(){}){return this.__view.withinTargets(e,((e,n)=>e.pushHookEvent(n,t,i,s)))}handleEvent(e,t){let i=(i,s)=>s?e:t(i.detail);return window.addEventListener(`phx:${e}`,i),this.__listeners.add(i),i}removeHandleEvent(e){let t=e(null,!0);window.removeEventListener(`phx:${t}`,e),this.__listeners.delete(e)}upload(e,t){return this.__view.dispatchUploads(e,t)}uploadTo(e,t,i){return this.__view.withinTargets(e,(e=>e.dispatchUploads(t,i)))}__cleanup__(){this.__listeners.forEach((e=>this.removeHandleEvent(e)))}},it={exec(e,t,i,s,n){let[r,o]=n||[null,{}];("["===t.charAt(0)?JSON.parse(t):[[r,o]]).forEach((([n,a])=>{n===r&&o.data&&(a.data=Object.assign(a.data||{},o.data)),this.filterToEls(s,a).forEach((r=>{this[`exec_${n}`](e,t,i,s,r,a)}))}))},isVisible:e=>!!(e.offsetWidth||e.offsetHeight||e.getClientRects().length>0),exec_dispatch(e,t,i,s,n,{to:r,event:o,detail:a,bubbles:l}){(a=a||{}).dispatcher=s,Ne.dispatchEvent(n,o,{detail:a,bubbles:l})},exec_push(e,t,i,s,n,r){if(!i.isConnected())return;let{event:o,data:a,target:l,page_loading:h,loading:d,value:c,dispatcher:u}=r,p={loading:d,value:c,target:l,page_loading:!!h},f="change"===e&&u?u:s,g=l||f.getAttribute(i.binding("target"))||f;i.withinTargets(g,((i,n)=>{if("change"===e){let{newCid:e,_target:a,callback:l}=r;a=a||(s instanceof HTMLInputElement?s.name:void 0),a&&(p._target=a),i.pushInput(s,n,e,o||t,p,l)}else"submit"===e?i.submitForm(s,n,o||t,p):i.pushEvent(e,s,n,o||t,a,p)}))},exec_add_class(e,t,i,s,n,{names:r,transition:o,time:a}){this.addOrRemoveClasses(n,r,[],o,a,i)},exec_remove_class(e,t,i,s,n,{names:r,transition:o,time:a}){this.addOrRemoveClasses(n,[],r,o,a,i)},exec_transition(e,t,i,s,n,{time:r,transition:o}){let[a,l,h]=o;i.transition(r,(()=>this.addOrRemoveClasses(n,a.concat(l),[])),(()=>this.addOrRemoveClasses(n,h,a.concat(l))))},exec_toggle(e,t,i,s,n,{display:r,ins:o,outs:a,time:l}){this.toggle(e,i,n,r,o,a,l)},exec_show(e,t,i,s,n,{display:r,transition:o,time:a}){this.show(e,i,n,r,o,a)},exec_hide(e,t,i,s,n,{display:r,transition:o,time:a}){this.hide(e,i,n,r,o,a)},exec_set_attr(e,t,i,s,n,{attr:[r,o]}){this.setOrRemoveAttrs(n,[[r,o]],[])},exec_remove_attr(e,t,i,s,n,{attr:r}){this.setOrRemoveAttrs(n,[],[r])},show(e,t,i,s,n,r){this.isVisible(i)||this.toggle(e,t,i,s,n,null,r)},hide(e,t,i,s,n,r){this.isVisible(i)&&this.toggle(e,t,i,s,null,n,r)},toggle(e,t,i,s,n,r,o){let[a,l,h]=n||[[],[],[]],[d,c,u]=r||[[],[],[]];if(a.length>0||d.length>0)if(this.isVisible(i)){let e=()=>{this.addOrRemoveClasses(i,c,a.concat(l).concat(h)),window.requestAnimationFrame((()=>{this.addOrRemoveClasses(i,d,[]),window.requestAnimationFrame((()=>this.addOrRemoveClasses(i,u,c)))}))};i.dispatchEvent(new Event("phx:hide-start")),t.transition(o,e,(()=>{this.addOrRemoveClasses(i,[],d.concat(u)),Ne.putSticky(i,"toggle",(e=>e.style.display="none")),i.dispatchEvent(new Event("phx:hide-end"))}))}else{if("remove"===e)return;let n=()=>{this.addOrRemoveClasses(i,l,d.concat(c).concat(u)),Ne.putSticky(i,"toggle",(e=>e.style.display=s||"block")),window.requestAnimationFrame((()=>{this.addOrRemoveClasses(i,a,[]),window.requestAnimationFrame((()=>this.addOrRemoveClasses(i,h,l)))}))};i.dispatchEvent(new Event("phx:show-start")),t.transition(o,n,(()=>{this.addOrRemoveClasses(i,[],a.concat(h)),i.dispatchEvent(new Event("phx:show-end"))}))}else this.isVisible(i)?window.requestAnimationFrame((()=>{i.dispatchEvent(new Event("phx:hide-start")),Ne.putSticky(i,"toggle",(e=>e.style.display="none")),i.dispatchEvent(new Event("phx:hide-end"))})):window.requestAnimationFrame((()=>{i.dispatchEvent(new Event("phx:show-start")),Ne.putSticky(i,"toggle",(e=>e.style.display=s||"block")),i.dispatchEvent(new Event("phx:show-end"))}))},addOrRemoveClasses(e,t,i,s,n,r){let[o,a,l]=s||[[],[],[]];if(o.length>0){let s=()=>this.addOrRemoveClasses(e,a.concat(o),[]),h=()=>this.addOrRemoveClasses(e,t.concat(l),i.concat(o).concat(a));return r.transition(n,s,h)}window.requestAnimationFrame((()=>{let[s,n]=Ne.getSticky(e,"classes",[[],[]]),r=t.filter((t=>s.indexOf(t)<0&&!e.classList.contains(t))),o=i.filter((t=>n.indexOf(t)<0&&e.classList.contains(t))),a=s.filter((e=>i.indexOf(e)<0)).concat(r),l=n.filter((e=>t.indexOf(e)<0)).concat(o);Ne.putSticky(e,"classes",(e=>(e.classList.remove(...l),e.classList.add(...a),[a,l])))}))},setOrRemoveAttrs(e,t,i){let[s,n]=Ne.getSticky(e,"attrs",[[],[]]),r=t.map((([e,t])=>e)).concat(i),o=s.filter((([e,t])=>!r.includes(e))).concat(t),a=n.filter((e=>!r.includes(e))).concat(i);Ne.putSticky(e,"attrs",(e=>(a.forEach((t=>e.removeAttribute(t))),o.forEach((([t,i])=>e.setAttribute(t,i))),[o,a])))},hasAllClasses:(e,t)=>t.every((t=>e.classList.contains(t))),isToggledOut(e,t){return!this.isVisible(e)||this.hasAllClasses(e,t)},filterToEls:(e,{to:t})=>t?Ne.all(document,t):[e]},st=(e,t,i=[])=>{let s=new FormData(e),n=[];s.forEach(((e,t,i)=>{e instanceof File&&n.push(t)})),n.forEach((e=>s.delete(e)));let r=new URLSearchParams;for(let[e,t]of s.entries())(0===i.length||i.indexOf(e)>=0)&&r.append(e,t);for(let e in t)r.append(e,t[e]);return r.toString()},nt=class{constructor(e,t,i,s){this.liveSocket=t,this.flash=s,this.parent=i,this.root=i?i.root:this,this.el=e,this.id=this.el.id,this.ref=0,this.childJoins=0,this.loaderTimer=null,this.pendingDiffs=[],this.pruningCIDs=[],this.redirect=!1,this.href=null,this.joinCount=this.parent?this.parent.joinCount-1:0,this.joinPending=!0,this.destroyed=!1,this.joinCallback=function(e){e&&e()},this.stopCallback=function(){},this.pendingJoinOps=this.parent?null:[],this.viewHooks={},this.uploaders={},this.formSubmits=[],this.children=this.parent?null:{},this.root.children[this.id]={},this.channel=this.liveSocket.channel(`lv:${this.id}`,(()=>({redirect:this.redirect?this.href:void 0,url:this.redirect?void 0:this.href||void 0,params:this.connectParams(),session:this.getSession(),static:this.getStatic(),flash:this.flash}))),this.showLoader(this.liveSocket.loaderTimeout),this.bindChannel()}setHref(e){this.href=e}setRedirect(e){this.redirect=!0,this.href=e}isMain(){return null!==this.el.getAttribute(W)}connectParams(){let e=this.liveSocket.params(this.el),t=Ne.all(document,`[${this.binding("track-static")}]`).map((e=>e.src||e.href)).filter((e=>"string"==typeof e));return t.length>0&&(e._track_static=t),e._mounts=this.joinCount,e}isConnected(){return this.channel.canPush()}getSession(){return this.el.getAttribute(Z)}getStatic(){let e=this.el.getAttribute(ie);return""===e?null:e}destroy(e=function(){}){this.destroyAllChildren(),this.destroyed=!0,delete this.root.children[this.id],this.parent&&delete this.root.children[this.parent.id][this.id],clearTimeout(this.loaderTimer);let t=()=>{e();for(let e in this.viewHooks)this.destroyHook(this.viewHooks[e])};Ne.markPhxChildDestroyed(this.el),this.log("destroyed",(()=>["the child has been removed from the parent"])),this.channel.leave().receive("ok",t).receive("error",t).receive("timeout",t)}setContainerClasses(...e){this.el.classList.remove(F,B,q),this.el.classList.add(...e)}showLoader(e){if(clearTimeout(this.loaderTimer),e)this.loaderTimer=setTimeout((()=>this.showLoader()),e);else{for(let e in this.viewHooks)this.viewHooks[e].__disconnected();this.setContainerClasses(B)}}hideLoader(){clearTimeout(this.loaderTimer),this.setContainerClasses(F)}triggerReconnected(){for(let e in this.viewHooks)this.viewHooks[e].__reconnected()}log(e,t){this.liveSocket.log(this,e,t)}transition(e,t,i=function(){}){this.liveSocket.transition(e,t,i)}withinTargets(e,t){if(e instanceof HTMLElement||e instanceof SVGElement)return this.liveSocket.owner(e,(i=>t(i,e)));if(Ce(e)){0===Ne.findComponentNodeList(this.el,e).length?Ee(`no component found matching phx-target of ${e}`):t(this,parseInt(e))}else{let i=Array.from(document.querySelectorAll(e));0===i.length&&Ee(`nothing found matching the phx-target selector "${e}"`),i.forEach((e=>this.liveSocket.owner(e,(i=>t(i,e)))))}}applyDiff(e,t,i){this.log(e,(()=>["",Te(t)]));let{diff:s,reply:n,events:r,title:o}=Ze.extract(t);return o&&Ne.putTitle(o),i({diff:s,reply:n,events:r}),n}onJoin(e){let{rendered:t,container:i}=e;if(i){let[e,t]=i;this.el=Ne.replaceRootContainer(this.el,e,t)}this.childJoins=0,this.joinPending=!0,this.flash=null,Ie.dropLocal(this.liveSocket.localStorage,window.location.pathname,T),this.applyDiff("mount",t,(({diff:t,events:i})=>{this.rendered=new Ze(this.id,t);let s=this.renderContainer(null,"join");this.dropPendingRefs();let n=this.formsForRecovery(s);this.joinCount++,n.length>0?n.forEach((([e,t,r],o)=>{this.pushFormRecovery(e,r,(e=>{o===n.length-1&&this.onJoinComplete(e,s,i)}))})):this.onJoinComplete(e,s,i)}))}dropPendingRefs(){Ne.all(document,`[${_}="${this.id}"][${R}]`,(e=>{e.removeAttribute(R),e.removeAttribute(_)}))}onJoinComplete({live_patch:e},t,i){if(this.joinCount>1||this.parent&&!this.parent.isJoinPending())return this.applyJoinPatch(e,t,i);0===Ne.findPhxChildrenInFragment(t,this.id).filter((e=>{let t=e.id&&this.el.querySelector(`[id="${e.id}"]`),i=t&&t.getAttribute(ie);return i&&e.setAttribute(ie,i),this.joinChild(e)})).length?this.parent?(this.root.pendingJoinOps.push([this,()=>this.applyJoinPatch(e,t,i)]),this.parent.ackJoin(this)):(this.onAllChildJoinsComplete(),this.applyJoinPatch(e,t,i)):this.root.pendingJoinOps.push([this,()=>this.applyJoinPatch(e,t,i)])}attachTrueDocEl(){this.el=Ne.byId(this.id),this.el.setAttribute(z,this.root.id)}applyJoinPatch(e,t,i){this.attachTrueDocEl();let s=new Qe(this,this.el,this.id,t,null);if(s.markPrunableContentForRemoval(),this.performPatch(s,!1),this.joinNewChildren(),Ne.all(this.el,`[${this.binding(ae)}], [data-phx-hook]`,(e=>{let t=this.addHook(e);t&&t.__mounted()})),this.joinPending=!1,this.liveSocket.dispatchEvents(i),this.applyPendingUpdates(),e){let{kind:t,to:i}=e;this.liveSocket.historyPatch(i,t)}this.hideLoader(),this.joinCount>1&&this.triggerReconnected(),this.stopCallback()}triggerBeforeUpdateHook(e,t){this.liveSocket.triggerDOM("onBeforeElUpdated",[e,t]);let i=this.getHook(e),s=i&&Ne.isIgnored(e,this.binding(le));if(i&&!e.isEqualNode(t)&&(!s||(n=e.dataset,r=t.dataset,JSON.stringify(n)!==JSON.stringify(r))))return i.__beforeUpdate(),i;var n,r}performPatch(e,t){let i=[],s=!1,n=new Set;return e.after("added",(e=>{this.liveSocket.triggerDOM("onNodeAdded",[e]);let t=this.addHook(e);t&&t.__mounted()})),e.after("phxChildAdded",(e=>{Ne.isPhxSticky(e)?this.liveSocket.joinRootViews():s=!0})),e.before("updated",((e,t)=>{this.triggerBeforeUpdateHook(e,t)&&n.add(e.id)})),e.after("updated",(e=>{n.has(e.id)&&this.getHook(e).__updated()})),e.after("discarded",(e=>{e.nodeType===Node.ELEMENT_NODE&&i.push(e)})),e.after("transitionsDiscarded",(e=>this.afterElementsRemoved(e,t))),e.perform(),this.afterElementsRemoved(i,t),s}afterElementsRemoved(e,t){let i=[];e.forEach((e=>{let t=Ne.all(e,`[${L}]`),s=Ne.all(e,`[${this.binding(ae)}]`);t.concat(e).forEach((e=>{let t=this.componentID(e);Ce(t)&&-1===i.indexOf(t)&&i.push(t)})),s.concat(e).forEach((e=>{let t=this.getHook(e);t&&this.destroyHook(t)}))})),t&&this.maybePushComponentsDestroyed(i)}joinNewChildren(){Ne.findPhxChildren(this.el,this.id).forEach((e=>this.joinChild(e)))}getChildById(e){return this.root.children[this.id][e]}getDescendentByEl(e){return e.id===this.id?this:this.children[e.getAttribute(V)][e.id]}destroyDescendent(e){for(let t in this.root.children)for(let i in this.root.children[t])if(i===e)return this.root.children[t][i].destroy()}joinChild(e){if(!this.getChildById(e.id)){let t=new nt(e,this.liveSocket,this);return this.root.children[this.id][t.id]=t,t.join(),this.childJoins++,!0}}isJoinPending(){return this.joinPending}ackJoin(e){this.childJoins--,0===this.childJoins&&(this.parent?this.parent.ackJoin(this):this.onAllChildJoinsComplete())}onAllChildJoinsComplete(){this.joinCallback((()=>{this.pendingJoinOps.forEach((([e,t])=>{e.isDestroyed()||t()})),this.pendingJoinOps=[]}))}update(e,t){if(this.isJoinPending()||this.liveSocket.hasPendingLink()&&!Ne.isPhxSticky(this.el))return this.pendingDiffs.push({diff:e,events:t});this.rendered.mergeDiff(e);let i=!1;this.rendered.isComponentOnlyDiff(e)?this.liveSocket.time("component patch complete",(()=>{Ne.findParentCIDs(this.el,this.rendered.componentCIDs(e)).forEach((t=>{this.componentPatch(this.rendered.getComponent(e,t),t)&&(i=!0)}))})):Pe(e)||this.liveSocket.time("full patch complete",(()=>{let t=this.renderContainer(e,"update"),s=new Qe(this,this.el,this.id,t,null);i=this.performPatch(s,!0)})),this.liveSocket.dispatchEvents(t),i&&this.joinNewChildren()}renderContainer(e,t){return this.liveSocket.time(`toString diff (${t})`,(()=>{let t=this.el.tagName,i=e?this.rendered.componentCIDs(e).concat(this.pruningCIDs):null;return`<${t}>${this.rendered.toString(i)}</${t}>`}))}componentPatch(e,t){if(Pe(e))return!1;let i=this.rendered.componentToString(t),s=new Qe(this,this.el,this.id,i,t);return this.performPatch(s,!0)}getHook(e){return this.viewHooks[tt.elementID(e)]}addHook(e){if(tt.elementID(e)||!e.getAttribute)return;let t=e.getAttribute("data-phx-hook")||e.getAttribute(this.binding(ae));if(t&&!this.ownsElement(e))return;let i=this.liveSocket.getHookCallbacks(t);if(i){e.id||Ee(`no DOM ID for hook "${t}". Hooks require a unique ID on each element.`,e);let s=new tt(this,e,i);return this.viewHooks[tt.elementID(s.el)]=s,s}null!==t&&Ee(`unknown hook found for "${t}"`,e)}destroyHook(e){e.__destroyed(),e.__cleanup__(),delete this.viewHooks[tt.elementID(e.el)]}applyPendingUpdates(){this.pendingDiffs.forEach((({diff:e,events:t})=>this.update(e,t))),this.pendingDiffs=[]}onChannel(e,t){this.liveSocket.onChannel(this.channel,e,(e=>{this.isJoinPending()?this.root.pendingJoinOps.push([this,()=>t(e)]):this.liveSocket.requestDOMUpdate((()=>t(e)))}))}bindChannel(){this.liveSocket.onChannel(this.channel,"diff",(e=>{this.liveSocket.requestDOMUpdate((()=>{this.applyDiff("update",e,(({diff:e,events:t})=>this.update(e,t)))}))})),this.onChannel("redirect",(({to:e,flash:t})=>this.onRedirect({to:e,flash:t}))),this.onChannel("live_patch",(e=>this.onLivePatch(e))),this.onChannel("live_redirect",(e=>this.onLiveRedirect(e))),this.channel.onError((e=>this.onError(e))),this.channel.onClose((e=>this.onClose(e)))}destroyAllChildren(){for(let e in this.root.children[this.id])this.getChildById(e).destroy()}onLiveRedirect(e){let{to:t,kind:i,flash:s}=e,n=this.expandURL(t);this.liveSocket.historyRedirect(n,i,s)}onLivePatch(e){let{to:t,kind:i}=e;this.href=this.expandURL(t),this.liveSocket.historyPatch(t,i)}expandURL(e){return e.startsWith("/")?`${window.location.protocol}//${window.location.host}${e}`:e}onRedirect({to:e,flash:t}){this.liveSocket.redirect(e,t)}isDestroyed(){return this.destroyed}join(e){this.isMain()&&(this.stopCallback=this.liveSocket.withPageLoading({to:this.href,kind:"initial"})),this.joinCallback=t=>{t=t||function(){},e?e(this.joinCount,t):t()},this.liveSocket.wrapPush(this,{timeout:!1},(()=>this.channel.join().receive("ok",(e=>{this.isDestroyed()||this.liveSocket.requestDOMUpdate((()=>this.onJoin(e)))})).receive("error",(e=>!this.isDestroyed()&&this.onJoinError(e))).receive("timeout",(()=>!this.isDestroyed()&&this.onJoinError({reason:"timeout"})))))}onJoinError(e){return"unauthorized"===e.reason||"stale"===e.reason?(this.log("error",(()=>["unauthorized live_redirect. Falling back to page request",e])),this.onRedirect({to:this.href})):((e.redirect||e.live_redirect)&&(this.joinPending=!1,this.channel.leave()),e.redirect?this.onRedirect(e.redirect):e.live_redirect?this.onLiveRedirect(e.live_redirect):(this.log("error",(()=>["unable to join",e])),void(this.liveSocket.isConnected()&&this.liveSocket.reloadWithJitter(this))))}onClose(e){if(!this.isDestroyed()){if(this.liveSocket.hasPendingLink()&&"leave"!==e)return this.liveSocket.reloadWithJitter(this);this.destroyAllChildren(),this.liveSocket.dropActiveElement(this),document.activeElement&&document.activeElement.blur(),this.liveSocket.isUnloaded()&&this.showLoader(200)}}onError(e){this.onClose(e),this.liveSocket.isConnected()&&this.log("error",(()=>["view crashed",e])),this.liveSocket.isUnloaded()||this.displayError()}displayError(){this.isMain()&&Ne.dispatchEvent(window,"phx:page-loading-start",{detail:{to:this.href,kind:"error"}}),this.showLoader(),this.setContainerClasses(B,q)}pushWithReply(e,t,i,s=function(){}){if(!this.isConnected())return;let[n,[r],o]=e?e():[null,[],{}],a=function(){};return(o.page_loading||r&&null!==r.getAttribute(this.binding(H)))&&(a=this.liveSocket.withPageLoading({kind:"element",target:r})),"number"!=typeof i.cid&&delete i.cid,this.liveSocket.wrapPush(this,{timeout:!0},(()=>this.channel.push(t,i,3e4).receive("ok",(e=>{null!==n&&this.undoRefs(n);let t=t=>{e.redirect&&this.onRedirect(e.redirect),e.live_patch&&this.onLivePatch(e.live_patch),e.live_redirect&&this.onLiveRedirect(e.live_redirect),a(),s(e,t)};e.diff?this.liveSocket.requestDOMUpdate((()=>{let i=this.applyDiff("update",e.diff,(({diff:e,events:t})=>{this.update(e,t)}));t(i)})):t(null)}))))}undoRefs(e){Ne.all(document,`[${_}="${this.id}"][${R}="${e}"]`,(e=>{let t=e.getAttribute(ne);e.removeAttribute(R),e.removeAttribute(_),null!==e.getAttribute(se)&&(e.readOnly=!1,e.removeAttribute(se)),null!==t&&(e.disabled="true"===t,e.removeAttribute(ne)),x.forEach((t=>Ne.removeClass(e,t)));let i=e.getAttribute(oe);null!==i&&(e.innerText=i,e.removeAttribute(oe));let s=Ne.private(e,R);if(s){let t=this.triggerBeforeUpdateHook(e,s);Qe.patchEl(e,s,this.liveSocket.getActiveElement()),t&&t.__updated(),Ne.deletePrivate(e,R)}}))}putRef(e,t,i={}){let s=this.ref++,n=this.binding(re);return i.loading&&(e=e.concat(Ne.all(document,i.loading))),e.forEach((e=>{e.classList.add(`phx-${t}-loading`),e.setAttribute(R,s),e.setAttribute(_,this.el.id);let i=e.getAttribute(n);null!==i&&(e.getAttribute(oe)||e.setAttribute(oe,e.innerText),""!==i&&(e.innerText=i),e.setAttribute("disabled",""))})),[s,e,i]}componentID(e){let t=e.getAttribute&&e.getAttribute(L);return t?parseInt(t):null}targetComponentID(e,t,i={}){if(Ce(t))return t;let s=e.getAttribute(this.binding("target"));return Ce(s)?parseInt(s):t&&(null!==s||i.target)?this.closestComponentID(t):null}closestComponentID(e){return Ce(e)?e:e?Re(e.closest(`[${L}]`),(e=>this.ownsElement(e)&&this.componentID(e))):null}pushHookEvent(e,t,i,s){if(!this.isConnected())return this.log("hook",(()=>["unable to push hook event. LiveView not connected",t,i])),!1;let[n,r,o]=this.putRef([],"hook");return this.pushWithReply((()=>[n,r,o]),"event",{type:"hook",event:t,value:i,cid:this.closestComponentID(e)},((e,t)=>s(t,n))),n}extractMeta(e,t,i){let s=this.binding("value-");for(let i=0;i<e.attributes.length;i++){t||(t={});let n=e.attributes[i].name;n.startsWith(s)&&(t[n.replace(s,"")]=e.getAttribute(n))}if(void 0!==e.value&&(t||(t={}),t.value=e.value,"INPUT"===e.tagName&&Y.indexOf(e.type)>=0&&!e.checked&&delete t.value),i){t||(t={});for(let e in i)t[e]=i[e]}return t}pushEvent(e,t,i,s,n,r={}){this.pushWithReply((()=>this.putRef([t],e,r)),"event",{type:e,event:s,value:this.extractMeta(t,n,r.value),cid:this.targetComponentID(t,i,r)})}pushFileProgress(e,t,i,s=function(){}){this.liveSocket.withinOwners(e.form,((n,r)=>{n.pushWithReply(null,"progress",{event:e.getAttribute(n.binding("progress")),ref:e.getAttribute(I),entry_ref:t,progress:i,cid:n.targetComponentID(e.form,r)},s)}))}pushInput(e,t,i,s,n,r){let o,a,l=Ce(i)?i:this.targetComponentID(e.form,t),h=()=>this.putRef([e,e.form],"change",n);a=e.getAttribute(this.binding("change"))?st(e.form,{_target:n._target},[e.name]):st(e.form,{_target:n._target}),Ne.isUploadInput(e)&&e.files&&e.files.length>0&&je.trackFiles(e,Array.from(e.files)),o=je.serializeUploads(e);let d={type:"form",event:s,value:a,uploads:o,cid:l};this.pushWithReply(h,"event",d,(i=>{if(Ne.showError(e,this.liveSocket.binding(G)),Ne.isUploadInput(e)&&null!==e.getAttribute("data-phx-auto-upload")){if(je.filesAwaitingPreflight(e).length>0){let[s,n]=h();this.uploadFiles(e.form,t,s,l,(t=>{r&&r(i),this.triggerAwaitingSubmit(e.form)}))}}else r&&r(i)}))}triggerAwaitingSubmit(e){let t=this.getScheduledSubmit(e);if(t){let[i,s,n,r]=t;this.cancelSubmit(e),r()}}getScheduledSubmit(e){return this.formSubmits.find((([t,i,s,n])=>t.isSameNode(e)))}scheduleSubmit(e,t,i,s){if(this.getScheduledSubmit(e))return!0;this.formSubmits.push([e,t,i,s])}cancelSubmit(e){this.formSubmits=this.formSubmits.filter((([t,i,s])=>!t.isSameNode(e)||(this.undoRefs(i),!1)))}pushFormSubmit(e,t,i,s,n){let r=e=>!(xe(e,`${this.binding(le)}=ignore`,e.form)||xe(e,"data-phx-update=ignore",e.form)),o=e=>e.hasAttribute(this.binding(re)),a=e=>"BUTTON"==e.tagName,l=e=>["INPUT","TEXTAREA","SELECT"].includes(e.tagName),h=()=>{let t=Array.from(e.elements),i=t.filter(o),n=t.filter(a).filter(r),h=t.filter(l).filter(r);return n.forEach((e=>{e.setAttribute(ne,e.disabled),e.disabled=!0})),h.forEach((e=>{e.setAttribute(se,e.readOnly),e.readOnly=!0,e.files&&(e.setAttribute(ne,e.disabled),e.disabled=!0)})),e.setAttribute(this.binding(H),""),this.putRef([e].concat(i).concat(n).concat(h),"submit",s)},d=this.targetComponentID(e,t);if(je.hasUploadsInProgress(e)){let[r,o]=h(),a=()=>this.pushFormSubmit(e,t,i,s,n);return this.scheduleSubmit(e,r,s,a)}if(je.inputsAwaitingPreflight(e).length>0){let[r,o]=h(),a=()=>[r,o,s];this.uploadFiles(e,t,r,d,(t=>{let s=st(e,{});this.pushWithReply(a,"event",{type:"form",event:i,value:s,cid:d},n)}))}else{let t=st(e,{});this.pushWithReply(h,"event",{type:"form",event:i,value:t,cid:d},n)}}uploadFiles(e,t,i,s,n){let r=this.joinCount,o=je.activeFileInputs(e),a=o.length;o.forEach((e=>{let s=new je(e,this,(()=>{a--,0===a&&n()}));this.uploaders[e]=s;let o=s.entries().map((e=>e.toPreflightPayload())),l={ref:e.getAttribute(I),entries:o,cid:this.targetComponentID(e.form,t)};this.log("upload",(()=>["sending preflight request",l])),this.pushWithReply(null,"allow_upload",l,(e=>{if(this.log("upload",(()=>["got preflight response",e])),e.error){this.undoRefs(i);let[t,s]=e.error;this.log("upload",(()=>[`error for entry ${t}`,s]))}else{let t=e=>{this.channel.onError((()=>{this.joinCount===r&&e()}))};s.initAdapterUpload(e,t,this.liveSocket)}}))}))}dispatchUploads(e,t){let i=Ne.findUploadInputs(this.el).filter((t=>t.name===e));0===i.length?Ee(`no live file inputs found matching the name "${e}"`):i.length>1?Ee(`duplicate live file inputs found matching the name "${e}"`):Ne.dispatchEvent(i[0],D,{detail:{files:t}})}pushFormRecovery(e,t,i){this.liveSocket.withinOwners(e,((s,n)=>{let r=e.elements[0],o=e.getAttribute(this.binding(he))||e.getAttribute(this.binding("change"));it.exec("change",o,s,r,["push",{_target:r.name,newCid:t,callback:i}])}))}pushLinkPatch(e,t,i){let s=this.liveSocket.setPendingLink(e),n=t?()=>this.putRef([t],"click"):null,r=()=>this.liveSocket.redirect(window.location.href),o=this.pushWithReply(n,"live_patch",{url:e},(t=>{this.liveSocket.requestDOMUpdate((()=>{t.link_redirect?this.liveSocket.replaceMain(e,null,i,s):(this.liveSocket.commitPendingLink(s)&&(this.href=e),this.applyPendingUpdates(),i&&i(s))}))}));o?o.receive("timeout",r):r()}formsForRecovery(e){if(0===this.joinCount)return[];let t=this.binding("change"),i=document.createElement("template");return i.innerHTML=e,Ne.all(this.el,`form[${t}]`).filter((e=>e.id&&this.ownsElement(e))).filter((e=>e.elements.length>0)).filter((e=>"ignore"!==e.getAttribute(this.binding(he)))).map((e=>{let s=i.content.querySelector(`form[id="${e.id}"][${t}="${e.getAttribute(t)}"]`);return s?[e,s,this.targetComponentID(s)]:[e,null,null]})).filter((([e,t,i])=>t))}maybePushComponentsDestroyed(e){let t=e.filter((e=>0===Ne.findComponentNodeList(this.el,e).length));t.length>0&&(this.pruningCIDs.push(...t),this.pushWithReply(null,"cids_will_destroy",{cids:t},(()=>{this.pruningCIDs=this.pruningCIDs.filter((e=>-1!==t.indexOf(e)));let e=t.filter((e=>0===Ne.findComponentNodeList(this.el,e).length));e.length>0&&this.pushWithReply(null,"cids_destroyed",{cids:e},(e=>{this.rendered.pruneCIDs(e.cids)}))})))}ownsElement(e){return e.getAttribute(V)===this.id||Re(e.closest(ee),(e=>e.id))===this.id}submitForm(e,t,i,s={}){Ne.putPrivate(e,Q,!0);let n=this.liveSocket.binding(G),r=Array.from(e.elements);this.liveSocket.blurActiveElement(this),this.pushFormSubmit(e,t,i,s,(()=>{r.forEach((e=>Ne.showError(e,n))),this.liveSocket.restorePreviouslyActiveFocus()}))}binding(e){return this.liveSocket.binding(e)}},rt=class{constructor(){this.transitions=new Set,this.pendingOps=[],this.reset()}reset(){this.transitions.forEach((e=>{cancelTimeout(e),this.transitions.delete(e)})),this.flushPendingOps()}after(e){0===this.size()?e():this.pushPendingOp(e)}addTransition(e,t,i){t();let s=setTimeout((()=>{this.transitions.delete(s),i(),0===this.size()&&this.flushPendingOps()}),e);this.transitions.add(s)}pushPendingOp(e){this.pendingOps.push(e)}size(){return this.transitions.size}flushPendingOps(){this.pendingOps.forEach((e=>e())),this.pendingOps=[]}},ot="undefined"!=typeof globalThis?globalThis:"undefined"!=typeof window?window:"undefined"!=typeof global?global:"undefined"!=typeof self?self:{},at={exports:{}};Xe=at,function(e,t){function i(){s.width=e.innerWidth,s.height=5*l.barThickness;var t=s.getContext("2d");t.shadowBlur=l.shadowBlur,t.shadowColor=l.shadowColor;var i,n=t.createLinearGradient(0,0,s.width,0);for(i in l.barColors)n.addColorStop(i,l.barColors[i]);t.lineWidth=l.barThickness,t.beginPath(),t.moveTo(0,l.barThickness/2),t.lineTo(Math.ceil(o*s.width),l.barThickness/2),t.strokeStyle=n,t.stroke()}!function(){for(var t=0,i=["ms","moz","webkit","o"],s=0;s<i.length&&!e.requestAnimationFrame;++s)e.requestAnimationFrame=e[i[s]+"RequestAnimationFrame"],e.cancelAnimationFrame=e[i[s]+"CancelAnimationFrame"]||e[i[s]+"CancelRequestAnimationFrame"];e.requestAnimationFrame||(e.requestAnimationFrame=function(i,s){var n=(new Date).getTime(),r=Math.max(0,16-(n-t)),o=e.setTimeout((function(){i(n+r)}),r);return t=n+r,o}),e.cancelAnimationFrame||(e.cancelAnimationFrame=function(e){clearTimeout(e)})}();var s,n,r,o,a,l={autoRun:!0,barThickness:3,barColors:{0:"rgba(26,  188, 156, .9)",".25":"rgba(52,  152, 219, .9)",".50":"rgba(241, 196, 15,  .9)",".75":"rgba(230, 126, 34,  .9)","1.0":"rgba(211, 84,  0,   .9)"},shadowBlur:10,shadowColor:"rgba(0,   0,   0,   .6)",className:null},h={config:function(e){for(var t in e)l.hasOwnProperty(t)&&(l[t]=e[t])},show:function(){var d,c,u;a||(a=!0,null!==r&&e.cancelAnimationFrame(r),s||((u=(s=t.createElement("canvas")).style).position="fixed",u.top=u.left=u.right=u.margin=u.padding=0,u.zIndex=100001,u.display="none",l.className&&s.classList.add(l.className),t.body.appendChild(s),d="resize",c=i,(u=e).addEventListener?u.addEventListener(d,c,!1):u.attachEvent?u.attachEvent("on"+d,c):u.onresize=c),s.style.opacity=1,s.style.display="block",h.progress(0),l.autoRun&&function t(){n=e.requestAnimationFrame(t),h.progress("+"+.05*Math.pow(1-Math.sqrt(o),2))}())},progress:function(e){return void 0===e||("string"==typeof e&&(e=(0<=e.indexOf("+")||0<=e.indexOf("-")?o:0)+parseFloat(e)),o=1<e?1:e,i()),o},hide:function(){a&&(a=!1,null!=n&&(e.cancelAnimationFrame(n),n=null),function t(){return 1<=h.progress("+.1")&&(s.style.opacity-=.05,s.style.opacity<=.05)?(s.style.display="none",void(r=null)):void(r=e.requestAnimationFrame(t))}())}};Xe.exports=h}.call(ot,window,document);var lt=at.exports;const ht=document.querySelector("meta[name='csrf-token']").getAttribute("content");const dt=new class{constructor(e,t,i={}){if(this.unloaded=!1,!t||"Object"===t.constructor.name)throw new Error('\n      a phoenix Socket must be provided as the second argument to the LiveSocket constructor. For example:\n\n          import {Socket} from "phoenix"\n          import {LiveSocket} from "phoenix_live_view"\n          let liveSocket = new LiveSocket("/live", Socket, {...})\n      ');this.socket=new t(e,i),this.bindingPrefix=i.bindingPrefix||"phx-",this.opts=i,this.params=Se(i.params||{}),this.viewLogger=i.viewLogger,this.metadataCallbacks=i.metadata||{},this.defaults=Object.assign(Te(me),i.defaults||{}),this.activeElement=null,this.prevActive=null,this.silenced=!1,this.main=null,this.outgoingMainEl=null,this.clickStartedAtTarget=null,this.linkRef=1,this.roots={},this.href=window.location.href,this.pendingLink=null,this.currentLocation=Te(window.location),this.hooks=i.hooks||{},this.uploaders=i.uploaders||{},this.loaderTimeout=i.loaderTimeout||1,this.reloadWithJitterTimer=null,this.maxReloads=i.maxReloads||10,this.reloadJitterMin=i.reloadJitterMin||5e3,this.reloadJitterMax=i.reloadJitterMax||1e4,this.failsafeJitter=i.failsafeJitter||3e4,this.localStorage=i.localStorage||window.localStorage,this.sessionStorage=i.sessionStorage||window.sessionStorage,this.boundTopLevelEvents=!1,this.domCallbacks=Object.assign({onNodeAdded:Se(),onBeforeElUpdated:Se()},i.dom||{}),this.transitions=new rt,window.addEventListener("pagehide",(e=>{this.unloaded=!0})),this.socket.onOpen((()=>{this.isUnloaded()&&window.location.reload()}))}isProfileEnabled(){return"true"===this.sessionStorage.getItem(ce)}isDebugEnabled(){return"true"===this.sessionStorage.getItem(de)}isDebugDisabled(){return"false"===this.sessionStorage.getItem(de)}enableDebug(){this.sessionStorage.setItem(de,"true")}enableProfiling(){this.sessionStorage.setItem(ce,"true")}disableDebug(){this.sessionStorage.setItem(de,"false")}disableProfiling(){this.sessionStorage.removeItem(ce)}enableLatencySim(e){this.enableDebug(),console.log("latency simulator enabled for the duration of this browser session. Call disableLatencySim() to disable"),this.sessionStorage.setItem(ue,e)}disableLatencySim(){this.sessionStorage.removeItem(ue)}getLatencySim(){let e=this.sessionStorage.getItem(ue);return e?parseInt(e):null}getSocket(){return this.socket}connect(){"localhost"!==window.location.hostname||this.isDebugDisabled()||this.enableDebug();let e=()=>{this.joinRootViews()?(this.bindTopLevelEvents(),this.socket.connect()):this.main&&this.socket.connect()};["complete","loaded","interactive"].indexOf(document.readyState)>=0?e():document.addEventListener("DOMContentLoaded",(()=>e()))}disconnect(e){clearTimeout(this.reloadWithJitterTimer),this.socket.disconnect(e)}replaceTransport(e){clearTimeout(this.reloadWithJitterTimer),this.socket.replaceTransport(e),this.connect()}execJS(e,t,i=null){this.owner(e,(s=>it.exec(i,t,s,e)))}triggerDOM(e,t){this.domCallbacks[e](...t)}time(e,t){if(!this.isProfileEnabled()||!console.time)return t();console.time(e);let i=t();return console.timeEnd(e),i}log(e,t,i){if(this.viewLogger){let[s,n]=i();this.viewLogger(e,t,s,n)}else if(this.isDebugEnabled()){let[s,n]=i();((e,t,i,s)=>{e.liveSocket.isDebugEnabled()&&console.log(`${e.id} ${t}: ${i} - `,s)})(e,t,s,n)}}requestDOMUpdate(e){this.transitions.after(e)}transition(e,t,i=function(){}){this.transitions.addTransition(e,t,i)}onChannel(e,t,i){e.on(t,(e=>{let t=this.getLatencySim();t?(console.log(`simulating ${t}ms of latency from server to client`),setTimeout((()=>i(e)),t)):i(e)}))}wrapPush(e,t,i){let s=this.getLatencySim(),n=e.joinCount;if(!s)return this.isConnected()&&t.timeout?i().receive("timeout",(()=>{e.joinCount!==n||e.isDestroyed()||this.reloadWithJitter(e,(()=>{this.log(e,"timeout",(()=>["received timeout while communicating with server. Falling back to hard refresh for recovery"]))}))})):i();console.log(`simulating ${s}ms of latency from client to server`);let r={receives:[],receive(e,t){this.receives.push([e,t])}};return setTimeout((()=>{e.isDestroyed()||r.receives.reduce(((e,[t,i])=>e.receive(t,i)),i())}),s),r}reloadWithJitter(e,t){clearTimeout(this.reloadWithJitterTimer),this.disconnect();let i=this.reloadJitterMin,s=this.reloadJitterMax,n=Math.floor(Math.random()*(s-i+1))+i,r=Ie.updateLocal(this.localStorage,window.location.pathname,T,0,(e=>e+1));r>this.maxReloads&&(n=this.failsafeJitter),this.reloadWithJitterTimer=setTimeout((()=>{e.isDestroyed()||e.isConnected()||(e.destroy(),t?t():this.log(e,"join",(()=>[`encountered ${r} consecutive reloads`])),r>this.maxReloads&&this.log(e,"join",(()=>[`exceeded ${this.maxReloads} consecutive reloads. Entering failsafe mode`])),this.hasPendingLink()?window.location=this.pendingLink:window.location.reload())}),n)}getHookCallbacks(e){return e&&e.startsWith("Phoenix.")?Ue[e.split(".")[1]]:this.hooks[e]}isUnloaded(){return this.unloaded}isConnected(){return this.socket.isConnected()}getBindingPrefix(){return this.bindingPrefix}binding(e){return`${this.getBindingPrefix()}${e}`}channel(e,t){return this.socket.channel(e,t)}joinRootViews(){let e=!1;return Ne.all(document,`${ee}:not([${V}])`,(t=>{if(!this.getRootById(t.id)){let e=this.newRootView(t);e.setHref(this.getHref()),e.join(),t.getAttribute(W)&&(this.main=e)}e=!0})),e}redirect(e,t){this.disconnect(),Ie.redirect(e,t)}replaceMain(e,t,i=null,s=this.setPendingLink(e)){this.outgoingMainEl=this.outgoingMainEl||this.main.el;let n=Ne.cloneNode(this.outgoingMainEl,"");this.main.showLoader(this.loaderTimeout),this.main.destroy(),this.main=this.newRootView(n,t),this.main.setRedirect(e),this.transitionRemoves(),this.main.join(((e,t)=>{1===e&&this.commitPendingLink(s)&&this.requestDOMUpdate((()=>{Ne.findPhxSticky(document).forEach((e=>n.appendChild(e))),this.outgoingMainEl.replaceWith(n),this.outgoingMainEl=null,i&&requestAnimationFrame(i),t()}))}))}transitionRemoves(e){let t=this.binding("remove");(e=e||Ne.all(document,`[${t}]`)).forEach((e=>{document.body.contains(e)&&this.execJS(e,e.getAttribute(t),"remove")}))}isPhxView(e){return e.getAttribute&&null!==e.getAttribute(Z)}newRootView(e,t){let i=new nt(e,this,null,t);return this.roots[i.id]=i,i}owner(e,t){let i=Re(e.closest(ee),(e=>this.getViewByEl(e)))||this.main;i&&t(i)}withinOwners(e,t){this.owner(e,(i=>t(i,e)))}getViewByEl(e){let t=e.getAttribute(z);return Re(this.getRootById(t),(t=>t.getDescendentByEl(e)))}getRootById(e){return this.roots[e]}destroyAllViews(){for(let e in this.roots)this.roots[e].destroy(),delete this.roots[e];this.main=null}destroyViewByEl(e){let t=this.getRootById(e.getAttribute(z));t&&t.id===e.id?(t.destroy(),delete this.roots[t.id]):t&&t.destroyDescendent(e.id)}setActiveElement(e){if(this.activeElement===e)return;this.activeElement=e;let t=()=>{e===this.activeElement&&(this.activeElement=null),e.removeEventListener("mouseup",this),e.removeEventListener("touchend",this)};e.addEventListener("mouseup",t),e.addEventListener("touchend",t)}getActiveElement(){return document.activeElement===document.body?this.activeElement||document.activeElement:document.activeElement||document.body}dropActiveElement(e){this.prevActive&&e.ownsElement(this.prevActive)&&(this.prevActive=null)}restorePreviouslyActiveFocus(){this.prevActive&&this.prevActive!==document.body&&this.prevActive.focus()}blurActiveElement(){this.prevActive=this.getActiveElement(),this.prevActive!==document.body&&this.prevActive.blur()}bindTopLevelEvents(){this.boundTopLevelEvents||(this.boundTopLevelEvents=!0,this.socket.onClose((e=>{e&&1e3===e.code&&this.main&&this.reloadWithJitter(this.main)})),document.body.addEventListener("click",(function(){})),window.addEventListener("pageshow",(e=>{e.persisted&&(this.getSocket().disconnect(),this.withPageLoading({to:window.location.href,kind:"redirect"}),window.location.reload())}),!0),this.bindNav(),this.bindClicks(),this.bindForms(),this.bind({keyup:"keyup",keydown:"keydown"},((e,t,i,s,n,r)=>{let o=s.getAttribute(this.binding("key")),a=e.key&&e.key.toLowerCase();if(o&&o.toLowerCase()!==a)return;let l={key:e.key,...this.eventMeta(t,e,s)};it.exec(t,n,i,s,["push",{data:l}])})),this.bind({blur:"focusout",focus:"focusin"},((e,t,i,s,n,r)=>{if(!r){let r={key:e.key,...this.eventMeta(t,e,s)};it.exec(t,n,i,s,["push",{data:r}])}})),this.bind({blur:"blur",focus:"focus"},((e,t,i,s,n,r,o)=>{if("window"===o){let n=this.eventMeta(t,e,s);it.exec(t,r,i,s,["push",{data:n}])}})),window.addEventListener("dragover",(e=>e.preventDefault())),window.addEventListener("drop",(e=>{e.preventDefault();let t=Re(xe(e.target,this.binding(N)),(e=>e.getAttribute(this.binding(N)))),i=t&&document.getElementById(t),s=Array.from(e.dataTransfer.files||[]);i&&!i.disabled&&0!==s.length&&i.files instanceof FileList&&(je.trackFiles(i,s),i.dispatchEvent(new Event("input",{bubbles:!0})))})),this.on(D,(e=>{let t=e.target;if(!Ne.isUploadInput(t))return;let i=Array.from(e.detail.files||[]).filter((e=>e instanceof File||e instanceof Blob));je.trackFiles(t,i),t.dispatchEvent(new Event("input",{bubbles:!0}))})))}eventMeta(e,t,i){let s=this.metadataCallbacks[e];return s?s(t,i):{}}setPendingLink(e){return this.linkRef++,this.pendingLink=e,this.linkRef}commitPendingLink(e){return this.linkRef===e&&(this.href=this.pendingLink,this.pendingLink=null,!0)}getHref(){return this.href}hasPendingLink(){return!!this.pendingLink}bind(e,t){for(let i in e){let s=e[i];this.on(s,(e=>{let n=this.binding(i),r=this.binding(`window-${i}`),o=e.target.getAttribute&&e.target.getAttribute(n);o?this.debounce(e.target,e,s,(()=>{this.withinOwners(e.target,(s=>{t(e,i,s,e.target,o,null)}))})):Ne.all(document,`[${r}]`,(n=>{let o=n.getAttribute(r);this.debounce(n,e,s,(()=>{this.withinOwners(n,(s=>{t(e,i,s,n,o,"window")}))}))}))}))}}bindClicks(){window.addEventListener("mousedown",(e=>this.clickStartedAtTarget=e.target)),this.bindClick("click","click",!1),this.bindClick("mousedown","capture-click",!0)}bindClick(e,t,i){let s=this.binding(t);window.addEventListener(e,(e=>{let t=null;if(i)t=e.target.matches(`[${s}]`)?e.target:e.target.querySelector(`[${s}]`);else{let i=this.clickStartedAtTarget||e.target;t=xe(i,s),this.dispatchClickAway(e,i),this.clickStartedAtTarget=null}let n=t&&t.getAttribute(s);n&&("#"===t.getAttribute("href")&&e.preventDefault(),this.debounce(t,e,"click",(()=>{this.withinOwners(t,(i=>{it.exec("click",n,i,t,["push",{data:this.eventMeta("click",e,t)}])}))})))}),i)}dispatchClickAway(e,t){let i=this.binding("click-away");Ne.all(document,`[${i}]`,(s=>{s.isSameNode(t)||s.contains(t)||this.withinOwners(e.target,(t=>{let n=s.getAttribute(i);it.isVisible(s)&&it.exec("click",n,t,s,["push",{data:this.eventMeta("click",e,e.target)}])}))}))}bindNav(){if(!Ie.canPushState())return;history.scrollRestoration&&(history.scrollRestoration="manual");let e=null;window.addEventListener("scroll",(t=>{clearTimeout(e),e=setTimeout((()=>{Ie.updateCurrentState((e=>Object.assign(e,{scroll:window.scrollY})))}),100)})),window.addEventListener("popstate",(e=>{if(!this.registerNewLocation(window.location))return;let{type:t,id:i,root:s,scroll:n}=e.state||{},r=window.location.href;this.requestDOMUpdate((()=>{this.main.isConnected()&&"patch"===t&&i===this.main.id?this.main.pushLinkPatch(r,null):this.replaceMain(r,null,(()=>{s&&this.replaceRootHistory(),"number"==typeof n&&setTimeout((()=>{window.scrollTo(0,n)}),0)}))}))}),!1),window.addEventListener("click",(e=>{let t=xe(e.target,P),i=t&&t.getAttribute(P),s=e.metaKey||e.ctrlKey||1===e.button;if(!i||!this.isConnected()||!this.main||s)return;let n=t.href,r=t.getAttribute("data-phx-link-state");e.preventDefault(),e.stopImmediatePropagation(),this.pendingLink!==n&&this.requestDOMUpdate((()=>{if("patch"===i)this.pushHistoryPatch(n,r,t);else{if("redirect"!==i)throw new Error(`expected data-phx-link to be "patch" or "redirect", got: ${i}`);this.historyRedirect(n,r)}}))}),!1)}dispatchEvent(e,t={}){Ne.dispatchEvent(window,`phx:${e}`,{detail:t})}dispatchEvents(e){e.forEach((([e,t])=>this.dispatchEvent(e,t)))}withPageLoading(e,t){Ne.dispatchEvent(window,"phx:page-loading-start",{detail:e});let i=()=>Ne.dispatchEvent(window,"phx:page-loading-stop",{detail:e});return t?t(i):i}pushHistoryPatch(e,t,i){this.withPageLoading({to:e,kind:"patch"},(s=>{this.main.pushLinkPatch(e,i,(i=>{this.historyPatch(e,t,i),s()}))}))}historyPatch(e,t,i=this.setPendingLink(e)){this.commitPendingLink(i)&&(Ie.pushState(t,{type:"patch",id:this.main.id},e),this.registerNewLocation(window.location))}historyRedirect(e,t,i){let s=window.scrollY;this.withPageLoading({to:e,kind:"redirect"},(n=>{this.replaceMain(e,i,(()=>{Ie.pushState(t,{type:"redirect",id:this.main.id,scroll:s},e),this.registerNewLocation(window.location),n()}))}))}replaceRootHistory(){Ie.pushState("replace",{root:!0,type:"patch",id:this.main.id})}registerNewLocation(e){let{pathname:t,search:i}=this.currentLocation;return t+i!==e.pathname+e.search&&(this.currentLocation=Te(e),!0)}bindForms(){let e=0;this.on("submit",(e=>{let t=e.target.getAttribute(this.binding("submit"));t&&(e.preventDefault(),e.target.disabled=!0,this.withinOwners(e.target,(i=>{it.exec("submit",t,i,e.target,["push",{}])})))}),!1);for(let t of["change","input"])this.on(t,(i=>{let s=this.binding("change"),n=i.target,r=n.getAttribute(s),o=n.form&&n.form.getAttribute(s),a=r||o;if(!a)return;if("number"===n.type&&n.validity&&n.validity.badInput)return;let l=r?n:n.form,h=e;e++;let{at:d,type:c}=Ne.private(n,"prev-iteration")||{};d===h-1&&t!==c||(Ne.putPrivate(n,"prev-iteration",{at:h,type:t}),this.debounce(n,i,t,(()=>{this.withinOwners(l,(e=>{Ne.putPrivate(n,K,!0),Ne.isTextualInput(n)||this.setActiveElement(n),it.exec("change",a,e,n,["push",{_target:i.target.name,dispatcher:l}])}))})))}),!1)}debounce(e,t,i,s){if("blur"===i||"focusout"===i)return s();let n=this.binding("debounce"),r=this.binding("throttle"),o=this.defaults.debounce.toString(),a=this.defaults.throttle.toString();this.withinOwners(e,(i=>{Ne.debounce(e,t,n,o,r,a,(()=>!i.isDestroyed()&&document.body.contains(e)),(()=>{s()}))}))}silenceEvents(e){this.silenced=!0,e(),this.silenced=!1}on(e,t){window.addEventListener(e,(e=>{this.silenced||t(e)}))}}("/",class{constructor(t,b){const k=new class{constructor(t,n={}){this.stateChangeCallbacks={open:[],close:[],error:[],message:[]},this.channels=[],this.sendBuffer=[],this.ref=0,this.timeout=n.timeout||1e4,this.transport=n.transport||s.WebSocket||A,this.establishedConnections=0,this.defaultEncoder=S.encode.bind(S),this.defaultDecoder=S.decode.bind(S),this.closeWasClean=!1,this.binaryType=n.binaryType||"arraybuffer",this.connectClock=1,this.transport!==A?(this.encode=n.encode||this.defaultEncoder,this.decode=n.decode||this.defaultDecoder):(this.encode=this.defaultEncoder,this.decode=this.defaultDecoder);let r=null;i&&i.addEventListener&&(i.addEventListener("pagehide",(e=>{this.conn&&(this.disconnect(),r=this.connectClock)})),i.addEventListener("pageshow",(e=>{r===this.connectClock&&(r=null,this.connect())}))),this.heartbeatIntervalMs=n.heartbeatIntervalMs||3e4,this.rejoinAfterMs=e=>n.rejoinAfterMs?n.rejoinAfterMs(e):[1e3,2e3,5e3][e-1]||1e4,this.reconnectAfterMs=e=>n.reconnectAfterMs?n.reconnectAfterMs(e):[10,50,100,150,200,250,500,1e3,2e3][e-1]||5e3,this.logger=n.logger||null,this.longpollerTimeout=n.longpollerTimeout||2e4,this.params=e(n.params||{}),this.endPoint=`${t}/${y}`,this.vsn=n.vsn||"2.0.0",this.heartbeatTimer=null,this.pendingHeartbeatRef=null,this.reconnectTimer=new E((()=>{this.teardown((()=>this.connect()))}),this.reconnectAfterMs)}getLongPollTransport(){return A}replaceTransport(e){this.connectClock++,this.closeWasClean=!0,this.reconnectTimer.reset(),this.sendBuffer=[],this.conn&&(this.conn.close(),this.conn=null),this.transport=e}protocol(){return location.protocol.match(/^https/)?"wss":"ws"}endPointURL(){let e=C.appendParams(C.appendParams(this.endPoint,this.params()),{vsn:this.vsn});return"/"!==e.charAt(0)?e:"/"===e.charAt(1)?`${this.protocol()}:${e}`:`${this.protocol()}://${location.host}${e}`}disconnect(e,t,i){this.connectClock++,this.closeWasClean=!0,this.reconnectTimer.reset(),this.teardown(e,t,i)}connect(t){t&&(console&&console.log("passing params to connect is deprecated. Instead pass :params to the Socket constructor"),this.params=e(t)),this.conn||(this.connectClock++,this.closeWasClean=!1,this.conn=new this.transport(this.endPointURL()),this.conn.binaryType=this.binaryType,this.conn.timeout=this.longpollerTimeout,this.conn.onopen=()=>this.onConnOpen(),this.conn.onerror=e=>this.onConnError(e),this.conn.onmessage=e=>this.onConnMessage(e),this.conn.onclose=e=>this.onConnClose(e))}log(e,t,i){this.logger(e,t,i)}hasLogger(){return null!==this.logger}onOpen(e){let t=this.makeRef();return this.stateChangeCallbacks.open.push([t,e]),t}onClose(e){let t=this.makeRef();return this.stateChangeCallbacks.close.push([t,e]),t}onError(e){let t=this.makeRef();return this.stateChangeCallbacks.error.push([t,e]),t}onMessage(e){let t=this.makeRef();return this.stateChangeCallbacks.message.push([t,e]),t}ping(e){if(!this.isConnected())return!1;let t=this.makeRef(),i=Date.now();this.push({topic:"phoenix",event:"heartbeat",payload:{},ref:t});let s=this.onMessage((n=>{n.ref===t&&(this.off([s]),e(Date.now()-i))}));return!0}onConnOpen(){this.hasLogger()&&this.log("transport",`connected to ${this.endPointURL()}`),this.closeWasClean=!1,this.establishedConnections++,this.flushSendBuffer(),this.reconnectTimer.reset(),this.resetHeartbeat(),this.stateChangeCallbacks.open.forEach((([,e])=>e()))}heartbeatTimeout(){this.pendingHeartbeatRef&&(this.pendingHeartbeatRef=null,this.hasLogger()&&this.log("transport","heartbeat timeout. Attempting to re-establish connection"),this.abnormalClose("heartbeat timeout"))}resetHeartbeat(){this.conn&&this.conn.skipHeartbeat||(this.pendingHeartbeatRef=null,clearTimeout(this.heartbeatTimer),setTimeout((()=>this.sendHeartbeat()),this.heartbeatIntervalMs))}teardown(e,t,i){if(!this.conn)return e&&e();this.waitForBufferDone((()=>{this.conn&&(t?this.conn.close(t,i||""):this.conn.close()),this.waitForSocketClosed((()=>{this.conn&&(this.conn.onclose=function(){},this.conn=null),e&&e()}))}))}waitForBufferDone(e,t=1){5!==t&&this.conn&&this.conn.bufferedAmount?setTimeout((()=>{this.waitForBufferDone(e,t+1)}),150*t):e()}waitForSocketClosed(e,t=1){5!==t&&this.conn&&this.conn.readyState!==a?setTimeout((()=>{this.waitForSocketClosed(e,t+1)}),150*t):e()}onConnClose(e){let t=e&&e.code;this.hasLogger()&&this.log("transport","close",e),this.triggerChanError(),clearTimeout(this.heartbeatTimer),this.closeWasClean||1e3===t||this.reconnectTimer.scheduleTimeout(),this.stateChangeCallbacks.close.forEach((([,t])=>t(e)))}onConnError(e){this.hasLogger()&&this.log("transport",e);let t=this.transport,i=this.establishedConnections;this.stateChangeCallbacks.error.forEach((([,s])=>{s(e,t,i)})),(t===this.transport||i>0)&&this.triggerChanError()}triggerChanError(){this.channels.forEach((e=>{e.isErrored()||e.isLeaving()||e.isClosed()||e.trigger(f)}))}connectionState(){switch(this.conn&&this.conn.readyState){case n:return"connecting";case r:return"open";case o:return"closing";default:return"closed"}}isConnected(){return"open"===this.connectionState()}remove(e){this.off(e.stateChangeRefs),this.channels=this.channels.filter((t=>t.joinRef()!==e.joinRef()))}off(e){for(let t in this.stateChangeCallbacks)this.stateChangeCallbacks[t]=this.stateChangeCallbacks[t].filter((([t])=>-1===e.indexOf(t)))}channel(t,i={}){let s=new class{constructor(t,i,s){this.state=l,this.topic=t,this.params=e(i||{}),this.socket=s,this.bindings=[],this.bindingRef=0,this.timeout=this.socket.timeout,this.joinedOnce=!1,this.joinPush=new w(this,g,this.params,this.timeout),this.pushBuffer=[],this.stateChangeRefs=[],this.rejoinTimer=new E((()=>{this.socket.isConnected()&&this.rejoin()}),this.socket.rejoinAfterMs),this.stateChangeRefs.push(this.socket.onError((()=>this.rejoinTimer.reset()))),this.stateChangeRefs.push(this.socket.onOpen((()=>{this.rejoinTimer.reset(),this.isErrored()&&this.rejoin()}))),this.joinPush.receive("ok",(()=>{this.state=d,this.rejoinTimer.reset(),this.pushBuffer.forEach((e=>e.send())),this.pushBuffer=[]})),this.joinPush.receive("error",(()=>{this.state=h,this.socket.isConnected()&&this.rejoinTimer.scheduleTimeout()})),this.onClose((()=>{this.rejoinTimer.reset(),this.socket.hasLogger()&&this.socket.log("channel",`close ${this.topic} ${this.joinRef()}`),this.state=l,this.socket.remove(this)})),this.onError((e=>{this.socket.hasLogger()&&this.socket.log("channel",`error ${this.topic}`,e),this.isJoining()&&this.joinPush.reset(),this.state=h,this.socket.isConnected()&&this.rejoinTimer.scheduleTimeout()})),this.joinPush.receive("timeout",(()=>{this.socket.hasLogger()&&this.socket.log("channel",`timeout ${this.topic} (${this.joinRef()})`,this.joinPush.timeout),new w(this,v,e({}),this.timeout).send(),this.state=h,this.joinPush.reset(),this.socket.isConnected()&&this.rejoinTimer.scheduleTimeout()})),this.on(m,((e,t)=>{this.trigger(this.replyEventName(t),e)}))}join(e=this.timeout){if(this.joinedOnce)throw new Error("tried to join multiple times. 'join' can only be called a single time per channel instance");return this.timeout=e,this.joinedOnce=!0,this.rejoin(),this.joinPush}onClose(e){this.on(p,e)}onError(e){return this.on(f,(t=>e(t)))}on(e,t){let i=this.bindingRef++;return this.bindings.push({event:e,ref:i,callback:t}),i}off(e,t){this.bindings=this.bindings.filter((i=>!(i.event===e&&(void 0===t||t===i.ref))))}canPush(){return this.socket.isConnected()&&this.isJoined()}push(e,t,i=this.timeout){if(t=t||{},!this.joinedOnce)throw new Error(`tried to push '${e}' to '${this.topic}' before joining. Use channel.join() before pushing events`);let s=new w(this,e,(function(){return t}),i);return this.canPush()?s.send():(s.startTimeout(),this.pushBuffer.push(s)),s}leave(t=this.timeout){this.rejoinTimer.reset(),this.joinPush.cancelTimeout(),this.state=u;let i=()=>{this.socket.hasLogger()&&this.socket.log("channel",`leave ${this.topic}`),this.trigger(p,"leave")},s=new w(this,v,e({}),t);return s.receive("ok",(()=>i())).receive("timeout",(()=>i())),s.send(),this.canPush()||s.trigger("ok",{}),s}onMessage(e,t,i){return t}isMember(e,t,i,s){return!(this.topic!==e||s&&s!==this.joinRef()&&(this.socket.hasLogger()&&this.socket.log("channel","dropping outdated message",{topic:e,event:t,payload:i,joinRef:s}),1))}joinRef(){return this.joinPush.ref}rejoin(e=this.timeout){this.isLeaving()||(this.socket.leaveOpenTopic(this.topic),this.state=c,this.joinPush.resend(e))}trigger(e,t,i,s){let n=this.onMessage(e,t,i,s);if(t&&!n)throw new Error("channel onMessage callbacks must return the payload, modified or unmodified");let r=this.bindings.filter((t=>t.event===e));for(let e=0;e<r.length;e++)r[e].callback(n,i,s||this.joinRef())}replyEventName(e){return`chan_reply_${e}`}isClosed(){return this.state===l}isErrored(){return this.state===h}isJoined(){return this.state===d}isJoining(){return this.state===c}isLeaving(){return this.state===u}}(t,i,this);return this.channels.push(s),s}push(e){if(this.hasLogger()){let{topic:t,event:i,payload:s,ref:n,join_ref:r}=e;this.log("push",`${t} ${i} (${r}, ${n})`,s)}this.isConnected()?this.encode(e,(e=>this.conn.send(e))):this.sendBuffer.push((()=>this.encode(e,(e=>this.conn.send(e)))))}makeRef(){let e=this.ref+1;return e===this.ref?this.ref=0:this.ref=e,this.ref.toString()}sendHeartbeat(){this.pendingHeartbeatRef&&!this.isConnected()||(this.pendingHeartbeatRef=this.makeRef(),this.push({topic:"phoenix",event:"heartbeat",payload:{},ref:this.pendingHeartbeatRef}),this.heartbeatTimer=setTimeout((()=>this.heartbeatTimeout()),this.heartbeatIntervalMs))}abnormalClose(e){this.closeWasClean=!1,this.isConnected()&&this.conn.close(1e3,e)}flushSendBuffer(){this.isConnected()&&this.sendBuffer.length>0&&(this.sendBuffer.forEach((e=>e())),this.sendBuffer=[])}onConnMessage(e){this.decode(e.data,(e=>{let{topic:t,event:i,payload:s,ref:n,join_ref:r}=e;n&&n===this.pendingHeartbeatRef&&(clearTimeout(this.heartbeatTimer),this.pendingHeartbeatRef=null,setTimeout((()=>this.sendHeartbeat()),this.heartbeatIntervalMs)),this.hasLogger()&&this.log("receive",`${s.status||""} ${t} ${i} ${n&&"("+n+")"||""}`,s);for(let e=0;e<this.channels.length;e++){const o=this.channels[e];o.isMember(t,i,s,r)&&o.trigger(i,s,n,r)}for(let t=0;t<this.stateChangeCallbacks.message.length;t++){let[,i]=this.stateChangeCallbacks.message[t];i(e)}}))}leaveOpenTopic(e){let t=this.channels.find((t=>t.topic===e&&(t.isJoined()||t.isJoining())));t&&(this.hasLogger()&&this.log("transport",`leaving duplicate topic "${e}"`),t.leave())}}(t,b);return k.endPoint=t,k}},{params:{_csrf_token:ht},metadata:{click:(e,t)=>({detail:e.detail})}});lt.config({barColors:{0:"#29d"},shadowColor:"rgba(0, 0, 0, .3)"}),window.addEventListener("phx:page-loading-start",(e=>lt.show())),window.addEventListener("phx:page-loading-stop",(e=>lt.hide())),dt.connect(),"production"!==process.env.NODE_ENV&&dt.enableDebug(),window.liveSocket=dt}));
+!function(e){"function"==typeof define&&define.amd?define(e):e()}((function(){"use strict";!function(){const e={NODE_ENV:"production"};try{if(process)return process.env=Object.assign({},process.env),void Object.assign(process.env,e)}catch(e){}globalThis.process={env:e}}();var e=e=>{if("function"==typeof e)return e;return function(){return e}},t="undefined"!=typeof self?self:null,i="undefined"!=typeof window?window:null,s=t||i||s,n=0,r=1,o=2,a=3,l="closed",h="errored",d="joined",c="joining",u="leaving",p="phx_close",f="phx_error",g="phx_join",m="phx_reply",v="phx_leave",b="longpoll",y="websocket",k=4,w=class{constructor(e,t,i,s){this.channel=e,this.event=t,this.payload=i||function(){return{}},this.receivedResp=null,this.timeout=s,this.timeoutTimer=null,this.recHooks=[],this.sent=!1}resend(e){this.timeout=e,this.reset(),this.send()}send(){this.hasReceived("timeout")||(this.startTimeout(),this.sent=!0,this.channel.socket.push({topic:this.channel.topic,event:this.event,payload:this.payload(),ref:this.ref,join_ref:this.channel.joinRef()}))}receive(e,t){return this.hasReceived(e)&&t(this.receivedResp.response),this.recHooks.push({status:e,callback:t}),this}reset(){this.cancelRefEvent(),this.ref=null,this.refEvent=null,this.receivedResp=null,this.sent=!1}matchReceive({status:e,response:t,_ref:i}){this.recHooks.filter((t=>t.status===e)).forEach((e=>e.callback(t)))}cancelRefEvent(){this.refEvent&&this.channel.off(this.refEvent)}cancelTimeout(){clearTimeout(this.timeoutTimer),this.timeoutTimer=null}startTimeout(){this.timeoutTimer&&this.cancelTimeout(),this.ref=this.channel.socket.makeRef(),this.refEvent=this.channel.replyEventName(this.ref),this.channel.on(this.refEvent,(e=>{this.cancelRefEvent(),this.cancelTimeout(),this.receivedResp=e,this.matchReceive(e)})),this.timeoutTimer=setTimeout((()=>{this.trigger("timeout",{})}),this.timeout)}hasReceived(e){return this.receivedResp&&this.receivedResp.status===e}trigger(e,t){this.channel.trigger(this.refEvent,{status:e,response:t})}},E=class{constructor(e,t){this.callback=e,this.timerCalc=t,this.timer=null,this.tries=0}reset(){this.tries=0,clearTimeout(this.timer)}scheduleTimeout(){clearTimeout(this.timer),this.timer=setTimeout((()=>{this.tries=this.tries+1,this.callback()}),this.timerCalc(this.tries+1))}},C=class{static request(e,t,i,n,r,o,a){if(s.XDomainRequest){let i=new s.XDomainRequest;return this.xdomainRequest(i,e,t,n,r,o,a)}{let l=new s.XMLHttpRequest;return this.xhrRequest(l,e,t,i,n,r,o,a)}}static xdomainRequest(e,t,i,s,n,r,o){return e.timeout=n,e.open(t,i),e.onload=()=>{let t=this.parseJSON(e.responseText);o&&o(t)},r&&(e.ontimeout=r),e.onprogress=()=>{},e.send(s),e}static xhrRequest(e,t,i,s,n,r,o,a){return e.open(t,i,!0),e.timeout=r,e.setRequestHeader("Content-Type",s),e.onerror=()=>a&&a(null),e.onreadystatechange=()=>{if(e.readyState===k&&a){let t=this.parseJSON(e.responseText);a(t)}},o&&(e.ontimeout=o),e.send(n),e}static parseJSON(e){if(!e||""===e)return null;try{return JSON.parse(e)}catch(t){return console&&console.log("failed to parse JSON response",e),null}}static serialize(e,t){let i=[];for(var s in e){if(!Object.prototype.hasOwnProperty.call(e,s))continue;let n=t?`${t}[${s}]`:s,r=e[s];"object"==typeof r?i.push(this.serialize(r,n)):i.push(encodeURIComponent(n)+"="+encodeURIComponent(r))}return i.join("&")}static appendParams(e,t){if(0===Object.keys(t).length)return e;let i=e.match(/\?/)?"&":"?";return`${e}${i}${this.serialize(t)}`}},A=class{constructor(e){this.endPoint=null,this.token=null,this.skipHeartbeat=!0,this.reqs=new Set,this.onopen=function(){},this.onerror=function(){},this.onmessage=function(){},this.onclose=function(){},this.pollEndpoint=this.normalizeEndpoint(e),this.readyState=n,this.poll()}normalizeEndpoint(e){return e.replace("ws://","http://").replace("wss://","https://").replace(new RegExp("(.*)/"+y),"$1/"+b)}endpointURL(){return C.appendParams(this.pollEndpoint,{token:this.token})}closeAndRetry(e,t,i){this.close(e,t,i),this.readyState=n}ontimeout(){this.onerror("timeout"),this.closeAndRetry(1005,"timeout",!1)}isActive(){return this.readyState===r||this.readyState===n}poll(){this.ajax("GET",null,(()=>this.ontimeout()),(e=>{if(e){var{status:t,token:i,messages:s}=e;this.token=i}else t=0;switch(t){case 200:s.forEach((e=>{setTimeout((()=>this.onmessage({data:e})),0)})),this.poll();break;case 204:this.poll();break;case 410:this.readyState=r,this.onopen({}),this.poll();break;case 403:this.onerror(403),this.close(1008,"forbidden",!1);break;case 0:case 500:this.onerror(500),this.closeAndRetry(1011,"internal server error",500);break;default:throw new Error(`unhandled poll status ${t}`)}}))}send(e){this.ajax("POST",e,(()=>this.onerror("timeout")),(e=>{e&&200===e.status||(this.onerror(e&&e.status),this.closeAndRetry(1011,"internal server error",!1))}))}close(e,t,i){for(let e of this.reqs)e.abort();this.readyState=a;let s=Object.assign({code:1e3,reason:void 0,wasClean:!0},{code:e,reason:t,wasClean:i});"undefined"!=typeof CloseEvent?this.onclose(new CloseEvent("close",s)):this.onclose(s)}ajax(e,t,i,s){let n;n=C.request(e,this.endpointURL(),"application/json",t,this.timeout,(()=>{this.reqs.delete(n),i()}),(e=>{this.reqs.delete(n),this.isActive()&&s(e)})),this.reqs.add(n)}},S={HEADER_LENGTH:1,META_LENGTH:4,KINDS:{push:0,reply:1,broadcast:2},encode(e,t){if(e.payload.constructor===ArrayBuffer)return t(this.binaryEncode(e));{let i=[e.join_ref,e.ref,e.topic,e.event,e.payload];return t(JSON.stringify(i))}},decode(e,t){if(e.constructor===ArrayBuffer)return t(this.binaryDecode(e));{let[i,s,n,r,o]=JSON.parse(e);return t({join_ref:i,ref:s,topic:n,event:r,payload:o})}},binaryEncode(e){let{join_ref:t,ref:i,event:s,topic:n,payload:r}=e,o=this.META_LENGTH+t.length+i.length+n.length+s.length,a=new ArrayBuffer(this.HEADER_LENGTH+o),l=new DataView(a),h=0;l.setUint8(h++,this.KINDS.push),l.setUint8(h++,t.length),l.setUint8(h++,i.length),l.setUint8(h++,n.length),l.setUint8(h++,s.length),Array.from(t,(e=>l.setUint8(h++,e.charCodeAt(0)))),Array.from(i,(e=>l.setUint8(h++,e.charCodeAt(0)))),Array.from(n,(e=>l.setUint8(h++,e.charCodeAt(0)))),Array.from(s,(e=>l.setUint8(h++,e.charCodeAt(0))));var d=new Uint8Array(a.byteLength+r.byteLength);return d.set(new Uint8Array(a),0),d.set(new Uint8Array(r),a.byteLength),d.buffer},binaryDecode(e){let t=new DataView(e),i=t.getUint8(0),s=new TextDecoder;switch(i){case this.KINDS.push:return this.decodePush(e,t,s);case this.KINDS.reply:return this.decodeReply(e,t,s);case this.KINDS.broadcast:return this.decodeBroadcast(e,t,s)}},decodePush(e,t,i){let s=t.getUint8(1),n=t.getUint8(2),r=t.getUint8(3),o=this.HEADER_LENGTH+this.META_LENGTH-1,a=i.decode(e.slice(o,o+s));o+=s;let l=i.decode(e.slice(o,o+n));o+=n;let h=i.decode(e.slice(o,o+r));return o+=r,{join_ref:a,ref:null,topic:l,event:h,payload:e.slice(o,e.byteLength)}},decodeReply(e,t,i){let s=t.getUint8(1),n=t.getUint8(2),r=t.getUint8(3),o=t.getUint8(4),a=this.HEADER_LENGTH+this.META_LENGTH,l=i.decode(e.slice(a,a+s));a+=s;let h=i.decode(e.slice(a,a+n));a+=n;let d=i.decode(e.slice(a,a+r));a+=r;let c=i.decode(e.slice(a,a+o));a+=o;let u=e.slice(a,e.byteLength);return{join_ref:l,ref:h,topic:d,event:m,payload:{status:c,response:u}}},decodeBroadcast(e,t,i){let s=t.getUint8(1),n=t.getUint8(2),r=this.HEADER_LENGTH+2,o=i.decode(e.slice(r,r+s));r+=s;let a=i.decode(e.slice(r,r+n));return r+=n,{join_ref:null,ref:null,topic:o,event:a,payload:e.slice(r,e.byteLength)}}},T="consecutive-reloads",x=["phx-click-loading","phx-change-loading","phx-submit-loading","phx-keydown-loading","phx-keyup-loading","phx-blur-loading","phx-focus-loading"],L="data-phx-component",P="data-phx-link",R="data-phx-ref",_="data-phx-ref-src",D="track-uploads",I="data-phx-upload-ref",O="data-phx-preflighted-refs",N="drop-target",$="data-phx-active-refs",M="phx:live-file:updated",j="data-phx-skip",U="data-phx-prune",H="page-loading",F="phx-connected",B="phx-loading",J="phx-no-feedback",q="phx-error",V="data-phx-parent-id",W="data-phx-main",z="data-phx-root-id",G="feedback-for",K="phx-has-focused",X=["text","textarea","number","email","password","search","tel","url","date","time","datetime-local","color","range"],Y=["checkbox","radio"],Q="phx-has-submitted",Z="data-phx-session",ee=`[${Z}]`,te="data-phx-sticky",ie="data-phx-static",se="data-phx-readonly",ne="data-phx-disabled",re="disable-with",oe="data-phx-disable-with-restore",ae="hook",le="update",he="auto-recover",de="phx:live-socket:debug",ce="phx:live-socket:profiling",ue="phx:live-socket:latency-sim",pe="debounce-trigger",fe="throttled",ge="debounce-prev-key",me={debounce:300,throttle:300},ve="d",be="s",ye="e",ke="r",we="t",Ee=(e,t)=>console.error&&console.error(e,t),Ce=e=>{let t=typeof e;return"number"===t||"string"===t&&/^(0|[1-9]\d*)$/.test(e)};var Ae,Se=e=>"function"==typeof e?e:function(){return e},Te=e=>JSON.parse(JSON.stringify(e)),xe=(e,t,i)=>{do{if(e.matches(`[${t}]`))return e;e=e.parentElement||e.parentNode}while(null!==e&&1===e.nodeType&&!(i&&i.isSameNode(e)||e.matches(ee)));return null},Le=e=>null!==e&&"object"==typeof e&&!(e instanceof Array),Pe=e=>{for(let t in e)return!1;return!0},Re=(e,t)=>e&&t(e),_e=function(e,t,i,s){e.forEach((e=>{let t=new class{constructor(e,t,i){this.liveSocket=i,this.entry=e,this.offset=0,this.chunkSize=t,this.chunkTimer=null,this.uploadChannel=i.channel(`lvu:${e.ref}`,{token:e.metadata()})}error(e){clearTimeout(this.chunkTimer),this.uploadChannel.leave(),this.entry.error(e)}upload(){this.uploadChannel.onError((e=>this.error(e))),this.uploadChannel.join().receive("ok",(e=>this.readNextChunk())).receive("error",(e=>this.error(e)))}isDone(){return this.offset>=this.entry.file.size}readNextChunk(){let e=new window.FileReader,t=this.entry.file.slice(this.offset,this.chunkSize+this.offset);e.onload=e=>{if(null!==e.target.error)return Ee("Read error: "+e.target.error);this.offset+=e.target.result.byteLength,this.pushChunk(e.target.result)},e.readAsArrayBuffer(t)}pushChunk(e){this.uploadChannel.isJoined()&&this.uploadChannel.push("chunk",e).receive("ok",(()=>{this.entry.progress(this.offset/this.entry.file.size*100),this.isDone()||(this.chunkTimer=setTimeout((()=>this.readNextChunk()),this.liveSocket.getLatencySim()||0))}))}}(e,i.config.chunk_size,s);t.upload()}))},De={canPushState:()=>void 0!==history.pushState,dropLocal(e,t,i){return e.removeItem(this.localKey(t,i))},updateLocal(e,t,i,s,n){let r=this.getLocal(e,t,i),o=this.localKey(t,i),a=null===r?s:n(r);return e.setItem(o,JSON.stringify(a)),a},getLocal(e,t,i){return JSON.parse(e.getItem(this.localKey(t,i)))},updateCurrentState(e){this.canPushState()&&history.replaceState(e(history.state||{}),"",window.location.href)},pushState(e,t,i){if(this.canPushState()){if(i!==window.location.href){if("redirect"==t.type&&t.scroll){let e=history.state||{};e.scroll=t.scroll,history.replaceState(e,"",window.location.href)}delete t.scroll,history[e+"State"](t,"",i||null);let s=this.getHashTargetEl(window.location.hash);s?s.scrollIntoView():"redirect"===t.type&&window.scroll(0,0)}}else this.redirect(i)},setCookie(e,t){document.cookie=`${e}=${t}`},getCookie:e=>document.cookie.replace(new RegExp(`(?:(?:^|.*;s*)${e}s*=s*([^;]*).*$)|^.*$`),"$1"),redirect(e,t){t&&De.setCookie("__phoenix_flash__",t+"; max-age=60000; path=/"),window.location=e},localKey:(e,t)=>`${e}-${t}`,getHashTargetEl(e){let t=e.toString().substring(1);if(""!==t)return document.getElementById(t)||document.querySelector(`a[name="${t}"]`)}},Ie=De,Oe={byId:e=>document.getElementById(e)||Ee(`no id found for ${e}`),removeClass(e,t){e.classList.remove(t),0===e.classList.length&&e.removeAttribute("class")},all(e,t,i){if(!e)return[];let s=Array.from(e.querySelectorAll(t));return i?s.forEach(i):s},childNodeLength(e){let t=document.createElement("template");return t.innerHTML=e,t.content.childElementCount},isUploadInput:e=>"file"===e.type&&null!==e.getAttribute(I),findUploadInputs(e){return this.all(e,`input[type="file"][${I}]`)},findComponentNodeList(e,t){return this.filterWithinSameLiveView(this.all(e,`[${L}="${t}"]`),e)},isPhxDestroyed:e=>!(!e.id||!Oe.private(e,"destroyed")),markPhxChildDestroyed(e){this.isPhxChild(e)&&e.setAttribute(Z,""),this.putPrivate(e,"destroyed",!0)},findPhxChildrenInFragment(e,t){let i=document.createElement("template");return i.innerHTML=e,this.findPhxChildren(i.content,t)},isIgnored:(e,t)=>"ignore"===(e.getAttribute(t)||e.getAttribute("data-phx-update")),isPhxUpdate:(e,t,i)=>e.getAttribute&&i.indexOf(e.getAttribute(t))>=0,findPhxSticky(e){return this.all(e,`[${te}]`)},findPhxChildren(e,t){return this.all(e,`${ee}[${V}="${t}"]`)},findParentCIDs(e,t){let i=new Set(t);return t.reduce(((t,i)=>{let s=`[${L}="${i}"] [${L}]`;return this.filterWithinSameLiveView(this.all(e,s),e).map((e=>parseInt(e.getAttribute(L)))).forEach((e=>t.delete(e))),t}),i)},filterWithinSameLiveView(e,t){return t.querySelector(ee)?e.filter((e=>this.withinSameLiveView(e,t))):e},withinSameLiveView(e,t){for(;e=e.parentNode;){if(e.isSameNode(t))return!0;if(null!==e.getAttribute(Z))return!1}},private:(e,t)=>e.phxPrivate&&e.phxPrivate[t],deletePrivate(e,t){e.phxPrivate&&delete e.phxPrivate[t]},putPrivate(e,t,i){e.phxPrivate||(e.phxPrivate={}),e.phxPrivate[t]=i},updatePrivate(e,t,i,s){let n=this.private(e,t);void 0===n?this.putPrivate(e,t,s(i)):this.putPrivate(e,t,s(n))},copyPrivates(e,t){t.phxPrivate&&(e.phxPrivate=t.phxPrivate)},putTitle(e){let t=document.querySelector("title"),{prefix:i,suffix:s}=t.dataset;document.title=`${i||""}${e}${s||""}`},debounce(e,t,i,s,n,r,o,a){let l=e.getAttribute(i),h=e.getAttribute(n);""===l&&(l=s),""===h&&(h=r);let d=l||h;switch(d){case null:return a();case"blur":return void(this.once(e,"debounce-blur")&&e.addEventListener("blur",(()=>a())));default:let i=parseInt(d),s=()=>h?this.deletePrivate(e,fe):a(),n=this.incCycle(e,pe,s);if(isNaN(i))return Ee(`invalid throttle/debounce value: ${d}`);if(h){let s=!1;if("keydown"===t.type){let i=this.private(e,ge);this.putPrivate(e,ge,t.key),s=i!==t.key}if(!s&&this.private(e,fe))return!1;a(),this.putPrivate(e,fe,!0),setTimeout((()=>{o()&&this.triggerCycle(e,pe)}),i)}else setTimeout((()=>{o()&&this.triggerCycle(e,pe,n)}),i);let r=e.form;r&&this.once(r,"bind-debounce")&&r.addEventListener("submit",(()=>{Array.from(new FormData(r).entries(),(([e])=>{let t=r.querySelector(`[name="${e}"]`);this.incCycle(t,pe),this.deletePrivate(t,fe)}))})),this.once(e,"bind-debounce")&&e.addEventListener("blur",(()=>this.triggerCycle(e,pe)))}},triggerCycle(e,t,i){let[s,n]=this.private(e,t);i||(i=s),i===s&&(this.incCycle(e,t),n())},once(e,t){return!0!==this.private(e,t)&&(this.putPrivate(e,t,!0),!0)},incCycle(e,t,i=function(){}){let[s]=this.private(e,t)||[0,i];return s++,this.putPrivate(e,t,[s,i]),s},discardError(e,t,i){let s=t.getAttribute&&t.getAttribute(i),n=s&&e.querySelector(`[id="${s}"], [name="${s}"]`);n&&(this.private(n,K)||this.private(n.form,Q)||t.classList.add(J))},showError(e,t){(e.id||e.name)&&this.all(e.form,`[${t}="${e.id}"], [${t}="${e.name}"]`,(e=>{this.removeClass(e,J)}))},isPhxChild:e=>e.getAttribute&&e.getAttribute(V),isPhxSticky:e=>e.getAttribute&&null!==e.getAttribute(te),firstPhxChild(e){return this.isPhxChild(e)?e:this.all(e,`[${V}]`)[0]},dispatchEvent(e,t,i={}){let s={bubbles:void 0===i.bubbles||!!i.bubbles,cancelable:!0,detail:i.detail||{}},n="click"===t?new MouseEvent("click",s):new CustomEvent(t,s);e.dispatchEvent(n)},cloneNode(e,t){if(void 0===t)return e.cloneNode(!0);{let i=e.cloneNode(!1);return i.innerHTML=t,i}},mergeAttrs(e,t,i={}){let s=i.exclude||[],n=i.isIgnored,r=t.attributes;for(let i=r.length-1;i>=0;i--){let n=r[i].name;s.indexOf(n)<0&&e.setAttribute(n,t.getAttribute(n))}let o=e.attributes;for(let i=o.length-1;i>=0;i--){let s=o[i].name;n?s.startsWith("data-")&&!t.hasAttribute(s)&&e.removeAttribute(s):t.hasAttribute(s)||e.removeAttribute(s)}},mergeFocusedInput(e,t){e instanceof HTMLSelectElement||Oe.mergeAttrs(e,t,{exclude:["value"]}),t.readOnly?e.setAttribute("readonly",!0):e.removeAttribute("readonly")},hasSelectionRange:e=>e.setSelectionRange&&("text"===e.type||"textarea"===e.type),restoreFocus(e,t,i){if(!Oe.isTextualInput(e))return;let s=e.matches(":focus");e.readOnly&&e.blur(),s||e.focus(),this.hasSelectionRange(e)&&e.setSelectionRange(t,i)},isFormInput:e=>/^(?:input|select|textarea)$/i.test(e.tagName)&&"button"!==e.type,syncAttrsToProps(e){e instanceof HTMLInputElement&&Y.indexOf(e.type.toLocaleLowerCase())>=0&&(e.checked=null!==e.getAttribute("checked"))},isTextualInput:e=>X.indexOf(e.type)>=0,isNowTriggerFormExternal:(e,t)=>e.getAttribute&&null!==e.getAttribute(t),syncPendingRef(e,t,i){let s=e.getAttribute(R);if(null===s)return!0;let n=e.getAttribute(_);return Oe.isFormInput(e)||null!==e.getAttribute(i)?(Oe.isUploadInput(e)&&Oe.mergeAttrs(e,t,{isIgnored:!0}),Oe.putPrivate(e,R,t),!1):(x.forEach((i=>{e.classList.contains(i)&&t.classList.add(i)})),t.setAttribute(R,s),t.setAttribute(_,n),!0)},cleanChildNodes(e,t){if(Oe.isPhxUpdate(e,t,["append","prepend"])){let t=[];e.childNodes.forEach((e=>{if(!e.id){e.nodeType===Node.TEXT_NODE&&""===e.nodeValue.trim()||Ee(`only HTML element tags with an id are allowed inside containers with phx-update.\n\nremoving illegal node: "${(e.outerHTML||e.nodeValue).trim()}"\n\n`),t.push(e)}})),t.forEach((e=>e.remove()))}},replaceRootContainer(e,t,i){let s=new Set(["id",Z,ie,W,z]);if(e.tagName.toLowerCase()===t.toLowerCase())return Array.from(e.attributes).filter((e=>!s.has(e.name.toLowerCase()))).forEach((t=>e.removeAttribute(t.name))),Object.keys(i).filter((e=>!s.has(e.toLowerCase()))).forEach((t=>e.setAttribute(t,i[t]))),e;{let n=document.createElement(t);return Object.keys(i).forEach((e=>n.setAttribute(e,i[e]))),s.forEach((t=>n.setAttribute(t,e.getAttribute(t)))),n.innerHTML=e.innerHTML,e.replaceWith(n),n}},getSticky(e,t,i){let s=(Oe.private(e,"sticky")||[]).find((([e])=>t===e));if(s){let[e,t,i]=s;return i}return"function"==typeof i?i():i},deleteSticky(e,t){this.updatePrivate(e,"sticky",[],(e=>e.filter((([e,i])=>e!==t))))},putSticky(e,t,i){let s=i(e);this.updatePrivate(e,"sticky",[],(e=>{let n=e.findIndex((([e])=>t===e));return n>=0?e[n]=[t,i,s]:e.push([t,i,s]),e}))},applyStickyOperations(e){let t=Oe.private(e,"sticky");t&&t.forEach((([t,i,s])=>this.putSticky(e,t,i)))}},Ne=Oe,$e=class{static isActive(e,t){let i=void 0===t._phxRef,s=e.getAttribute($).split(",").indexOf(je.genFileRef(t))>=0;return t.size>0&&(i||s)}static isPreflighted(e,t){return e.getAttribute(O).split(",").indexOf(je.genFileRef(t))>=0&&this.isActive(e,t)}constructor(e,t,i){this.ref=je.genFileRef(t),this.fileEl=e,this.file=t,this.view=i,this.meta=null,this._isCancelled=!1,this._isDone=!1,this._progress=0,this._lastProgressSent=-1,this._onDone=function(){},this._onElUpdated=this.onElUpdated.bind(this),this.fileEl.addEventListener(M,this._onElUpdated)}metadata(){return this.meta}progress(e){this._progress=Math.floor(e),this._progress>this._lastProgressSent&&(this._progress>=100?(this._progress=100,this._lastProgressSent=100,this._isDone=!0,this.view.pushFileProgress(this.fileEl,this.ref,100,(()=>{je.untrackFile(this.fileEl,this.file),this._onDone()}))):(this._lastProgressSent=this._progress,this.view.pushFileProgress(this.fileEl,this.ref,this._progress)))}cancel(){this._isCancelled=!0,this._isDone=!0,this._onDone()}isDone(){return this._isDone}error(e="failed"){this.view.pushFileProgress(this.fileEl,this.ref,{error:e}),je.clearFiles(this.fileEl)}onDone(e){this._onDone=()=>{this.fileEl.removeEventListener(M,this._onElUpdated),e()}}onElUpdated(){-1===this.fileEl.getAttribute($).split(",").indexOf(this.ref)&&this.cancel()}toPreflightPayload(){return{last_modified:this.file.lastModified,name:this.file.name,size:this.file.size,type:this.file.type,ref:this.ref}}uploader(e){if(this.meta.uploader){let t=e[this.meta.uploader]||Ee(`no uploader configured for ${this.meta.uploader}`);return{name:this.meta.uploader,callback:t}}return{name:"channel",callback:_e}}zipPostFlight(e){this.meta=e.entries[this.ref],this.meta||Ee(`no preflight upload response returned with ref ${this.ref}`,{input:this.fileEl,response:e})}},Me=0,je=class{static genFileRef(e){let t=e._phxRef;return void 0!==t?t:(e._phxRef=(Me++).toString(),e._phxRef)}static getEntryDataURL(e,t,i){let s=this.activeFiles(e).find((e=>this.genFileRef(e)===t));i(URL.createObjectURL(s))}static hasUploadsInProgress(e){let t=0;return Ne.findUploadInputs(e).forEach((e=>{e.getAttribute(O)!==e.getAttribute("data-phx-done-refs")&&t++})),t>0}static serializeUploads(e){let t=this.activeFiles(e),i={};return t.forEach((t=>{let s={path:e.name},n=e.getAttribute(I);i[n]=i[n]||[],s.ref=this.genFileRef(t),s.name=t.name||s.ref,s.type=t.type,s.size=t.size,i[n].push(s)})),i}static clearFiles(e){e.value=null,e.removeAttribute(I),Ne.putPrivate(e,"files",[])}static untrackFile(e,t){Ne.putPrivate(e,"files",Ne.private(e,"files").filter((e=>!Object.is(e,t))))}static trackFiles(e,t){if(null!==e.getAttribute("multiple")){let i=t.filter((t=>!this.activeFiles(e).find((e=>Object.is(e,t)))));Ne.putPrivate(e,"files",this.activeFiles(e).concat(i)),e.value=null}else Ne.putPrivate(e,"files",t)}static activeFileInputs(e){let t=Ne.findUploadInputs(e);return Array.from(t).filter((e=>e.files&&this.activeFiles(e).length>0))}static activeFiles(e){return(Ne.private(e,"files")||[]).filter((t=>$e.isActive(e,t)))}static inputsAwaitingPreflight(e){let t=Ne.findUploadInputs(e);return Array.from(t).filter((e=>this.filesAwaitingPreflight(e).length>0))}static filesAwaitingPreflight(e){return this.activeFiles(e).filter((t=>!$e.isPreflighted(e,t)))}constructor(e,t,i){this.view=t,this.onComplete=i,this._entries=Array.from(je.filesAwaitingPreflight(e)||[]).map((i=>new $e(e,i,t))),this.numEntriesInProgress=this._entries.length}entries(){return this._entries}initAdapterUpload(e,t,i){this._entries=this._entries.map((t=>(t.zipPostFlight(e),t.onDone((()=>{this.numEntriesInProgress--,0===this.numEntriesInProgress&&this.onComplete()})),t)));let s=this._entries.reduce(((e,t)=>{let{name:s,callback:n}=t.uploader(i.uploaders);return e[s]=e[s]||{callback:n,entries:[]},e[s].entries.push(t),e}),{});for(let n in s){let{callback:r,entries:o}=s[n];r(o,t,e,i)}}},Ue={LiveFileUpload:{activeRefs(){return this.el.getAttribute($)},preflightedRefs(){return this.el.getAttribute(O)},mounted(){this.preflightedWas=this.preflightedRefs()},updated(){let e=this.preflightedRefs();this.preflightedWas!==e&&(this.preflightedWas=e,""===e&&this.__view.cancelSubmit(this.el.form)),""===this.activeRefs()&&(this.el.value=null),this.el.dispatchEvent(new CustomEvent(M))}},LiveImgPreview:{mounted(){this.ref=this.el.getAttribute("data-phx-entry-ref"),this.inputEl=document.getElementById(this.el.getAttribute(I)),je.getEntryDataURL(this.inputEl,this.ref,(e=>{this.url=e,this.el.src=e}))},destroyed(){URL.revokeObjectURL(this.url)}}};var He="undefined"==typeof document?void 0:document,Fe=!!He&&"content"in He.createElement("template"),Be=!!He&&He.createRange&&"createContextualFragment"in He.createRange();function Je(e){return e=e.trim(),Fe?function(e){var t=He.createElement("template");return t.innerHTML=e,t.content.childNodes[0]}(e):Be?function(e){return Ae||(Ae=He.createRange()).selectNode(He.body),Ae.createContextualFragment(e).childNodes[0]}(e):function(e){var t=He.createElement("body");return t.innerHTML=e,t.childNodes[0]}(e)}function qe(e,t){var i,s,n=e.nodeName,r=t.nodeName;return n===r||(i=n.charCodeAt(0),s=r.charCodeAt(0),i<=90&&s>=97?n===r.toUpperCase():s<=90&&i>=97&&r===n.toUpperCase())}function Ve(e,t,i){e[i]!==t[i]&&(e[i]=t[i],e[i]?e.setAttribute(i,""):e.removeAttribute(i))}var We={OPTION:function(e,t){var i=e.parentNode;if(i){var s=i.nodeName.toUpperCase();"OPTGROUP"===s&&(s=(i=i.parentNode)&&i.nodeName.toUpperCase()),"SELECT"!==s||i.hasAttribute("multiple")||(e.hasAttribute("selected")&&!t.selected&&(e.setAttribute("selected","selected"),e.removeAttribute("selected")),i.selectedIndex=-1)}Ve(e,t,"selected")},INPUT:function(e,t){Ve(e,t,"checked"),Ve(e,t,"disabled"),e.value!==t.value&&(e.value=t.value),t.hasAttribute("value")||e.removeAttribute("value")},TEXTAREA:function(e,t){var i=t.value;e.value!==i&&(e.value=i);var s=e.firstChild;if(s){var n=s.nodeValue;if(n==i||!i&&n==e.placeholder)return;s.nodeValue=i}},SELECT:function(e,t){if(!t.hasAttribute("multiple")){for(var i,s,n=-1,r=0,o=e.firstChild;o;)if("OPTGROUP"===(s=o.nodeName&&o.nodeName.toUpperCase()))o=(i=o).firstChild;else{if("OPTION"===s){if(o.hasAttribute("selected")){n=r;break}r++}!(o=o.nextSibling)&&i&&(o=i.nextSibling,i=null)}e.selectedIndex=n}}};function ze(){}function Ge(e){if(e)return e.getAttribute&&e.getAttribute("id")||e.id}var Ke,Xe,Ye=(Ke=function(e,t){var i,s,n,r,o=t.attributes;if(11!==t.nodeType&&11!==e.nodeType){for(var a=o.length-1;a>=0;a--)s=(i=o[a]).name,n=i.namespaceURI,r=i.value,n?(s=i.localName||s,e.getAttributeNS(n,s)!==r&&("xmlns"===i.prefix&&(s=i.name),e.setAttributeNS(n,s,r))):e.getAttribute(s)!==r&&e.setAttribute(s,r);for(var l=e.attributes,h=l.length-1;h>=0;h--)s=(i=l[h]).name,(n=i.namespaceURI)?(s=i.localName||s,t.hasAttributeNS(n,s)||e.removeAttributeNS(n,s)):t.hasAttribute(s)||e.removeAttribute(s)}},function(e,t,i){if(i||(i={}),"string"==typeof t)if("#document"===e.nodeName||"HTML"===e.nodeName||"BODY"===e.nodeName){var s=t;(t=He.createElement("html")).innerHTML=s}else t=Je(t);var n=i.getNodeKey||Ge,r=i.onBeforeNodeAdded||ze,o=i.onNodeAdded||ze,a=i.onBeforeElUpdated||ze,l=i.onElUpdated||ze,h=i.onBeforeNodeDiscarded||ze,d=i.onNodeDiscarded||ze,c=i.onBeforeElChildrenUpdated||ze,u=!0===i.childrenOnly,p=Object.create(null),f=[];function g(e){f.push(e)}function m(e,t){if(1===e.nodeType)for(var i=e.firstChild;i;){var s=void 0;t&&(s=n(i))?g(s):(d(i),i.firstChild&&m(i,t)),i=i.nextSibling}}function v(e,t,i){!1!==h(e)&&(t&&t.removeChild(e),d(e),m(e,i))}function b(e){o(e);for(var t=e.firstChild;t;){var i=t.nextSibling,s=n(t);if(s){var r=p[s];r&&qe(t,r)?(t.parentNode.replaceChild(r,t),y(r,t)):b(t)}else b(t);t=i}}function y(e,t,i){var s=n(t);if(s&&delete p[s],!i){if(!1===a(e,t))return;if(Ke(e,t),l(e),!1===c(e,t))return}"TEXTAREA"!==e.nodeName?function(e,t){var i,s,o,a,l,h=t.firstChild,d=e.firstChild;e:for(;h;){for(a=h.nextSibling,i=n(h);d;){if(o=d.nextSibling,h.isSameNode&&h.isSameNode(d)){h=a,d=o;continue e}s=n(d);var c=d.nodeType,u=void 0;if(c===h.nodeType&&(1===c?(i?i!==s&&((l=p[i])?o===l?u=!1:(e.insertBefore(l,d),s?g(s):v(d,e,!0),d=l):u=!1):s&&(u=!1),(u=!1!==u&&qe(d,h))&&y(d,h)):3!==c&&8!=c||(u=!0,d.nodeValue!==h.nodeValue&&(d.nodeValue=h.nodeValue))),u){h=a,d=o;continue e}s?g(s):v(d,e,!0),d=o}if(i&&(l=p[i])&&qe(l,h))e.appendChild(l),y(l,h);else{var f=r(h);!1!==f&&(f&&(h=f),h.actualize&&(h=h.actualize(e.ownerDocument||He)),e.appendChild(h),b(h))}h=a,d=o}!function(e,t,i){for(;t;){var s=t.nextSibling;(i=n(t))?g(i):v(t,e,!0),t=s}}(e,d,s);var m=We[e.nodeName];m&&m(e,t)}(e,t):We.TEXTAREA(e,t)}!function e(t){if(1===t.nodeType||11===t.nodeType)for(var i=t.firstChild;i;){var s=n(i);s&&(p[s]=i),e(i),i=i.nextSibling}}(e);var k,w,E=e,C=E.nodeType,A=t.nodeType;if(!u)if(1===C)1===A?qe(e,t)||(d(e),E=function(e,t){for(var i=e.firstChild;i;){var s=i.nextSibling;t.appendChild(i),i=s}return t}(e,(k=t.nodeName,(w=t.namespaceURI)&&"http://www.w3.org/1999/xhtml"!==w?He.createElementNS(w,k):He.createElement(k)))):E=t;else if(3===C||8===C){if(A===C)return E.nodeValue!==t.nodeValue&&(E.nodeValue=t.nodeValue),E;E=t}if(E===t)d(e);else{if(t.isSameNode&&t.isSameNode(E))return;if(y(E,t,u),f)for(var S=0,T=f.length;S<T;S++){var x=p[f[S]];x&&v(x,x.parentNode,!1)}}return!u&&E!==e&&e.parentNode&&(E.actualize&&(E=E.actualize(e.ownerDocument||He)),e.parentNode.replaceChild(E,e)),E}),Qe=class{static patchEl(e,t,i){Ye(e,t,{childrenOnly:!1,onBeforeElUpdated:(e,t)=>{if(i&&i.isSameNode(e)&&Ne.isFormInput(e))return Ne.mergeFocusedInput(e,t),!1}})}constructor(e,t,i,s,n){this.view=e,this.liveSocket=e.liveSocket,this.container=t,this.id=i,this.rootID=e.root.id,this.html=s,this.targetCID=n,this.cidPatch=Ce(this.targetCID),this.callbacks={beforeadded:[],beforeupdated:[],beforephxChildAdded:[],afteradded:[],afterupdated:[],afterdiscarded:[],afterphxChildAdded:[],aftertransitionsDiscarded:[]}}before(e,t){this.callbacks[`before${e}`].push(t)}after(e,t){this.callbacks[`after${e}`].push(t)}trackBefore(e,...t){this.callbacks[`before${e}`].forEach((e=>e(...t)))}trackAfter(e,...t){this.callbacks[`after${e}`].forEach((e=>e(...t)))}markPrunableContentForRemoval(){Ne.all(this.container,"[phx-update=append] > *, [phx-update=prepend] > *",(e=>{e.setAttribute(U,"")}))}perform(){let{view:e,liveSocket:t,container:i,html:s}=this,n=this.isCIDPatch()?this.targetCIDContainer(s):i;if(this.isCIDPatch()&&!n)return;let r=t.getActiveElement(),{selectionStart:o,selectionEnd:a}=r&&Ne.hasSelectionRange(r)?r:{},l=t.binding(le),h=t.binding(G),d=t.binding(re),c=t.binding("trigger-action"),u=t.binding("remove"),p=[],f=[],g=[],m=[],v=null,b=t.time("premorph container prep",(()=>this.buildDiffHTML(i,s,l,n)));return this.trackBefore("added",i),this.trackBefore("updated",i,i),t.time("morphdom",(()=>{Ye(n,b,{childrenOnly:null===n.getAttribute(L),getNodeKey:e=>Ne.isPhxDestroyed(e)?null:e.id,onBeforeNodeAdded:e=>(this.trackBefore("added",e),e),onNodeAdded:t=>{t instanceof HTMLImageElement&&t.srcset?t.srcset=t.srcset:t instanceof HTMLVideoElement&&t.autoplay&&t.play(),Ne.isNowTriggerFormExternal(t,c)&&(v=t),Ne.discardError(n,t,h),(Ne.isPhxChild(t)&&e.ownsElement(t)||Ne.isPhxSticky(t)&&e.ownsElement(t.parentNode))&&this.trackAfter("phxChildAdded",t),p.push(t)},onNodeDiscarded:e=>{(Ne.isPhxChild(e)||Ne.isPhxSticky(e))&&t.destroyViewByEl(e),this.trackAfter("discarded",e)},onBeforeNodeDiscarded:e=>!(!e.getAttribute||null===e.getAttribute(U))||(null===e.parentNode||!Ne.isPhxUpdate(e.parentNode,l,["append","prepend"])||!e.id)&&(e.getAttribute&&e.getAttribute(u)?(m.push(e),!1):!this.skipCIDSibling(e)),onElUpdated:e=>{Ne.isNowTriggerFormExternal(e,c)&&(v=e),f.push(e)},onBeforeElUpdated:(e,t)=>{if(Ne.cleanChildNodes(t,l),this.skipCIDSibling(t))return!1;if(Ne.isPhxSticky(e))return!1;if(Ne.isIgnored(e,l))return this.trackBefore("updated",e,t),Ne.mergeAttrs(e,t,{isIgnored:!0}),f.push(e),Ne.applyStickyOperations(e),!1;if("number"===e.type&&e.validity&&e.validity.badInput)return!1;if(!Ne.syncPendingRef(e,t,d))return Ne.isUploadInput(e)&&(this.trackBefore("updated",e,t),f.push(e)),Ne.applyStickyOperations(e),!1;if(Ne.isPhxChild(t)){let i=e.getAttribute(Z);return Ne.mergeAttrs(e,t,{exclude:[ie]}),""!==i&&e.setAttribute(Z,i),e.setAttribute(z,this.rootID),Ne.applyStickyOperations(e),!1}return Ne.copyPrivates(t,e),Ne.discardError(n,t,h),r&&e.isSameNode(r)&&Ne.isFormInput(e)?(this.trackBefore("updated",e,t),Ne.mergeFocusedInput(e,t),Ne.syncAttrsToProps(e),f.push(e),Ne.applyStickyOperations(e),!1):(Ne.isPhxUpdate(t,l,["append","prepend"])&&g.push(new class{constructor(e,t,i){let s=new Set,n=new Set([...t.children].map((e=>e.id))),r=[];Array.from(e.children).forEach((e=>{if(e.id&&(s.add(e.id),n.has(e.id))){let t=e.previousElementSibling&&e.previousElementSibling.id;r.push({elementId:e.id,previousElementId:t})}})),this.containerId=t.id,this.updateType=i,this.elementsToModify=r,this.elementIdsToAdd=[...n].filter((e=>!s.has(e)))}perform(){let e=Ne.byId(this.containerId);this.elementsToModify.forEach((t=>{t.previousElementId?Re(document.getElementById(t.previousElementId),(e=>{Re(document.getElementById(t.elementId),(t=>{t.previousElementSibling&&t.previousElementSibling.id==e.id||e.insertAdjacentElement("afterend",t)}))})):Re(document.getElementById(t.elementId),(t=>{null==t.previousElementSibling||e.insertAdjacentElement("afterbegin",t)}))})),"prepend"==this.updateType&&this.elementIdsToAdd.reverse().forEach((t=>{Re(document.getElementById(t),(t=>e.insertAdjacentElement("afterbegin",t)))}))}}(e,t,t.getAttribute(l))),Ne.syncAttrsToProps(t),Ne.applyStickyOperations(t),this.trackBefore("updated",e,t),!0)}})})),t.isDebugEnabled()&&function(){let e=new Set,t=document.querySelectorAll("*[id]");for(let i=0,s=t.length;i<s;i++)e.has(t[i].id)?console.error(`Multiple IDs detected: ${t[i].id}. Ensure unique element ids.`):e.add(t[i].id)}(),g.length>0&&t.time("post-morph append/prepend restoration",(()=>{g.forEach((e=>e.perform()))})),t.silenceEvents((()=>Ne.restoreFocus(r,o,a))),Ne.dispatchEvent(document,"phx:update"),p.forEach((e=>this.trackAfter("added",e))),f.forEach((e=>this.trackAfter("updated",e))),m.length>0&&(t.transitionRemoves(m),t.requestDOMUpdate((()=>{m.forEach((e=>{let i=Ne.firstPhxChild(e);i&&t.destroyViewByEl(i),e.remove()})),this.trackAfter("transitionsDiscarded",m)}))),v&&(t.disconnect(),v.submit()),!0}isCIDPatch(){return this.cidPatch}skipCIDSibling(e){return e.nodeType===Node.ELEMENT_NODE&&null!==e.getAttribute(j)}targetCIDContainer(e){if(!this.isCIDPatch())return;let[t,...i]=Ne.findComponentNodeList(this.container,this.targetCID);return 0===i.length&&1===Ne.childNodeLength(e)?t:t&&t.parentNode}buildDiffHTML(e,t,i,s){let n=this.isCIDPatch(),r=n&&s.getAttribute(L)===this.targetCID.toString();if(!n||r)return t;{let e=null,i=document.createElement("template");e=Ne.cloneNode(s);let[n,...r]=Ne.findComponentNodeList(e,this.targetCID);return i.innerHTML=t,r.forEach((e=>e.remove())),Array.from(e.childNodes).forEach((e=>{e.id&&e.nodeType===Node.ELEMENT_NODE&&e.getAttribute(L)!==this.targetCID.toString()&&(e.setAttribute(j,""),e.innerHTML="")})),Array.from(i.content.childNodes).forEach((t=>e.insertBefore(t,n))),n.remove(),e.outerHTML}}},Ze=class{static extract(e){let{[ke]:t,[ye]:i,[we]:s}=e;return delete e[ke],delete e[ye],delete e[we],{diff:e,title:s,reply:t||null,events:i||[]}}constructor(e,t){this.viewId=e,this.rendered={},this.mergeDiff(t)}parentViewId(){return this.viewId}toString(e){return this.recursiveToString(this.rendered,this.rendered.c,e)}recursiveToString(e,t=e.c,i){let s={buffer:"",components:t,onlyCids:i=i?new Set(i):null};return this.toOutputBuffer(e,null,s),s.buffer}componentCIDs(e){return Object.keys(e.c||{}).map((e=>parseInt(e)))}isComponentOnlyDiff(e){return!!e.c&&1===Object.keys(e).length}getComponent(e,t){return e.c[t]}mergeDiff(e){let t=e.c,i={};if(delete e.c,this.rendered=this.mutableMerge(this.rendered,e),this.rendered.c=this.rendered.c||{},t){let s=this.rendered.c;for(let e in t)t[e]=this.cachedFindComponent(e,t[e],s,t,i);for(let e in t)s[e]=t[e];e.c=t}}cachedFindComponent(e,t,i,s,n){if(n[e])return n[e];{let r,o,a=t[be];if(Ce(a)){let e;e=a>0?this.cachedFindComponent(a,s[a],i,s,n):i[-a],o=e[be],r=this.cloneMerge(e,t),r[be]=o}else r=void 0!==t[be]?t:this.cloneMerge(i[e]||{},t);return n[e]=r,r}}mutableMerge(e,t){return void 0!==t[be]?t:(this.doMutableMerge(e,t),e)}doMutableMerge(e,t){for(let i in t){let s=t[i],n=e[i];Le(s)&&void 0===s[be]&&Le(n)?this.doMutableMerge(n,s):e[i]=s}}cloneMerge(e,t){let i={...e,...t};for(let s in i){let n=t[s],r=e[s];Le(n)&&void 0===n[be]&&Le(r)&&(i[s]=this.cloneMerge(r,n))}return i}componentToString(e){return this.recursiveCIDToString(this.rendered.c,e)}pruneCIDs(e){e.forEach((e=>delete this.rendered.c[e]))}get(){return this.rendered}isNewFingerprint(e={}){return!!e[be]}templateStatic(e,t){return"number"==typeof e?t[e]:e}toOutputBuffer(e,t,i){if(e[ve])return this.comprehensionToBuffer(e,t,i);let{[be]:s}=e;s=this.templateStatic(s,t),i.buffer+=s[0];for(let n=1;n<s.length;n++)this.dynamicToBuffer(e[n-1],t,i),i.buffer+=s[n]}comprehensionToBuffer(e,t,i){let{[ve]:s,[be]:n}=e;n=this.templateStatic(n,t);let r=t||e.p;for(let e=0;e<s.length;e++){let t=s[e];i.buffer+=n[0];for(let e=1;e<n.length;e++)this.dynamicToBuffer(t[e-1],r,i),i.buffer+=n[e]}}dynamicToBuffer(e,t,i){"number"==typeof e?i.buffer+=this.recursiveCIDToString(i.components,e,i.onlyCids):Le(e)?this.toOutputBuffer(e,t,i):i.buffer+=e}recursiveCIDToString(e,t,i){let s=e[t]||Ee(`no component for CID ${t}`,e),n=document.createElement("template");n.innerHTML=this.recursiveToString(s,e,i);let r=n.content,o=i&&!i.has(t),[a,l]=Array.from(r.childNodes).reduce((([e,i],s,r)=>s.nodeType===Node.ELEMENT_NODE?s.getAttribute(L)?[e,!0]:(s.setAttribute(L,t),s.id||(s.id=`${this.parentViewId()}-${t}-${r}`),o&&(s.setAttribute(j,""),s.innerHTML=""),[!0,i]):""!==s.nodeValue.trim()?(Ee(`only HTML element tags are allowed at the root of components.\n\ngot: "${s.nodeValue.trim()}"\n\nwithin:\n`,n.innerHTML.trim()),s.replaceWith(this.createSpan(s.nodeValue,t)),[!0,i]):(s.remove(),[e,i])),[!1,!1]);return a||l?!a&&l?(Ee("expected at least one HTML element tag directly inside a component, but only subcomponents were found. A component must render at least one HTML tag directly inside itself.",n.innerHTML.trim()),n.innerHTML):n.innerHTML:(Ee("expected at least one HTML element tag inside a component, but the component is empty:\n",n.innerHTML.trim()),this.createSpan("",t).outerHTML)}createSpan(e,t){let i=document.createElement("span");return i.innerText=e,i.setAttribute(L,t),i}},et=1,tt=class{static makeID(){return et++}static elementID(e){return e.phxHookId}constructor(e,t,i){this.__view=e,this.liveSocket=e.liveSocket,this.__callbacks=i,this.__listeners=new Set,this.__isDisconnected=!1,this.el=t,this.el.phxHookId=this.constructor.makeID();for(let e in this.__callbacks)this[e]=this.__callbacks[e]}__mounted(){this.mounted&&this.mounted()}__updated(){this.updated&&this.updated()}__beforeUpdate(){this.beforeUpdate&&this.beforeUpdate()}__destroyed(){this.destroyed&&this.destroyed()}__reconnected(){this.__isDisconnected&&(this.__isDisconnected=!1,this.reconnected&&this.reconnected())}__disconnected(){this.__isDisconnected=!0,this.disconnected&&this.disconnected()}pushEvent(e,t={},i=function(){}){return this.__view.pushHookEvent(null,e,t,i)}pushEventTo(e,t,i={},s=function(){}){return this.__view.withinTargets(e,((e,n)=>e.pushHookEvent(n,t,i,s)))}handleEvent(e,t){let i=(i,s)=>s?e:t(i.detail);return window.addEventListener(`phx:${e}`,i),this.__listeners.add(i),i}removeHandleEvent(e){let t=e(null,!0);window.removeEventListener(`phx:${t}`,e),this.__listeners.delete(e)}upload(e,t){return this.__view.dispatchUploads(e,t)}uploadTo(e,t,i){return this.__view.withinTargets(e,(e=>e.dispatchUploads(t,i)))}__cleanup__(){this.__listeners.forEach((e=>this.removeHandleEvent(e)))}},it={exec(e,t,i,s,n){let[r,o]=n||[null,{}];("["===t.charAt(0)?JSON.parse(t):[[r,o]]).forEach((([n,a])=>{n===r&&o.data&&(a.data=Object.assign(a.data||{},o.data)),this.filterToEls(s,a).forEach((r=>{this[`exec_${n}`](e,t,i,s,r,a)}))}))},isVisible:e=>!!(e.offsetWidth||e.offsetHeight||e.getClientRects().length>0),exec_dispatch(e,t,i,s,n,{to:r,event:o,detail:a,bubbles:l}){(a=a||{}).dispatcher=s,Ne.dispatchEvent(n,o,{detail:a,bubbles:l})},exec_push(e,t,i,s,n,r){if(!i.isConnected())return;let{event:o,data:a,target:l,page_loading:h,loading:d,value:c,dispatcher:u}=r,p={loading:d,value:c,target:l,page_loading:!!h},f="change"===e&&u?u:s,g=l||f.getAttribute(i.binding("target"))||f;i.withinTargets(g,((i,n)=>{if("change"===e){let{newCid:e,_target:a,callback:l}=r;a=a||(s instanceof HTMLInputElement?s.name:void 0),a&&(p._target=a),i.pushInput(s,n,e,o||t,p,l)}else"submit"===e?i.submitForm(s,n,o||t,p):i.pushEvent(e,s,n,o||t,a,p)}))},exec_add_class(e,t,i,s,n,{names:r,transition:o,time:a}){this.addOrRemoveClasses(n,r,[],o,a,i)},exec_remove_class(e,t,i,s,n,{names:r,transition:o,time:a}){this.addOrRemoveClasses(n,[],r,o,a,i)},exec_transition(e,t,i,s,n,{time:r,transition:o}){let[a,l,h]=o;i.transition(r,(()=>this.addOrRemoveClasses(n,a.concat(l),[])),(()=>this.addOrRemoveClasses(n,h,a.concat(l))))},exec_toggle(e,t,i,s,n,{display:r,ins:o,outs:a,time:l}){this.toggle(e,i,n,r,o,a,l)},exec_show(e,t,i,s,n,{display:r,transition:o,time:a}){this.show(e,i,n,r,o,a)},exec_hide(e,t,i,s,n,{display:r,transition:o,time:a}){this.hide(e,i,n,r,o,a)},exec_set_attr(e,t,i,s,n,{attr:[r,o]}){this.setOrRemoveAttrs(n,[[r,o]],[])},exec_remove_attr(e,t,i,s,n,{attr:r}){this.setOrRemoveAttrs(n,[],[r])},show(e,t,i,s,n,r){this.isVisible(i)||this.toggle(e,t,i,s,n,null,r)},hide(e,t,i,s,n,r){this.isVisible(i)&&this.toggle(e,t,i,s,null,n,r)},toggle(e,t,i,s,n,r,o){let[a,l,h]=n||[[],[],[]],[d,c,u]=r||[[],[],[]];if(a.length>0||d.length>0)if(this.isVisible(i)){let e=()=>{this.addOrRemoveClasses(i,c,a.concat(l).concat(h)),window.requestAnimationFrame((()=>{this.addOrRemoveClasses(i,d,[]),window.requestAnimationFrame((()=>this.addOrRemoveClasses(i,u,c)))}))};i.dispatchEvent(new Event("phx:hide-start")),t.transition(o,e,(()=>{this.addOrRemoveClasses(i,[],d.concat(u)),Ne.putSticky(i,"toggle",(e=>e.style.display="none")),i.dispatchEvent(new Event("phx:hide-end"))}))}else{if("remove"===e)return;let n=()=>{this.addOrRemoveClasses(i,l,d.concat(c).concat(u)),Ne.putSticky(i,"toggle",(e=>e.style.display=s||"block")),window.requestAnimationFrame((()=>{this.addOrRemoveClasses(i,a,[]),window.requestAnimationFrame((()=>this.addOrRemoveClasses(i,h,l)))}))};i.dispatchEvent(new Event("phx:show-start")),t.transition(o,n,(()=>{this.addOrRemoveClasses(i,[],a.concat(h)),i.dispatchEvent(new Event("phx:show-end"))}))}else this.isVisible(i)?window.requestAnimationFrame((()=>{i.dispatchEvent(new Event("phx:hide-start")),Ne.putSticky(i,"toggle",(e=>e.style.display="none")),i.dispatchEvent(new Event("phx:hide-end"))})):window.requestAnimationFrame((()=>{i.dispatchEvent(new Event("phx:show-start")),Ne.putSticky(i,"toggle",(e=>e.style.display=s||"block")),i.dispatchEvent(new Event("phx:show-end"))}))},addOrRemoveClasses(e,t,i,s,n,r){let[o,a,l]=s||[[],[],[]];if(o.length>0){let s=()=>this.addOrRemoveClasses(e,a.concat(o),[]),h=()=>this.addOrRemoveClasses(e,t.concat(l),i.concat(o).concat(a));return r.transition(n,s,h)}window.requestAnimationFrame((()=>{let[s,n]=Ne.getSticky(e,"classes",[[],[]]),r=t.filter((t=>s.indexOf(t)<0&&!e.classList.contains(t))),o=i.filter((t=>n.indexOf(t)<0&&e.classList.contains(t))),a=s.filter((e=>i.indexOf(e)<0)).concat(r),l=n.filter((e=>t.indexOf(e)<0)).concat(o);Ne.putSticky(e,"classes",(e=>(e.classList.remove(...l),e.classList.add(...a),[a,l])))}))},setOrRemoveAttrs(e,t,i){let[s,n]=Ne.getSticky(e,"attrs",[[],[]]),r=t.map((([e,t])=>e)).concat(i),o=s.filter((([e,t])=>!r.includes(e))).concat(t),a=n.filter((e=>!r.includes(e))).concat(i);Ne.putSticky(e,"attrs",(e=>(a.forEach((t=>e.removeAttribute(t))),o.forEach((([t,i])=>e.setAttribute(t,i))),[o,a])))},hasAllClasses:(e,t)=>t.every((t=>e.classList.contains(t))),isToggledOut(e,t){return!this.isVisible(e)||this.hasAllClasses(e,t)},filterToEls:(e,{to:t})=>t?Ne.all(document,t):[e]},st=(e,t,i=[])=>{let s=new FormData(e),n=[];s.forEach(((e,t,i)=>{e instanceof File&&n.push(t)})),n.forEach((e=>s.delete(e)));let r=new URLSearchParams;for(let[e,t]of s.entries())(0===i.length||i.indexOf(e)>=0)&&r.append(e,t);for(let e in t)r.append(e,t[e]);return r.toString()},nt=class{constructor(e,t,i,s){this.liveSocket=t,this.flash=s,this.parent=i,this.root=i?i.root:this,this.el=e,this.id=this.el.id,this.ref=0,this.childJoins=0,this.loaderTimer=null,this.pendingDiffs=[],this.pruningCIDs=[],this.redirect=!1,this.href=null,this.joinCount=this.parent?this.parent.joinCount-1:0,this.joinPending=!0,this.destroyed=!1,this.joinCallback=function(e){e&&e()},this.stopCallback=function(){},this.pendingJoinOps=this.parent?null:[],this.viewHooks={},this.uploaders={},this.formSubmits=[],this.children=this.parent?null:{},this.root.children[this.id]={},this.channel=this.liveSocket.channel(`lv:${this.id}`,(()=>({redirect:this.redirect?this.href:void 0,url:this.redirect?void 0:this.href||void 0,params:this.connectParams(),session:this.getSession(),static:this.getStatic(),flash:this.flash}))),this.showLoader(this.liveSocket.loaderTimeout),this.bindChannel()}setHref(e){this.href=e}setRedirect(e){this.redirect=!0,this.href=e}isMain(){return null!==this.el.getAttribute(W)}connectParams(){let e=this.liveSocket.params(this.el),t=Ne.all(document,`[${this.binding("track-static")}]`).map((e=>e.src||e.href)).filter((e=>"string"==typeof e));return t.length>0&&(e._track_static=t),e._mounts=this.joinCount,e}isConnected(){return this.channel.canPush()}getSession(){return this.el.getAttribute(Z)}getStatic(){let e=this.el.getAttribute(ie);return""===e?null:e}destroy(e=function(){}){this.destroyAllChildren(),this.destroyed=!0,delete this.root.children[this.id],this.parent&&delete this.root.children[this.parent.id][this.id],clearTimeout(this.loaderTimer);let t=()=>{e();for(let e in this.viewHooks)this.destroyHook(this.viewHooks[e])};Ne.markPhxChildDestroyed(this.el),this.log("destroyed",(()=>["the child has been removed from the parent"])),this.channel.leave().receive("ok",t).receive("error",t).receive("timeout",t)}setContainerClasses(...e){this.el.classList.remove(F,B,q),this.el.classList.add(...e)}showLoader(e){if(clearTimeout(this.loaderTimer),e)this.loaderTimer=setTimeout((()=>this.showLoader()),e);else{for(let e in this.viewHooks)this.viewHooks[e].__disconnected();this.setContainerClasses(B)}}hideLoader(){clearTimeout(this.loaderTimer),this.setContainerClasses(F)}triggerReconnected(){for(let e in this.viewHooks)this.viewHooks[e].__reconnected()}log(e,t){this.liveSocket.log(this,e,t)}transition(e,t,i=function(){}){this.liveSocket.transition(e,t,i)}withinTargets(e,t){if(e instanceof HTMLElement||e instanceof SVGElement)return this.liveSocket.owner(e,(i=>t(i,e)));if(Ce(e)){0===Ne.findComponentNodeList(this.el,e).length?Ee(`no component found matching phx-target of ${e}`):t(this,parseInt(e))}else{let i=Array.from(document.querySelectorAll(e));0===i.length&&Ee(`nothing found matching the phx-target selector "${e}"`),i.forEach((e=>this.liveSocket.owner(e,(i=>t(i,e)))))}}applyDiff(e,t,i){this.log(e,(()=>["",Te(t)]));let{diff:s,reply:n,events:r,title:o}=Ze.extract(t);return o&&Ne.putTitle(o),i({diff:s,reply:n,events:r}),n}onJoin(e){let{rendered:t,container:i}=e;if(i){let[e,t]=i;this.el=Ne.replaceRootContainer(this.el,e,t)}this.childJoins=0,this.joinPending=!0,this.flash=null,Ie.dropLocal(this.liveSocket.localStorage,window.location.pathname,T),this.applyDiff("mount",t,(({diff:t,events:i})=>{this.rendered=new Ze(this.id,t);let s=this.renderContainer(null,"join");this.dropPendingRefs();let n=this.formsForRecovery(s);this.joinCount++,n.length>0?n.forEach((([e,t,r],o)=>{this.pushFormRecovery(e,r,(e=>{o===n.length-1&&this.onJoinComplete(e,s,i)}))})):this.onJoinComplete(e,s,i)}))}dropPendingRefs(){Ne.all(document,`[${_}="${this.id}"][${R}]`,(e=>{e.removeAttribute(R),e.removeAttribute(_)}))}onJoinComplete({live_patch:e},t,i){if(this.joinCount>1||this.parent&&!this.parent.isJoinPending())return this.applyJoinPatch(e,t,i);0===Ne.findPhxChildrenInFragment(t,this.id).filter((e=>{let t=e.id&&this.el.querySelector(`[id="${e.id}"]`),i=t&&t.getAttribute(ie);return i&&e.setAttribute(ie,i),this.joinChild(e)})).length?this.parent?(this.root.pendingJoinOps.push([this,()=>this.applyJoinPatch(e,t,i)]),this.parent.ackJoin(this)):(this.onAllChildJoinsComplete(),this.applyJoinPatch(e,t,i)):this.root.pendingJoinOps.push([this,()=>this.applyJoinPatch(e,t,i)])}attachTrueDocEl(){this.el=Ne.byId(this.id),this.el.setAttribute(z,this.root.id)}applyJoinPatch(e,t,i){this.attachTrueDocEl();let s=new Qe(this,this.el,this.id,t,null);if(s.markPrunableContentForRemoval(),this.performPatch(s,!1),this.joinNewChildren(),Ne.all(this.el,`[${this.binding(ae)}], [data-phx-hook]`,(e=>{let t=this.addHook(e);t&&t.__mounted()})),this.joinPending=!1,this.liveSocket.dispatchEvents(i),this.applyPendingUpdates(),e){let{kind:t,to:i}=e;this.liveSocket.historyPatch(i,t)}this.hideLoader(),this.joinCount>1&&this.triggerReconnected(),this.stopCallback()}triggerBeforeUpdateHook(e,t){this.liveSocket.triggerDOM("onBeforeElUpdated",[e,t]);let i=this.getHook(e),s=i&&Ne.isIgnored(e,this.binding(le));if(i&&!e.isEqualNode(t)&&(!s||(n=e.dataset,r=t.dataset,JSON.stringify(n)!==JSON.stringify(r))))return i.__beforeUpdate(),i;var n,r}performPatch(e,t){let i=[],s=!1,n=new Set;return e.after("added",(e=>{this.liveSocket.triggerDOM("onNodeAdded",[e]);let t=this.addHook(e);t&&t.__mounted()})),e.after("phxChildAdded",(e=>{Ne.isPhxSticky(e)?this.liveSocket.joinRootViews():s=!0})),e.before("updated",((e,t)=>{this.triggerBeforeUpdateHook(e,t)&&n.add(e.id)})),e.after("updated",(e=>{n.has(e.id)&&this.getHook(e).__updated()})),e.after("discarded",(e=>{e.nodeType===Node.ELEMENT_NODE&&i.push(e)})),e.after("transitionsDiscarded",(e=>this.afterElementsRemoved(e,t))),e.perform(),this.afterElementsRemoved(i,t),s}afterElementsRemoved(e,t){let i=[];e.forEach((e=>{let t=Ne.all(e,`[${L}]`),s=Ne.all(e,`[${this.binding(ae)}]`);t.concat(e).forEach((e=>{let t=this.componentID(e);Ce(t)&&-1===i.indexOf(t)&&i.push(t)})),s.concat(e).forEach((e=>{let t=this.getHook(e);t&&this.destroyHook(t)}))})),t&&this.maybePushComponentsDestroyed(i)}joinNewChildren(){Ne.findPhxChildren(this.el,this.id).forEach((e=>this.joinChild(e)))}getChildById(e){return this.root.children[this.id][e]}getDescendentByEl(e){return e.id===this.id?this:this.children[e.getAttribute(V)][e.id]}destroyDescendent(e){for(let t in this.root.children)for(let i in this.root.children[t])if(i===e)return this.root.children[t][i].destroy()}joinChild(e){if(!this.getChildById(e.id)){let t=new nt(e,this.liveSocket,this);return this.root.children[this.id][t.id]=t,t.join(),this.childJoins++,!0}}isJoinPending(){return this.joinPending}ackJoin(e){this.childJoins--,0===this.childJoins&&(this.parent?this.parent.ackJoin(this):this.onAllChildJoinsComplete())}onAllChildJoinsComplete(){this.joinCallback((()=>{this.pendingJoinOps.forEach((([e,t])=>{e.isDestroyed()||t()})),this.pendingJoinOps=[]}))}update(e,t){if(this.isJoinPending()||this.liveSocket.hasPendingLink()&&!Ne.isPhxSticky(this.el))return this.pendingDiffs.push({diff:e,events:t});this.rendered.mergeDiff(e);let i=!1;this.rendered.isComponentOnlyDiff(e)?this.liveSocket.time("component patch complete",(()=>{Ne.findParentCIDs(this.el,this.rendered.componentCIDs(e)).forEach((t=>{this.componentPatch(this.rendered.getComponent(e,t),t)&&(i=!0)}))})):Pe(e)||this.liveSocket.time("full patch complete",(()=>{let t=this.renderContainer(e,"update"),s=new Qe(this,this.el,this.id,t,null);i=this.performPatch(s,!0)})),this.liveSocket.dispatchEvents(t),i&&this.joinNewChildren()}renderContainer(e,t){return this.liveSocket.time(`toString diff (${t})`,(()=>{let t=this.el.tagName,i=e?this.rendered.componentCIDs(e).concat(this.pruningCIDs):null;return`<${t}>${this.rendered.toString(i)}</${t}>`}))}componentPatch(e,t){if(Pe(e))return!1;let i=this.rendered.componentToString(t),s=new Qe(this,this.el,this.id,i,t);return this.performPatch(s,!0)}getHook(e){return this.viewHooks[tt.elementID(e)]}addHook(e){if(tt.elementID(e)||!e.getAttribute)return;let t=e.getAttribute("data-phx-hook")||e.getAttribute(this.binding(ae));if(t&&!this.ownsElement(e))return;let i=this.liveSocket.getHookCallbacks(t);if(i){e.id||Ee(`no DOM ID for hook "${t}". Hooks require a unique ID on each element.`,e);let s=new tt(this,e,i);return this.viewHooks[tt.elementID(s.el)]=s,s}null!==t&&Ee(`unknown hook found for "${t}"`,e)}destroyHook(e){e.__destroyed(),e.__cleanup__(),delete this.viewHooks[tt.elementID(e.el)]}applyPendingUpdates(){this.pendingDiffs.forEach((({diff:e,events:t})=>this.update(e,t))),this.pendingDiffs=[]}onChannel(e,t){this.liveSocket.onChannel(this.channel,e,(e=>{this.isJoinPending()?this.root.pendingJoinOps.push([this,()=>t(e)]):this.liveSocket.requestDOMUpdate((()=>t(e)))}))}bindChannel(){this.liveSocket.onChannel(this.channel,"diff",(e=>{this.liveSocket.requestDOMUpdate((()=>{this.applyDiff("update",e,(({diff:e,events:t})=>this.update(e,t)))}))})),this.onChannel("redirect",(({to:e,flash:t})=>this.onRedirect({to:e,flash:t}))),this.onChannel("live_patch",(e=>this.onLivePatch(e))),this.onChannel("live_redirect",(e=>this.onLiveRedirect(e))),this.channel.onError((e=>this.onError(e))),this.channel.onClose((e=>this.onClose(e)))}destroyAllChildren(){for(let e in this.root.children[this.id])this.getChildById(e).destroy()}onLiveRedirect(e){let{to:t,kind:i,flash:s}=e,n=this.expandURL(t);this.liveSocket.historyRedirect(n,i,s)}onLivePatch(e){let{to:t,kind:i}=e;this.href=this.expandURL(t),this.liveSocket.historyPatch(t,i)}expandURL(e){return e.startsWith("/")?`${window.location.protocol}//${window.location.host}${e}`:e}onRedirect({to:e,flash:t}){this.liveSocket.redirect(e,t)}isDestroyed(){return this.destroyed}join(e){this.isMain()&&(this.stopCallback=this.liveSocket.withPageLoading({to:this.href,kind:"initial"})),this.joinCallback=t=>{t=t||function(){},e?e(this.joinCount,t):t()},this.liveSocket.wrapPush(this,{timeout:!1},(()=>this.channel.join().receive("ok",(e=>{this.isDestroyed()||this.liveSocket.requestDOMUpdate((()=>this.onJoin(e)))})).receive("error",(e=>!this.isDestroyed()&&this.onJoinError(e))).receive("timeout",(()=>!this.isDestroyed()&&this.onJoinError({reason:"timeout"})))))}onJoinError(e){return"unauthorized"===e.reason||"stale"===e.reason?(this.log("error",(()=>["unauthorized live_redirect. Falling back to page request",e])),this.onRedirect({to:this.href})):((e.redirect||e.live_redirect)&&(this.joinPending=!1,this.channel.leave()),e.redirect?this.onRedirect(e.redirect):e.live_redirect?this.onLiveRedirect(e.live_redirect):(this.log("error",(()=>["unable to join",e])),void(this.liveSocket.isConnected()&&this.liveSocket.reloadWithJitter(this))))}onClose(e){if(!this.isDestroyed()){if(this.liveSocket.hasPendingLink()&&"leave"!==e)return this.liveSocket.reloadWithJitter(this);this.destroyAllChildren(),this.liveSocket.dropActiveElement(this),document.activeElement&&document.activeElement.blur(),this.liveSocket.isUnloaded()&&this.showLoader(200)}}onError(e){this.onClose(e),this.liveSocket.isConnected()&&this.log("error",(()=>["view crashed",e])),this.liveSocket.isUnloaded()||this.displayError()}displayError(){this.isMain()&&Ne.dispatchEvent(window,"phx:page-loading-start",{detail:{to:this.href,kind:"error"}}),this.showLoader(),this.setContainerClasses(B,q)}pushWithReply(e,t,i,s=function(){}){if(!this.isConnected())return;let[n,[r],o]=e?e():[null,[],{}],a=function(){};return(o.page_loading||r&&null!==r.getAttribute(this.binding(H)))&&(a=this.liveSocket.withPageLoading({kind:"element",target:r})),"number"!=typeof i.cid&&delete i.cid,this.liveSocket.wrapPush(this,{timeout:!0},(()=>this.channel.push(t,i,3e4).receive("ok",(e=>{null!==n&&this.undoRefs(n);let t=t=>{e.redirect&&this.onRedirect(e.redirect),e.live_patch&&this.onLivePatch(e.live_patch),e.live_redirect&&this.onLiveRedirect(e.live_redirect),a(),s(e,t)};e.diff?this.liveSocket.requestDOMUpdate((()=>{let i=this.applyDiff("update",e.diff,(({diff:e,events:t})=>{this.update(e,t)}));t(i)})):t(null)}))))}undoRefs(e){Ne.all(document,`[${_}="${this.id}"][${R}="${e}"]`,(e=>{let t=e.getAttribute(ne);e.removeAttribute(R),e.removeAttribute(_),null!==e.getAttribute(se)&&(e.readOnly=!1,e.removeAttribute(se)),null!==t&&(e.disabled="true"===t,e.removeAttribute(ne)),x.forEach((t=>Ne.removeClass(e,t)));let i=e.getAttribute(oe);null!==i&&(e.innerText=i,e.removeAttribute(oe));let s=Ne.private(e,R);if(s){let t=this.triggerBeforeUpdateHook(e,s);Qe.patchEl(e,s,this.liveSocket.getActiveElement()),t&&t.__updated(),Ne.deletePrivate(e,R)}}))}putRef(e,t,i={}){let s=this.ref++,n=this.binding(re);return i.loading&&(e=e.concat(Ne.all(document,i.loading))),e.forEach((e=>{e.classList.add(`phx-${t}-loading`),e.setAttribute(R,s),e.setAttribute(_,this.el.id);let i=e.getAttribute(n);null!==i&&(e.getAttribute(oe)||e.setAttribute(oe,e.innerText),""!==i&&(e.innerText=i),e.setAttribute("disabled",""))})),[s,e,i]}componentID(e){let t=e.getAttribute&&e.getAttribute(L);return t?parseInt(t):null}targetComponentID(e,t,i={}){if(Ce(t))return t;let s=e.getAttribute(this.binding("target"));return Ce(s)?parseInt(s):t&&(null!==s||i.target)?this.closestComponentID(t):null}closestComponentID(e){return Ce(e)?e:e?Re(e.closest(`[${L}]`),(e=>this.ownsElement(e)&&this.componentID(e))):null}pushHookEvent(e,t,i,s){if(!this.isConnected())return this.log("hook",(()=>["unable to push hook event. LiveView not connected",t,i])),!1;let[n,r,o]=this.putRef([],"hook");return this.pushWithReply((()=>[n,r,o]),"event",{type:"hook",event:t,value:i,cid:this.closestComponentID(e)},((e,t)=>s(t,n))),n}extractMeta(e,t,i){let s=this.binding("value-");for(let i=0;i<e.attributes.length;i++){t||(t={});let n=e.attributes[i].name;n.startsWith(s)&&(t[n.replace(s,"")]=e.getAttribute(n))}if(void 0!==e.value&&(t||(t={}),t.value=e.value,"INPUT"===e.tagName&&Y.indexOf(e.type)>=0&&!e.checked&&delete t.value),i){t||(t={});for(let e in i)t[e]=i[e]}return t}pushEvent(e,t,i,s,n,r={}){this.pushWithReply((()=>this.putRef([t],e,r)),"event",{type:e,event:s,value:this.extractMeta(t,n,r.value),cid:this.targetComponentID(t,i,r)})}pushFileProgress(e,t,i,s=function(){}){this.liveSocket.withinOwners(e.form,((n,r)=>{n.pushWithReply(null,"progress",{event:e.getAttribute(n.binding("progress")),ref:e.getAttribute(I),entry_ref:t,progress:i,cid:n.targetComponentID(e.form,r)},s)}))}pushInput(e,t,i,s,n,r){let o,a,l=Ce(i)?i:this.targetComponentID(e.form,t),h=()=>this.putRef([e,e.form],"change",n);a=e.getAttribute(this.binding("change"))?st(e.form,{_target:n._target},[e.name]):st(e.form,{_target:n._target}),Ne.isUploadInput(e)&&e.files&&e.files.length>0&&je.trackFiles(e,Array.from(e.files)),o=je.serializeUploads(e);let d={type:"form",event:s,value:a,uploads:o,cid:l};this.pushWithReply(h,"event",d,(i=>{if(Ne.showError(e,this.liveSocket.binding(G)),Ne.isUploadInput(e)&&null!==e.getAttribute("data-phx-auto-upload")){if(je.filesAwaitingPreflight(e).length>0){let[s,n]=h();this.uploadFiles(e.form,t,s,l,(t=>{r&&r(i),this.triggerAwaitingSubmit(e.form)}))}}else r&&r(i)}))}triggerAwaitingSubmit(e){let t=this.getScheduledSubmit(e);if(t){let[i,s,n,r]=t;this.cancelSubmit(e),r()}}getScheduledSubmit(e){return this.formSubmits.find((([t,i,s,n])=>t.isSameNode(e)))}scheduleSubmit(e,t,i,s){if(this.getScheduledSubmit(e))return!0;this.formSubmits.push([e,t,i,s])}cancelSubmit(e){this.formSubmits=this.formSubmits.filter((([t,i,s])=>!t.isSameNode(e)||(this.undoRefs(i),!1)))}pushFormSubmit(e,t,i,s,n){let r=e=>!(xe(e,`${this.binding(le)}=ignore`,e.form)||xe(e,"data-phx-update=ignore",e.form)),o=e=>e.hasAttribute(this.binding(re)),a=e=>"BUTTON"==e.tagName,l=e=>["INPUT","TEXTAREA","SELECT"].includes(e.tagName),h=()=>{let t=Array.from(e.elements),i=t.filter(o),n=t.filter(a).filter(r),h=t.filter(l).filter(r);return n.forEach((e=>{e.setAttribute(ne,e.disabled),e.disabled=!0})),h.forEach((e=>{e.setAttribute(se,e.readOnly),e.readOnly=!0,e.files&&(e.setAttribute(ne,e.disabled),e.disabled=!0)})),e.setAttribute(this.binding(H),""),this.putRef([e].concat(i).concat(n).concat(h),"submit",s)},d=this.targetComponentID(e,t);if(je.hasUploadsInProgress(e)){let[r,o]=h(),a=()=>this.pushFormSubmit(e,t,i,s,n);return this.scheduleSubmit(e,r,s,a)}if(je.inputsAwaitingPreflight(e).length>0){let[r,o]=h(),a=()=>[r,o,s];this.uploadFiles(e,t,r,d,(t=>{let s=st(e,{});this.pushWithReply(a,"event",{type:"form",event:i,value:s,cid:d},n)}))}else{let t=st(e,{});this.pushWithReply(h,"event",{type:"form",event:i,value:t,cid:d},n)}}uploadFiles(e,t,i,s,n){let r=this.joinCount,o=je.activeFileInputs(e),a=o.length;o.forEach((e=>{let s=new je(e,this,(()=>{a--,0===a&&n()}));this.uploaders[e]=s;let o=s.entries().map((e=>e.toPreflightPayload())),l={ref:e.getAttribute(I),entries:o,cid:this.targetComponentID(e.form,t)};this.log("upload",(()=>["sending preflight request",l])),this.pushWithReply(null,"allow_upload",l,(e=>{if(this.log("upload",(()=>["got preflight response",e])),e.error){this.undoRefs(i);let[t,s]=e.error;this.log("upload",(()=>[`error for entry ${t}`,s]))}else{let t=e=>{this.channel.onError((()=>{this.joinCount===r&&e()}))};s.initAdapterUpload(e,t,this.liveSocket)}}))}))}dispatchUploads(e,t){let i=Ne.findUploadInputs(this.el).filter((t=>t.name===e));0===i.length?Ee(`no live file inputs found matching the name "${e}"`):i.length>1?Ee(`duplicate live file inputs found matching the name "${e}"`):Ne.dispatchEvent(i[0],D,{detail:{files:t}})}pushFormRecovery(e,t,i){this.liveSocket.withinOwners(e,((s,n)=>{let r=e.elements[0],o=e.getAttribute(this.binding(he))||e.getAttribute(this.binding("change"));it.exec("change",o,s,r,["push",{_target:r.name,newCid:t,callback:i}])}))}pushLinkPatch(e,t,i){let s=this.liveSocket.setPendingLink(e),n=t?()=>this.putRef([t],"click"):null,r=()=>this.liveSocket.redirect(window.location.href),o=this.pushWithReply(n,"live_patch",{url:e},(t=>{this.liveSocket.requestDOMUpdate((()=>{t.link_redirect?this.liveSocket.replaceMain(e,null,i,s):(this.liveSocket.commitPendingLink(s)&&(this.href=e),this.applyPendingUpdates(),i&&i(s))}))}));o?o.receive("timeout",r):r()}formsForRecovery(e){if(0===this.joinCount)return[];let t=this.binding("change"),i=document.createElement("template");return i.innerHTML=e,Ne.all(this.el,`form[${t}]`).filter((e=>e.id&&this.ownsElement(e))).filter((e=>e.elements.length>0)).filter((e=>"ignore"!==e.getAttribute(this.binding(he)))).map((e=>{let s=i.content.querySelector(`form[id="${e.id}"][${t}="${e.getAttribute(t)}"]`);return s?[e,s,this.targetComponentID(s)]:[e,null,null]})).filter((([e,t,i])=>t))}maybePushComponentsDestroyed(e){let t=e.filter((e=>0===Ne.findComponentNodeList(this.el,e).length));t.length>0&&(this.pruningCIDs.push(...t),this.pushWithReply(null,"cids_will_destroy",{cids:t},(()=>{this.pruningCIDs=this.pruningCIDs.filter((e=>-1!==t.indexOf(e)));let e=t.filter((e=>0===Ne.findComponentNodeList(this.el,e).length));e.length>0&&this.pushWithReply(null,"cids_destroyed",{cids:e},(e=>{this.rendered.pruneCIDs(e.cids)}))})))}ownsElement(e){return e.getAttribute(V)===this.id||Re(e.closest(ee),(e=>e.id))===this.id}submitForm(e,t,i,s={}){Ne.putPrivate(e,Q,!0);let n=this.liveSocket.binding(G),r=Array.from(e.elements);this.liveSocket.blurActiveElement(this),this.pushFormSubmit(e,t,i,s,(()=>{r.forEach((e=>Ne.showError(e,n))),this.liveSocket.restorePreviouslyActiveFocus()}))}binding(e){return this.liveSocket.binding(e)}},rt=class{constructor(){this.transitions=new Set,this.pendingOps=[],this.reset()}reset(){this.transitions.forEach((e=>{cancelTimeout(e),this.transitions.delete(e)})),this.flushPendingOps()}after(e){0===this.size()?e():this.pushPendingOp(e)}addTransition(e,t,i){t();let s=setTimeout((()=>{this.transitions.delete(s),i(),0===this.size()&&this.flushPendingOps()}),e);this.transitions.add(s)}pushPendingOp(e){this.pendingOps.push(e)}size(){return this.transitions.size}flushPendingOps(){this.pendingOps.forEach((e=>e())),this.pendingOps=[]}},ot="undefined"!=typeof globalThis?globalThis:"undefined"!=typeof window?window:"undefined"!=typeof global?global:"undefined"!=typeof self?self:{},at={exports:{}};Xe=at,function(e,t){function i(){s.width=e.innerWidth,s.height=5*l.barThickness;var t=s.getContext("2d");t.shadowBlur=l.shadowBlur,t.shadowColor=l.shadowColor;var i,n=t.createLinearGradient(0,0,s.width,0);for(i in l.barColors)n.addColorStop(i,l.barColors[i]);t.lineWidth=l.barThickness,t.beginPath(),t.moveTo(0,l.barThickness/2),t.lineTo(Math.ceil(o*s.width),l.barThickness/2),t.strokeStyle=n,t.stroke()}!function(){for(var t=0,i=["ms","moz","webkit","o"],s=0;s<i.length&&!e.requestAnimationFrame;++s)e.requestAnimationFrame=e[i[s]+"RequestAnimationFrame"],e.cancelAnimationFrame=e[i[s]+"CancelAnimationFrame"]||e[i[s]+"CancelRequestAnimationFrame"];e.requestAnimationFrame||(e.requestAnimationFrame=function(i,s){var n=(new Date).getTime(),r=Math.max(0,16-(n-t)),o=e.setTimeout((function(){i(n+r)}),r);return t=n+r,o}),e.cancelAnimationFrame||(e.cancelAnimationFrame=function(e){clearTimeout(e)})}();var s,n,r,o,a,l={autoRun:!0,barThickness:3,barColors:{0:"rgba(26,  188, 156, .9)",".25":"rgba(52,  152, 219, .9)",".50":"rgba(241, 196, 15,  .9)",".75":"rgba(230, 126, 34,  .9)","1.0":"rgba(211, 84,  0,   .9)"},shadowBlur:10,shadowColor:"rgba(0,   0,   0,   .6)",className:null},h={config:function(e){for(var t in e)l.hasOwnProperty(t)&&(l[t]=e[t])},show:function(){var d,c,u;a||(a=!0,null!==r&&e.cancelAnimationFrame(r),s||((u=(s=t.createElement("canvas")).style).position="fixed",u.top=u.left=u.right=u.margin=u.padding=0,u.zIndex=100001,u.display="none",l.className&&s.classList.add(l.className),t.body.appendChild(s),d="resize",c=i,(u=e).addEventListener?u.addEventListener(d,c,!1):u.attachEvent?u.attachEvent("on"+d,c):u.onresize=c),s.style.opacity=1,s.style.display="block",h.progress(0),l.autoRun&&function t(){n=e.requestAnimationFrame(t),h.progress("+"+.05*Math.pow(1-Math.sqrt(o),2))}())},progress:function(e){return void 0===e||("string"==typeof e&&(e=(0<=e.indexOf("+")||0<=e.indexOf("-")?o:0)+parseFloat(e)),o=1<e?1:e,i()),o},hide:function(){a&&(a=!1,null!=n&&(e.cancelAnimationFrame(n),n=null),function t(){return 1<=h.progress("+.1")&&(s.style.opacity-=.05,s.style.opacity<=.05)?(s.style.display="none",void(r=null)):void(r=e.requestAnimationFrame(t))}())}};Xe.exports=h}.call(ot,window,document);var lt=at.exports;function ht(){const e=document.querySelector("meta[name='csrf-token']");if(e)return e.getAttribute("content");const t=document.cookie.split("; ").find((e=>e.startsWith("_live_view_csrf_token=")));return t?decodeURIComponent(t.slice(t.indexOf("=")+1)):null}const dt=new class{constructor(e,t,i={}){if(this.unloaded=!1,!t||"Object"===t.constructor.name)throw new Error('\n      a phoenix Socket must be provided as the second argument to the LiveSocket constructor. For example:\n\n          import {Socket} from "phoenix"\n          import {LiveSocket} from "phoenix_live_view"\n          let liveSocket = new LiveSocket("/live", Socket, {...})\n      ');this.socket=new t(e,i),this.bindingPrefix=i.bindingPrefix||"phx-",this.opts=i,this.params=Se(i.params||{}),this.viewLogger=i.viewLogger,this.metadataCallbacks=i.metadata||{},this.defaults=Object.assign(Te(me),i.defaults||{}),this.activeElement=null,this.prevActive=null,this.silenced=!1,this.main=null,this.outgoingMainEl=null,this.clickStartedAtTarget=null,this.linkRef=1,this.roots={},this.href=window.location.href,this.pendingLink=null,this.currentLocation=Te(window.location),this.hooks=i.hooks||{},this.uploaders=i.uploaders||{},this.loaderTimeout=i.loaderTimeout||1,this.reloadWithJitterTimer=null,this.maxReloads=i.maxReloads||10,this.reloadJitterMin=i.reloadJitterMin||5e3,this.reloadJitterMax=i.reloadJitterMax||1e4,this.failsafeJitter=i.failsafeJitter||3e4,this.localStorage=i.localStorage||window.localStorage,this.sessionStorage=i.sessionStorage||window.sessionStorage,this.boundTopLevelEvents=!1,this.domCallbacks=Object.assign({onNodeAdded:Se(),onBeforeElUpdated:Se()},i.dom||{}),this.transitions=new rt,window.addEventListener("pagehide",(e=>{this.unloaded=!0})),this.socket.onOpen((()=>{this.isUnloaded()&&window.location.reload()}))}isProfileEnabled(){return"true"===this.sessionStorage.getItem(ce)}isDebugEnabled(){return"true"===this.sessionStorage.getItem(de)}isDebugDisabled(){return"false"===this.sessionStorage.getItem(de)}enableDebug(){this.sessionStorage.setItem(de,"true")}enableProfiling(){this.sessionStorage.setItem(ce,"true")}disableDebug(){this.sessionStorage.setItem(de,"false")}disableProfiling(){this.sessionStorage.removeItem(ce)}enableLatencySim(e){this.enableDebug(),console.log("latency simulator enabled for the duration of this browser session. Call disableLatencySim() to disable"),this.sessionStorage.setItem(ue,e)}disableLatencySim(){this.sessionStorage.removeItem(ue)}getLatencySim(){let e=this.sessionStorage.getItem(ue);return e?parseInt(e):null}getSocket(){return this.socket}connect(){"localhost"!==window.location.hostname||this.isDebugDisabled()||this.enableDebug();let e=()=>{this.joinRootViews()?(this.bindTopLevelEvents(),this.socket.connect()):this.main&&this.socket.connect()};["complete","loaded","interactive"].indexOf(document.readyState)>=0?e():document.addEventListener("DOMContentLoaded",(()=>e()))}disconnect(e){clearTimeout(this.reloadWithJitterTimer),this.socket.disconnect(e)}replaceTransport(e){clearTimeout(this.reloadWithJitterTimer),this.socket.replaceTransport(e),this.connect()}execJS(e,t,i=null){this.owner(e,(s=>it.exec(i,t,s,e)))}triggerDOM(e,t){this.domCallbacks[e](...t)}time(e,t){if(!this.isProfileEnabled()||!console.time)return t();console.time(e);let i=t();return console.timeEnd(e),i}log(e,t,i){if(this.viewLogger){let[s,n]=i();this.viewLogger(e,t,s,n)}else if(this.isDebugEnabled()){let[s,n]=i();((e,t,i,s)=>{e.liveSocket.isDebugEnabled()&&console.log(`${e.id} ${t}: ${i} - `,s)})(e,t,s,n)}}requestDOMUpdate(e){this.transitions.after(e)}transition(e,t,i=function(){}){this.transitions.addTransition(e,t,i)}onChannel(e,t,i){e.on(t,(e=>{let t=this.getLatencySim();t?(console.log(`simulating ${t}ms of latency from server to client`),setTimeout((()=>i(e)),t)):i(e)}))}wrapPush(e,t,i){let s=this.getLatencySim(),n=e.joinCount;if(!s)return this.isConnected()&&t.timeout?i().receive("timeout",(()=>{e.joinCount!==n||e.isDestroyed()||this.reloadWithJitter(e,(()=>{this.log(e,"timeout",(()=>["received timeout while communicating with server. Falling back to hard refresh for recovery"]))}))})):i();console.log(`simulating ${s}ms of latency from client to server`);let r={receives:[],receive(e,t){this.receives.push([e,t])}};return setTimeout((()=>{e.isDestroyed()||r.receives.reduce(((e,[t,i])=>e.receive(t,i)),i())}),s),r}reloadWithJitter(e,t){clearTimeout(this.reloadWithJitterTimer),this.disconnect();let i=this.reloadJitterMin,s=this.reloadJitterMax,n=Math.floor(Math.random()*(s-i+1))+i,r=Ie.updateLocal(this.localStorage,window.location.pathname,T,0,(e=>e+1));r>this.maxReloads&&(n=this.failsafeJitter),this.reloadWithJitterTimer=setTimeout((()=>{e.isDestroyed()||e.isConnected()||(e.destroy(),t?t():this.log(e,"join",(()=>[`encountered ${r} consecutive reloads`])),r>this.maxReloads&&this.log(e,"join",(()=>[`exceeded ${this.maxReloads} consecutive reloads. Entering failsafe mode`])),this.hasPendingLink()?window.location=this.pendingLink:window.location.reload())}),n)}getHookCallbacks(e){return e&&e.startsWith("Phoenix.")?Ue[e.split(".")[1]]:this.hooks[e]}isUnloaded(){return this.unloaded}isConnected(){return this.socket.isConnected()}getBindingPrefix(){return this.bindingPrefix}binding(e){return`${this.getBindingPrefix()}${e}`}channel(e,t){return this.socket.channel(e,t)}joinRootViews(){let e=!1;return Ne.all(document,`${ee}:not([${V}])`,(t=>{if(!this.getRootById(t.id)){let e=this.newRootView(t);e.setHref(this.getHref()),e.join(),t.getAttribute(W)&&(this.main=e)}e=!0})),e}redirect(e,t){this.disconnect(),Ie.redirect(e,t)}replaceMain(e,t,i=null,s=this.setPendingLink(e)){this.outgoingMainEl=this.outgoingMainEl||this.main.el;let n=Ne.cloneNode(this.outgoingMainEl,"");this.main.showLoader(this.loaderTimeout),this.main.destroy(),this.main=this.newRootView(n,t),this.main.setRedirect(e),this.transitionRemoves(),this.main.join(((e,t)=>{1===e&&this.commitPendingLink(s)&&this.requestDOMUpdate((()=>{Ne.findPhxSticky(document).forEach((e=>n.appendChild(e))),this.outgoingMainEl.replaceWith(n),this.outgoingMainEl=null,i&&requestAnimationFrame(i),t()}))}))}transitionRemoves(e){let t=this.binding("remove");(e=e||Ne.all(document,`[${t}]`)).forEach((e=>{document.body.contains(e)&&this.execJS(e,e.getAttribute(t),"remove")}))}isPhxView(e){return e.getAttribute&&null!==e.getAttribute(Z)}newRootView(e,t){let i=new nt(e,this,null,t);return this.roots[i.id]=i,i}owner(e,t){let i=Re(e.closest(ee),(e=>this.getViewByEl(e)))||this.main;i&&t(i)}withinOwners(e,t){this.owner(e,(i=>t(i,e)))}getViewByEl(e){let t=e.getAttribute(z);return Re(this.getRootById(t),(t=>t.getDescendentByEl(e)))}getRootById(e){return this.roots[e]}destroyAllViews(){for(let e in this.roots)this.roots[e].destroy(),delete this.roots[e];this.main=null}destroyViewByEl(e){let t=this.getRootById(e.getAttribute(z));t&&t.id===e.id?(t.destroy(),delete this.roots[t.id]):t&&t.destroyDescendent(e.id)}setActiveElement(e){if(this.activeElement===e)return;this.activeElement=e;let t=()=>{e===this.activeElement&&(this.activeElement=null),e.removeEventListener("mouseup",this),e.removeEventListener("touchend",this)};e.addEventListener("mouseup",t),e.addEventListener("touchend",t)}getActiveElement(){return document.activeElement===document.body?this.activeElement||document.activeElement:document.activeElement||document.body}dropActiveElement(e){this.prevActive&&e.ownsElement(this.prevActive)&&(this.prevActive=null)}restorePreviouslyActiveFocus(){this.prevActive&&this.prevActive!==document.body&&this.prevActive.focus()}blurActiveElement(){this.prevActive=this.getActiveElement(),this.prevActive!==document.body&&this.prevActive.blur()}bindTopLevelEvents(){this.boundTopLevelEvents||(this.boundTopLevelEvents=!0,this.socket.onClose((e=>{e&&1e3===e.code&&this.main&&this.reloadWithJitter(this.main)})),document.body.addEventListener("click",(function(){})),window.addEventListener("pageshow",(e=>{e.persisted&&(this.getSocket().disconnect(),this.withPageLoading({to:window.location.href,kind:"redirect"}),window.location.reload())}),!0),this.bindNav(),this.bindClicks(),this.bindForms(),this.bind({keyup:"keyup",keydown:"keydown"},((e,t,i,s,n,r)=>{let o=s.getAttribute(this.binding("key")),a=e.key&&e.key.toLowerCase();if(o&&o.toLowerCase()!==a)return;let l={key:e.key,...this.eventMeta(t,e,s)};it.exec(t,n,i,s,["push",{data:l}])})),this.bind({blur:"focusout",focus:"focusin"},((e,t,i,s,n,r)=>{if(!r){let r={key:e.key,...this.eventMeta(t,e,s)};it.exec(t,n,i,s,["push",{data:r}])}})),this.bind({blur:"blur",focus:"focus"},((e,t,i,s,n,r,o)=>{if("window"===o){let n=this.eventMeta(t,e,s);it.exec(t,r,i,s,["push",{data:n}])}})),window.addEventListener("dragover",(e=>e.preventDefault())),window.addEventListener("drop",(e=>{e.preventDefault();let t=Re(xe(e.target,this.binding(N)),(e=>e.getAttribute(this.binding(N)))),i=t&&document.getElementById(t),s=Array.from(e.dataTransfer.files||[]);i&&!i.disabled&&0!==s.length&&i.files instanceof FileList&&(je.trackFiles(i,s),i.dispatchEvent(new Event("input",{bubbles:!0})))})),this.on(D,(e=>{let t=e.target;if(!Ne.isUploadInput(t))return;let i=Array.from(e.detail.files||[]).filter((e=>e instanceof File||e instanceof Blob));je.trackFiles(t,i),t.dispatchEvent(new Event("input",{bubbles:!0}))})))}eventMeta(e,t,i){let s=this.metadataCallbacks[e];return s?s(t,i):{}}setPendingLink(e){return this.linkRef++,this.pendingLink=e,this.linkRef}commitPendingLink(e){return this.linkRef===e&&(this.href=this.pendingLink,this.pendingLink=null,!0)}getHref(){return this.href}hasPendingLink(){return!!this.pendingLink}bind(e,t){for(let i in e){let s=e[i];this.on(s,(e=>{let n=this.binding(i),r=this.binding(`window-${i}`),o=e.target.getAttribute&&e.target.getAttribute(n);o?this.debounce(e.target,e,s,(()=>{this.withinOwners(e.target,(s=>{t(e,i,s,e.target,o,null)}))})):Ne.all(document,`[${r}]`,(n=>{let o=n.getAttribute(r);this.debounce(n,e,s,(()=>{this.withinOwners(n,(s=>{t(e,i,s,n,o,"window")}))}))}))}))}}bindClicks(){window.addEventListener("mousedown",(e=>this.clickStartedAtTarget=e.target)),this.bindClick("click","click",!1),this.bindClick("mousedown","capture-click",!0)}bindClick(e,t,i){let s=this.binding(t);window.addEventListener(e,(e=>{let t=null;if(i)t=e.target.matches(`[${s}]`)?e.target:e.target.querySelector(`[${s}]`);else{let i=this.clickStartedAtTarget||e.target;t=xe(i,s),this.dispatchClickAway(e,i),this.clickStartedAtTarget=null}let n=t&&t.getAttribute(s);n&&("#"===t.getAttribute("href")&&e.preventDefault(),this.debounce(t,e,"click",(()=>{this.withinOwners(t,(i=>{it.exec("click",n,i,t,["push",{data:this.eventMeta("click",e,t)}])}))})))}),i)}dispatchClickAway(e,t){let i=this.binding("click-away");Ne.all(document,`[${i}]`,(s=>{s.isSameNode(t)||s.contains(t)||this.withinOwners(e.target,(t=>{let n=s.getAttribute(i);it.isVisible(s)&&it.exec("click",n,t,s,["push",{data:this.eventMeta("click",e,e.target)}])}))}))}bindNav(){if(!Ie.canPushState())return;history.scrollRestoration&&(history.scrollRestoration="manual");let e=null;window.addEventListener("scroll",(t=>{clearTimeout(e),e=setTimeout((()=>{Ie.updateCurrentState((e=>Object.assign(e,{scroll:window.scrollY})))}),100)})),window.addEventListener("popstate",(e=>{if(!this.registerNewLocation(window.location))return;let{type:t,id:i,root:s,scroll:n}=e.state||{},r=window.location.href;this.requestDOMUpdate((()=>{this.main.isConnected()&&"patch"===t&&i===this.main.id?this.main.pushLinkPatch(r,null):this.replaceMain(r,null,(()=>{s&&this.replaceRootHistory(),"number"==typeof n&&setTimeout((()=>{window.scrollTo(0,n)}),0)}))}))}),!1),window.addEventListener("click",(e=>{let t=xe(e.target,P),i=t&&t.getAttribute(P),s=e.metaKey||e.ctrlKey||1===e.button;if(!i||!this.isConnected()||!this.main||s)return;let n=t.href,r=t.getAttribute("data-phx-link-state");e.preventDefault(),e.stopImmediatePropagation(),this.pendingLink!==n&&this.requestDOMUpdate((()=>{if("patch"===i)this.pushHistoryPatch(n,r,t);else{if("redirect"!==i)throw new Error(`expected data-phx-link to be "patch" or "redirect", got: ${i}`);this.historyRedirect(n,r)}}))}),!1)}dispatchEvent(e,t={}){Ne.dispatchEvent(window,`phx:${e}`,{detail:t})}dispatchEvents(e){e.forEach((([e,t])=>this.dispatchEvent(e,t)))}withPageLoading(e,t){Ne.dispatchEvent(window,"phx:page-loading-start",{detail:e});let i=()=>Ne.dispatchEvent(window,"phx:page-loading-stop",{detail:e});return t?t(i):i}pushHistoryPatch(e,t,i){this.withPageLoading({to:e,kind:"patch"},(s=>{this.main.pushLinkPatch(e,i,(i=>{this.historyPatch(e,t,i),s()}))}))}historyPatch(e,t,i=this.setPendingLink(e)){this.commitPendingLink(i)&&(Ie.pushState(t,{type:"patch",id:this.main.id},e),this.registerNewLocation(window.location))}historyRedirect(e,t,i){let s=window.scrollY;this.withPageLoading({to:e,kind:"redirect"},(n=>{this.replaceMain(e,i,(()=>{Ie.pushState(t,{type:"redirect",id:this.main.id,scroll:s},e),this.registerNewLocation(window.location),n()}))}))}replaceRootHistory(){Ie.pushState("replace",{root:!0,type:"patch",id:this.main.id})}registerNewLocation(e){let{pathname:t,search:i}=this.currentLocation;return t+i!==e.pathname+e.search&&(this.currentLocation=Te(e),!0)}bindForms(){let e=0;this.on("submit",(e=>{let t=e.target.getAttribute(this.binding("submit"));t&&(e.preventDefault(),e.target.disabled=!0,this.withinOwners(e.target,(i=>{it.exec("submit",t,i,e.target,["push",{}])})))}),!1);for(let t of["change","input"])this.on(t,(i=>{let s=this.binding("change"),n=i.target,r=n.getAttribute(s),o=n.form&&n.form.getAttribute(s),a=r||o;if(!a)return;if("number"===n.type&&n.validity&&n.validity.badInput)return;let l=r?n:n.form,h=e;e++;let{at:d,type:c}=Ne.private(n,"prev-iteration")||{};d===h-1&&t!==c||(Ne.putPrivate(n,"prev-iteration",{at:h,type:t}),this.debounce(n,i,t,(()=>{this.withinOwners(l,(e=>{Ne.putPrivate(n,K,!0),Ne.isTextualInput(n)||this.setActiveElement(n),it.exec("change",a,e,n,["push",{_target:i.target.name,dispatcher:l}])}))})))}),!1)}debounce(e,t,i,s){if("blur"===i||"focusout"===i)return s();let n=this.binding("debounce"),r=this.binding("throttle"),o=this.defaults.debounce.toString(),a=this.defaults.throttle.toString();this.withinOwners(e,(i=>{Ne.debounce(e,t,n,o,r,a,(()=>!i.isDestroyed()&&document.body.contains(e)),(()=>{s()}))}))}silenceEvents(e){this.silenced=!0,e(),this.silenced=!1}on(e,t){window.addEventListener(e,(e=>{this.silenced||t(e)}))}}("/",class{constructor(t,b){const k=new class{constructor(t,n={}){this.stateChangeCallbacks={open:[],close:[],error:[],message:[]},this.channels=[],this.sendBuffer=[],this.ref=0,this.timeout=n.timeout||1e4,this.transport=n.transport||s.WebSocket||A,this.establishedConnections=0,this.defaultEncoder=S.encode.bind(S),this.defaultDecoder=S.decode.bind(S),this.closeWasClean=!1,this.binaryType=n.binaryType||"arraybuffer",this.connectClock=1,this.transport!==A?(this.encode=n.encode||this.defaultEncoder,this.decode=n.decode||this.defaultDecoder):(this.encode=this.defaultEncoder,this.decode=this.defaultDecoder);let r=null;i&&i.addEventListener&&(i.addEventListener("pagehide",(e=>{this.conn&&(this.disconnect(),r=this.connectClock)})),i.addEventListener("pageshow",(e=>{r===this.connectClock&&(r=null,this.connect())}))),this.heartbeatIntervalMs=n.heartbeatIntervalMs||3e4,this.rejoinAfterMs=e=>n.rejoinAfterMs?n.rejoinAfterMs(e):[1e3,2e3,5e3][e-1]||1e4,this.reconnectAfterMs=e=>n.reconnectAfterMs?n.reconnectAfterMs(e):[10,50,100,150,200,250,500,1e3,2e3][e-1]||5e3,this.logger=n.logger||null,this.longpollerTimeout=n.longpollerTimeout||2e4,this.params=e(n.params||{}),this.endPoint=`${t}/${y}`,this.vsn=n.vsn||"2.0.0",this.heartbeatTimer=null,this.pendingHeartbeatRef=null,this.reconnectTimer=new E((()=>{this.teardown((()=>this.connect()))}),this.reconnectAfterMs)}getLongPollTransport(){return A}replaceTransport(e){this.connectClock++,this.closeWasClean=!0,this.reconnectTimer.reset(),this.sendBuffer=[],this.conn&&(this.conn.close(),this.conn=null),this.transport=e}protocol(){return location.protocol.match(/^https/)?"wss":"ws"}endPointURL(){let e=C.appendParams(C.appendParams(this.endPoint,this.params()),{vsn:this.vsn});return"/"!==e.charAt(0)?e:"/"===e.charAt(1)?`${this.protocol()}:${e}`:`${this.protocol()}://${location.host}${e}`}disconnect(e,t,i){this.connectClock++,this.closeWasClean=!0,this.reconnectTimer.reset(),this.teardown(e,t,i)}connect(t){t&&(console&&console.log("passing params to connect is deprecated. Instead pass :params to the Socket constructor"),this.params=e(t)),this.conn||(this.connectClock++,this.closeWasClean=!1,this.conn=new this.transport(this.endPointURL()),this.conn.binaryType=this.binaryType,this.conn.timeout=this.longpollerTimeout,this.conn.onopen=()=>this.onConnOpen(),this.conn.onerror=e=>this.onConnError(e),this.conn.onmessage=e=>this.onConnMessage(e),this.conn.onclose=e=>this.onConnClose(e))}log(e,t,i){this.logger(e,t,i)}hasLogger(){return null!==this.logger}onOpen(e){let t=this.makeRef();return this.stateChangeCallbacks.open.push([t,e]),t}onClose(e){let t=this.makeRef();return this.stateChangeCallbacks.close.push([t,e]),t}onError(e){let t=this.makeRef();return this.stateChangeCallbacks.error.push([t,e]),t}onMessage(e){let t=this.makeRef();return this.stateChangeCallbacks.message.push([t,e]),t}ping(e){if(!this.isConnected())return!1;let t=this.makeRef(),i=Date.now();this.push({topic:"phoenix",event:"heartbeat",payload:{},ref:t});let s=this.onMessage((n=>{n.ref===t&&(this.off([s]),e(Date.now()-i))}));return!0}onConnOpen(){this.hasLogger()&&this.log("transport",`connected to ${this.endPointURL()}`),this.closeWasClean=!1,this.establishedConnections++,this.flushSendBuffer(),this.reconnectTimer.reset(),this.resetHeartbeat(),this.stateChangeCallbacks.open.forEach((([,e])=>e()))}heartbeatTimeout(){this.pendingHeartbeatRef&&(this.pendingHeartbeatRef=null,this.hasLogger()&&this.log("transport","heartbeat timeout. Attempting to re-establish connection"),this.abnormalClose("heartbeat timeout"))}resetHeartbeat(){this.conn&&this.conn.skipHeartbeat||(this.pendingHeartbeatRef=null,clearTimeout(this.heartbeatTimer),setTimeout((()=>this.sendHeartbeat()),this.heartbeatIntervalMs))}teardown(e,t,i){if(!this.conn)return e&&e();this.waitForBufferDone((()=>{this.conn&&(t?this.conn.close(t,i||""):this.conn.close()),this.waitForSocketClosed((()=>{this.conn&&(this.conn.onclose=function(){},this.conn=null),e&&e()}))}))}waitForBufferDone(e,t=1){5!==t&&this.conn&&this.conn.bufferedAmount?setTimeout((()=>{this.waitForBufferDone(e,t+1)}),150*t):e()}waitForSocketClosed(e,t=1){5!==t&&this.conn&&this.conn.readyState!==a?setTimeout((()=>{this.waitForSocketClosed(e,t+1)}),150*t):e()}onConnClose(e){let t=e&&e.code;this.hasLogger()&&this.log("transport","close",e),this.triggerChanError(),clearTimeout(this.heartbeatTimer),this.closeWasClean||1e3===t||this.reconnectTimer.scheduleTimeout(),this.stateChangeCallbacks.close.forEach((([,t])=>t(e)))}onConnError(e){this.hasLogger()&&this.log("transport",e);let t=this.transport,i=this.establishedConnections;this.stateChangeCallbacks.error.forEach((([,s])=>{s(e,t,i)})),(t===this.transport||i>0)&&this.triggerChanError()}triggerChanError(){this.channels.forEach((e=>{e.isErrored()||e.isLeaving()||e.isClosed()||e.trigger(f)}))}connectionState(){switch(this.conn&&this.conn.readyState){case n:return"connecting";case r:return"open";case o:return"closing";default:return"closed"}}isConnected(){return"open"===this.connectionState()}remove(e){this.off(e.stateChangeRefs),this.channels=this.channels.filter((t=>t.joinRef()!==e.joinRef()))}off(e){for(let t in this.stateChangeCallbacks)this.stateChangeCallbacks[t]=this.stateChangeCallbacks[t].filter((([t])=>-1===e.indexOf(t)))}channel(t,i={}){let s=new class{constructor(t,i,s){this.state=l,this.topic=t,this.params=e(i||{}),this.socket=s,this.bindings=[],this.bindingRef=0,this.timeout=this.socket.timeout,this.joinedOnce=!1,this.joinPush=new w(this,g,this.params,this.timeout),this.pushBuffer=[],this.stateChangeRefs=[],this.rejoinTimer=new E((()=>{this.socket.isConnected()&&this.rejoin()}),this.socket.rejoinAfterMs),this.stateChangeRefs.push(this.socket.onError((()=>this.rejoinTimer.reset()))),this.stateChangeRefs.push(this.socket.onOpen((()=>{this.rejoinTimer.reset(),this.isErrored()&&this.rejoin()}))),this.joinPush.receive("ok",(()=>{this.state=d,this.rejoinTimer.reset(),this.pushBuffer.forEach((e=>e.send())),this.pushBuffer=[]})),this.joinPush.receive("error",(()=>{this.state=h,this.socket.isConnected()&&this.rejoinTimer.scheduleTimeout()})),this.onClose((()=>{this.rejoinTimer.reset(),this.socket.hasLogger()&&this.socket.log("channel",`close ${this.topic} ${this.joinRef()}`),this.state=l,this.socket.remove(this)})),this.onError((e=>{this.socket.hasLogger()&&this.socket.log("channel",`error ${this.topic}`,e),this.isJoining()&&this.joinPush.reset(),this.state=h,this.socket.isConnected()&&this.rejoinTimer.scheduleTimeout()})),this.joinPush.receive("timeout",(()=>{this.socket.hasLogger()&&this.socket.log("channel",`timeout ${this.topic} (${this.joinRef()})`,this.joinPush.timeout),new w(this,v,e({}),this.timeout).send(),this.state=h,this.joinPush.reset(),this.socket.isConnected()&&this.rejoinTimer.scheduleTimeout()})),this.on(m,((e,t)=>{this.trigger(this.replyEventName(t),e)}))}join(e=this.timeout){if(this.joinedOnce)throw new Error("tried to join multiple times. 'join' can only be called a single time per channel instance");return this.timeout=e,this.joinedOnce=!0,this.rejoin(),this.joinPush}onClose(e){this.on(p,e)}onError(e){return this.on(f,(t=>e(t)))}on(e,t){let i=this.bindingRef++;return this.bindings.push({event:e,ref:i,callback:t}),i}off(e,t){this.bindings=this.bindings.filter((i=>!(i.event===e&&(void 0===t||t===i.ref))))}canPush(){return this.socket.isConnected()&&this.isJoined()}push(e,t,i=this.timeout){if(t=t||{},!this.joinedOnce)throw new Error(`tried to push '${e}' to '${this.topic}' before joining. Use channel.join() before pushing events`);let s=new w(this,e,(function(){return t}),i);return this.canPush()?s.send():(s.startTimeout(),this.pushBuffer.push(s)),s}leave(t=this.timeout){this.rejoinTimer.reset(),this.joinPush.cancelTimeout(),this.state=u;let i=()=>{this.socket.hasLogger()&&this.socket.log("channel",`leave ${this.topic}`),this.trigger(p,"leave")},s=new w(this,v,e({}),t);return s.receive("ok",(()=>i())).receive("timeout",(()=>i())),s.send(),this.canPush()||s.trigger("ok",{}),s}onMessage(e,t,i){return t}isMember(e,t,i,s){return!(this.topic!==e||s&&s!==this.joinRef()&&(this.socket.hasLogger()&&this.socket.log("channel","dropping outdated message",{topic:e,event:t,payload:i,joinRef:s}),1))}joinRef(){return this.joinPush.ref}rejoin(e=this.timeout){this.isLeaving()||(this.socket.leaveOpenTopic(this.topic),this.state=c,this.joinPush.resend(e))}trigger(e,t,i,s){let n=this.onMessage(e,t,i,s);if(t&&!n)throw new Error("channel onMessage callbacks must return the payload, modified or unmodified");let r=this.bindings.filter((t=>t.event===e));for(let e=0;e<r.length;e++)r[e].callback(n,i,s||this.joinRef())}replyEventName(e){return`chan_reply_${e}`}isClosed(){return this.state===l}isErrored(){return this.state===h}isJoined(){return this.state===d}isJoining(){return this.state===c}isLeaving(){return this.state===u}}(t,i,this);return this.channels.push(s),s}push(e){if(this.hasLogger()){let{topic:t,event:i,payload:s,ref:n,join_ref:r}=e;this.log("push",`${t} ${i} (${r}, ${n})`,s)}this.isConnected()?this.encode(e,(e=>this.conn.send(e))):this.sendBuffer.push((()=>this.encode(e,(e=>this.conn.send(e)))))}makeRef(){let e=this.ref+1;return e===this.ref?this.ref=0:this.ref=e,this.ref.toString()}sendHeartbeat(){this.pendingHeartbeatRef&&!this.isConnected()||(this.pendingHeartbeatRef=this.makeRef(),this.push({topic:"phoenix",event:"heartbeat",payload:{},ref:this.pendingHeartbeatRef}),this.heartbeatTimer=setTimeout((()=>this.heartbeatTimeout()),this.heartbeatIntervalMs))}abnormalClose(e){this.closeWasClean=!1,this.isConnected()&&this.conn.close(1e3,e)}flushSendBuffer(){this.isConnected()&&this.sendBuffer.length>0&&(this.sendBuffer.forEach((e=>e())),this.sendBuffer=[])}onConnMessage(e){this.decode(e.data,(e=>{let{topic:t,event:i,payload:s,ref:n,join_ref:r}=e;n&&n===this.pendingHeartbeatRef&&(clearTimeout(this.heartbeatTimer),this.pendingHeartbeatRef=null,setTimeout((()=>this.sendHeartbeat()),this.heartbeatIntervalMs)),this.hasLogger()&&this.log("receive",`${s.status||""} ${t} ${i} ${n&&"("+n+")"||""}`,s);for(let e=0;e<this.channels.length;e++){const o=this.channels[e];o.isMember(t,i,s,r)&&o.trigger(i,s,n,r)}for(let t=0;t<this.stateChangeCallbacks.message.length;t++){let[,i]=this.stateChangeCallbacks.message[t];i(e)}}))}leaveOpenTopic(e){let t=this.channels.find((t=>t.topic===e&&(t.isJoined()||t.isJoining())));t&&(this.hasLogger()&&this.log("transport",`leaving duplicate topic "${e}"`),t.leave())}}(t,b);return k.endPoint=t,k}},{hooks:window.liveViewHooks||{},params:e=>({_csrf_token:ht(),_auth_token:sessionStorage.getItem("live_view_auth_token"),_timezone:Lt,_statics:Zs[e.id]&&Zs[e.id].fingerprint,_serializer:"msgpack"}),metadata:{click:(e,t)=>({detail:e.detail})}});var Zs={};function Mp(e){const t=new DataView(e.buffer,e.byteOffset,e.byteLength),i=new TextDecoder;let s=0;const n=e=>(s+=e,s-e),o=t=>i.decode(e.subarray(n(t),s)),r=e=>Array.from({length:e},(()=>l())),a=e=>{const t={};for(let i=0;i<e;i++){const e=l();t[e]=l()}return t};function l(){const i=t.getUint8(n(1));if(i<128)return i;if(i<144)return a(15&i);if(i<160)return r(15&i);if(i<192)return o(31&i);if(i>=224)return i-256;switch(i){case 192:return null;case 194:return!1;case 195:return!0;case 196:return e.slice(n(t.getUint8(n(1))),s);case 197:return e.slice(n(t.getUint16(n(2))),s);case 198:return e.slice(n(t.getUint32(n(4))),s);case 202:return t.getFloat32(n(4));case 203:return t.getFloat64(n(8));case 204:return t.getUint8(n(1));case 205:return t.getUint16(n(2));case 206:return t.getUint32(n(4));case 207:return Number(t.getBigUint64(n(8)));case 208:return t.getInt8(n(1));case 209:return t.getInt16(n(2));case 210:return t.getInt32(n(4));case 211:return Number(t.getBigInt64(n(8)));case 217:return o(t.getUint8(n(1)));case 218:return o(t.getUint16(n(2)));case 219:return o(t.getUint32(n(4)));case 220:return r(t.getUint16(n(2)));case 221:return r(t.getUint32(n(4)));case 222:return a(t.getUint16(n(2)));case 223:return a(t.getUint32(n(4)));default:throw new Error(`unsupported MessagePack type ${i}`)}}return l()}function Sf(e,t){if(e&&t&&"object"==typeof e&&"object"==typeof t){Array.isArray(e)||["s","p"].forEach((i=>{!(i in e)&&i in t&&(e[i]=t[i])}));for(const i in e)"s"!==i&&"p"!==i&&Sf(e[i],t[i])}}function jS(e){const t=((e.payload||{}).response||{}).rendered;if(!t||"string"!=typeof t.sf)return e;const i=e.topic.replace(/^lv:/,""),s=t.sf;delete t.sf;const n=Zs[i];return!("s"in t)&&n&&n.fingerprint===s&&Sf(t,n.rendered),Zs[i]={fingerprint:s,rendered:JSON.parse(JSON.stringify(t))},e}function ft(e){const t=e.payload||{},i=t.response||{},n="diff"===e.event?t:i.diff||i.rendered;if(!n||"string"!=typeof n.st)return e;const s=document.getElementById(e.topic.replace(/^lv:/,""));return s&&s.setAttribute("data-phx-static",n.st),delete n.st,e}function pt(e,t){if(Array.isArray(e))return e.map((e=>pt(e,t)));if(null===e||"object"!=typeof e)return e;if("number"==typeof e.i&&1===Object.keys(e).length)return t[e.i];for(const i in e)e[i]=pt(e[i],t);return e}function Pt(e){const t=e.payload||{},i=t.response||{},n="diff"===e.event?t:i.diff||i.rendered;if(!n||!Array.isArray(n.i))return e;const s=n.i;delete n.i;for(const e in n)"r"!==e&&"e"!==e&&(n[e]=pt(n[e],s));return e}function ut(e,t){if(!e)return!1;const i="form"===t.type?e.form:null;return e.hasAttribute("phx-no-reply")||!!i&&i.hasAttribute("phx-no-reply")}function yt(){let e=document.getElementById("live-view-toasts");return e||(e=document.createElement("div"),e.id="live-view-toasts",e.setAttribute("aria-live","polite"),document.body.appendChild(e)),e}const xt=new WeakSet;function kt(e){return Array.from(e.elements).filter((e=>e.name&&"hidden"!==e.type&&"password"!==e.type))}function St(e){const t=new URLSearchParams;for(const i of kt(e))("checkbox"!==i.type&&"radio"!==i.type||i.checked)&&t.append(i.name,i.value);localStorage.setItem("live_view_draft:"+e.dataset.autosave,t.toString())}function Et(e){xt.add(e);const t=localStorage.getItem("live_view_draft:"+e.dataset.autosave);if(null===t)return;const i=new URLSearchParams(t),n=kt(e);for(const e of n)"checkbox"===e.type||"radio"===e.type?e.checked=i.getAll(e.name).includes(e.value):i.has(e.name)&&(e.value=i.get(e.name));n[0]&&n[0].dispatchEvent(new Event("input",{bubbles:!0}))}let Ct=0,Ot=null;function At(e){const t=e.className,i=e.textContent;return(e.dataset.optimisticClass||"").split(" ").filter((e=>e)).forEach((t=>e.classList.toggle(t))),null!=e.dataset.optimisticText&&(e.textContent=e.dataset.optimisticText),()=>{e.className=t,null!=e.dataset.optimisticText&&(e.textContent=i)}}const Lt=Intl.DateTimeFormat().resolvedOptions().timeZone;document.cookie=`_live_view_timezone=${encodeURIComponent(Lt)}; Path=/; SameSite=Lax`;function mt(e){return e.getClientRects().length>0}function gt(){const e=Array.from(document.querySelectorAll("[data-focus-trap]")).filter(mt);return e[e.length-1]}function vt(e){return Array.from(e.querySelectorAll("a[href], button:not([disabled]), input:not([disabled]), select:not([disabled]), textarea:not([disabled]), [tabindex]:not([tabindex='-1'])")).filter(mt)}(function(e){const t=e.newRootView.bind(e);let i=!1;e.newRootView=(e,s)=>{const n=t(e,s);if(i)return n;i=!0;const o=Object.getPrototypeOf(n),r=o.pushWithReply;return o.pushWithReply=function(e,t,i,s){const n=e&&(()=>{const s=e();return"event"===t&&ut(s[1][0],i)&&(i.no_reply=!0),s});return r.call(this,n,t,i,s)},n}}(dt),function(e){const t=e.channel.bind(e);e.channel=(e,i)=>{const s=t(e,i),n=s.push.bind(s);return s.push=(e,t,i)=>{if("event"!==e||!t.no_reply)return n(e,t,i);const s=n(e,t,i);return s.trigger("ok",{}),s},s}})(dt.socket),function(e){const t=e.channel.bind(e);e.channel=(e,i)=>{const s=t(e,i),n=s.push.bind(s);return s.push=(e,t,i)=>{const o=Ot;return"event"===e&&o?(Ot=null,t.no_reply?n(e,t,i):n(e,{...t,optimistic_ref:o.ref},i).receive("ok",(e=>{const t=e.optimistic||{};t.ref===o.ref&&t.accepted||o.revert()})).receive("error",(()=>o.revert())).receive("timeout",(()=>o.revert()))):n(e,t,i)},s}}(dt.socket),window.addEventListener("click",(e=>{const t=e.target.closest&&e.target.closest("[phx-click][data-optimistic-class], [phx-click][data-optimistic-text]");if(!t)return;const i={ref:++Ct,revert:At(t)};Ot=i,setTimeout((()=>{Ot===i&&(Ot=null,i.revert())}),0)}),!0),function(e){const t=e.decode;e.decode=(e,i)=>{if(!(e instanceof ArrayBuffer)||149!==new Uint8Array(e)[0])return t(e,i);const[s,n,o,r,a]=Mp(new Uint8Array(e));return i({join_ref:s,ref:n,topic:o,event:r,payload:a})}}(dt.socket),function(e){const t=e.decode;e.decode=(e,i)=>t(e,(e=>i(Pt(e))))}(dt.socket),function(e){const t=e.decode;e.decode=(e,i)=>t(e,(e=>i(ft(e))))}(dt.socket),function(e){const t=e.decode;e.decode=(e,i)=>t(e,(e=>i(jS(e))))}(dt.socket),window.addEventListener("phx:live_view:auth_token",(e=>{sessionStorage.setItem("live_view_auth_token",e.detail.token),dt.main.channel.push("auth_ack",{})})),window.addEventListener("phx:live_view:session",(e=>{dt.main.el.setAttribute("data-phx-session",e.detail.session);const t=document.querySelector("meta[name='csrf-token']");t&&t.setAttribute("content",e.detail.csrf_token),fetch(window.location.href,{cache:"reload",credentials:"same-origin"}).catch((()=>{}))})),window.addEventListener("phx:live_view:notify",(e=>{const{level:t,message:i,timeout:n}=e.detail,s=document.createElement("div");s.className=`toast toast-${t}`,s.setAttribute("role","error"===t?"alert":"status");const r=document.createElement("span");r.className="toast-message",r.textContent=i;const o=document.createElement("button");o.className="toast-close",o.type="button",o.setAttribute("aria-label","Close"),o.textContent="×",o.addEventListener("click",(()=>s.remove())),s.append(r,o),yt().appendChild(s),null!=n&&setTimeout((()=>s.remove()),n)})),window.addEventListener("phx:live_view:download",(e=>{const{url:t,content:i,content_type:n,filename:s}=e.detail;let r=t;if(null!=i){const e=Uint8Array.from(atob(i),(e=>e.charCodeAt(0)));r=URL.createObjectURL(new Blob([e],{type:n}))}const o=document.createElement("a");o.href=r,o.download=s||"",document.body.appendChild(o),o.click(),o.remove(),null!=i&&setTimeout((()=>URL.revokeObjectURL(r)),0)})),window.addEventListener("input",(e=>{const t=e.target.form;t&&t.dataset.autosave&&(clearTimeout(t.autosaveTimer),t.autosaveTimer=setTimeout((()=>St(t)),300))})),window.addEventListener("submit",(e=>{e.target.dataset.autosave&&(clearTimeout(e.target.autosaveTimer),localStorage.removeItem("live_view_draft:"+e.target.dataset.autosave))})),window.addEventListener("phx:page-loading-stop",(()=>{document.querySelectorAll("form[data-autosave]").forEach((e=>xt.has(e)||Et(e)))})),window.addEventListener("keydown",(e=>{const t=gt();if("Tab"!==e.key||!t)return;const i=vt(t),n=i[0],s=i[i.length-1],r=!t.contains(document.activeElement);n?e.shiftKey&&(r||document.activeElement===n)?(e.preventDefault(),s.focus()):e.shiftKey||!r&&document.activeElement!==s||(e.preventDefault(),n.focus()):e.preventDefault()})),new MutationObserver((()=>{const e=gt();if(e&&!e.contains(document.activeElement)){const[t]=vt(e);t&&t.focus()}})).observe(document.body,{childList:!0,subtree:!0}),document.addEventListener("phx:update",(()=>{document.querySelectorAll("[data-stream-deleted]").forEach((e=>{e.getAttribute("data-stream-deleted").split(" ").forEach((t=>{const i=t&&document.getElementById(t);i&&i.parentNode===e&&i.remove()}))}))})),["input","change"].forEach((e=>{document.addEventListener(e,(e=>{const t=e.target;if(!(t instanceof Element)||t.hasAttribute("phx-debounce")||t.hasAttribute("phx-throttle"))return;const i=t.closest("[data-default-debounce], [data-default-throttle]");i&&["debounce","throttle"].forEach((e=>{const n=i.getAttribute(`data-default-${e}`);n&&t.setAttribute(`phx-${e}`,n)}))}),!0)})),lt.config({barColors:{0:"#29d"},shadowColor:"rgba(0, 0, 0, .3)"}),window.addEventListener("phx:page-loading-start",(e=>lt.show())),window.addEventListener("phx:page-loading-stop",(e=>lt.hide())),dt.connect(),"production"!==process.env.NODE_ENV&&dt.enableDebug(),window.liveSocket=dt}));
@@ -8,7 +8,7 @@ use submillisecond_live_view::prelude::*;
 
 fn main() -> std::io::Result<()> {
     Application::new(router! {
-        GET "/" => Clock::handler("examples/clock.html", "#app")
+        GET "/" => Clock::handler().layout("examples/clock.html", "#app")
         "/static" => static_router!("./static")
     })
     .serve("127.0.0.1:3000")
@@ -25,39 +25,44 @@ struct Clock {
 
 impl LiveView for Clock {
     type Events = (Tick, ChangeTimezone, ChangeTickFrequency);
+    type Message = ();
 
-    fn mount(_uri: Uri, socket: Option<Socket>) -> Self {
-        let ticker = if let Some(socket) = socket.clone() {
-            let ticker = Process::spawn_link(socket, |mut socket, mailbox: Mailbox<u64>| {
-                let mut update_frequency = 500;
-                loop {
-                    match mailbox.receive_timeout(Duration::from_millis(update_frequency)) {
-                        Ok(ms) => {
-                            update_frequency = ms;
-                        }
-                        Err(MailboxError::TimedOut) => {
-                            socket.send_event(Tick {}).unwrap();
-                        }
-                        err => panic!("{err:?}"),
-                    }
-                }
-            });
-            // TODO: Use this code when <https://github.com/lunatic-solutions/lunatic-rs/pull/88> is merged and published.
-            // let ticker = spawn_link!(|socket, mailbox: Mailbox<u64>| {});
-            Some(ticker)
-        } else {
-            None
-        };
-
-        Clock {
-            socket,
+    fn mount(_uri: Uri, _socket: Option<Socket>) -> Result<Self, MountError> {
+        Ok(Clock {
+            socket: None,
             tick_frequency: 500,
-            ticker,
-            time: Utc::now()
-                .with_timezone(&chrono_tz::UTC)
+            ticker: None,
+            time: local_time(&Utc::now())
                 .format("%A, %H:%M:%S%.3f")
                 .to_string(),
-            timezone: chrono_tz::UTC,
+            timezone: viewer_timezone(),
+        })
+    }
+
+    fn after_join(&mut self, socket: &mut Socket) {
+        let ticker = Process::spawn_link(socket.clone(), |mut socket, mailbox: Mailbox<u64>| {
+            let mut update_frequency = 500;
+            loop {
+                match mailbox.receive_timeout(Duration::from_millis(update_frequency)) {
+                    Ok(ms) => {
+                        update_frequency = ms;
+                    }
+                    Err(MailboxError::TimedOut) => {
+                        socket.send_event(Tick {}).unwrap();
+                    }
+                    err => panic!("{err:?}"),
+                }
+            }
+        });
+        // TODO: Use this code when <https://github.com/lunatic-solutions/lunatic-rs/pull/88> is merged and published.
+        // let ticker = spawn_link!(|socket, mailbox: Mailbox<u64>| {});
+        self.socket = Some(socket.clone());
+        self.ticker = Some(ticker);
+    }
+
+    fn terminate(&mut self, _reason: TerminateReason) {
+        if let Some(ticker) = self.ticker.take() {
+            ticker.kill();
         }
     }
 
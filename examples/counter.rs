@@ -4,7 +4,7 @@ use submillisecond_live_view::prelude::*;
 
 fn main() -> std::io::Result<()> {
     Application::new(router! {
-        GET "/" => Counter::handler("examples/counter.html", "#app")
+        GET "/" => Counter::handler().layout("examples/counter.html", "#app")
         "/static" => static_router!("./static")
     })
     .serve("127.0.0.1:3000")
@@ -17,9 +17,10 @@ struct Counter {
 
 impl LiveView for Counter {
     type Events = (Increment, Decrement);
+    type Message = ();
 
-    fn mount(_uri: Uri, _socket: Option<Socket>) -> Self {
-        Counter { count: 0 }
+    fn mount(_uri: Uri, _socket: Option<Socket>) -> Result<Self, MountError> {
+        Ok(Counter { count: 0 })
     }
 
     fn render(&self) -> Rendered {
@@ -5,7 +5,7 @@ use uuid::Uuid;
 
 fn main() -> std::io::Result<()> {
     Application::new(router! {
-        GET "/" => Todos::handler("examples/todos.html", "#app")
+        GET "/" => Todos::handler().layout("examples/todos.html", "#app")
         "/static" => static_router!("./static")
     })
     .serve("127.0.0.1:3000")
@@ -27,12 +27,13 @@ impl LiveView for Todos {
         ClearCompleted,
         SetFilter,
     );
+    type Message = ();
 
-    fn mount(_uri: Uri, _socket: Option<Socket>) -> Self {
-        Todos {
+    fn mount(_uri: Uri, _socket: Option<Socket>) -> Result<Self, MountError> {
+        Ok(Todos {
             filter: Filter::All,
             todos: vec![Todo::new("Hello".to_string())],
-        }
+        })
     }
 
     fn render(&self) -> Rendered {
@@ -202,13 +203,7 @@ impl Todos {
                 label for="toggle-all" { "Mark all as complete" }
                 ul.todo-list {
                     @for todo in visible_todos {
-                        @let classes = match (todo.completed, todo.editing) {
-                            (true, true) => "completed editing",
-                            (true, false) => "completed",
-                            (false, true) => "editing",
-                            (false, false) => "",
-                        };
-                        li class=(classes) {
+                        li class=(classes![("completed", todo.completed), ("editing", todo.editing)]) {
                             @let id = todo.id.to_string();
                             form
                                 method="post"
@@ -261,10 +256,9 @@ impl Todos {
                 ul.filters {
                     @for (label, filter, selected) in filter_links {
                         li {
-                            @let selected_class = if selected { "selected" } else { "" };
                             @let filter_value = serde_json::to_string(&filter).unwrap();
                             a
-                                class=(selected_class)
+                                class=(classes![("selected", selected)])
                                 href={"#/" (label)}
                                 :filter=(filter_value.trim_matches('"'))
                                 @click=(SetFilter)
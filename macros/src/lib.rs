@@ -0,0 +1,248 @@
+//! Derive macros for submillisecond-live-view.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitInt, LitStr, Path};
+
+/// Derives `LiveForm` for a struct with named fields.
+///
+/// Fields accept `#[form(label = "...", input = "...")]` to override the label
+/// and input type, and `#[form(skip)]` to leave them out of the form. The
+/// struct accepts `#[form(submit = "...")]` to set the submit button's label,
+/// `#[form(autosave = "...")]` to save drafts under a key and
+/// `#[form(debounce = 300)]` to debounce changes by milliseconds.
+#[proc_macro_derive(LiveForm, attributes(form))]
+pub fn derive_live_form(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    live_form(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct FormAttrs {
+    label: Option<LitStr>,
+    input: Option<LitStr>,
+    submit: Option<LitStr>,
+    autosave: Option<LitStr>,
+    debounce: Option<LitInt>,
+    skip: bool,
+}
+
+fn live_form(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "LiveForm can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "LiveForm can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut form_fields = Vec::new();
+    for field in fields {
+        let attrs = form_attrs(&field.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().expect("named field");
+        let name = ident.to_string();
+        let label = match attrs.label {
+            Some(label) => label.value(),
+            None => label_from_name(&name),
+        };
+        let input_type = match attrs.input {
+            Some(input) => quote!(::std::option::Option::Some(#input)),
+            None => quote!(::std::option::Option::None),
+        };
+        form_fields.push(quote! {
+            submillisecond_live_view::FormField::new(#name, #label, #input_type, &self.#ident)
+        });
+    }
+
+    let struct_attrs = form_attrs(&input.attrs)?;
+    let submit_label = struct_attrs.submit.map(|submit| {
+        quote! {
+            fn submit_label() -> &'static str {
+                #submit
+            }
+        }
+    });
+    let autosave_key = struct_attrs.autosave.map(|autosave| {
+        quote! {
+            fn autosave_key() -> ::std::option::Option<&'static str> {
+                ::std::option::Option::Some(#autosave)
+            }
+        }
+    });
+    let debounce = struct_attrs.debounce.map(|debounce| {
+        quote! {
+            fn debounce() -> ::std::option::Option<u32> {
+                ::std::option::Option::Some(#debounce)
+            }
+        }
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics submillisecond_live_view::LiveForm for #ident #ty_generics #where_clause {
+            fn fields(&self) -> ::std::vec::Vec<submillisecond_live_view::FormField> {
+                ::std::vec![#( #form_fields ),*]
+            }
+
+            #submit_label
+            #autosave_key
+            #debounce
+        }
+    })
+}
+
+fn form_attrs(attrs: &[syn::Attribute]) -> syn::Result<FormAttrs> {
+    let mut form_attrs = FormAttrs::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("form")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("label") {
+                form_attrs.label = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("input") {
+                form_attrs.input = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("submit") {
+                form_attrs.submit = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("autosave") {
+                form_attrs.autosave = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("debounce") {
+                form_attrs.debounce = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("skip") {
+                form_attrs.skip = true;
+            } else {
+                return Err(meta.error(
+                    "expected `label`, `input`, `submit`, `autosave`, `debounce` or `skip`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(form_attrs)
+}
+
+/// Derives `LiveViewEvent` for an event, handled by a LiveView with a method.
+///
+/// Each `#[event(live_view = Counter)]` attribute implements the event for a
+/// LiveView, calling the LiveView's method named after the event in snake
+/// case, such as `Counter::increment` for `Increment`. Another function can be
+/// set with `#[event(live_view = Counter, handler = Counter::add_one)]`, taking
+/// `&mut Counter` and the event.
+///
+/// Unit structs also get a `Deserialize` implementation ignoring the event's
+/// value, as they can't otherwise be deserialized from it.
+#[proc_macro_derive(LiveViewEvent, attributes(event))]
+pub fn derive_live_view_event(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    live_view_event(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn live_view_event(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let mut impls = Vec::new();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("event"))
+    {
+        let mut live_view: Option<Path> = None;
+        let mut handler: Option<Path> = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("live_view") {
+                live_view = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("handler") {
+                handler = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("expected `live_view` or `handler`"));
+            }
+            Ok(())
+        })?;
+        let live_view =
+            live_view.ok_or_else(|| syn::Error::new_spanned(attr, "missing `live_view`"))?;
+        let handler = match handler {
+            Some(handler) => quote!(#handler),
+            None => {
+                let method = syn::Ident::new(&snake_case(&ident.to_string()), ident.span());
+                quote!(<#live_view>::#method)
+            }
+        };
+        impls.push(quote! {
+            impl submillisecond_live_view::LiveViewEvent<#ident> for #live_view {
+                fn handle(state: &mut Self, event: #ident) {
+                    #handler(state, event)
+                }
+            }
+        });
+    }
+    if impls.is_empty() {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "expected an `#[event(live_view = ...)]` attribute",
+        ));
+    }
+
+    let deserialize = match &input.data {
+        Data::Struct(data) if matches!(data.fields, Fields::Unit) => Some(quote! {
+            impl<'de> submillisecond_live_view::__private::serde::Deserialize<'de> for #ident {
+                fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+                where
+                    D: submillisecond_live_view::__private::serde::Deserializer<'de>,
+                {
+                    <submillisecond_live_view::__private::serde::de::IgnoredAny
+                        as submillisecond_live_view::__private::serde::Deserialize>::deserialize(
+                            deserializer,
+                        )?;
+                    ::std::result::Result::Ok(#ident)
+                }
+            }
+        }),
+        _ => None,
+    };
+
+    Ok(quote! {
+        #( #impls )*
+        #deserialize
+    })
+}
+
+/// Turns a type name such as `SetName` into a method name such as `set_name`.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// Turns a field name such as `first_name` into a label such as `First name`.
+fn label_from_name(name: &str) -> String {
+    let name = name.trim_start_matches("r#").replace('_', " ");
+    let mut chars = name.trim().chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,390 @@
+//! Ephemeral activity of users, such as typing or cursor positions.
+
+use std::any::type_name;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use lunatic::serializer::Json;
+use lunatic::{Mailbox, MailboxError, MessageSignal, Process, ProcessDiedSignal};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::rendered::Rendered;
+use crate::socket::Socket;
+use crate::{self as submillisecond_live_view, html};
+
+const ACTIVITY_PROCESS_ID: &str = "e3a95c07-81f4-4d2b-b6a0-5f9c2d7e41b8";
+
+/// How long activity is shown after its last update by default.
+const DEFAULT_TTL: Duration = Duration::from_secs(5);
+
+/// Minimum time between broadcasts of a topic's activity.
+const THROTTLE: Duration = Duration::from_millis(250);
+
+/// A topic of ephemeral activity, such as who is typing in a chat room, held
+/// by a named process.
+///
+/// Each LiveView joins the topic as a user, and receives an
+/// [`ActivityChanged`] event with the activity of the other users whenever it
+/// changes. Activity expires unless it's updated again, and broadcasts are
+/// throttled, so updates can be sent on every keystroke or mouse move.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Clone, PartialEq, Serialize, Deserialize)]
+/// struct Typing;
+///
+/// impl LiveView for Chat {
+///     type Events = (Type, ActivityChanged<Typing>);
+///
+///     fn after_join(&mut self, socket: &mut Socket) {
+///         let topic = ActivityTopic::open(&self.room_id);
+///         self.typing = Some(topic.join(socket, &self.user_name));
+///     }
+///
+///     fn render(&self) -> Rendered {
+///         html! {
+///             input phx-no-reply @keyup=(Type) {}
+///             @if let Some(typing) = &self.typing {
+///                 @(typing.render_typing())
+///             }
+///         }
+///     }
+///     // ...
+/// }
+///
+/// impl LiveViewEvent<Type> for Chat {
+///     fn handle(state: &mut Self, _event: Type) {
+///         if let Some(typing) = &state.typing {
+///             typing.update(Typing);
+///         }
+///     }
+/// }
+///
+/// impl LiveViewEvent<ActivityChanged<Typing>> for Chat {
+///     fn handle(state: &mut Self, event: ActivityChanged<Typing>) {
+///         if let Some(typing) = &mut state.typing {
+///             typing.handle(event);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ActivityTopic<M>
+where
+    M: Clone + Serialize + DeserializeOwned,
+{
+    id: String,
+    process: Process<ActivityMessage<M>, Json>,
+}
+
+/// A LiveView's membership of an [`ActivityTopic`], with the activity of the
+/// other users.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Activity<M>
+where
+    M: Clone + Serialize + DeserializeOwned,
+{
+    topic: ActivityTopic<M>,
+    /// Id of the LiveView's event handler.
+    sender: u64,
+    others: Vec<UserActivity<M>>,
+}
+
+/// Event sent to the LiveViews of an [`ActivityTopic`] when the activity of
+/// other users changes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityChanged<M> {
+    /// Id of the topic.
+    pub id: String,
+    /// Activity of the other users.
+    pub others: Vec<UserActivity<M>>,
+}
+
+/// The current activity of a user.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserActivity<M> {
+    /// The user, as given when joining the topic.
+    pub user: String,
+    /// The activity, such as a cursor position.
+    pub meta: M,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+enum ActivityMessage<M>
+where
+    M: Clone + Serialize + DeserializeOwned,
+{
+    Subscribe(Box<Socket>, String),
+    Update { sender: u64, meta: M, ttl: Duration },
+    Clear(u64),
+}
+
+struct Entry<M> {
+    meta: M,
+    expires_at: Instant,
+}
+
+struct Subscriber<M> {
+    user: String,
+    forwarder: Process<ActivityChanged<M>, Json>,
+}
+
+impl<M> ActivityTopic<M>
+where
+    M: Clone + Serialize + DeserializeOwned,
+{
+    /// Opens a topic by its id, starting it if it isn't running.
+    pub fn open(id: &str) -> Self {
+        let name = Self::process_name(id);
+        let process = Process::lookup(&name).unwrap_or_else(|| {
+            let process = Process::spawn(id.to_string(), activity::<M>);
+            process.register(&name);
+            process
+        });
+        ActivityTopic {
+            id: id.to_string(),
+            process,
+        }
+    }
+
+    /// Returns the id of the topic.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Joins the topic as a user, receiving [`ActivityChanged`] events.
+    ///
+    /// Activity of the same user in other LiveViews, such as other tabs, isn't
+    /// included.
+    pub fn join(&self, socket: &Socket, user: impl Into<String>) -> Activity<M> {
+        self.process.send(ActivityMessage::Subscribe(
+            Box::new(socket.clone()),
+            user.into(),
+        ));
+        Activity {
+            topic: self.clone(),
+            sender: socket.event_handler.process().id(),
+            others: Vec::new(),
+        }
+    }
+
+    fn process_name(id: &str) -> String {
+        format!("{ACTIVITY_PROCESS_ID}-{}-{id}", type_name::<M>())
+    }
+}
+
+impl<M> Clone for ActivityTopic<M>
+where
+    M: Clone + Serialize + DeserializeOwned,
+{
+    fn clone(&self) -> Self {
+        ActivityTopic {
+            id: self.id.clone(),
+            process: self.process,
+        }
+    }
+}
+
+impl<M> Activity<M>
+where
+    M: Clone + Serialize + DeserializeOwned,
+{
+    /// Sets the user's activity, which expires after 5 seconds unless updated
+    /// again.
+    pub fn update(&self, meta: M) {
+        self.update_for(meta, DEFAULT_TTL);
+    }
+
+    /// Sets the user's activity, which expires after `ttl` unless updated
+    /// again.
+    pub fn update_for(&self, meta: M, ttl: Duration) {
+        self.topic.process.send(ActivityMessage::Update {
+            sender: self.sender,
+            meta,
+            ttl,
+        });
+    }
+
+    /// Clears the user's activity, such as when a message is sent.
+    pub fn clear(&self) {
+        self.topic.process.send(ActivityMessage::Clear(self.sender));
+    }
+
+    /// Returns the activity of the other users.
+    pub fn others(&self) -> &[UserActivity<M>] {
+        &self.others
+    }
+
+    /// Applies a change received from the topic, ignoring changes of other
+    /// topics.
+    pub fn handle(&mut self, event: ActivityChanged<M>) {
+        if event.id == self.topic.id {
+            self.others = event.others;
+        }
+    }
+
+    /// Renders which of the other users are active, such as "Ari is typing…".
+    ///
+    /// The element is rendered even without activity, as a live region
+    /// announcing changes to screen readers.
+    pub fn render_typing(&self) -> Rendered {
+        let text = typing_text(&self.others);
+        html! {
+            p.typing-indicator aria-live="polite" { (text) }
+        }
+    }
+}
+
+fn typing_text<M>(others: &[UserActivity<M>]) -> String {
+    match others {
+        [] => String::new(),
+        [one] => format!("{} is typing…", one.user),
+        [one, two] => format!("{} and {} are typing…", one.user, two.user),
+        others => format!("{} people are typing…", others.len()),
+    }
+}
+
+fn activity<M>(id: String, mailbox: Mailbox<ActivityMessage<M>, Json>)
+where
+    M: Clone + Serialize + DeserializeOwned,
+{
+    let mailbox = mailbox.monitorable();
+    // Subscribers and their activity, by the id of their event handler.
+    let mut subscribers: HashMap<u64, Subscriber<M>> = HashMap::new();
+    let mut entries: HashMap<u64, Entry<M>> = HashMap::new();
+    let mut changed = false;
+    let mut last_broadcast: Option<Instant> = None;
+
+    loop {
+        // Wake up for the next expiry, or to send a throttled broadcast.
+        let mut deadline = entries.values().map(|entry| entry.expires_at).min();
+        if let (true, Some(last_broadcast)) = (changed, last_broadcast) {
+            let next_broadcast = last_broadcast + THROTTLE;
+            deadline = Some(deadline.map_or(next_broadcast, |d| d.min(next_broadcast)));
+        }
+        let message = match deadline {
+            Some(deadline) => {
+                mailbox.receive_timeout(deadline.saturating_duration_since(Instant::now()))
+            }
+            None => Ok(mailbox.receive()),
+        };
+
+        match message {
+            Ok(MessageSignal::Message(ActivityMessage::Subscribe(socket, user))) => {
+                let event_handler = socket.event_handler.process();
+                subscribers.entry(event_handler.id()).or_insert_with(|| {
+                    mailbox.monitor(event_handler);
+                    Subscriber {
+                        user,
+                        forwarder: Process::spawn(*socket, forward::<M>),
+                    }
+                });
+                changed = true;
+            }
+            Ok(MessageSignal::Message(ActivityMessage::Update { sender, meta, ttl })) => {
+                if subscribers.contains_key(&sender) {
+                    let expires_at = Instant::now() + ttl;
+                    entries.insert(sender, Entry { meta, expires_at });
+                    changed = true;
+                }
+            }
+            Ok(MessageSignal::Message(ActivityMessage::Clear(sender))) => {
+                changed |= entries.remove(&sender).is_some();
+            }
+            Ok(MessageSignal::Signal(ProcessDiedSignal(id))) => {
+                changed |= entries.remove(&id).is_some();
+                if let Some(subscriber) = subscribers.remove(&id) {
+                    subscriber.forwarder.kill();
+                }
+            }
+            Err(MailboxError::TimedOut) => {}
+            Err(_) => continue,
+        }
+
+        let now = Instant::now();
+        let count = entries.len();
+        entries.retain(|_, entry| entry.expires_at > now);
+        changed |= entries.len() != count;
+
+        let throttled = last_broadcast.is_some_and(|last| now < last + THROTTLE);
+        if changed && !throttled {
+            broadcast(&id, &subscribers, &entries);
+            changed = false;
+            last_broadcast = Some(now);
+        }
+    }
+}
+
+/// Sends each subscriber the activity of the other users.
+fn broadcast<M>(
+    id: &str,
+    subscribers: &HashMap<u64, Subscriber<M>>,
+    entries: &HashMap<u64, Entry<M>>,
+) where
+    M: Clone + Serialize + DeserializeOwned,
+{
+    for subscriber in subscribers.values() {
+        let mut others: Vec<_> = entries
+            .iter()
+            .filter_map(|(sender, entry)| {
+                let user = &subscribers.get(sender)?.user;
+                (*user != subscriber.user).then(|| UserActivity {
+                    user: user.clone(),
+                    meta: entry.meta.clone(),
+                })
+            })
+            .collect();
+        others.sort_by(|a, b| a.user.cmp(&b.user));
+        others.dedup_by(|a, b| a.user == b.user);
+        subscriber.forwarder.send(ActivityChanged {
+            id: id.to_string(),
+            others,
+        });
+    }
+}
+
+/// Sends the latest activity to a subscriber, without blocking the topic
+/// while the LiveView re-renders.
+fn forward<M>(mut socket: Socket, mailbox: Mailbox<ActivityChanged<M>, Json>)
+where
+    M: Serialize + DeserializeOwned,
+{
+    loop {
+        let mut event = mailbox.receive();
+        // Skip activity replaced while the LiveView was busy.
+        while let Ok(newer) = mailbox.receive_timeout(Duration::ZERO) {
+            event = newer;
+        }
+        if socket.send_event(event).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{typing_text, UserActivity};
+
+    #[test]
+    fn typing_text_names_up_to_two_users() {
+        let user = |user: &str| UserActivity {
+            user: user.to_string(),
+            meta: (),
+        };
+        assert_eq!(typing_text::<()>(&[]), "");
+        assert_eq!(typing_text(&[user("Ari")]), "Ari is typing…");
+        assert_eq!(
+            typing_text(&[user("Ari"), user("Bo")]),
+            "Ari and Bo are typing…"
+        );
+        assert_eq!(
+            typing_text(&[user("Ari"), user("Bo"), user("Cy")]),
+            "3 people are typing…"
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! Static assets with fingerprinted URLs.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::{fs, io};
+
+use serde::{Deserialize, Serialize};
+
+use crate::maud::hash;
+
+thread_local! {
+    /// Static assets of the handler serving the LiveView in the current process.
+    static STATIC_ASSETS: RefCell<StaticAssets> = RefCell::new(StaticAssets::default());
+}
+
+/// A manifest of static assets, versioning their URLs by the hash of their
+/// content.
+///
+/// URLs returned by [`static_path`] end with a `vsn` query holding the hash,
+/// so they change whenever the asset does. Assets rendered with the
+/// `phx-track-static` attribute are reported by the client when joining, and
+/// clients which loaded an older version of one, such as before a deploy,
+/// reload the page.
+///
+/// # Example
+///
+/// ```
+/// let assets = StaticAssets::load("static", "/static")?;
+///
+/// router! {
+///     GET "/" => Counter::handler()
+///         .layout("index.html", "#app")
+///         .static_assets(assets)
+/// }
+///
+/// impl LiveView for Counter {
+///     fn head(&self) -> Option<Rendered> {
+///         Some(html! {
+///             link phx-track-static rel="stylesheet" href=(static_path("/static/app.css"));
+///         })
+///     }
+///     // ...
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaticAssets {
+    /// Versioned URLs by path.
+    urls: BTreeMap<String, String>,
+}
+
+impl StaticAssets {
+    /// Creates an empty manifest.
+    pub fn new() -> Self {
+        StaticAssets::default()
+    }
+
+    /// Loads the files in `dir` and its subdirectories, served under
+    /// `url_prefix`.
+    pub fn load(dir: impl AsRef<Path>, url_prefix: &str) -> io::Result<Self> {
+        let mut assets = StaticAssets::new();
+        assets.load_dir(dir.as_ref(), url_prefix.trim_end_matches('/'))?;
+        Ok(assets)
+    }
+
+    /// Adds an asset served at `path`, versioned by its content.
+    pub fn insert(&mut self, path: impl Into<String>, content: &[u8]) {
+        let path = path.into();
+        let url = format!("{path}?vsn={}", hash([content]));
+        self.urls.insert(path, url);
+    }
+
+    /// Returns the versioned URL of an asset, or `path` unchanged if it isn't
+    /// in the manifest.
+    pub fn url(&self, path: &str) -> String {
+        self.urls
+            .get(path)
+            .cloned()
+            .unwrap_or_else(|| path.to_string())
+    }
+
+    /// Returns whether any of the URLs tracked by the client is an older
+    /// version of an asset in the manifest.
+    ///
+    /// URLs of assets which aren't in the manifest, such as scripts served by
+    /// a CDN, are ignored.
+    pub(crate) fn is_stale(&self, tracked: &[String]) -> bool {
+        tracked.iter().any(|url| {
+            let url = strip_origin(url);
+            let path = url.split_once('?').map_or(url, |(path, _)| path);
+            self.urls.get(path).is_some_and(|current| current != url)
+        })
+    }
+
+    fn load_dir(&mut self, dir: &Path, url_prefix: &str) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = format!("{url_prefix}/{}", entry.file_name().to_string_lossy());
+            if entry.file_type()?.is_dir() {
+                self.load_dir(&entry.path(), &path)?;
+            } else {
+                self.insert(path, &fs::read(entry.path())?);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the versioned URL of a static asset, from the manifest set with
+/// [`LiveViewHandler::static_assets`](crate::handler::LiveViewHandler::static_assets).
+///
+/// Paths which aren't in the manifest are returned unchanged.
+pub fn static_path(path: &str) -> String {
+    STATIC_ASSETS.with(|assets| assets.borrow().url(path))
+}
+
+/// Sets the static assets of the LiveView in the current process.
+pub(crate) fn set_static_assets(new_assets: StaticAssets) {
+    STATIC_ASSETS.with(|assets| *assets.borrow_mut() = new_assets);
+}
+
+/// Returns the path and query of a URL, which the client sends as absolute
+/// URLs.
+fn strip_origin(url: &str) -> &str {
+    match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("/", |start| &rest[start..]),
+        None => url,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StaticAssets;
+
+    #[test]
+    fn older_versions_of_tracked_assets_are_stale() {
+        let mut assets = StaticAssets::new();
+        assets.insert("/static/app.js", b"console.log(1)");
+        let loaded = format!("https://example.com{}", assets.url("/static/app.js"));
+        assert!(loaded.contains("/static/app.js?vsn="));
+        assert_eq!(assets.url("/static/other.js"), "/static/other.js");
+
+        let cdn = "https://cdn.example.com/lib.js".to_string();
+        assert!(!assets.is_stale(&[loaded.clone(), cdn.clone()]));
+
+        assets.insert("/static/app.js", b"console.log(2)");
+        assert!(assets.is_stale(&[loaded, cdn]));
+    }
+}
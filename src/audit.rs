@@ -0,0 +1,54 @@
+//! Audit trail of events handled by LiveViews.
+
+use std::time::SystemTime;
+
+use lunatic::function::FuncRef;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::identity;
+use crate::Identity;
+
+/// An event handled by a LiveView, passed to the hook registered with
+/// [`LiveViewHandler::on_audit`](crate::handler::LiveViewHandler::on_audit).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    /// Id of the LiveView process handling the connection.
+    pub connection_id: u64,
+    /// Identity authenticated with
+    /// [`LiveViewHandler::token_auth`](crate::handler::LiveViewHandler::token_auth),
+    /// if any.
+    pub identity: Option<Identity>,
+    /// Name of the event.
+    pub event: String,
+    /// When the event was handled.
+    pub timestamp: SystemTime,
+    /// Outcome of handling the event.
+    pub result: AuditResult,
+}
+
+/// Outcome of handling an event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditResult {
+    /// The event was handled by the LiveView.
+    Handled,
+    /// The event is not one of the LiveView's events.
+    UnknownEvent,
+    /// The event's payload could not be deserialized, such as a tampered
+    /// [`Signed`](crate::Signed) value.
+    InvalidPayload,
+}
+
+/// Passes a handled event to the audit hook.
+pub(crate) fn record(hook: Option<FuncRef<fn(AuditEvent)>>, event: &str, result: AuditResult) {
+    let Some(hook) = hook else {
+        return;
+    };
+
+    (hook.get())(AuditEvent {
+        connection_id: lunatic::host::process_id(),
+        identity: identity(),
+        event: event.to_string(),
+        timestamp: SystemTime::now(),
+        result,
+    });
+}
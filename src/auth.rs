@@ -0,0 +1,236 @@
+//! Short-lived auth tokens, refreshed over the live socket.
+//!
+//! The client sends its token when joining, which is verified to establish the
+//! connection's [`Identity`]. Before the identity expires, the server pushes a
+//! refreshed token to the client, and switches to the refreshed identity once
+//! the client acknowledges storing it. An identity which expires without being
+//! refreshed is cleared, re-rendering the LiveView without it.
+
+use std::cell::RefCell;
+use std::time::{Duration, SystemTime};
+
+use lunatic::function::FuncRef;
+use lunatic_log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Name of the event pushed to the client with a refreshed token.
+const AUTH_TOKEN_EVENT: &str = "live_view:auth_token";
+
+thread_local! {
+    static IDENTITY: RefCell<Option<Identity>> = const { RefCell::new(None) };
+}
+
+/// Verifies and refreshes short-lived auth tokens, such as JWTs.
+///
+/// The client reads its token from the `live_view_auth_token` session storage
+/// item, which should be set by the app when the user authenticates.
+///
+/// # Example
+///
+/// ```
+/// fn verify(token: String) -> Option<Identity> {
+///     let claims: Claims = decode_jwt(&token)?;
+///     Some(Identity::new(json!(claims), claims.expires_at()))
+/// }
+///
+/// fn refresh(identity: Identity) -> Option<String> {
+///     Some(encode_jwt(&identity.claims))
+/// }
+///
+/// router! {
+///     GET "/" => MyLiveView::handler().layout("index.html", "#app")
+///         .token_auth(TokenAuth::new(verify, refresh))
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct TokenAuth {
+    verify: FuncRef<fn(String) -> Option<Identity>>,
+    refresh: FuncRef<fn(Identity) -> Option<String>>,
+    refresh_before: Duration,
+}
+
+/// An authenticated identity, established from an auth token.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Identity {
+    /// Claims of the token, such as the user id.
+    pub claims: Value,
+    /// When the token expires.
+    pub expires_at: SystemTime,
+}
+
+/// Auth state of a connection.
+pub(crate) struct AuthState {
+    config: TokenAuth,
+    /// Refreshed identity which the client has not yet acknowledged.
+    pending: Option<Identity>,
+}
+
+impl TokenAuth {
+    /// Creates token auth from a function verifying a token, and a function
+    /// issuing a new token for an identity.
+    pub fn new(
+        verify: fn(String) -> Option<Identity>,
+        refresh: fn(Identity) -> Option<String>,
+    ) -> Self {
+        TokenAuth {
+            verify: FuncRef::new(verify),
+            refresh: FuncRef::new(refresh),
+            refresh_before: Duration::from_secs(60),
+        }
+    }
+
+    /// Sets how long before a token expires it is refreshed.
+    ///
+    /// Tokens living for less than this are refreshed halfway through their
+    /// remaining lifetime instead. Defaults to 60 seconds.
+    pub fn refresh_before(mut self, duration: Duration) -> Self {
+        self.refresh_before = duration;
+        self
+    }
+
+    fn verify(&self, token: String) -> Option<Identity> {
+        (self.verify.get())(token).filter(|identity| !identity.is_expired())
+    }
+
+    /// Returns the delay until an identity should be refreshed, which is at
+    /// least half of its remaining lifetime so short-lived tokens aren't
+    /// refreshed in a loop.
+    fn refresh_in(&self, identity: &Identity) -> Duration {
+        let remaining = identity.remaining();
+        remaining
+            .saturating_sub(self.refresh_before)
+            .max(remaining / 2)
+    }
+
+    /// Returns whether an identity is close enough to expiring to be
+    /// refreshed.
+    fn is_due(&self, identity: &Identity) -> bool {
+        identity.remaining() <= self.refresh_before
+    }
+}
+
+impl Identity {
+    /// Creates an identity from the claims of a token.
+    pub fn new(claims: Value, expires_at: SystemTime) -> Self {
+        Identity { claims, expires_at }
+    }
+
+    /// Returns true if the token has expired.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= SystemTime::now()
+    }
+
+    fn remaining(&self) -> Duration {
+        self.expires_at
+            .duration_since(SystemTime::now())
+            .unwrap_or_default()
+    }
+}
+
+impl AuthState {
+    /// Verifies the token sent when joining, returning the auth state and the
+    /// delay until the identity should be refreshed.
+    pub(crate) fn join(config: TokenAuth, token: Option<String>) -> (Self, Option<Duration>) {
+        let identity = token.and_then(|token| config.verify(token));
+        let refresh_in = identity
+            .as_ref()
+            .map(|identity| config.refresh_in(identity));
+        set_identity(identity);
+        (
+            AuthState {
+                config,
+                pending: None,
+            },
+            refresh_in,
+        )
+    }
+
+    /// Issues a refreshed token for the current identity if it's due, returning
+    /// the event to push to the client.
+    pub(crate) fn refresh(&mut self) -> Option<Value> {
+        let identity = identity().filter(|identity| self.config.is_due(identity))?;
+        let refreshed = (self.config.refresh.get())(identity)
+            .and_then(|token| Some((self.config.verify(token.clone())?, token)));
+        match refreshed {
+            Some((identity, token)) => {
+                self.pending = Some(identity);
+                Some(json!({ "e": [[AUTH_TOKEN_EVENT, { "token": token }]] }))
+            }
+            None => {
+                warn!("failed to refresh auth token");
+                None
+            }
+        }
+    }
+
+    /// Switches to the refreshed identity once the client has stored its token.
+    pub(crate) fn acknowledge(&mut self) {
+        if let Some(identity) = self.pending.take() {
+            set_identity(Some(identity));
+        }
+    }
+
+    /// Returns the delay until the current identity should be refreshed.
+    ///
+    /// Returns `None` once the identity has expired without being refreshed,
+    /// clearing it so the LiveView is no longer rendered with it.
+    pub(crate) fn refresh_in(&mut self) -> Option<Duration> {
+        match identity() {
+            Some(identity) => Some(self.config.refresh_in(&identity)),
+            None => {
+                warn!("auth token expired without being refreshed");
+                self.pending = None;
+                set_identity(None);
+                None
+            }
+        }
+    }
+}
+
+/// Returns the identity of the current LiveView process, if authenticated and
+/// not expired.
+pub fn identity() -> Option<Identity> {
+    IDENTITY.with(|identity| {
+        identity
+            .borrow()
+            .clone()
+            .filter(|identity| !identity.is_expired())
+    })
+}
+
+fn set_identity(new_identity: Option<Identity>) {
+    IDENTITY.with(|identity| *identity.borrow_mut() = new_identity);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use serde_json::Value;
+
+    use super::{Identity, TokenAuth};
+
+    fn verify(_token: String) -> Option<Identity> {
+        None
+    }
+
+    fn refresh(_identity: Identity) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn short_lived_tokens_are_refreshed_halfway() {
+        let auth = TokenAuth::new(verify, refresh).refresh_before(Duration::from_secs(60));
+        let identity =
+            |secs| Identity::new(Value::Null, SystemTime::now() + Duration::from_secs(secs));
+
+        let refresh_in = auth.refresh_in(&identity(600));
+        assert!(refresh_in > Duration::from_secs(530) && refresh_in <= Duration::from_secs(540));
+        assert!(!auth.is_due(&identity(600)));
+
+        let refresh_in = auth.refresh_in(&identity(30));
+        assert!(refresh_in > Duration::from_secs(14) && refresh_in <= Duration::from_secs(15));
+        assert!(auth.is_due(&identity(30)));
+    }
+}
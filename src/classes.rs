@@ -0,0 +1,52 @@
+//! Conditional class names.
+
+/// Joins the class names whose condition is true with spaces, for a `class`
+/// attribute.
+///
+/// Unlike toggling each class with maud's `.name[cond]` syntax, the attribute
+/// is rendered as a single dynamic, sent again only when the joined classes
+/// change.
+///
+/// # Example
+///
+/// ```
+/// html! {
+///     li class=(classes![("completed", todo.completed), ("editing", todo.editing)]) {
+///         (todo.title)
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! classes {
+    ($( ($class: expr, $cond: expr) ),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut classes = ::std::string::String::new();
+        $(
+            if $cond {
+                if !classes.is_empty() {
+                    classes.push(' ');
+                }
+                classes.push_str(::std::convert::AsRef::<str>::as_ref(&$class));
+            }
+        )*
+        classes
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn only_true_classes_are_joined() {
+        let editing = String::from("editing");
+        assert_eq!(
+            classes![("completed", true), (editing, true)],
+            "completed editing"
+        );
+        assert_eq!(
+            classes![("completed", false), ("editing", true),],
+            "editing"
+        );
+        assert_eq!(classes![("completed", false)], "");
+        assert_eq!(classes![], "");
+    }
+}
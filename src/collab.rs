@@ -0,0 +1,436 @@
+//! Collaborative editing of documents shared between LiveViews.
+
+use std::any::type_name;
+use std::collections::{HashMap, VecDeque};
+
+use lunatic::serializer::Json;
+use lunatic::{Mailbox, MessageSignal, Process, ProcessDiedSignal, Tag};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::socket::Socket;
+
+const DOCUMENT_PROCESS_ID: &str = "b81d5f2e-6c47-4a03-9d1e-72f0a8c3e615";
+
+/// Number of operations kept for rebasing concurrent operations.
+const HISTORY_LEN: usize = 1000;
+
+/// A document edited collaboratively, by applying operations.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct Board {
+///     cards: Vec<Card>,
+/// }
+///
+/// #[derive(Clone, Serialize, Deserialize)]
+/// enum BoardOp {
+///     Add(Card),
+///     Move { card: u32, column: u32 },
+/// }
+///
+/// impl Document for Board {
+///     type Op = BoardOp;
+///
+///     fn apply(&mut self, op: &BoardOp) {
+///         match op {
+///             BoardOp::Add(card) => self.cards.push(card.clone()),
+///             BoardOp::Move { card, column } => self.move_card(*card, *column),
+///         }
+///     }
+/// }
+/// ```
+pub trait Document: Clone + Serialize + DeserializeOwned {
+    /// An operation changing the document.
+    type Op: Clone + Serialize + DeserializeOwned;
+
+    /// Applies an operation to the document.
+    fn apply(&mut self, op: &Self::Op);
+
+    /// Adjusts an operation made without knowing of `concurrent` operations,
+    /// which were applied first, returning `None` to reject it.
+    ///
+    /// Operations from other LiveViews are applied to a [`Replica`] as they
+    /// arrive, even if its own operations haven't been applied yet, so
+    /// replicas only converge if rebased operations have the same effect in
+    /// either order. By default operations are applied unchanged, which suits
+    /// operations such as setting a field or moving a card.
+    fn rebase(op: Self::Op, concurrent: &[Self::Op]) -> Option<Self::Op> {
+        let _ = concurrent;
+        Some(op)
+    }
+}
+
+/// A document's topic, held by a named process which applies operations in
+/// order and broadcasts them to the LiveViews editing the document.
+///
+/// LiveViews edit the document through a [`Replica`], joined in
+/// [`LiveView::after_join`](crate::LiveView::after_join), and receive a
+/// [`DocumentUpdate`] event for each operation made by other LiveViews.
+///
+/// # Example
+///
+/// ```
+/// impl LiveView for BoardPage {
+///     type Events = (MoveCard, DocumentUpdate<Board>);
+///
+///     fn after_join(&mut self, socket: &mut Socket) {
+///         let topic = DocumentTopic::open(&self.board_id, Board::default());
+///         self.board = Some(topic.join(socket));
+///     }
+///     // ...
+/// }
+///
+/// impl LiveViewEvent<MoveCard> for BoardPage {
+///     fn handle(state: &mut Self, event: MoveCard) {
+///         if let Some(board) = &mut state.board {
+///             board.apply(BoardOp::Move { card: event.card, column: event.column });
+///         }
+///     }
+/// }
+///
+/// impl LiveViewEvent<DocumentUpdate<Board>> for BoardPage {
+///     fn handle(state: &mut Self, update: DocumentUpdate<Board>) {
+///         if let Some(board) = &mut state.board {
+///             board.handle(update);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct DocumentTopic<D: Document> {
+    id: String,
+    process: Process<DocumentMessage<D>, Json>,
+}
+
+/// A LiveView's copy of a document, kept in sync with its [`DocumentTopic`].
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Replica<D: Document> {
+    topic: DocumentTopic<D>,
+    /// Id of the LiveView's event handler, excluded from its own broadcasts.
+    sender: u64,
+    version: u64,
+    document: D,
+}
+
+/// Path of [`DocumentUpdate`], which its events are named by followed by their
+/// document type.
+pub(crate) const DOCUMENT_UPDATE_EVENT: &str = concat!(module_path!(), "::DocumentUpdate");
+
+/// Event sent to a LiveView editing a document when the document changes.
+///
+/// Only the server sends it, so clients pushing an event of the same name are
+/// rejected as an unknown event.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct DocumentUpdate<D: Document> {
+    /// Id of the document.
+    pub id: String,
+    /// Version of the document after the change.
+    pub version: u64,
+    /// The change.
+    pub change: DocumentChange<D>,
+}
+
+/// A change in a [`DocumentUpdate`].
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub enum DocumentChange<D: Document> {
+    /// Another LiveView's operation was applied.
+    Applied(D::Op),
+    /// The LiveView's own operation was applied.
+    Acknowledged,
+    /// The LiveView's own operation was rejected, and its copy of the
+    /// document is replaced.
+    Reset(D),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+enum DocumentMessage<D: Document> {
+    Snapshot(Process<(u64, D), Json>, Tag),
+    Subscribe(Box<Socket>),
+    Submit { sender: u64, base: u64, op: D::Op },
+}
+
+impl<D> DocumentTopic<D>
+where
+    D: Document,
+{
+    /// Opens the topic of a document by its id, starting it with an initial
+    /// value if it isn't running.
+    ///
+    /// The topic keeps running after its LiveViews close, until
+    /// [`DocumentTopic::close`] is called.
+    pub fn open(id: &str, initial: D) -> Self {
+        let name = Self::process_name(id);
+        let process = Process::lookup(&name).unwrap_or_else(|| {
+            let process = Process::spawn((id.to_string(), initial), document::<D>);
+            process.register(&name);
+            process
+        });
+        DocumentTopic {
+            id: id.to_string(),
+            process,
+        }
+    }
+
+    /// Returns the id of the document.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the current version and value of the document.
+    pub fn snapshot(&self) -> (u64, D) {
+        let tag = Tag::new();
+        self.process
+            .send(DocumentMessage::Snapshot(unsafe { Process::this() }, tag));
+        let mailbox: Mailbox<(u64, D), Json> = unsafe { Mailbox::new() };
+        mailbox.tag_receive(&[tag])
+    }
+
+    /// Subscribes a LiveView to the document, returning its copy of the
+    /// document.
+    pub fn join(&self, socket: &Socket) -> Replica<D> {
+        self.process
+            .send(DocumentMessage::Subscribe(Box::new(socket.clone())));
+        let (version, document) = self.snapshot();
+        Replica {
+            topic: self.clone(),
+            sender: socket.event_handler.process().id(),
+            version,
+            document,
+        }
+    }
+
+    /// Stops the topic.
+    pub fn close(self) {
+        self.process.kill();
+    }
+
+    fn submit(&self, sender: u64, base: u64, op: D::Op) {
+        self.process
+            .send(DocumentMessage::Submit { sender, base, op });
+    }
+
+    fn process_name(id: &str) -> String {
+        format!("{DOCUMENT_PROCESS_ID}-{}-{id}", type_name::<D>())
+    }
+}
+
+impl<D> Clone for DocumentTopic<D>
+where
+    D: Document,
+{
+    fn clone(&self) -> Self {
+        DocumentTopic {
+            id: self.id.clone(),
+            process: self.process,
+        }
+    }
+}
+
+impl<D> Replica<D>
+where
+    D: Document,
+{
+    /// Returns the document.
+    pub fn get(&self) -> &D {
+        &self.document
+    }
+
+    /// Returns the last version of the document received.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the document's topic.
+    pub fn topic(&self) -> &DocumentTopic<D> {
+        &self.topic
+    }
+
+    /// Applies an operation to the copy, and sends it to be applied to the
+    /// document.
+    pub fn apply(&mut self, op: D::Op) {
+        self.document.apply(&op);
+        self.topic.submit(self.sender, self.version, op);
+    }
+
+    /// Applies an update received from the document's topic, ignoring updates
+    /// of other documents.
+    pub fn handle(&mut self, update: DocumentUpdate<D>) {
+        if update.id != self.topic.id || update.version <= self.version {
+            return;
+        }
+        match update.change {
+            DocumentChange::Applied(op) => self.document.apply(&op),
+            DocumentChange::Acknowledged => {}
+            DocumentChange::Reset(document) => self.document = document,
+        }
+        self.version = update.version;
+    }
+}
+
+fn document<D>((id, mut document): (String, D), mailbox: Mailbox<DocumentMessage<D>, Json>)
+where
+    D: Document,
+{
+    let mailbox = mailbox.monitorable();
+    let mut version = 0;
+    // Recent operations with their sender, the last being at `version`.
+    let mut history: VecDeque<(u64, D::Op)> = VecDeque::new();
+    // Versions each sender was last reset to, before which its operations are
+    // stale.
+    let mut resets: HashMap<u64, u64> = HashMap::new();
+    // Forwarding processes of subscribers, by the id of their event handler.
+    let mut subscribers: HashMap<u64, Process<DocumentUpdate<D>, Json>> = HashMap::new();
+
+    loop {
+        match mailbox.receive() {
+            MessageSignal::Message(DocumentMessage::Snapshot(parent, tag)) => {
+                parent.tag_send(tag, (version, document.clone()));
+            }
+            MessageSignal::Message(DocumentMessage::Subscribe(socket)) => {
+                let event_handler = socket.event_handler.process();
+                subscribers.entry(event_handler.id()).or_insert_with(|| {
+                    mailbox.monitor(event_handler);
+                    Process::spawn(*socket, forward::<D>)
+                });
+            }
+            MessageSignal::Message(DocumentMessage::Submit { sender, base, op }) => {
+                if resets.get(&sender).is_some_and(|reset| base < *reset) {
+                    continue;
+                }
+                match rebase::<D>(&history, version, sender, base, op) {
+                    Some(op) => {
+                        document.apply(&op);
+                        version += 1;
+                        history.push_back((sender, op.clone()));
+                        if history.len() > HISTORY_LEN {
+                            history.pop_front();
+                        }
+                        for (subscriber, forwarder) in &subscribers {
+                            let change = if *subscriber == sender {
+                                DocumentChange::Acknowledged
+                            } else {
+                                DocumentChange::Applied(op.clone())
+                            };
+                            forwarder.send(DocumentUpdate {
+                                id: id.clone(),
+                                version,
+                                change,
+                            });
+                        }
+                    }
+                    None => {
+                        resets.insert(sender, version);
+                        if let Some(forwarder) = subscribers.get(&sender) {
+                            forwarder.send(DocumentUpdate {
+                                id: id.clone(),
+                                version,
+                                change: DocumentChange::Reset(document.clone()),
+                            });
+                        }
+                    }
+                }
+            }
+            MessageSignal::Signal(ProcessDiedSignal(id)) => {
+                resets.remove(&id);
+                if let Some(forwarder) = subscribers.remove(&id) {
+                    forwarder.kill();
+                }
+            }
+        }
+    }
+}
+
+/// Rebases an operation made at version `base` onto the operations applied
+/// since by other senders, returning `None` if it's rejected or too old.
+fn rebase<D>(
+    history: &VecDeque<(u64, D::Op)>,
+    version: u64,
+    sender: u64,
+    base: u64,
+    op: D::Op,
+) -> Option<D::Op>
+where
+    D: Document,
+{
+    let oldest = version - history.len() as u64;
+    if base < oldest || base > version {
+        return None;
+    }
+    let concurrent: Vec<_> = history
+        .iter()
+        .skip((base - oldest) as usize)
+        .filter(|(from, _)| *from != sender)
+        .map(|(_, op)| op.clone())
+        .collect();
+    if concurrent.is_empty() {
+        Some(op)
+    } else {
+        D::rebase(op, &concurrent)
+    }
+}
+
+/// Sends updates to a subscriber in order, without blocking the topic while
+/// the LiveView re-renders.
+fn forward<D>(mut socket: Socket, mailbox: Mailbox<DocumentUpdate<D>, Json>)
+where
+    D: Document,
+{
+    loop {
+        if socket.send_event(mailbox.receive()).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::{Document, DocumentTopic};
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct List(Vec<String>);
+
+    #[derive(Clone, Serialize, Deserialize)]
+    struct Insert(usize, String);
+
+    impl Document for List {
+        type Op = Insert;
+
+        fn apply(&mut self, Insert(index, item): &Insert) {
+            self.0.insert((*index).min(self.0.len()), item.clone());
+        }
+
+        fn rebase(Insert(mut index, item): Insert, concurrent: &[Insert]) -> Option<Insert> {
+            for Insert(other, _) in concurrent {
+                if *other <= index {
+                    index += 1;
+                }
+            }
+            Some(Insert(index, item))
+        }
+    }
+
+    #[lunatic::test]
+    fn concurrent_operations_are_rebased() {
+        let topic = DocumentTopic::open("list", List::default());
+        topic.submit(1, 0, Insert(0, "a".to_string()));
+        topic.submit(2, 0, Insert(0, "b".to_string()));
+        // Sender 1's own operation isn't concurrent with it.
+        topic.submit(1, 0, Insert(1, "c".to_string()));
+        // Operations based on unknown versions are rejected.
+        topic.submit(3, 5, Insert(0, "d".to_string()));
+
+        let (version, List(items)) = topic.snapshot();
+        assert_eq!(version, 3);
+        assert_eq!(items, ["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,312 @@
+//! Stateful components with their own events, rendered within a LiveView.
+
+use std::any::{type_name, Any};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use crate::live_view::{DeserializeEventError, EventList};
+use crate::rendered::Rendered;
+use crate::socket::Event;
+
+thread_local! {
+    /// Components of the LiveView in the current process.
+    static COMPONENTS: RefCell<Components> = RefCell::new(Components::default());
+    /// Id of the component being rendered.
+    static RENDERING: Cell<Option<u32>> = const { Cell::new(None) };
+}
+
+/// A stateful component rendered within a LiveView, with its own state and
+/// events.
+///
+/// Components are rendered with [`live_component`], by a type and id unique
+/// within the LiveView. They're mounted the first time they're rendered, and
+/// updated with new props whenever the LiveView re-renders. Events are sent
+/// to a component rather than the LiveView with `phx-target=[myself()]`, and
+/// only re-render the component, sending its changes under the `c` key of the
+/// diff without diffing the rest of the LiveView.
+///
+/// A component must render a single root element, and can't be rendered
+/// within an `@for` loop.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Serialize, Deserialize)]
+/// struct Toggle {}
+///
+/// struct Card {
+///     title: String,
+///     expanded: bool,
+/// }
+///
+/// impl LiveComponent for Card {
+///     type Events = (Toggle,);
+///     type Props = String;
+///
+///     fn mount(title: String) -> Self {
+///         Card { title, expanded: false }
+///     }
+///
+///     fn update(&mut self, title: String) {
+///         self.title = title;
+///     }
+///
+///     fn render(&self) -> Rendered {
+///         html! {
+///             section {
+///                 button phx-target=[myself()] @click=(Toggle) { (self.title) }
+///                 @if self.expanded { p { "Details" } }
+///             }
+///         }
+///     }
+/// }
+///
+/// impl LiveViewEvent<Toggle> for Card {
+///     fn handle(state: &mut Self, _event: Toggle) {
+///         state.expanded = !state.expanded;
+///     }
+/// }
+///
+/// // In the LiveView's render:
+/// html! {
+///     @(live_component::<Card>("summary", "Summary".to_string()))
+/// }
+/// ```
+pub trait LiveComponent: Sized + 'static {
+    /// Events handled by the component, as a tuple of event types.
+    type Events: EventList<Self>;
+
+    /// Props passed to the component by the LiveView rendering it.
+    type Props;
+
+    /// Mounts the component when it's first rendered.
+    fn mount(props: Self::Props) -> Self;
+
+    /// Updates the component with the props of a later render of the
+    /// LiveView.
+    fn update(&mut self, props: Self::Props);
+
+    /// Renders the component.
+    fn render(&self) -> Rendered;
+}
+
+#[derive(Default)]
+struct Components {
+    /// Id of the last component mounted.
+    last_cid: u32,
+    /// Ids of components by their type name and id.
+    cids: HashMap<(&'static str, String), u32>,
+    /// Components by their id, taken while they're rendered or handle an
+    /// event.
+    components: HashMap<u32, Option<Box<dyn AnyComponent>>>,
+}
+
+trait AnyComponent {
+    fn handle_event(&mut self, event: Event) -> Result<bool, DeserializeEventError>;
+
+    fn render(&self) -> Rendered;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<C> AnyComponent for C
+where
+    C: LiveComponent,
+{
+    fn handle_event(&mut self, event: Event) -> Result<bool, DeserializeEventError> {
+        <C::Events as EventList<C>>::handle_event(self, event)
+    }
+
+    fn render(&self) -> Rendered {
+        LiveComponent::render(self)
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Renders a component, mounting it the first time it's rendered with an id
+/// and otherwise updating it with `props`.
+///
+/// Components are kept while they're rendered by the LiveView, and removed once
+/// the client no longer shows them.
+pub fn live_component<C>(id: impl Into<String>, props: C::Props) -> Rendered
+where
+    C: LiveComponent,
+{
+    let key = (type_name::<C>(), id.into());
+    let (cid, component) = COMPONENTS.with(|components| {
+        let mut components = components.borrow_mut();
+        match components.cids.get(&key).copied() {
+            Some(cid) => {
+                let component = components.components.get_mut(&cid).and_then(Option::take);
+                (cid, component)
+            }
+            None => {
+                components.last_cid += 1;
+                let cid = components.last_cid;
+                components.cids.insert(key, cid);
+                (cid, None)
+            }
+        }
+    });
+    let component = match component {
+        Some(mut component) => {
+            if let Some(component) = component.as_any_mut().downcast_mut::<C>() {
+                component.update(props);
+            }
+            component
+        }
+        // Components rendering themselves are mounted again.
+        None => Box::new(C::mount(props)),
+    };
+    let rendered = render_component(cid, component.as_ref());
+    put_component(cid, component);
+    Rendered::component(cid, rendered)
+}
+
+/// Returns the id of the component being rendered, to target its events with
+/// `phx-target=[myself()]`.
+///
+/// Returns `None` outside of a component's render, in which case events target
+/// the LiveView.
+pub fn myself() -> Option<u32> {
+    RENDERING.with(Cell::get)
+}
+
+/// Passes an event to the component it targets, returning whether the
+/// component handles it.
+pub(crate) fn handle_event(cid: u32, event: Event) -> Result<bool, DeserializeEventError> {
+    let Some(mut component) = take_component(cid) else {
+        return Ok(false);
+    };
+    let handled = component.handle_event(event);
+    put_component(cid, component);
+    handled
+}
+
+/// Renders a component alone, such as after it handled an event.
+pub(crate) fn render(cid: u32) -> Option<Rendered> {
+    let component = take_component(cid)?;
+    let rendered = render_component(cid, component.as_ref());
+    put_component(cid, component);
+    Some(rendered)
+}
+
+/// Removes components the client no longer shows, returning their ids.
+pub(crate) fn destroy(cids: &[u32], is_rendered: impl Fn(u32) -> bool) -> Vec<u32> {
+    COMPONENTS.with(|components| {
+        let mut components = components.borrow_mut();
+        let destroyed: Vec<_> = cids
+            .iter()
+            .copied()
+            .filter(|cid| !is_rendered(*cid))
+            .filter(|cid| components.components.remove(cid).is_some())
+            .collect();
+        components.cids.retain(|_, cid| !destroyed.contains(cid));
+        destroyed
+    })
+}
+
+fn render_component(cid: u32, component: &dyn AnyComponent) -> Rendered {
+    let parent = RENDERING.with(|rendering| rendering.replace(Some(cid)));
+    let rendered = component.render();
+    RENDERING.with(|rendering| rendering.set(parent));
+    rendered
+}
+
+fn take_component(cid: u32) -> Option<Box<dyn AnyComponent>> {
+    COMPONENTS.with(|components| {
+        components
+            .borrow_mut()
+            .components
+            .get_mut(&cid)
+            .and_then(Option::take)
+    })
+}
+
+fn put_component(cid: u32, component: Box<dyn AnyComponent>) {
+    COMPONENTS.with(|components| {
+        components
+            .borrow_mut()
+            .components
+            .insert(cid, Some(component));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    use super::{destroy, handle_event, live_component, myself, render, LiveComponent};
+    use crate::rendered::Rendered;
+    use crate::socket::Event;
+    use crate::{self as submillisecond_live_view, html, LiveViewEvent};
+
+    #[derive(Serialize, Deserialize)]
+    struct Increment {}
+
+    struct Counter {
+        step: i32,
+        count: i32,
+    }
+
+    impl LiveComponent for Counter {
+        type Events = (Increment,);
+        type Props = i32;
+
+        fn mount(step: i32) -> Self {
+            Counter { step, count: 0 }
+        }
+
+        fn update(&mut self, step: i32) {
+            self.step = step;
+        }
+
+        fn render(&self) -> Rendered {
+            html! { button phx-target=[myself()] { (self.count) } }
+        }
+    }
+
+    impl LiveViewEvent<Increment> for Counter {
+        fn handle(state: &mut Self, _event: Increment) {
+            state.count += state.step;
+        }
+    }
+
+    #[test]
+    fn components_keep_state_between_renders() {
+        let first = live_component::<Counter>("a", 1).to_string();
+        assert_eq!(first, r#"<button phx-target="1">0</button>"#);
+        assert_eq!(myself(), None);
+
+        let event = Event {
+            name: std::any::type_name::<Increment>().to_string(),
+            ty: "click".to_string(),
+            value: json!({}),
+            cid: Some(1),
+        };
+        assert!(handle_event(1, event.clone()).unwrap());
+        assert_eq!(
+            render(1).unwrap().to_string(),
+            r#"<button phx-target="1">1</button>"#
+        );
+
+        live_component::<Counter>("a", 5);
+        live_component::<Counter>("b", 1);
+        handle_event(1, event).unwrap();
+        assert_eq!(
+            render(1).unwrap().to_string(),
+            r#"<button phx-target="1">6</button>"#
+        );
+
+        assert_eq!(destroy(&[1, 2], |cid| cid == 2), [1]);
+        assert!(render(1).is_none());
+        assert_eq!(
+            live_component::<Counter>("a", 1).to_string(),
+            r#"<button phx-target="3">0</button>"#
+        );
+    }
+}
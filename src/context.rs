@@ -0,0 +1,254 @@
+//! Shared application state held by a named process.
+
+use std::any::type_name;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use lunatic::function::FuncRef;
+use lunatic::serializer::Json;
+use lunatic::{Mailbox, MailboxError, Process, Tag};
+use lunatic_log::error;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+const CONTEXT_PROCESS_ID: &str = "8d3b6f52-4c0e-4a9f-b1d7-2e5a9c7f3b60";
+
+/// How long a request waits for the context's response.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Shared state, such as config, caches or connection pools, held by a named
+/// process which LiveViews can query from `mount` and event handlers.
+///
+/// Each state type has a single context, started once with
+/// [`AppContext::start`] and found from any process with
+/// [`AppContext::lookup`]. The state is accessed with typed
+/// [`ContextRequest`]s, which are handled one at a time by the context's
+/// process. A request fails if the context can't handle it, such as when the
+/// context stopped after a request panicked, rather than waiting forever.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Serialize, Deserialize)]
+/// struct Config {
+///     title: String,
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct GetTitle;
+///
+/// impl ContextRequest<Config> for GetTitle {
+///     type Response = String;
+///
+///     fn handle(self, config: &mut Config) -> String {
+///         config.title.clone()
+///     }
+/// }
+///
+/// fn main() -> std::io::Result<()> {
+///     AppContext::start(Config {
+///         title: "My App".to_string(),
+///     });
+///
+///     Application::new(router! {
+///         GET "/" => MyLiveView::handler().layout("index.html", "#app")
+///     })
+///     .serve("127.0.0.1:3000")
+/// }
+///
+/// impl LiveView for MyLiveView {
+///     fn mount(_uri: Uri, _socket: Option<Socket>) -> Result<Self, MountError> {
+///         let title = AppContext::<Config>::get()
+///             .request(GetTitle)
+///             .map_err(|err| MountError::new(StatusCode::SERVICE_UNAVAILABLE, err.to_string()))?;
+///         Ok(MyLiveView { title })
+///     }
+///     // ...
+/// }
+/// ```
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct AppContext<T> {
+    process: Process<ContextMessage<T>, Json>,
+    phantom: PhantomData<T>,
+}
+
+/// A request handled by the process of an [`AppContext`].
+pub trait ContextRequest<T>: Serialize + DeserializeOwned {
+    /// Response returned to the caller.
+    type Response: Serialize + DeserializeOwned;
+
+    /// Handles the request with the context's state.
+    fn handle(self, state: &mut T) -> Self::Response;
+}
+
+/// An error requesting an [`AppContext`].
+#[derive(Clone, Debug, PartialEq, Eq, Error, Serialize, Deserialize)]
+pub enum ContextError {
+    /// The request or its response couldn't be serialized or deserialized.
+    #[error("invalid context request: {0}")]
+    Invalid(String),
+    /// The context didn't respond in time, such as when it stopped.
+    #[error("context request timed out")]
+    TimedOut,
+}
+
+/// Handles a serialized request with the state, returning the new state and
+/// the serialized response.
+type RequestHandler<T> = FuncRef<fn(T, Value) -> (T, Result<Value, ContextError>)>;
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+enum ContextMessage<T> {
+    Request(
+        Process<Result<Value, ContextError>, Json>,
+        Tag,
+        RequestHandler<T>,
+        Value,
+    ),
+}
+
+impl<T> AppContext<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    /// Starts the context with its initial state, replacing a context of the
+    /// same type if one is already running.
+    ///
+    /// The context isn't linked to the current process, so a request panicking
+    /// only stops the context.
+    pub fn start(state: T) -> Self {
+        let process = Process::spawn(state, context::<T>);
+        process.register(&Self::process_name());
+        AppContext {
+            process,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the running context of the state type, if any.
+    pub fn lookup() -> Option<Self> {
+        Process::lookup(&Self::process_name()).map(|process| AppContext {
+            process,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns the running context of the state type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the context hasn't been started.
+    pub fn get() -> Self {
+        Self::lookup().unwrap_or_else(|| {
+            panic!(
+                "AppContext<{}> should be started with AppContext::start",
+                type_name::<T>()
+            )
+        })
+    }
+
+    /// Sends a request to the context, waiting for its response.
+    ///
+    /// Returns an error if the request or response can't be serialized, or if
+    /// the context doesn't respond within 30 seconds.
+    pub fn request<R>(&self, request: R) -> Result<R::Response, ContextError>
+    where
+        R: ContextRequest<T>,
+    {
+        let request =
+            serde_json::to_value(request).map_err(|err| ContextError::Invalid(err.to_string()))?;
+        let tag = Tag::new();
+        self.process.send(ContextMessage::Request(
+            unsafe { Process::this() },
+            tag,
+            FuncRef::new(handle_request::<T, R>),
+            request,
+        ));
+        let mailbox: Mailbox<Result<Value, ContextError>, Json> = unsafe { Mailbox::new() };
+        let response = mailbox
+            .tag_receive_timeout(&[tag], REQUEST_TIMEOUT)
+            .map_err(|err| match err {
+                MailboxError::TimedOut => ContextError::TimedOut,
+                err => ContextError::Invalid(err.to_string()),
+            })??;
+        serde_json::from_value(response).map_err(|err| ContextError::Invalid(err.to_string()))
+    }
+
+    fn process_name() -> String {
+        format!("{CONTEXT_PROCESS_ID}-{}", type_name::<T>())
+    }
+}
+
+impl<T> Clone for AppContext<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for AppContext<T> {}
+
+fn context<T>(mut state: T, mailbox: Mailbox<ContextMessage<T>, Json>)
+where
+    T: Serialize + DeserializeOwned,
+{
+    loop {
+        match mailbox.receive() {
+            ContextMessage::Request(parent, tag, handler, request) => {
+                let (new_state, response) = (handler.get())(state, request);
+                state = new_state;
+                parent.tag_send(tag, response);
+            }
+        }
+    }
+}
+
+/// Handles a request of type `R`, referenced by the requesting process so the
+/// context can dispatch requests of any type.
+///
+/// Requests which can't be deserialized are returned to the caller as errors,
+/// rather than stopping the context.
+fn handle_request<T, R>(mut state: T, request: Value) -> (T, Result<Value, ContextError>)
+where
+    R: ContextRequest<T>,
+{
+    let request: R = match serde_json::from_value(request) {
+        Ok(request) => request,
+        Err(err) => {
+            error!("failed to deserialize context request: {err}");
+            return (state, Err(ContextError::Invalid(err.to_string())));
+        }
+    };
+    let response = request.handle(&mut state);
+    let response =
+        serde_json::to_value(response).map_err(|err| ContextError::Invalid(err.to_string()));
+    (state, response)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::{AppContext, ContextRequest};
+
+    #[derive(Serialize, Deserialize)]
+    struct Increment(u32);
+
+    impl ContextRequest<u32> for Increment {
+        type Response = u32;
+
+        fn handle(self, count: &mut u32) -> u32 {
+            *count += self.0;
+            *count
+        }
+    }
+
+    #[lunatic::test]
+    fn requests_share_state() {
+        AppContext::start(1u32);
+        assert_eq!(AppContext::<u32>::get().request(Increment(2)), Ok(3));
+        assert_eq!(AppContext::<u32>::get().request(Increment(4)), Ok(7));
+    }
+}
@@ -0,0 +1,304 @@
+//! Content Security Policy for dead renders.
+
+use std::fmt;
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use submillisecond::http::header::{
+    HeaderName, HeaderValue, CONTENT_SECURITY_POLICY, CONTENT_SECURITY_POLICY_REPORT_ONLY,
+};
+use submillisecond::response::Response;
+
+use crate::head::PRELOAD_ONLOAD;
+
+/// A `Content-Security-Policy` header sent with dead renders.
+///
+/// Inline scripts in the template, including the embedded LiveView JavaScript,
+/// are allowed by adding their hashes to `script-src`. Hashes are used rather
+/// than nonces so dead renders can still be revalidated with an `ETag`. The
+/// `onload` handler of a preloaded [`Stylesheet`](crate::Stylesheet) is
+/// allowed by its hash with `'unsafe-hashes'`.
+///
+/// # Example
+///
+/// ```
+/// router! {
+///     GET "/" => MyLiveView::handler().layout("index.html", "#app")
+///         .content_security_policy(
+///             ContentSecurityPolicy::strict()
+///                 .directive(Directive::ImgSrc, [Source::SelfOrigin, Source::scheme("data")]),
+///         )
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentSecurityPolicy {
+    directives: Vec<(Directive, Vec<Source>)>,
+    report_only: bool,
+}
+
+/// A CSP directive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Directive {
+    /// `default-src`
+    DefaultSrc,
+    /// `script-src`
+    ScriptSrc,
+    /// `style-src`
+    StyleSrc,
+    /// `img-src`
+    ImgSrc,
+    /// `connect-src`
+    ConnectSrc,
+    /// `font-src`
+    FontSrc,
+    /// `media-src`
+    MediaSrc,
+    /// `object-src`
+    ObjectSrc,
+    /// `frame-src`
+    FrameSrc,
+    /// `worker-src`
+    WorkerSrc,
+    /// `frame-ancestors`
+    FrameAncestors,
+    /// `base-uri`
+    BaseUri,
+    /// `form-action`
+    FormAction,
+    /// `report-uri`
+    ReportUri,
+}
+
+/// A source allowed by a CSP directive.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Source {
+    /// `'self'`
+    SelfOrigin,
+    /// `'none'`
+    None,
+    /// `'unsafe-inline'`
+    UnsafeInline,
+    /// `'unsafe-eval'`
+    UnsafeEval,
+    /// `'strict-dynamic'`
+    StrictDynamic,
+    /// `'unsafe-hashes'`, allowing hashes to match inline event handlers.
+    UnsafeHashes,
+    /// A host or URL, such as `https://cdn.example.com`.
+    Host(String),
+    /// A scheme, such as `data:`.
+    Scheme(String),
+    /// The SHA-256 hash of an inline script or style.
+    Sha256(String),
+}
+
+impl ContentSecurityPolicy {
+    /// Creates an empty policy.
+    pub fn new() -> Self {
+        ContentSecurityPolicy::default()
+    }
+
+    /// Creates a policy only allowing resources from the same origin, and
+    /// disallowing plugins, framing and changing the base URI.
+    pub fn strict() -> Self {
+        ContentSecurityPolicy::new()
+            .directive(Directive::DefaultSrc, [Source::SelfOrigin])
+            .directive(Directive::ObjectSrc, [Source::None])
+            .directive(Directive::BaseUri, [Source::SelfOrigin])
+            .directive(Directive::FrameAncestors, [Source::SelfOrigin])
+    }
+
+    /// Sets the sources allowed by a directive, replacing any set previously.
+    pub fn directive(
+        mut self,
+        directive: Directive,
+        sources: impl IntoIterator<Item = Source>,
+    ) -> Self {
+        let sources = sources.into_iter().collect();
+        match self.directives.iter_mut().find(|(d, _)| *d == directive) {
+            Some((_, existing)) => *existing = sources,
+            None => self.directives.push((directive, sources)),
+        }
+        self
+    }
+
+    /// Sends the policy with `Content-Security-Policy-Report-Only`, reporting
+    /// violations without enforcing the policy.
+    pub fn report_only(mut self) -> Self {
+        self.report_only = true;
+        self
+    }
+
+    /// Adds the policy header to a response.
+    pub(crate) fn apply(&self, response: &mut Response, script_hashes: &[String]) {
+        let (name, value) = self.header(script_hashes);
+        if value.is_empty() {
+            return;
+        }
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            response.headers_mut().insert(name, value);
+        }
+    }
+
+    fn header(&self, script_hashes: &[String]) -> (HeaderName, String) {
+        let mut directives = self.directives.clone();
+
+        // Scripts fall back to `default-src`, which the hashes are added to
+        // under `script-src` so they don't also allow other resources. Hashes
+        // disable `'unsafe-inline'`, so aren't added to policies using it.
+        let script_src = directives
+            .iter()
+            .position(|(directive, _)| *directive == Directive::ScriptSrc)
+            .or_else(|| {
+                let default_src = directives
+                    .iter()
+                    .find(|(directive, _)| *directive == Directive::DefaultSrc)?
+                    .1
+                    .clone();
+                directives.push((Directive::ScriptSrc, default_src));
+                Some(directives.len() - 1)
+            });
+        if let Some(index) = script_src {
+            let sources = &mut directives[index].1;
+            if !sources.contains(&Source::UnsafeInline) {
+                sources.retain(|source| *source != Source::None);
+                sources.extend(script_hashes.iter().cloned().map(Source::Sha256));
+                sources.push(Source::UnsafeHashes);
+                sources.push(Source::sha256(PRELOAD_ONLOAD));
+            }
+        }
+
+        let value = directives
+            .iter()
+            .map(|(directive, sources)| {
+                let mut value = directive.to_string();
+                for source in sources {
+                    value.push(' ');
+                    value.push_str(&source.to_string());
+                }
+                value
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        let name = if self.report_only {
+            CONTENT_SECURITY_POLICY_REPORT_ONLY
+        } else {
+            CONTENT_SECURITY_POLICY
+        };
+        (name, value)
+    }
+}
+
+impl Source {
+    /// Creates a host source.
+    pub fn host(host: impl Into<String>) -> Self {
+        Source::Host(host.into())
+    }
+
+    /// Creates a scheme source, with or without the trailing `:`.
+    pub fn scheme(scheme: impl Into<String>) -> Self {
+        let mut scheme = scheme.into();
+        if scheme.ends_with(':') {
+            scheme.pop();
+        }
+        Source::Scheme(scheme)
+    }
+
+    /// Creates a hash source for an inline script or style.
+    pub fn sha256(content: &str) -> Self {
+        Source::Sha256(script_hash(content))
+    }
+}
+
+impl fmt::Display for Directive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Directive::DefaultSrc => "default-src",
+            Directive::ScriptSrc => "script-src",
+            Directive::StyleSrc => "style-src",
+            Directive::ImgSrc => "img-src",
+            Directive::ConnectSrc => "connect-src",
+            Directive::FontSrc => "font-src",
+            Directive::MediaSrc => "media-src",
+            Directive::ObjectSrc => "object-src",
+            Directive::FrameSrc => "frame-src",
+            Directive::WorkerSrc => "worker-src",
+            Directive::FrameAncestors => "frame-ancestors",
+            Directive::BaseUri => "base-uri",
+            Directive::FormAction => "form-action",
+            Directive::ReportUri => "report-uri",
+        };
+        f.write_str(name)
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::SelfOrigin => f.write_str("'self'"),
+            Source::None => f.write_str("'none'"),
+            Source::UnsafeInline => f.write_str("'unsafe-inline'"),
+            Source::UnsafeEval => f.write_str("'unsafe-eval'"),
+            Source::StrictDynamic => f.write_str("'strict-dynamic'"),
+            Source::UnsafeHashes => f.write_str("'unsafe-hashes'"),
+            Source::Host(host) => f.write_str(host),
+            Source::Scheme(scheme) => write!(f, "{scheme}:"),
+            Source::Sha256(hash) => write!(f, "'sha256-{hash}'"),
+        }
+    }
+}
+
+/// Returns the base64 encoded SHA-256 hash of an inline script.
+pub(crate) fn script_hash(content: &str) -> String {
+    general_purpose::STANDARD.encode(Sha256::digest(content.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{script_hash, ContentSecurityPolicy, Directive, Source};
+    use crate::head::PRELOAD_ONLOAD;
+
+    #[test]
+    fn script_hashes_extend_default_src() {
+        let csp = ContentSecurityPolicy::strict();
+        let (name, value) = csp.header(&["abc".to_string()]);
+        assert_eq!(name, "content-security-policy");
+        assert_eq!(
+            value,
+            format!(
+                "default-src 'self'; object-src 'none'; base-uri 'self'; \
+                 frame-ancestors 'self'; script-src 'self' 'sha256-abc' 'unsafe-hashes' 'sha256-{}'",
+                script_hash(PRELOAD_ONLOAD)
+            )
+        );
+    }
+
+    #[test]
+    fn script_hashes_respect_script_src() {
+        let csp = ContentSecurityPolicy::new()
+            .directive(Directive::ScriptSrc, [Source::None])
+            .report_only();
+        let (name, value) = csp.header(&["abc".to_string()]);
+        assert_eq!(name, "content-security-policy-report-only");
+        assert_eq!(
+            value,
+            format!(
+                "script-src 'sha256-abc' 'unsafe-hashes' 'sha256-{}'",
+                script_hash(PRELOAD_ONLOAD)
+            )
+        );
+
+        let csp =
+            ContentSecurityPolicy::new().directive(Directive::ScriptSrc, [Source::UnsafeInline]);
+        assert_eq!(
+            csp.header(&["abc".to_string()]).1,
+            "script-src 'unsafe-inline'"
+        );
+        assert_eq!(
+            ContentSecurityPolicy::new().header(&["abc".to_string()]).1,
+            ""
+        );
+    }
+}
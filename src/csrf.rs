@@ -1,6 +1,9 @@
 use base64::{engine::general_purpose, Engine};
 use rand::{thread_rng, Rng};
 
+/// Name of the cookie holding the CSRF token with cookie based protection.
+pub const CSRF_COOKIE: &str = "_live_view_csrf_token";
+
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct CsrfToken {
     pub masked: String,
@@ -17,8 +20,17 @@ impl CsrfToken {
     }
 }
 
+/// Returns the value of a cookie from a `Cookie` header.
+pub fn cookie_value<'a>(header: &'a str, name: &str) -> Option<&'a str> {
+    header
+        .split(';')
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(cookie_name, _)| *cookie_name == name)
+        .map(|(_, value)| value)
+}
+
 /// Generates a crypto secure random key url-safe base64 encoded.
-fn generate_token() -> String {
+pub(crate) fn generate_token() -> String {
     let mut rng = thread_rng();
     let key: [u8; 18] = rng.gen();
     general_purpose::URL_SAFE.encode(key)
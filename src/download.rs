@@ -0,0 +1,128 @@
+//! Server-initiated file downloads.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::signed::Signed;
+
+/// A file downloaded by the client, started with
+/// [`Socket::download`](crate::socket::Socket::download).
+///
+/// Small generated files, such as CSV exports, can be sent with their content
+/// over the socket. Larger files should be served by a route, linked with a
+/// [signed URL](Download::signed) so only the LiveView can grant access to
+/// them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Download {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filename: Option<String>,
+}
+
+impl Download {
+    /// Creates a download of generated content, saved by the client with the
+    /// filename.
+    pub fn content(
+        filename: impl Into<String>,
+        content_type: impl Into<String>,
+        content: impl AsRef<[u8]>,
+    ) -> Self {
+        Download {
+            url: None,
+            content: Some(general_purpose::STANDARD.encode(content)),
+            content_type: Some(content_type.into()),
+            filename: Some(filename.into()),
+        }
+    }
+
+    /// Creates a download of a URL.
+    ///
+    /// The filename is taken from the response, unless set with
+    /// [`Download::filename`].
+    pub fn url(url: impl Into<String>) -> Self {
+        Download {
+            url: Some(url.into()),
+            content: None,
+            content_type: None,
+            filename: None,
+        }
+    }
+
+    /// Creates a download of a URL with a `token` query parameter, holding a
+    /// value signed with the app secret which expires after a duration.
+    ///
+    /// The route serving the file reads the value with
+    /// [`Download::verify`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // In the LiveView.
+    /// socket.download(Download::signed("/reports", report.id, Duration::from_secs(60)))?;
+    ///
+    /// // In the route serving "/reports".
+    /// let report_id: u64 = Download::verify(&query.token).ok_or(StatusCode::FORBIDDEN)?;
+    /// ```
+    pub fn signed<T>(url: &str, value: T, expires_in: Duration) -> Self
+    where
+        T: Serialize,
+    {
+        let expires_at = (SystemTime::now() + expires_in)
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        let token = Signed((value, expires_at)).token();
+        let separator = if url.contains('?') { '&' } else { '?' };
+        Download::url(format!("{url}{separator}token={token}"))
+    }
+
+    /// Verifies the token of a [signed](Download::signed) download, returning
+    /// its value if the signature is valid and it hasn't expired.
+    pub fn verify<T>(token: &str) -> Option<T>
+    where
+        T: DeserializeOwned,
+    {
+        // The token expires at its own time rather than a day after it's signed.
+        let Signed((value, expires_at)) = Signed::<(T, u64)>::verify_max_age(token, Duration::MAX)?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        (now < expires_at).then_some(value)
+    }
+
+    /// Sets the filename the download is saved as.
+    pub fn filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Download;
+
+    #[test]
+    fn signed_url_verifies_until_expired() {
+        let download = Download::signed("/reports?format=csv", 42u64, Duration::from_secs(60));
+        let url = download.url.unwrap();
+        let token = url.strip_prefix("/reports?format=csv&token=").unwrap();
+        assert_eq!(Download::verify::<u64>(token), Some(42));
+
+        let expired = Download::signed("/reports", 42u64, Duration::ZERO);
+        let url = expired.url.unwrap();
+        let token = url.strip_prefix("/reports?token=").unwrap();
+        assert_eq!(Download::verify::<u64>(token), None);
+    }
+}
@@ -1,15 +1,39 @@
+use std::collections::VecDeque;
+use std::ops::ControlFlow;
+use std::time::Duration;
+
+use lunatic::function::FuncRef;
 use lunatic::serializer::Json;
 use lunatic::{Mailbox, Process, Tag};
+use lunatic_log::{error, info, warn};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
+use submillisecond::http::Uri;
 use thiserror::Error;
+use tungstenite::protocol::frame::coding::CloseCode;
 
+use crate::audit::{self, AuditEvent, AuditResult};
+use crate::auth::{AuthState, TokenAuth};
+use crate::collab::DOCUMENT_UPDATE_EVENT;
+use crate::flags::{self, FeatureFlags};
+use crate::handler::{LiveViewError, LogError, UnknownEventPolicy};
+use crate::job::JobUpdated;
 use crate::manager::{Join, LiveViewManager};
-use crate::socket::{Event, JoinEvent, RawSocket, Socket};
-use crate::{EventList, LiveView};
+use crate::metrics::Metrics;
+use crate::navigation::Navigation;
+use crate::optimistic::{self, OptimisticReply};
+use crate::pubsub::BROADCAST_EVENT;
+use crate::socket::{
+    queue_client_event, set_uri, take_pushed_events, uri, Event, JoinEvent, Message, ProtocolEvent,
+    RawSocket, Socket,
+};
+use crate::timings::{self, Phase, Timings};
+use crate::{component, live_view, upload, EventList, LiveView, TerminateReason, RECOVER_EVENT};
 
 #[derive(Clone, Debug, Error, Serialize, Deserialize)]
 pub enum EventHandlerError {
+    #[error("invalid url")]
+    InvalidUrl,
     #[error("deserialize event failed")]
     DeserializeEvent,
     #[error("serialize event failed")]
@@ -22,6 +46,8 @@ pub enum EventHandlerError {
     SocketError(String),
     #[error("unknown event")]
     UnknownEvent,
+    #[error("LiveView isn't served by a handler")]
+    UnknownLiveView,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -29,8 +55,19 @@ pub(crate) struct EventHandler {
     event_handler: Process<EventHandlerMessage, Json>,
 }
 
+/// Options configured on the handler, passed to the event handler process.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct EventHandlerOptions {
+    pub(crate) unknown_events: UnknownEventPolicy,
+    pub(crate) token_auth: Option<TokenAuth>,
+    pub(crate) on_timings: Option<FuncRef<fn(Timings)>>,
+    pub(crate) on_audit: Option<FuncRef<fn(AuditEvent)>>,
+    pub(crate) on_error: Option<FuncRef<fn(LiveViewError)>>,
+}
+
+#[allow(clippy::enum_variant_names)]
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
-enum EventHandlerMessage {
+pub(crate) enum EventHandlerMessage {
     HandleJoin(
         Process<Result<Value, EventHandlerError>, Json>,
         Tag,
@@ -41,15 +78,55 @@ enum EventHandlerMessage {
         Tag,
         Event,
     ),
+    /// Runs `after_join` and loads the LiveView, pushing the diff directly on
+    /// the socket.
+    Load,
+    /// An event from the client, replied to directly on the socket.
+    PushEvent(Message),
+    /// The client patched the URL, replied to directly on the socket.
+    Patch(Message),
+    /// Patches the client's URL, pushing it with the diff on the socket.
+    PushPatch(String),
+    /// A request of an upload, replied to directly on the socket.
+    Upload(Message),
+    /// The client removed components from the page, replied to with those
+    /// destroyed.
+    DestroyComponents(Message),
+    /// A chunk of an uploaded file, replied to directly on the socket.
+    UploadChunk(Message, Vec<u8>),
+    /// Navigates the client to another LiveView.
+    Navigate(Navigation, String),
+    /// The client left, stopping the event handler. Replied to with the
+    /// LiveView navigated to, if any.
+    Leave(Process<Option<Navigation>, Json>, Tag),
+    /// Pushes a refreshed auth token to the client.
+    RefreshAuth,
+    /// The client stored the refreshed auth token.
+    AcknowledgeAuth,
+    /// Feature flags changed, re-rendering the LiveView.
+    RefreshFlags(FeatureFlags),
+    /// The connection closed or timed out, stopping the event handler.
+    Shutdown(TerminateReason),
+    /// A message sent by another process, handled by the LiveView's
+    /// `handle_info`.
+    Info(Value),
+    /// The template changed, reloading the client's page.
+    Reload,
+    /// An event pushed to the client from another process, sent with the
+    /// next diff.
+    PushClientEvent(String, Value),
+    /// Reports the connection's metrics, sent by the event handler to itself
+    /// periodically while the LiveView renders.
+    ReportMetrics,
 }
 
 impl EventHandler {
-    pub(crate) fn spawn<L, T>(socket: RawSocket, manager: L) -> Self
+    pub(crate) fn spawn<L, T>(socket: RawSocket, manager: L, options: EventHandlerOptions) -> Self
     where
         L: LiveViewManager<T> + Serialize + for<'de> Deserialize<'de>,
         T: LiveView,
     {
-        let process = Process::spawn_link((socket, manager), event_handler);
+        let process = Process::spawn_link((socket, manager, options), event_handler);
         EventHandler {
             event_handler: process,
         }
@@ -77,22 +154,144 @@ impl EventHandler {
             unsafe { Mailbox::new() };
         mailbox.tag_receive(&[tag])
     }
+
+    /// Pushes an event from the client without waiting for it to be handled.
+    ///
+    /// Events queued up while the LiveView is busy are handled together with a
+    /// single render.
+    pub(crate) fn push_event(&self, message: Message) {
+        self.event_handler
+            .send(EventHandlerMessage::PushEvent(message));
+    }
+
+    /// Handles the client patching the URL without waiting for the LiveView
+    /// to render.
+    pub(crate) fn patch(&self, message: Message) {
+        self.event_handler.send(EventHandlerMessage::Patch(message));
+    }
+
+    /// Patches the client's URL once queued events have been handled.
+    pub(crate) fn push_patch(&self, url: String) {
+        self.event_handler.send(EventHandlerMessage::PushPatch(url));
+    }
+
+    /// Handles a request of an upload, such as joining its channel.
+    pub(crate) fn upload(&self, message: Message) {
+        self.event_handler
+            .send(EventHandlerMessage::Upload(message));
+    }
+
+    /// Receives a chunk of an uploaded file.
+    pub(crate) fn upload_chunk(&self, message: Message, chunk: Vec<u8>) {
+        self.event_handler
+            .send(EventHandlerMessage::UploadChunk(message, chunk));
+    }
+
+    /// Destroys components the client removed, unless they were rendered
+    /// again since.
+    pub(crate) fn destroy_components(&self, message: Message) {
+        self.event_handler
+            .send(EventHandlerMessage::DestroyComponents(message));
+    }
+
+    /// Navigates the client to another LiveView, mounted once it rejoins.
+    pub(crate) fn navigate(&self, navigation: Navigation, url: String) {
+        self.event_handler
+            .send(EventHandlerMessage::Navigate(navigation, url));
+    }
+
+    /// Stops the event handler after the client left, returning the LiveView
+    /// it's navigating to, if any.
+    pub(crate) fn leave(&self) -> Option<Navigation> {
+        let tag = Tag::new();
+        self.event_handler
+            .send(EventHandlerMessage::Leave(unsafe { Process::this() }, tag));
+        let mailbox: Mailbox<Option<Navigation>, Json> = unsafe { Mailbox::new() };
+        mailbox.tag_receive(&[tag])
+    }
+
+    /// Notifies the event handler that the client stored a refreshed auth
+    /// token.
+    pub(crate) fn acknowledge_auth(&self) {
+        self.event_handler
+            .send(EventHandlerMessage::AcknowledgeAuth);
+    }
+
+    /// Stops the event handler once the connection is gone, releasing the
+    /// LiveView.
+    pub(crate) fn shutdown(&self, reason: TerminateReason) {
+        self.event_handler
+            .send(EventHandlerMessage::Shutdown(reason));
+    }
+
+    /// Returns the process handling the LiveView's events.
+    pub(crate) fn process(&self) -> Process<EventHandlerMessage, Json> {
+        self.event_handler
+    }
+
+    /// Pushes an event to the client from another process than the event
+    /// handler's.
+    pub(crate) fn push_client_event(&self, event: String, payload: Value) {
+        self.event_handler
+            .send(EventHandlerMessage::PushClientEvent(event, payload));
+    }
+
+    /// Loads the LiveView without waiting for it to finish, so the socket
+    /// keeps responding to heartbeats while data is loaded.
+    pub(crate) fn load(&self) {
+        self.event_handler.send(EventHandlerMessage::Load);
+    }
 }
 
 fn event_handler<L, T>(
-    (socket, manager): (RawSocket, L),
+    (mut socket, manager, options): (RawSocket, L, EventHandlerOptions),
     mailbox: Mailbox<EventHandlerMessage, Json>,
 ) where
     L: LiveViewManager<T>,
     T: LiveView,
 {
     let this: Process<EventHandlerMessage, Json> = mailbox.this();
+    socket.attach(this);
     let mut state = None;
+    let mut metrics = Metrics::new::<T>(this);
+    let mut auth = None;
+    let mut navigation = None;
+    // Messages taken from the mailbox while coalescing events or reporting
+    // metrics, handled next in the order they were sent.
+    let mut pending = VecDeque::new();
 
     loop {
-        let message = mailbox.receive();
+        // Events pushed without a render are sent on their own.
+        if let Some(events) = take_pushed_events() {
+            socket
+                .send(ProtocolEvent::Diff, &json!({ "e": events }))
+                .log_warn();
+        }
+        let message = match pending
+            .pop_front()
+            .map(Ok)
+            .unwrap_or_else(|| mailbox.try_receive())
+        {
+            Ok(message) => message,
+            // Responses arriving after their request gave up, such as a late
+            // context response, aren't event handler messages.
+            Err(err) => {
+                warn!("dropping message: {err}");
+                continue;
+            }
+        };
         match message {
-            EventHandlerMessage::HandleJoin(parent, tag, join_event) => {
+            EventHandlerMessage::HandleJoin(parent, tag, mut join_event) => {
+                // Authenticate before mounting, so the identity is available in mount.
+                if let Some(token_auth) = options.token_auth {
+                    let token = join_event.params.auth_token.take();
+                    let (state, refresh_in) = AuthState::join(token_auth, token);
+                    if let Some(refresh_in) = refresh_in {
+                        this.send_after(EventHandlerMessage::RefreshAuth, refresh_in);
+                    }
+                    auth = Some(state);
+                }
+
                 let reply = match manager
                     .handle_join(
                         Socket {
@@ -108,9 +307,13 @@ fn event_handler<L, T>(
                     Ok(Join {
                         live_view,
                         state: new_state,
-                        reply,
+                        mut reply,
                     }) => {
+                        metrics.record_render();
                         state = Some((live_view, new_state));
+                        if let Some(events) = take_pushed_events() {
+                            reply["e"] = events;
+                        }
                         Ok(reply)
                     }
                     Err(err) => Err(EventHandlerError::ManagerError(err.to_string())),
@@ -118,16 +321,22 @@ fn event_handler<L, T>(
                 parent.tag_send(tag, reply);
             }
             EventHandlerMessage::HandleEvent(parent, tag, event) => {
+                timings::reset();
+                let name = event.name.clone();
                 let reply = match &mut state {
                     Some((live_view, state)) => {
-                        match <T::Events as EventList<T>>::handle_event(live_view, event.clone()) {
+                        match timings::measure(Phase::Handle, || {
+                            <T::Events as EventList<T>>::handle_event(live_view, event.clone())
+                        }) {
                             Ok(handled) => {
                                 if !handled {
                                     Err(EventHandlerError::UnknownEvent)
                                 } else {
+                                    metrics.record_render();
                                     manager
                                         .handle_event(event, state, live_view)
                                         .into_result()
+                                        .map(with_pushed_events)
                                         .map_err(|err| {
                                             EventHandlerError::ManagerError(err.to_string())
                                         })
@@ -138,8 +347,574 @@ fn event_handler<L, T>(
                     }
                     None => Err(EventHandlerError::NotMounted),
                 };
+                if !matches!(reply, Err(EventHandlerError::NotMounted)) {
+                    audit::record(options.on_audit, &name, audit_result(&reply));
+                }
+                if let Err(err) = &reply {
+                    report_error(&options, Some(&name), err);
+                }
                 parent.tag_send(tag, reply);
+                timings::report(options.on_timings, vec![name]);
+            }
+            EventHandlerMessage::Load => match &mut state {
+                Some((live_view, state)) => {
+                    live_view.after_join(&mut Socket {
+                        event_handler: EventHandler {
+                            event_handler: this,
+                        },
+                        socket: socket.clone(),
+                    });
+                    live_view.load();
+                    flags::watch(this);
+                    metrics.record_render();
+                    push_render(&manager, &mut socket, &options, live_view, state);
+                }
+                None => error!("{}", EventHandlerError::NotMounted),
+            },
+            EventHandlerMessage::Patch(mut message) => match &mut state {
+                Some((live_view, state)) => {
+                    let uri = message
+                        .payload
+                        .get("url")
+                        .and_then(Value::as_str)
+                        .and_then(|url| url.parse::<Uri>().ok());
+                    let Some(uri) = uri else {
+                        let reply = message.reply_err(json!({ "reason": "invalid url" }));
+                        socket.send_reply(reply).log_warn();
+                        continue;
+                    };
+                    set_uri(uri.clone());
+                    live_view.handle_params(uri);
+                    metrics.record_render();
+                    let reply = render_reply(&manager, &options, live_view, state, json!({}));
+                    socket.send_reply(message.reply_ok(reply)).log_warn();
+                }
+                None => error!("{}", EventHandlerError::NotMounted),
+            },
+            EventHandlerMessage::PushPatch(url) => match &mut state {
+                Some((live_view, state)) => {
+                    let Ok(uri) = url.parse::<Uri>() else {
+                        continue;
+                    };
+                    set_uri(uri.clone());
+                    live_view.handle_params(uri);
+                    socket
+                        .send(
+                            ProtocolEvent::LivePatch,
+                            &json!({ "to": url, "kind": "push" }),
+                        )
+                        .log_warn();
+                    metrics.record_render();
+                    push_render(&manager, &mut socket, &options, live_view, state);
+                }
+                None => error!("{}", EventHandlerError::NotMounted),
+            },
+            EventHandlerMessage::Upload(mut message) => match &mut state {
+                Some((live_view, state)) => {
+                    let reply = match message.event {
+                        ProtocolEvent::AllowUpload => {
+                            let reply = upload::preflight(&message.payload, |name, entry| {
+                                live_view.presign_upload(name, entry)
+                            });
+                            metrics.record_render();
+                            Ok(render_reply(&manager, &options, live_view, state, reply))
+                        }
+                        ProtocolEvent::Progress => {
+                            if let Some((name, entry)) = upload::progress(&message.payload) {
+                                live_view.handle_progress(&name, &entry);
+                            }
+                            metrics.record_render();
+                            Ok(render_reply(
+                                &manager,
+                                &options,
+                                live_view,
+                                state,
+                                json!({}),
+                            ))
+                        }
+                        ProtocolEvent::Join => {
+                            let token = message
+                                .payload
+                                .get("token")
+                                .and_then(Value::as_str)
+                                .unwrap_or_default();
+                            upload::join(&message.topic, token).map(|()| json!({}))
+                        }
+                        _ => continue,
+                    };
+                    let reply = match reply {
+                        Ok(reply) => message.reply_ok(reply),
+                        Err(err) => message.reply_err(json!({ "reason": err.to_string() })),
+                    };
+                    socket.send_reply(reply).log_warn();
+                }
+                None => error!("{}", EventHandlerError::NotMounted),
+            },
+            EventHandlerMessage::UploadChunk(mut message, chunk) => {
+                let reply = match upload::chunk(&message.topic, chunk) {
+                    Ok(()) => message.reply_ok(json!({})),
+                    Err(err) => message.reply_err(json!({ "reason": err.to_string() })),
+                };
+                socket.send_reply(reply).log_warn();
+            }
+            EventHandlerMessage::DestroyComponents(mut message) => match &mut state {
+                Some((_, state)) => {
+                    let cids: Vec<u32> = message
+                        .payload
+                        .get("cids")
+                        .and_then(|cids| serde_json::from_value(cids.clone()).ok())
+                        .unwrap_or_default();
+                    let destroyed = manager.handle_destroy_components(state, &cids);
+                    socket
+                        .send_reply(message.reply_ok(json!({ "cids": destroyed })))
+                        .log_warn();
+                }
+                None => error!("{}", EventHandlerError::NotMounted),
+            },
+            EventHandlerMessage::Navigate(next, url) => {
+                navigation = Some(next);
+                socket
+                    .send(
+                        ProtocolEvent::LiveRedirect,
+                        &json!({ "to": url, "kind": "push" }),
+                    )
+                    .log_warn();
+            }
+            EventHandlerMessage::Leave(parent, tag) => {
+                if let Some((live_view, _)) = &mut state {
+                    live_view.terminate(TerminateReason::Left);
+                }
+                parent.tag_send(tag, navigation);
+                return;
+            }
+            EventHandlerMessage::Shutdown(reason) => {
+                if let Some((live_view, _)) = &mut state {
+                    live_view.terminate(reason);
+                }
+                return;
+            }
+            EventHandlerMessage::Reload => {
+                let to = uri()
+                    .and_then(|uri| uri.path_and_query().map(ToString::to_string))
+                    .unwrap_or_else(|| "/".to_string());
+                info!("reloading {to}: template changed");
+                socket
+                    .send(ProtocolEvent::Redirect, &json!({ "to": to }))
+                    .log_warn();
+            }
+            EventHandlerMessage::RefreshAuth => {
+                let Some(auth) = &mut auth else {
+                    continue;
+                };
+                if let Some(event) = auth.refresh() {
+                    socket.send(ProtocolEvent::Diff, &event).log_warn();
+                }
+                // Checked again until the identity is refreshed, or it expires
+                // and the LiveView is rendered without it.
+                match auth.refresh_in() {
+                    Some(refresh_in) => {
+                        this.send_after(EventHandlerMessage::RefreshAuth, refresh_in);
+                    }
+                    None => {
+                        if let Some((live_view, state)) = &mut state {
+                            metrics.record_render();
+                            push_render(&manager, &mut socket, &options, live_view, state);
+                        }
+                    }
+                }
+            }
+            EventHandlerMessage::AcknowledgeAuth => {
+                if let Some(auth) = &mut auth {
+                    auth.acknowledge();
+                }
+            }
+            EventHandlerMessage::RefreshFlags(new_flags) => {
+                flags::set_current(new_flags);
+                if let Some((live_view, state)) = &mut state {
+                    metrics.record_render();
+                    push_render(&manager, &mut socket, &options, live_view, state);
+                }
+            }
+            EventHandlerMessage::Info(msg) => {
+                let Some((live_view, state)) = &mut state else {
+                    continue;
+                };
+                let msg = match serde_json::from_value(msg) {
+                    Ok(msg) => msg,
+                    Err(err) => {
+                        error!("failed to deserialize message: {err}");
+                        continue;
+                    }
+                };
+                timings::measure(Phase::Handle, || live_view.handle_info(msg));
+                metrics.record_render();
+                push_render(&manager, &mut socket, &options, live_view, state);
+            }
+            EventHandlerMessage::ReportMetrics => {
+                // Messages are counted by taking them from the mailbox, which
+                // has no length of its own.
+                while let Ok(message) = mailbox.receive_timeout(Duration::ZERO) {
+                    pending.push_back(message);
+                }
+                metrics.report(pending.len());
+            }
+            EventHandlerMessage::PushClientEvent(event, payload) => {
+                queue_client_event(event, payload);
+            }
+            EventHandlerMessage::PushEvent(message) => {
+                let mut messages = vec![message];
+                // Coalesce the events queued right behind this one into a
+                // single render, stopping at any other message so messages are
+                // still handled in the order they were sent.
+                while let Some(message) = pending
+                    .pop_front()
+                    .or_else(|| mailbox.receive_timeout(Duration::ZERO).ok())
+                {
+                    match message {
+                        EventHandlerMessage::PushEvent(message) => messages.push(message),
+                        message => {
+                            pending.push_front(message);
+                            break;
+                        }
+                    }
+                }
+                metrics.record_coalesced_events(messages.len());
+
+                timings::reset();
+                match &mut state {
+                    Some((live_view, state)) => match handle_pushed_events(
+                        &manager,
+                        &mut socket,
+                        &mut metrics,
+                        &options,
+                        live_view,
+                        state,
+                        messages,
+                    ) {
+                        ControlFlow::Continue(events) => {
+                            timings::report(options.on_timings, events)
+                        }
+                        ControlFlow::Break(()) => return,
+                    },
+                    None => error!("{}", EventHandlerError::NotMounted),
+                }
             }
         };
     }
 }
+
+/// Renders the LiveView, pushing the diff if it changed.
+fn push_render<L, T>(
+    manager: &L,
+    socket: &mut RawSocket,
+    options: &EventHandlerOptions,
+    live_view: &T,
+    state: &mut L::State,
+) where
+    L: LiveViewManager<T>,
+    T: LiveView,
+{
+    match manager.handle_render(state, live_view).into_result() {
+        Ok(diff) => {
+            if let Some(diff) = with_pushed_events(diff) {
+                socket.send(ProtocolEvent::Diff, &diff).log_warn();
+            }
+        }
+        Err(err) => {
+            let err = EventHandlerError::ManagerError(err.to_string());
+            error!("{err}");
+            report_error(options, None, &err);
+        }
+    }
+}
+
+/// Renders the LiveView, adding the diff to a reply if it changed.
+fn render_reply<L, T>(
+    manager: &L,
+    options: &EventHandlerOptions,
+    live_view: &T,
+    state: &mut L::State,
+    mut reply: Value,
+) -> Value
+where
+    L: LiveViewManager<T>,
+    T: LiveView,
+{
+    match manager.handle_render(state, live_view).into_result() {
+        Ok(diff) => {
+            if let Some(diff) = with_pushed_events(diff) {
+                reply["diff"] = diff;
+            }
+        }
+        Err(err) => {
+            let err = EventHandlerError::ManagerError(err.to_string());
+            error!("{err}");
+            report_error(options, None, &err);
+        }
+    }
+    reply
+}
+
+/// Handles a batch of client events, rendering once and attaching the diff to
+/// the reply of the last handled event.
+///
+/// Returns the names of the events handled, or breaks if the connection was
+/// closed due to an unknown event.
+fn handle_pushed_events<L, T>(
+    manager: &L,
+    socket: &mut RawSocket,
+    metrics: &mut Metrics,
+    options: &EventHandlerOptions,
+    live_view: &mut T,
+    state: &mut L::State,
+    messages: Vec<Message>,
+) -> ControlFlow<(), Vec<String>>
+where
+    L: LiveViewManager<T>,
+    T: LiveView,
+{
+    let known_events = <T::Events as EventList<T>>::names();
+    let mut handled = Vec::with_capacity(messages.len());
+    let mut events = Vec::with_capacity(messages.len());
+    // Components which handled events, re-rendered alone unless the LiveView
+    // handled one too.
+    let mut cids = Vec::new();
+    let mut render_live_view = false;
+    for mut message in messages {
+        let no_reply = message.is_no_reply();
+        let optimistic_ref = message.optimistic_ref();
+        if let Some(uploads) = message.payload.get("uploads") {
+            upload::track(uploads);
+        }
+        let mut name = None;
+        let mut target = None;
+        let result = message
+            .take_event()
+            .map_err(|_| EventHandlerError::DeserializeEvent)
+            .and_then(|event| {
+                info!("Received event {}", event.name);
+                name = Some(event.name.clone());
+                events.push(event.name.clone());
+                if let Some(cid) = event.cid {
+                    target = Some(cid);
+                    live_view::take_reply();
+                    return match timings::measure(Phase::Handle, || {
+                        component::handle_event(cid, event)
+                    }) {
+                        Ok(true) => Ok(()),
+                        Ok(false) => Err(EventHandlerError::UnknownEvent),
+                        Err(_) => Err(EventHandlerError::DeserializeEvent),
+                    };
+                }
+                if event.name == RECOVER_EVENT {
+                    let params = match event.value.as_str() {
+                        Some(value) => serde_qs::from_str(value)
+                            .map_err(|_| EventHandlerError::DeserializeEvent)?,
+                        None => return Err(EventHandlerError::DeserializeEvent),
+                    };
+                    timings::measure(Phase::Handle, || live_view.handle_recovery(params));
+                    return Ok(());
+                }
+                // Events sent by the server, such as job updates, are never
+                // accepted from the client.
+                match known_events.iter().find(|name| event.matches(name)) {
+                    Some(name) if !is_server_event(name) => {}
+                    _ => return Err(EventHandlerError::UnknownEvent),
+                }
+                optimistic::take_verdict();
+                live_view::take_reply();
+                match timings::measure(Phase::Handle, || {
+                    <T::Events as EventList<T>>::handle_event(live_view, event)
+                }) {
+                    Ok(true) => Ok(()),
+                    Ok(false) => Err(EventHandlerError::UnknownEvent),
+                    Err(_) => Err(EventHandlerError::DeserializeEvent),
+                }
+            });
+        if let Some(name) = &name {
+            audit::record(options.on_audit, name, audit_result(&result));
+        }
+        if let Err(err) = &result {
+            report_error(options, name.as_deref(), err);
+        }
+        match result {
+            Ok(()) => {
+                match target {
+                    Some(cid) => cids.push(cid),
+                    None => render_live_view = true,
+                }
+                let optimistic = optimistic_ref
+                    .map(|optimistic_ref| (optimistic_ref, optimistic::take_verdict()));
+                handled.push((message, no_reply, optimistic, live_view::take_reply()));
+            }
+            Err(EventHandlerError::UnknownEvent) => {
+                let name = events.last().map(String::as_str).unwrap_or_default();
+                match options.unknown_events {
+                    UnknownEventPolicy::Ignore => {
+                        if !no_reply {
+                            socket.send_reply(message.reply_ok(json!({}))).log_warn();
+                        }
+                    }
+                    UnknownEventPolicy::ErrorAck => {
+                        error!("{} {name}", EventHandlerError::UnknownEvent);
+                        if !no_reply {
+                            socket
+                                .send_reply(message.reply_err(json!({ "reason": "unknown event" })))
+                                .log_warn();
+                        }
+                    }
+                    UnknownEventPolicy::CloseConnection => {
+                        warn!(
+                            "closing connection after {} {name}",
+                            EventHandlerError::UnknownEvent
+                        );
+                        socket.close(CloseCode::Policy, "unknown event");
+                        return ControlFlow::Break(());
+                    }
+                }
+            }
+            Err(err) => error!("{err}"),
+        }
+    }
+
+    if handled.is_empty() {
+        return ControlFlow::Continue(events);
+    }
+
+    metrics.record_render();
+    let result = if render_live_view {
+        manager.handle_render(state, live_view)
+    } else {
+        cids.sort_unstable();
+        cids.dedup();
+        manager.handle_component_render(state, live_view, &cids)
+    };
+    let diff = match result.into_result() {
+        Ok(diff) => diff,
+        Err(err) => {
+            let err = EventHandlerError::ManagerError(err.to_string());
+            error!("{err}");
+            report_error(options, None, &err);
+            return ControlFlow::Continue(events);
+        }
+    };
+
+    let last = handled.len() - 1;
+    let changed = diff.is_some();
+    let diff = with_pushed_events(diff);
+    for (i, (mut message, no_reply, optimistic, reply)) in handled.into_iter().enumerate() {
+        let diff = diff.as_ref().filter(|_| i == last);
+        if no_reply {
+            if let Some(diff) = diff {
+                socket.send(ProtocolEvent::Diff, diff).log_warn();
+            }
+            continue;
+        }
+        // Optimistic updates are kept if the render changed, unless the
+        // handler acknowledged or denied them.
+        let optimistic = optimistic.map(|(optimistic_ref, verdict)| OptimisticReply {
+            optimistic_ref,
+            accepted: verdict.unwrap_or(changed),
+        });
+        let mut response = json!({});
+        if let Some(diff) = diff {
+            response["diff"] = json!(diff);
+        }
+        // Replies are sent to the client under the diff's `r` key.
+        if let Some(reply) = reply {
+            response["diff"]["r"] = reply;
+        }
+        if let Some(optimistic) = optimistic {
+            response["optimistic"] = json!(optimistic);
+        }
+        socket.send_reply(message.reply_ok(response)).log_warn();
+    }
+
+    ControlFlow::Continue(events)
+}
+
+/// Attaches the events pushed to the client while handling a message to its
+/// diff, under the `e` key, so they're dispatched once the diff is applied.
+fn with_pushed_events(diff: Option<Value>) -> Option<Value> {
+    let Some(events) = take_pushed_events() else {
+        return diff;
+    };
+    let mut diff = diff.unwrap_or_else(|| json!({}));
+    diff["e"] = events;
+    Some(diff)
+}
+
+/// Returns whether an event type is only sent by the server, so it can't be
+/// pushed by the client.
+fn is_server_event(type_name: &str) -> bool {
+    // Generic events are matched whatever their type parameters.
+    let path = type_name.split('<').next().unwrap_or_default();
+    [
+        std::any::type_name::<JobUpdated>(),
+        BROADCAST_EVENT,
+        DOCUMENT_UPDATE_EVENT,
+    ]
+    .contains(&path)
+}
+
+/// Passes an error to the `on_error` callback, as a render error if the
+/// manager failed and otherwise as an event error.
+fn report_error(options: &EventHandlerOptions, event: Option<&str>, err: &EventHandlerError) {
+    let err = match err {
+        EventHandlerError::NotMounted => return,
+        EventHandlerError::ManagerError(err) => LiveViewError::Render(err.clone()),
+        err => LiveViewError::Event {
+            event: event.map(str::to_string),
+            error: err.to_string(),
+        },
+    };
+    err.report(options.on_error);
+}
+
+fn audit_result<R>(result: &Result<R, EventHandlerError>) -> AuditResult {
+    match result {
+        Err(EventHandlerError::UnknownEvent) => AuditResult::UnknownEvent,
+        Err(EventHandlerError::DeserializeEvent) => AuditResult::InvalidPayload,
+        _ => AuditResult::Handled,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::type_name;
+
+    use serde_json::json;
+
+    use super::{is_server_event, with_pushed_events};
+    use crate::job::JobUpdated;
+    use crate::pubsub::Broadcast;
+    use crate::socket::queue_client_event;
+
+    #[test]
+    fn pushed_events_are_attached_to_the_next_diff() {
+        assert_eq!(with_pushed_events(None), None);
+
+        queue_client_event("scroll".to_string(), json!({ "top": 0 }));
+        assert_eq!(
+            with_pushed_events(Some(json!({ "0": "1" }))),
+            Some(json!({ "0": "1", "e": [["scroll", { "top": 0 }]] }))
+        );
+
+        queue_client_event("focus".to_string(), json!({}));
+        assert_eq!(
+            with_pushed_events(None),
+            Some(json!({ "e": [["focus", {}]] }))
+        );
+        assert_eq!(with_pushed_events(None), None);
+    }
+
+    #[test]
+    fn server_events_are_recognized_by_type() {
+        assert!(is_server_event(type_name::<JobUpdated>()));
+        assert!(is_server_event(type_name::<Broadcast<String>>()));
+        assert!(is_server_event(
+            "submillisecond_live_view::collab::DocumentUpdate<app::Board>"
+        ));
+        assert!(!is_server_event("app::JobUpdated"));
+    }
+}
@@ -0,0 +1,402 @@
+//! Exports of a LiveView's data as CSV or JSON.
+
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+use lunatic_log::error;
+use serde::ser::{self, Impossible, SerializeMap, SerializeStruct};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use submillisecond::http::{header, StatusCode};
+use submillisecond::response::{IntoResponse, Response};
+use submillisecond::{Handler, RequestContext};
+use thiserror::Error;
+
+use crate::assets::StaticAssets;
+use crate::maud::{mount_request, MountHook};
+use crate::LiveView;
+
+/// A handler exporting a LiveView's data, created with
+/// [`LiveViewHandler::export`](crate::handler::LiveViewHandler::export).
+///
+/// Each request mounts and loads the LiveView with the request's URI, like a
+/// page load, so query parameters such as filters apply to the export as they
+/// do to the page. The LiveView is mounted as configured by its handler, with
+/// the handler's mount hooks, such as authorization checks, and static
+/// assets.
+pub struct ExportHandler<T, R> {
+    rows: fn(&T) -> Vec<R>,
+    format: ExportFormat,
+    filename: Option<String>,
+    mount_hooks: Vec<MountHook>,
+    static_assets: StaticAssets,
+    phantom: PhantomData<T>,
+}
+
+/// Format of an [`ExportHandler`]'s response.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// A CSV file with a header row of the rows' field names.
+    #[default]
+    Csv,
+    /// A JSON array of the rows.
+    Json,
+}
+
+#[derive(Debug, Error)]
+#[error("{0}")]
+struct ExportError(String);
+
+impl<T, R> ExportHandler<T, R> {
+    pub(crate) fn new(
+        rows: fn(&T) -> Vec<R>,
+        mount_hooks: Vec<MountHook>,
+        static_assets: StaticAssets,
+    ) -> Self {
+        ExportHandler {
+            rows,
+            format: ExportFormat::default(),
+            filename: None,
+            mount_hooks,
+            static_assets,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Sets the format of the export, CSV by default.
+    pub fn format(mut self, format: ExportFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the filename the export is downloaded as, instead of being shown
+    /// in the browser.
+    pub fn filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+}
+
+impl<T, R> Handler for ExportHandler<T, R>
+where
+    T: LiveView,
+    R: Serialize,
+{
+    fn handle(&self, req: RequestContext) -> Response {
+        let live_view = mount_request::<T>(&req, &self.mount_hooks, &self.static_assets);
+        let mut live_view = match live_view {
+            Ok(live_view) => live_view,
+            Err(err) => return (err.status(), err.message().to_string()).into_response(),
+        };
+        live_view.load();
+
+        let rows = (self.rows)(&live_view);
+        let (body, content_type) = match self.format {
+            ExportFormat::Csv => (to_csv(&rows), "text/csv; charset=utf-8"),
+            ExportFormat::Json => (
+                serde_json::to_string(&rows).map_err(|err| ExportError(err.to_string())),
+                "application/json",
+            ),
+        };
+        let body = match body {
+            Ok(body) => body,
+            Err(err) => {
+                error!("export failed: {err}");
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+
+        let mut builder = Response::builder()
+            .header(header::CONTENT_TYPE, content_type)
+            .header(header::CACHE_CONTROL, "private, no-store");
+        if let Some(filename) = &self.filename {
+            let filename = filename.replace(['"', '\\', '\r', '\n'], "_");
+            builder = builder.header(
+                header::CONTENT_DISPOSITION,
+                format!(r#"attachment; filename="{filename}""#),
+            );
+        }
+        builder.body(body.into_bytes()).unwrap()
+    }
+}
+
+/// Writes rows as CSV, with a header row of the first row's field names.
+fn to_csv<R>(rows: &[R]) -> Result<String, ExportError>
+where
+    R: Serialize,
+{
+    let mut csv = String::new();
+    for (i, row) in rows.iter().enumerate() {
+        let fields = row.serialize(RowSerializer)?;
+        if i == 0 {
+            write_record(&mut csv, fields.iter().map(|(name, _)| name.as_str()));
+        }
+        write_record(&mut csv, fields.iter().map(|(_, value)| value.as_str()));
+    }
+    Ok(csv)
+}
+
+fn write_record<'a>(csv: &mut String, cells: impl Iterator<Item = &'a str>) {
+    for (i, cell) in cells.enumerate() {
+        if i > 0 {
+            csv.push(',');
+        }
+        if cell.contains([',', '"', '\r', '\n']) {
+            csv.push('"');
+            csv.push_str(&cell.replace('"', "\"\""));
+            csv.push('"');
+        } else {
+            csv.push_str(cell);
+        }
+    }
+    csv.push_str("\r\n");
+}
+
+/// Formats a field's value as a CSV cell.
+///
+/// Strings which spreadsheets would evaluate as formulas are prefixed with a
+/// `'`, so exported user input can't run formulas when opened.
+fn cell<V>(value: &V) -> Result<String, ExportError>
+where
+    V: Serialize + ?Sized,
+{
+    match serde_json::to_value(value).map_err(|err| ExportError(err.to_string()))? {
+        Value::Null => Ok(String::new()),
+        Value::String(s) if s.starts_with(['=', '+', '-', '@', '\t', '\r']) => Ok(format!("'{s}")),
+        Value::String(s) => Ok(s),
+        value => Ok(value.to_string()),
+    }
+}
+
+impl ser::Error for ExportError {
+    fn custom<M>(msg: M) -> Self
+    where
+        M: Display,
+    {
+        ExportError(msg.to_string())
+    }
+}
+
+/// Serializes a struct or map into its field names and CSV cells, in order.
+struct RowSerializer;
+
+struct RowFields {
+    fields: Vec<(String, String)>,
+    key: Option<String>,
+}
+
+macro_rules! unsupported_rows {
+    ($( $method: ident ( $( $ty: ty ),* ) ),* $(,)?) => {
+        $(
+            fn $method(self $(, _: $ty )*) -> Result<Self::Ok, Self::Error> {
+                Err(not_a_row())
+            }
+        )*
+    };
+}
+
+fn not_a_row() -> ExportError {
+    ExportError("exported rows must be structs or maps".to_string())
+}
+
+impl Serializer for RowSerializer {
+    type Ok = Vec<(String, String)>;
+    type Error = ExportError;
+    type SerializeSeq = Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleStruct = Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleVariant = Impossible<Self::Ok, Self::Error>;
+    type SerializeMap = RowFields;
+    type SerializeStruct = RowFields;
+    type SerializeStructVariant = Impossible<Self::Ok, Self::Error>;
+
+    unsupported_rows!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit(),
+        serialize_unit_struct(&'static str),
+        serialize_unit_variant(&'static str, u32, &'static str),
+    );
+
+    fn serialize_some<V>(self, value: &V) -> Result<Self::Ok, Self::Error>
+    where
+        V: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        value: &V,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        V: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<V>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &V,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        V: Serialize + ?Sized,
+    {
+        Err(not_a_row())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(not_a_row())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(not_a_row())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(not_a_row())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(not_a_row())
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(RowFields {
+            fields: Vec::with_capacity(len.unwrap_or_default()),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(not_a_row())
+    }
+}
+
+impl SerializeStruct for RowFields {
+    type Ok = Vec<(String, String)>;
+    type Error = ExportError;
+
+    fn serialize_field<V>(&mut self, key: &'static str, value: &V) -> Result<(), Self::Error>
+    where
+        V: Serialize + ?Sized,
+    {
+        self.fields.push((key.to_string(), cell(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.fields)
+    }
+}
+
+impl SerializeMap for RowFields {
+    type Ok = Vec<(String, String)>;
+    type Error = ExportError;
+
+    fn serialize_key<K>(&mut self, key: &K) -> Result<(), Self::Error>
+    where
+        K: Serialize + ?Sized,
+    {
+        self.key = Some(match serde_json::to_value(key) {
+            Ok(Value::String(key)) => key,
+            Ok(key) => key.to_string(),
+            Err(err) => return Err(ExportError(err.to_string())),
+        });
+        Ok(())
+    }
+
+    fn serialize_value<V>(&mut self, value: &V) -> Result<(), Self::Error>
+    where
+        V: Serialize + ?Sized,
+    {
+        let key = self.key.take().unwrap_or_default();
+        self.fields.push((key, cell(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use super::to_csv;
+
+    #[derive(Serialize)]
+    struct Order {
+        id: u32,
+        customer: String,
+        note: Option<String>,
+        total: f64,
+    }
+
+    #[test]
+    fn rows_are_written_as_csv() {
+        let rows = [
+            Order {
+                id: 2,
+                customer: "Ari, \"A\"".to_string(),
+                note: None,
+                total: 9.5,
+            },
+            Order {
+                id: 1,
+                customer: "=HYPERLINK()".to_string(),
+                note: Some("gift".to_string()),
+                total: -3.0,
+            },
+        ];
+        assert_eq!(
+            to_csv(&rows).unwrap(),
+            "id,customer,note,total\r\n\
+             2,\"Ari, \"\"A\"\"\",,9.5\r\n\
+             1,'=HYPERLINK(),gift,-3.0\r\n"
+        );
+        assert!(to_csv(&[1, 2]).is_err());
+    }
+}
@@ -0,0 +1,164 @@
+//! Feature flags, which re-render LiveViews when changed.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::env;
+use std::time::Duration;
+
+use lunatic::serializer::Json;
+use lunatic::{Mailbox, Process};
+use serde::{Deserialize, Serialize};
+
+use crate::event_handler::EventHandlerMessage;
+use crate::store::Store;
+
+thread_local! {
+    /// Flags of the LiveView in the current process, loaded when mounting.
+    static FLAGS: RefCell<FeatureFlags> = RefCell::new(FeatureFlags::new());
+}
+
+/// Feature flags, enabled or disabled by name.
+///
+/// Flags are started with [`FeatureFlags::start`], which provides them from a
+/// [`Store`]. LiveViews read them with [`feature_enabled`], such as in `html!`
+/// conditionals, and are re-rendered whenever the store changes. Flags which
+/// are unknown, or read before the store is started, are disabled.
+///
+/// # Example
+///
+/// ```
+/// fn main() -> std::io::Result<()> {
+///     // Enabled by `FEATURE_NEW_CHECKOUT=true`.
+///     let flags = FeatureFlags::from_env("FEATURE_").start();
+///     // Refreshed from a remote service.
+///     flags.schedule(Schedule::every(Duration::from_secs(30)), fetch_flags);
+///     // ...
+/// }
+///
+/// impl LiveView for Cart {
+///     fn render(&self) -> Rendered {
+///         html! {
+///             @if feature_enabled("new_checkout") {
+///                 @(self.render_checkout())
+///             } @else {
+///                 @(self.render_legacy_checkout())
+///             }
+///         }
+///     }
+///     // ...
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureFlags {
+    flags: BTreeMap<String, bool>,
+}
+
+impl FeatureFlags {
+    /// Creates feature flags with every flag disabled.
+    pub fn new() -> Self {
+        FeatureFlags::default()
+    }
+
+    /// Reads flags from environment variables starting with `prefix`.
+    ///
+    /// The rest of the variable's name is lowercased to get the flag's name,
+    /// so `FEATURE_NEW_CHECKOUT` sets the `new_checkout` flag. Flags are
+    /// enabled by `1`, `true` or `on`, and disabled by any other value.
+    pub fn from_env(prefix: &str) -> Self {
+        let flags = env::vars()
+            .filter_map(|(name, value)| {
+                let name = name.strip_prefix(prefix)?.to_lowercase();
+                let enabled = matches!(value.to_lowercase().as_str(), "1" | "true" | "on");
+                Some((name, enabled))
+            })
+            .collect();
+        FeatureFlags { flags }
+    }
+
+    /// Returns the flags with a flag enabled.
+    pub fn enable(mut self, name: impl Into<String>) -> Self {
+        self.set(name, true);
+        self
+    }
+
+    /// Enables or disables a flag.
+    pub fn set(&mut self, name: impl Into<String>, enabled: bool) {
+        self.flags.insert(name.into(), enabled);
+    }
+
+    /// Returns whether a flag is enabled.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.flags.get(name).copied().unwrap_or(false)
+    }
+
+    /// Starts providing the flags to LiveViews, replacing flags already
+    /// started.
+    ///
+    /// Changes made to the returned store, such as with
+    /// [`Store::set`] or on a [`Store::schedule`], re-render LiveViews.
+    pub fn start(self) -> Store<FeatureFlags> {
+        Store::start(self)
+    }
+
+    /// Returns the flags of the LiveView in the current process.
+    pub fn current() -> Self {
+        FLAGS.with(|flags| flags.borrow().clone())
+    }
+}
+
+/// Returns whether a flag is enabled for the LiveView in the current process.
+///
+/// Flags are loaded when the LiveView is mounted, and updated when they
+/// change, so they can be read from `render`.
+pub fn feature_enabled(name: &str) -> bool {
+    FLAGS.with(|flags| flags.borrow().is_enabled(name))
+}
+
+/// Loads the current flags into the current process, before mounting.
+pub(crate) fn load() {
+    if let Some(store) = Store::<FeatureFlags>::lookup() {
+        set_current(store.value());
+    }
+}
+
+pub(crate) fn set_current(new_flags: FeatureFlags) {
+    FLAGS.with(|flags| *flags.borrow_mut() = new_flags);
+}
+
+/// Sends changes of the flags to a LiveView's event handler, until it stops.
+pub(crate) fn watch(event_handler: Process<EventHandlerMessage, Json>) {
+    if let Some(store) = Store::<FeatureFlags>::lookup() {
+        Process::spawn_link((store, event_handler), watcher);
+    }
+}
+
+fn watcher(
+    (store, event_handler): (Store<FeatureFlags>, Process<EventHandlerMessage, Json>),
+    mailbox: Mailbox<FeatureFlags, Json>,
+) {
+    store.watch(mailbox.this());
+    loop {
+        let mut flags = mailbox.receive();
+        // Skip flags replaced while the LiveView was busy.
+        while let Ok(newer) = mailbox.receive_timeout(Duration::ZERO) {
+            flags = newer;
+        }
+        event_handler.send(EventHandlerMessage::RefreshFlags(flags));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FeatureFlags;
+
+    #[test]
+    fn flags_from_env() {
+        std::env::set_var("TEST_FLAG_NEW_CHECKOUT", "true");
+        std::env::set_var("TEST_FLAG_DARK_MODE", "off");
+        let flags = FeatureFlags::from_env("TEST_FLAG_");
+        assert!(flags.is_enabled("new_checkout"));
+        assert!(!flags.is_enabled("dark_mode"));
+        assert!(!flags.is_enabled("unknown"));
+        assert!(flags.enable("dark_mode").is_enabled("dark_mode"));
+    }
+}
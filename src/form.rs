@@ -0,0 +1,598 @@
+//! Typed forms rendered from a struct.
+
+use std::any::type_name;
+use std::collections::{BTreeMap, BTreeSet};
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::rendered::Rendered;
+use crate::{self as submillisecond_live_view, html};
+
+/// A struct edited with a form, usually implemented with
+/// `#[derive(LiveForm)]`.
+///
+/// The form is rendered with [`LiveForm::render_form`], which sends a
+/// [`FormChanged`] event as inputs change and a [`FormSubmitted`] event when
+/// submitted, each holding the deserialized struct.
+///
+/// Unchecked checkboxes aren't sent by the browser, so `bool` fields should
+/// be marked with `#[serde(default)]`. Numeric fields fail to deserialize
+/// while empty, so consider `Option` fields for them.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Clone, Default, Serialize, Deserialize, LiveForm)]
+/// #[form(submit = "Sign up", autosave = "signup", debounce = 300)]
+/// struct Signup {
+///     #[form(input = "email")]
+///     email: String,
+///     #[form(label = "Display name")]
+///     name: String,
+///     #[serde(default)]
+///     newsletter: bool,
+/// }
+///
+/// impl LiveView for SignupPage {
+///     type Events = (FormChanged<Signup>, FormSubmitted<Signup>);
+///
+///     fn render(&self) -> Rendered {
+///         self.signup.render_form(&self.errors)
+///     }
+///     // ...
+/// }
+///
+/// impl LiveViewEvent<FormChanged<Signup>> for SignupPage {
+///     fn handle(state: &mut Self, FormChanged(signup): FormChanged<Signup>) {
+///         state.errors = signup.validate();
+///         state.signup = signup;
+///     }
+/// }
+/// ```
+pub trait LiveForm: Serialize + DeserializeOwned {
+    /// Returns the fields of the form with their current values.
+    fn fields(&self) -> Vec<FormField>;
+
+    /// Validates the values, returning errors by field name.
+    fn validate(&self) -> FormErrors {
+        FormErrors::default()
+    }
+
+    /// Returns the label of the submit button.
+    fn submit_label() -> &'static str {
+        "Submit"
+    }
+
+    /// Returns the key the form's draft is saved under in the browser's local
+    /// storage, if drafts are saved.
+    ///
+    /// Drafts are saved as the form changes and restored when the LiveView is
+    /// loaded again, such as after a reload, until the form is submitted.
+    /// Hidden and password inputs aren't saved.
+    fn autosave_key() -> Option<&'static str> {
+        None
+    }
+
+    /// Returns how many milliseconds to wait for typing to stop before sending
+    /// [`FormChanged`], if changes are debounced.
+    fn debounce() -> Option<u32> {
+        None
+    }
+
+    /// Renders the form with its current values and errors.
+    ///
+    /// Each field is rendered as a `label` containing the field's label, its
+    /// input and its error, if any.
+    ///
+    /// The form's id is the struct's type name, so its values are sent again
+    /// with [`FormChanged`] after the socket reconnects.
+    fn render_form(&self, errors: &FormErrors) -> Rendered
+    where
+        Self: Sized,
+    {
+        render_live_form::<Self, FormChanged<Self>, FormSubmitted<Self>>(self.fields(), errors)
+    }
+}
+
+/// Renders the fields of a form sending the `Changed` and `Submitted` events.
+fn render_live_form<T, Changed, Submitted>(fields: Vec<FormField>, errors: &FormErrors) -> Rendered
+where
+    T: LiveForm,
+{
+    let submit_label = T::submit_label();
+    let autosave_key = T::autosave_key();
+    let debounce = T::debounce();
+    html! {
+        form.live-form
+            id=(type_name::<T>())
+            data-autosave=[autosave_key]
+            @change=(Changed)
+            @submit=(Submitted)
+        {
+            @for field in fields {
+                label.field {
+                    span.field-label { (field.label) }
+                    @if field.input == "checkbox" {
+                        input type="checkbox" name=(field.name) value="true" checked[field.checked];
+                    } @else if field.input == "textarea" {
+                        textarea name=(field.name) phx-debounce=[debounce] { (field.value) }
+                    } @else {
+                        input
+                            type=(field.input)
+                            name=(field.name)
+                            value=(field.value)
+                            phx-debounce=[debounce];
+                    }
+                    @if let Some(error) = errors.get(field.name) {
+                        span.field-error { (error) }
+                    }
+                }
+            }
+            button type="submit" { (submit_label) }
+        }
+    }
+}
+
+/// Event sent when an input of a [`LiveForm`] changes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FormChanged<T>(pub T);
+
+/// Event sent when a [`LiveForm`] is submitted.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FormSubmitted<T>(pub T);
+
+/// A field of a [`LiveForm`], with its current value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormField {
+    /// Name of the input, which is the name of the struct's field.
+    pub name: &'static str,
+    /// Label shown for the input.
+    pub label: &'static str,
+    /// Input type, such as `text` or `checkbox`, or `textarea`.
+    pub input: &'static str,
+    /// Current value of the input.
+    pub value: String,
+    /// Whether a checkbox is checked.
+    pub checked: bool,
+}
+
+impl FormField {
+    /// Creates a field from a value, with the value's input type unless one is
+    /// given.
+    pub fn new<V>(
+        name: &'static str,
+        label: &'static str,
+        input: Option<&'static str>,
+        value: &V,
+    ) -> Self
+    where
+        V: FormValue + ?Sized,
+    {
+        FormField {
+            name,
+            label,
+            input: input.unwrap_or(V::INPUT),
+            value: value.form_value(),
+            checked: value.is_checked(),
+        }
+    }
+}
+
+/// A value which can be edited in a form input.
+pub trait FormValue {
+    /// The input type used for the value by default.
+    const INPUT: &'static str = "text";
+
+    /// Returns the value as shown in the input.
+    fn form_value(&self) -> String;
+
+    /// Returns whether a checkbox for the value is checked.
+    fn is_checked(&self) -> bool {
+        false
+    }
+}
+
+impl FormValue for String {
+    fn form_value(&self) -> String {
+        self.clone()
+    }
+}
+
+impl FormValue for str {
+    fn form_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl FormValue for char {
+    fn form_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl FormValue for bool {
+    const INPUT: &'static str = "checkbox";
+
+    fn form_value(&self) -> String {
+        "true".to_string()
+    }
+
+    fn is_checked(&self) -> bool {
+        *self
+    }
+}
+
+impl<T> FormValue for Option<T>
+where
+    T: FormValue,
+{
+    const INPUT: &'static str = T::INPUT;
+
+    fn form_value(&self) -> String {
+        self.as_ref().map(T::form_value).unwrap_or_default()
+    }
+
+    fn is_checked(&self) -> bool {
+        self.as_ref().map(T::is_checked).unwrap_or_default()
+    }
+}
+
+macro_rules! impl_number_form_value {
+    ($( $t: ty ),*) => {
+        $(
+            impl FormValue for $t {
+                const INPUT: &'static str = "number";
+
+                fn form_value(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+impl_number_form_value!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
+/// Validation errors of a [`LiveForm`], by field name.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FormErrors {
+    errors: Vec<(String, String)>,
+}
+
+impl FormErrors {
+    /// Adds an error to a field, replacing any previous error.
+    pub fn add(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        let field = field.into();
+        let message = message.into();
+        match self.errors.iter_mut().find(|(name, _)| *name == field) {
+            Some((_, existing)) => *existing = message,
+            None => self.errors.push((field, message)),
+        }
+    }
+
+    /// Returns the error of a field, if any.
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.errors
+            .iter()
+            .find(|(name, _)| name == field)
+            .map(|(_, message)| message.as_str())
+    }
+
+    /// Returns whether there are no errors.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Removes all errors.
+    pub fn clear(&mut self) {
+        self.errors.clear();
+    }
+}
+
+/// The params of a form event as submitted, by input name, deserialized into a
+/// [`Changeset`] with [`Changeset::cast`].
+///
+/// Forms rendered with [`Changeset::render_form`] send
+/// `FormChanged<FormParams<T>>` and `FormSubmitted<FormParams<T>>` events.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FormParams<T> {
+    params: BTreeMap<String, String>,
+    #[serde(skip)]
+    form: PhantomData<T>,
+}
+
+/// A [`LiveForm`] being edited, tracking the params submitted, which fields
+/// are invalid, and which the user has used.
+///
+/// Params which fail to deserialize, such as a number being typed, are kept
+/// and rendered back in their input with an error, instead of failing the
+/// event. Errors are only shown for fields the user has changed, until the
+/// form is submitted.
+///
+/// # Example
+///
+/// ```
+/// impl LiveView for SignupPage {
+///     type Events = (FormChanged<FormParams<Signup>>, FormSubmitted<FormParams<Signup>>);
+///
+///     fn render(&self) -> Rendered {
+///         self.signup.render_form()
+///     }
+///     // ...
+/// }
+///
+/// impl LiveViewEvent<FormChanged<FormParams<Signup>>> for SignupPage {
+///     fn handle(state: &mut Self, FormChanged(params): FormChanged<FormParams<Signup>>) {
+///         state.signup.cast(params);
+///     }
+/// }
+///
+/// impl LiveViewEvent<FormSubmitted<FormParams<Signup>>> for SignupPage {
+///     fn handle(state: &mut Self, FormSubmitted(params): FormSubmitted<FormParams<Signup>>) {
+///         if let Some(signup) = state.signup.submit(params) {
+///             state.users.create(signup);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Changeset<T> {
+    data: T,
+    params: Option<BTreeMap<String, String>>,
+    errors: FormErrors,
+    used: BTreeSet<String>,
+    submitted: bool,
+}
+
+impl<T> Changeset<T>
+where
+    T: LiveForm,
+{
+    /// Creates a changeset editing `data`.
+    pub fn new(data: T) -> Self {
+        Changeset {
+            data,
+            params: None,
+            errors: FormErrors::default(),
+            used: BTreeSet::new(),
+            submitted: false,
+        }
+    }
+
+    /// Applies the params of a changed form, validating them.
+    ///
+    /// Valid params are deserialized into the data, while invalid ones are
+    /// kept with an error, leaving the data's field unchanged.
+    pub fn cast(&mut self, params: FormParams<T>) -> &mut Self {
+        let mut params = params.params;
+        if let Some(target) = params.remove("_target") {
+            self.used.insert(target);
+        }
+
+        let mut errors = FormErrors::default();
+        let mut valid = form_params(&self.data);
+        let mut merged = params.clone();
+        for (name, value) in &params {
+            let mut candidate = valid.clone();
+            candidate.insert(name.clone(), value.clone());
+            if deserialize_params::<T>(&candidate).is_some() {
+                valid = candidate;
+            } else {
+                errors.add(name.clone(), "is invalid");
+                match valid.get(name) {
+                    Some(value) => merged.insert(name.clone(), value.clone()),
+                    None => merged.remove(name),
+                };
+            }
+        }
+        if let Some(data) = deserialize_params(&merged) {
+            self.data = data;
+        }
+        for (name, message) in self.data.validate().errors {
+            if errors.get(&name).is_none() {
+                errors.add(name, message);
+            }
+        }
+
+        self.params = Some(params);
+        self.errors = errors;
+        self
+    }
+
+    /// Applies the params of a submitted form, returning the data if it's
+    /// valid.
+    ///
+    /// Errors of all fields are shown from now on.
+    pub fn submit(&mut self, params: FormParams<T>) -> Option<&T> {
+        self.submitted = true;
+        self.cast(params);
+        self.is_valid().then_some(&self.data)
+    }
+
+    /// Returns whether the params are valid.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns the data, with the valid params applied.
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    /// Returns the errors of all fields, including those not shown yet.
+    pub fn errors(&self) -> &FormErrors {
+        &self.errors
+    }
+
+    /// Returns the value of an input as submitted, or from the data if the
+    /// form hasn't changed.
+    pub fn value(&self, name: &str) -> String {
+        match &self.params {
+            Some(params) => params.get(name).cloned().unwrap_or_default(),
+            None => self
+                .data
+                .fields()
+                .into_iter()
+                .find(|field| field.name == name)
+                .map(|field| field.value)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Returns the error shown for a field, once the field has been used or
+    /// the form submitted.
+    pub fn error(&self, name: &str) -> Option<&str> {
+        if self.submitted || self.used.contains(name) {
+            self.errors.get(name)
+        } else {
+            None
+        }
+    }
+
+    /// Renders an error for a field, if one is shown, as a `span` with the
+    /// `field-error` class.
+    pub fn error_tag(&self, name: &str) -> Rendered {
+        let error = self.error(name);
+        html! {
+            @if let Some(error) = error {
+                span.field-error { (error) }
+            }
+        }
+    }
+
+    /// Renders the form like [`LiveForm::render_form`], with the values as
+    /// submitted and the errors shown.
+    pub fn render_form(&self) -> Rendered {
+        let mut errors = FormErrors::default();
+        let fields = self
+            .data
+            .fields()
+            .into_iter()
+            .map(|mut field| {
+                if let Some(error) = self.error(field.name) {
+                    errors.add(field.name, error);
+                }
+                if let Some(params) = &self.params {
+                    field.value = self.value(field.name);
+                    if field.input == "checkbox" {
+                        field.checked = params.contains_key(field.name);
+                        field.value = "true".to_string();
+                    }
+                }
+                field
+            })
+            .collect();
+        render_live_form::<T, FormChanged<FormParams<T>>, FormSubmitted<FormParams<T>>>(
+            fields, &errors,
+        )
+    }
+}
+
+/// Returns the params of a form showing `data`.
+fn form_params<T>(data: &T) -> BTreeMap<String, String>
+where
+    T: Serialize,
+{
+    serde_qs::to_string(data)
+        .ok()
+        .and_then(|query| serde_qs::from_str(&query).ok())
+        .unwrap_or_default()
+}
+
+fn deserialize_params<T>(params: &BTreeMap<String, String>) -> Option<T>
+where
+    T: DeserializeOwned,
+{
+    let query = serde_qs::to_string(params).ok()?;
+    serde_qs::from_str(&query).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::{Changeset, FormField, FormSubmitted, LiveForm};
+    use crate as submillisecond_live_view;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, submillisecond_live_view::LiveForm)]
+    #[form(submit = "Sign up", autosave = "signup", debounce = 300)]
+    struct Signup {
+        #[form(input = "email")]
+        email: String,
+        first_name: String,
+        age: Option<u8>,
+        #[serde(default)]
+        newsletter: bool,
+        #[form(skip)]
+        #[serde(skip)]
+        referrer: Option<String>,
+    }
+
+    #[test]
+    fn derived_fields() {
+        let signup = Signup {
+            email: "ari@example.com".to_string(),
+            first_name: "Ari".to_string(),
+            age: None,
+            newsletter: true,
+            referrer: None,
+        };
+        assert_eq!(
+            signup.fields(),
+            [
+                FormField::new("email", "Email", Some("email"), &signup.email),
+                FormField::new("first_name", "First name", None, &signup.first_name),
+                FormField::new("age", "Age", Some("number"), &signup.age),
+                FormField::new("newsletter", "Newsletter", Some("checkbox"), &true),
+            ]
+        );
+        assert_eq!(Signup::submit_label(), "Sign up");
+        assert_eq!(Signup::autosave_key(), Some("signup"));
+        assert_eq!(Signup::debounce(), Some(300));
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, submillisecond_live_view::LiveForm)]
+    struct Order {
+        quantity: u32,
+        note: String,
+    }
+
+    #[test]
+    fn changesets_keep_invalid_params() {
+        let mut changeset = Changeset::new(Order {
+            quantity: 1,
+            note: String::new(),
+        });
+        let params = serde_qs::from_str("quantity=abc&note=hi&_target=quantity").unwrap();
+        changeset.cast(params);
+        assert!(!changeset.is_valid());
+        assert_eq!(changeset.value("quantity"), "abc");
+        assert_eq!(changeset.error("quantity"), Some("is invalid"));
+        assert_eq!(changeset.error("note"), None);
+        assert_eq!(changeset.data().quantity, 1);
+        assert_eq!(changeset.data().note, "hi");
+
+        let params = serde_qs::from_str("quantity=3&note=hi").unwrap();
+        assert_eq!(
+            changeset.submit(params),
+            Some(&Order {
+                quantity: 3,
+                note: "hi".to_string(),
+            })
+        );
+        assert!(changeset.is_valid());
+    }
+
+    #[test]
+    fn submitted_form_deserializes() {
+        let FormSubmitted(signup): FormSubmitted<Signup> =
+            serde_qs::from_str("email=ari%40example.com&first_name=Ari&age=").unwrap();
+        assert_eq!(signup.first_name, "Ari");
+        assert!(!signup.newsletter);
+    }
+}
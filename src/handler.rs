@@ -1,50 +1,141 @@
 //! Handler functionality for handling LiveViews.
 
-use std::fmt;
 use std::marker::PhantomData;
+use std::time::Duration;
+use std::{fmt, io};
 
+use lunatic::function::FuncRef;
 use lunatic_log::{error, info, trace, warn};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use submillisecond::extract::FromOwnedRequest;
-use submillisecond::http::header;
+use submillisecond::http::{header, StatusCode, Uri};
 use submillisecond::response::{IntoResponse, Response};
 use submillisecond::websocket::{WebSocket, WebSocketConnection};
 use submillisecond::{Handler, RequestContext};
+use tungstenite::protocol::frame::coding::CloseCode;
+use tungstenite::protocol::CloseFrame;
 
-use crate::event_handler::EventHandler;
+use crate::assets::StaticAssets;
+use crate::audit::AuditEvent;
+use crate::csp::ContentSecurityPolicy;
+use crate::csrf::{cookie_value, CSRF_COOKIE};
+use crate::event_handler::{EventHandler, EventHandlerOptions};
+pub use crate::export::{ExportFormat, ExportHandler};
+use crate::limits::{ConnectionLimits, ConnectionRegistry};
 use crate::manager::LiveViewManager;
-use crate::maud::LiveViewMaud;
-use crate::socket::{Message, ProtocolEvent, RawSocket, SocketError, SocketMessage};
-use crate::template::TemplateProcess;
-use crate::LiveView;
+use crate::maud::{DiffOptions, ErrorPages, LiveViewMaud, MountHook, TitleOptions};
+use crate::navigation::{self, Navigation};
+use crate::rendered::Rendered;
+use crate::session::RevokedSessions;
+use crate::socket::{
+    JoinEvent, Message, ProtocolEvent, RawSocket, SocketError, SocketMessage, SocketOptions,
+};
+use crate::state_token::StateTokenOptions;
+use crate::template::{Layout, TemplateProcess};
+use crate::{pubsub, LiveView, MountError, TerminateReason, TokenAuth};
 
 type Manager<T> = LiveViewMaud<T>;
 
+/// Connection limits of a handler, with the client IP key of a request.
+type Limits = Option<(ConnectionLimits, ConnectionRegistry, Option<String>)>;
+
 /// A LiveView handler created with `LiveViewRouter::handler`.
 pub struct LiveViewHandler<'a, T> {
-    template: &'a str,
-    selector: &'a str,
+    layout: Option<(&'a str, &'a str)>,
+    diff_options: DiffOptions,
+    state_tokens: StateTokenOptions,
+    csrf: CsrfProtection,
+    csp: Option<ContentSecurityPolicy>,
+    error_pages: ErrorPages,
+    mount_hooks: Vec<MountHook>,
+    title: TitleOptions,
+    static_assets: StaticAssets,
+    event_handler_options: EventHandlerOptions,
+    limits: Option<ConnectionLimits>,
+    socket_options: SocketOptions,
     phantom: PhantomData<T>,
 }
 
+/// Values passed from the handler to the process of a websocket connection.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+struct Connection<T> {
+    live_view: Manager<T>,
+    options: EventHandlerOptions,
+    csrf_cookie: Option<String>,
+    limits: Limits,
+    socket_options: SocketOptions,
+}
+
+/// How events from the client which aren't in [`LiveView::Events`] are
+/// handled.
+///
+/// Unknown events are usually a sign of a client probing for event handlers,
+/// since events bound with the `html!` macro always exist.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnknownEventPolicy {
+    /// Acknowledge the event without logging it.
+    Ignore,
+    /// Log an error and reply to the event with an error status.
+    #[default]
+    ErrorAck,
+    /// Log a warning and close the connection.
+    CloseConnection,
+}
+
+/// How the CSRF token checked when the live socket joins is delivered to the
+/// client.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CsrfProtection {
+    /// The token is rendered in a `csrf-token` meta tag, and checked against
+    /// the token in the page's signed session.
+    #[default]
+    MetaTag,
+    /// The token is set in a `SameSite=Strict` cookie, and checked against the
+    /// cookie sent with the websocket upgrade request.
+    ///
+    /// Unlike [`CsrfProtection::MetaTag`], pages can be cached or have their
+    /// meta tags stripped, as the token is not part of the page.
+    Cookie,
+}
+
+/// An error handling a live socket connection, passed to the callback
+/// registered with [`LiveViewHandler::on_error`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LiveViewError {
+    /// The live socket failed to join, such as from an invalid CSRF token.
+    Join(String),
+    /// An event from the client could not be handled.
+    Event {
+        /// Name of the event, if it could be read.
+        event: Option<String>,
+        /// Description of the error.
+        error: String,
+    },
+    /// The LiveView failed to render.
+    Render(String),
+    /// Reading from the socket failed.
+    Socket(String),
+}
+
 /// Trait used to create a handler from a LiveView.
 pub trait LiveViewRouter: Sized {
-    /// Create handler for LiveView with a html template.
+    /// Create handler for LiveView, configured with the returned builder.
     ///
-    /// The LiveView is injected into the selector of the template.
+    /// A layout must be set with [`LiveViewHandler::layout`].
     ///
     /// # Example
     ///
     /// ```
     /// router! {
-    ///     GET "/" => MyLiveView::handler("index.html", "#app")
+    ///     GET "/" => MyLiveView::handler().layout("index.html", "#app")
     /// }
     /// ```
-    fn handler<'a>(template: &'a str, selector: &'a str) -> LiveViewHandler<'a, Self>;
+    fn handler<'a>() -> LiveViewHandler<'a, Self>;
 }
 
-trait LogError {
+pub(crate) trait LogError {
     fn log_warn(self);
     fn log_error(self);
 }
@@ -53,19 +144,391 @@ impl<T> LiveViewRouter for T
 where
     T: LiveView,
 {
-    fn handler<'a>(template: &'a str, selector: &'a str) -> LiveViewHandler<'a, Self> {
-        LiveViewHandler::new(template, selector)
+    fn handler<'a>() -> LiveViewHandler<'a, Self> {
+        LiveViewHandler::new()
     }
 }
 
+/// Creates a router for many LiveViews sharing a layout, mount hooks and
+/// static assets.
+///
+/// Each route is handled with [`LiveViewRouter::handler`], configured with the
+/// shared layout and [`on_mount`](LiveViewHandler::on_mount) hooks. Static
+/// assets are served with `submillisecond::static_router!`.
+///
+/// # Example
+///
+/// ```
+/// Application::new(live_router! {
+///     layout: ("index.html", "#app"),
+///     on_mount: [maintenance],
+///     static: ("/static", "./static"),
+///     routes: {
+///         "/" => Counter,
+///         "/todos" => Todos,
+///     }
+/// })
+/// .serve("127.0.0.1:3000")
+/// ```
+#[macro_export]
+macro_rules! live_router {
+    (
+        layout: $layout:tt,
+        $( on_mount: [$( $hook:path ),* $(,)?], )?
+        $( static: $static:tt, )?
+        routes: { $( $path:literal => $view:ty ),* $(,)? } $(,)?
+    ) => {
+        $crate::live_router!(
+            @router $layout [$($( $hook ),*)?] { $( $path => $view ),* } $( $static )?
+        )
+    };
+    (
+        @router $layout:tt $hooks:tt { $( $path:literal => $view:ty ),* }
+        $( ($static_path:literal, $static_dir:literal) )?
+    ) => {
+        ::submillisecond::router! {
+            $( GET $path => $crate::live_router!(@handler $view, $layout, $hooks) )*
+            $( $static_path => ::submillisecond::static_router!($static_dir) )?
+        }
+    };
+    (@handler $view:ty, ($template:literal, $selector:literal), [$( $hook:path ),*]) => {
+        <$view as $crate::handler::LiveViewRouter>::handler()
+            .layout($template, $selector)
+            $( .on_mount($hook) )*
+    };
+}
+
 impl<'a, T> LiveViewHandler<'a, T> {
-    pub(crate) fn new(template: &'a str, selector: &'a str) -> Self {
+    pub(crate) fn new() -> Self {
         LiveViewHandler {
-            template,
-            selector,
+            layout: None,
+            diff_options: DiffOptions::default(),
+            state_tokens: StateTokenOptions::default(),
+            csrf: CsrfProtection::default(),
+            csp: None,
+            error_pages: ErrorPages::default(),
+            mount_hooks: Vec::new(),
+            title: TitleOptions::default(),
+            static_assets: StaticAssets::default(),
+            event_handler_options: EventHandlerOptions::default(),
+            limits: None,
+            socket_options: SocketOptions::default(),
             phantom: PhantomData,
         }
     }
+
+    /// Sets the html template the LiveView is rendered in.
+    ///
+    /// The LiveView is injected into the selector of the template.
+    ///
+    /// In debug builds, the template is reloaded when its file is edited, and
+    /// the pages of connected clients are reloaded with it.
+    pub fn layout(mut self, template: &'a str, selector: &'a str) -> Self {
+        self.layout = Some((template, selector));
+        self
+    }
+
+    /// Sets how long to wait for the live socket to join after connecting, or
+    /// `None` to wait indefinitely.
+    ///
+    /// Defaults to 30 seconds.
+    pub fn join_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.socket_options.join_timeout = timeout;
+        self
+    }
+
+    /// Sets how long the client can go without sending a heartbeat or event
+    /// before the connection is closed and its LiveView stopped, or `None` to
+    /// never close it.
+    ///
+    /// Clients which disappear without closing the connection, such as after
+    /// losing their network, are only detected by this timeout.
+    ///
+    /// Clients send a heartbeat every 30 seconds. Defaults to 60 seconds.
+    pub fn heartbeat_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.socket_options.heartbeat_timeout = timeout;
+        self
+    }
+
+    /// Sets the maximum size of a diff relative to the full render.
+    ///
+    /// Diffs larger than the full render multiplied by this ratio are replaced
+    /// with the full render, avoiding pathological diffs for templates whose
+    /// structure changes heavily between renders.
+    ///
+    /// Defaults to `1.0`.
+    pub fn max_diff_ratio(mut self, ratio: f32) -> Self {
+        self.diff_options.max_diff_ratio = ratio;
+        self
+    }
+
+    /// Sets whether strings repeated within a diff are sent once and
+    /// referenced, reducing payloads for repetitive data such as tables.
+    ///
+    /// The client must expand interned strings before applying diffs, which the
+    /// bundled LiveView JavaScript does. Defaults to `true` when the
+    /// `liveview_js` feature is enabled.
+    pub fn intern_strings(mut self, enabled: bool) -> Self {
+        self.diff_options.intern_strings = enabled;
+        self
+    }
+
+    /// Sets text prepended to the titles returned by
+    /// [`LiveView::title`](crate::LiveView::title), such as `"MyApp · "`.
+    pub fn title_prefix(mut self, prefix: &str) -> Self {
+        self.title.prefix = prefix.to_string();
+        self
+    }
+
+    /// Sets text appended to the titles returned by
+    /// [`LiveView::title`](crate::LiveView::title), such as `" · MyApp"`.
+    pub fn title_suffix(mut self, suffix: &str) -> Self {
+        self.title.suffix = suffix.to_string();
+        self
+    }
+
+    /// Sets the static assets whose versioned URLs are returned by
+    /// [`static_path`](crate::static_path).
+    ///
+    /// Clients which loaded an older version of an asset rendered with the
+    /// `phx-track-static` attribute reload the page when they rejoin, such as
+    /// after a deploy.
+    pub fn static_assets(mut self, assets: StaticAssets) -> Self {
+        self.static_assets = assets;
+        self
+    }
+
+    /// Sets how the CSRF token is delivered to the client.
+    ///
+    /// Defaults to [`CsrfProtection::MetaTag`].
+    pub fn csrf_protection(mut self, csrf: CsrfProtection) -> Self {
+        self.csrf = csrf;
+        self
+    }
+
+    /// Adds a hook invoked before the LiveView is mounted, for both the dead
+    /// render and the live socket.
+    ///
+    /// Hooks run in the order they were added, and an error rejects the mount
+    /// as if returned by [`LiveView::mount`]. Hooks are useful for checks
+    /// shared by many LiveViews, such as a maintenance mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// fn maintenance(_uri: Uri) -> Result<(), MountError> {
+    ///     if AppContext::<Config>::get().request(IsMaintenance) == Ok(true) {
+    ///         return Err(MountError::new(StatusCode::SERVICE_UNAVAILABLE, "back soon"));
+    ///     }
+    ///     Ok(())
+    /// }
+    ///
+    /// router! {
+    ///     GET "/" => MyLiveView::handler()
+    ///         .layout("index.html", "#app")
+    ///         .on_mount(maintenance)
+    /// }
+    /// ```
+    pub fn on_mount(mut self, hook: fn(Uri) -> Result<(), MountError>) -> Self {
+        self.mount_hooks.push(FuncRef::new(hook));
+        self
+    }
+
+    /// Serves a static html page when mounting fails with a status, such as a
+    /// `404.html` page for [`MountError::not_found`].
+    pub fn error_page(mut self, status: StatusCode, path: &str) -> Self {
+        self.error_pages
+            .pages
+            .push((status.as_u16(), path.to_string()));
+        self
+    }
+
+    /// Renders a view in the layout when mounting fails with a status without
+    /// an [error page](LiveViewHandler::error_page).
+    ///
+    /// The view is rendered once without a live socket.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// fn error_view(err: MountError) -> Rendered {
+    ///     html! {
+    ///         h1 { (err.status().as_u16()) }
+    ///         p { (err.message()) }
+    ///     }
+    /// }
+    ///
+    /// router! {
+    ///     GET "/posts/:id" => Post::handler()
+    ///         .layout("index.html", "#app")
+    ///         .error_page(StatusCode::NOT_FOUND, "404.html")
+    ///         .error_view(error_view)
+    /// }
+    /// ```
+    pub fn error_view(mut self, view: fn(MountError) -> Rendered) -> Self {
+        self.error_pages.view = Some(FuncRef::new(view));
+        self
+    }
+
+    /// Sends a Content Security Policy with dead renders.
+    ///
+    /// Inline scripts in the template, including the LiveView JavaScript, are
+    /// allowed by their hashes.
+    pub fn content_security_policy(mut self, csp: ContentSecurityPolicy) -> Self {
+        self.csp = Some(csp);
+        self
+    }
+
+    /// Sets how long a state snapshot can be restored after being issued.
+    ///
+    /// See [`LiveView::snapshot`]. Defaults to one hour.
+    pub fn state_token_max_age(mut self, max_age: Duration) -> Self {
+        self.state_tokens.max_age = max_age;
+        self
+    }
+
+    /// Sets the maximum length of an encrypted state snapshot, in bytes.
+    ///
+    /// Snapshots exceeding the limit are not sent to the client, and are
+    /// rejected when joining. Defaults to `4096`.
+    pub fn state_token_max_len(mut self, max_len: usize) -> Self {
+        self.state_tokens.max_len = max_len;
+        self
+    }
+
+    /// Sets how events which aren't in the LiveView's events are handled.
+    ///
+    /// Defaults to [`UnknownEventPolicy::ErrorAck`].
+    pub fn unknown_events(mut self, policy: UnknownEventPolicy) -> Self {
+        self.event_handler_options.unknown_events = policy;
+        self
+    }
+
+    /// Authenticates the live socket with short-lived auth tokens, which are
+    /// refreshed over the socket before they expire.
+    ///
+    /// The identity is available with
+    /// [`Socket::identity`](crate::socket::Socket::identity).
+    pub fn token_auth(mut self, token_auth: TokenAuth) -> Self {
+        self.event_handler_options.token_auth = Some(token_auth);
+        self
+    }
+
+    /// Limits the number of concurrent live socket connections per client IP
+    /// and per session.
+    ///
+    /// Limits are shared by all LiveView handlers with connection limits.
+    pub fn connection_limits(mut self, limits: ConnectionLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Sets how long a write to the socket can block before the message is
+    /// left buffered, or `None` to block until it's written.
+    ///
+    /// Defaults to 30 seconds.
+    pub fn write_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.socket_options.write_timeout = timeout;
+        self
+    }
+
+    /// Sets the maximum bytes waiting to be written or left buffered by writes
+    /// which timed out before the LiveView is shut down and the connection's
+    /// messages are dropped, so clients which can't keep up don't hold on to
+    /// memory indefinitely.
+    ///
+    /// Defaults to 1 MiB.
+    pub fn max_send_buffer(mut self, bytes: usize) -> Self {
+        self.socket_options.max_buffered_bytes = bytes;
+        self
+    }
+
+    /// Sets a hook which receives every event handled, along with the
+    /// authenticated identity of the connection, for security audit trails.
+    ///
+    /// The hook runs in the LiveView's process before the event is replied
+    /// to, including for unknown events and events which fail to deserialize.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// fn audit(event: AuditEvent) {
+    ///     info!("{:?} {} {:?}", event.identity, event.event, event.result);
+    /// }
+    ///
+    /// router! {
+    ///     GET "/" => MyLiveView::handler()
+    ///         .layout("index.html", "#app")
+    ///         .on_audit(audit)
+    /// }
+    /// ```
+    pub fn on_audit(mut self, hook: fn(AuditEvent)) -> Self {
+        self.event_handler_options.on_audit = Some(FuncRef::new(hook));
+        self
+    }
+
+    /// Sets a callback which receives errors handling live socket connections,
+    /// such as failed joins and events which couldn't be handled.
+    ///
+    /// Errors are logged regardless of the callback.
+    pub fn on_error(mut self, callback: fn(LiveViewError)) -> Self {
+        self.event_handler_options.on_error = Some(FuncRef::new(callback));
+        self
+    }
+
+    /// Sets a callback which receives the time spent rendering, diffing and
+    /// writing to the socket for each event handled.
+    ///
+    /// The callback runs in the LiveView's process after replying to the
+    /// event, so slow callbacks delay subsequent events.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// fn log_timings(timings: Timings) {
+    ///     info!("{:?}: {:?}", timings.events, timings);
+    /// }
+    ///
+    /// router! {
+    ///     GET "/" => MyLiveView::handler()
+    ///         .layout("index.html", "#app")
+    ///         .on_timings(log_timings)
+    /// }
+    /// ```
+    #[cfg(feature = "timings")]
+    pub fn on_timings(mut self, callback: fn(crate::Timings)) -> Self {
+        self.event_handler_options.on_timings = Some(FuncRef::new(callback));
+        self
+    }
+
+    /// Creates a handler exporting the LiveView's data as CSV or JSON, for a
+    /// route next to the LiveView's.
+    ///
+    /// The LiveView is mounted with the mount hooks and static assets of this
+    /// handler, so an export is subject to the same checks as the page.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// fn rows(orders: &Orders) -> Vec<Order> {
+    ///     orders.filtered().cloned().collect()
+    /// }
+    ///
+    /// fn handler<'a>() -> LiveViewHandler<'a, Orders> {
+    ///     Orders::handler().layout("index.html", "#app").on_mount(require_admin)
+    /// }
+    ///
+    /// router! {
+    ///     GET "/orders" => handler()
+    ///     GET "/orders.csv" => handler().export(rows).filename("orders.csv")
+    /// }
+    /// ```
+    pub fn export<R>(self, rows: fn(&T) -> Vec<R>) -> ExportHandler<T, R>
+    where
+        R: Serialize,
+    {
+        ExportHandler::new(rows, self.mount_hooks, self.static_assets)
+    }
 }
 
 impl<'a, T> Handler for LiveViewHandler<'a, T>
@@ -73,13 +536,21 @@ where
     T: LiveView,
 {
     fn init(&self) {
-        TemplateProcess::start(self.template, self.selector).expect("failed to load index.html");
+        let (template, selector) = self.template_layout();
+        TemplateProcess::start(template, selector).expect("failed to load index.html");
+        if self.limits.is_some() {
+            ConnectionRegistry::start();
+        }
+        RevokedSessions::start();
+        pubsub::start();
+        let layout = Layout::lookup(template, selector).expect("TemplateProcess should be started");
+        navigation::register(self.live_view(layout), self.event_handler_options);
     }
 
     fn handle(&self, req: RequestContext) -> Response {
-        let process = TemplateProcess::lookup(self.template, self.selector)
-            .expect("TemplateProcess should be started");
-        let live_view: LiveViewMaud<T> = Manager::new(process);
+        let (template, selector) = self.template_layout();
+        let layout = Layout::lookup(template, selector).expect("TemplateProcess should be started");
+        let live_view = self.live_view(layout.clone());
 
         let is_websocket = req
             .headers()
@@ -88,70 +559,342 @@ where
             .map(|upgrade| upgrade == "websocket")
             .unwrap_or(false);
         if is_websocket {
+            // Only the cookie sent with the upgrade request is trusted, never
+            // one claimed in the join payload.
+            let csrf_cookie = req
+                .headers()
+                .get(header::COOKIE)
+                .and_then(|cookie| cookie.to_str().ok())
+                .and_then(|cookie| cookie_value(cookie, CSRF_COOKIE))
+                .map(str::to_string);
+
+            let limits = self.limits.as_ref().map(|limits| {
+                let registry =
+                    ConnectionRegistry::lookup().expect("ConnectionRegistry should be started");
+                (limits.clone(), registry, limits.ip_key(&req))
+            });
+            if let Some((limits, registry, Some(ip_key))) = &limits {
+                if let Some(max) = limits.per_ip {
+                    if registry.count(ip_key.clone()) >= max {
+                        warn!("rejecting connection: too many connections from {ip_key}");
+                        return (StatusCode::TOO_MANY_REQUESTS, "too many connections")
+                            .into_response();
+                    }
+                }
+            }
+
             let ws = match WebSocket::from_owned_request(req) {
                 Ok(ws) => ws,
                 Err(err) => return err.into_response(),
             };
 
-            ws.on_upgrade(live_view, |conn, live_view| {
-                let (mut socket, mut message) = match wait_for_join(conn) {
-                    Ok((socket, message)) => (socket, message),
-                    Err(err) => {
-                        error!("{err}");
-                        return;
-                    },
-                };
-                let mut conn = socket.conn.clone();
-                let event_handler = EventHandler::spawn(socket.clone(), live_view);
+            let connection = Connection {
+                live_view,
+                options: self.event_handler_options,
+                csrf_cookie,
+                limits,
+                socket_options: self.socket_options,
+            };
+            ws.on_upgrade(connection, handle_connection::<T>)
+                .into_response()
+        } else {
+            let mut response = live_view.handle_request(req);
+            if let Some(csp) = &self.csp {
+                csp.apply(&mut response, layout.template().script_hashes());
+            }
+            response
+        }
+    }
+}
 
-                match event_handler.handle_join(message.take_join_event().unwrap()) {
-                    Ok(reply) => {
-                        socket.send_reply(message.reply_ok(json!({ "rendered": reply }))).unwrap();
-                    }
-                    Err(err) => {
-                        error!("{err}");
-                        return
-                    }
-                }
+impl<'a, T> LiveViewHandler<'a, T> {
+    fn template_layout(&self) -> (&'a str, &'a str) {
+        self.layout
+            .expect("LiveView handler requires a layout, set with LiveViewHandler::layout")
+    }
 
-                loop {
-                    match RawSocket::receive_from_conn(&mut conn) {
-                        Ok(SocketMessage::Event(message)) => {
-                            if !handle_message::<Manager<T>, T>(&mut socket, message, &event_handler) {
-                                break;
-                            }
-                        }
-                        Ok(SocketMessage::Ping(_)) |
-                        Ok(SocketMessage::Pong(_)) => {}
-                        Ok(SocketMessage::Close) => {
-                            info!("Socket connection closed");
-                            break;
-                        }
-                        Err(SocketError::WebsocketError(tungstenite::Error::AlreadyClosed))
-                        | Err(SocketError::WebsocketError(
-                            tungstenite::Error::ConnectionClosed,
-                        )) => {
-                            info!("connection closed");
-                            break;
-                        }
-                        Err(SocketError::WebsocketError(err)) => {
-                            warn!("read message failed: {err}");
-                            break;
-                        }
-                        Err(SocketError::DeserializeError(err)) => {
-                            warn!("deserialization failed: {err}");
-                        }
-                    }
+    fn live_view(&self, layout: Layout) -> Manager<T> {
+        let manager = Manager::new(
+            layout,
+            self.diff_options,
+            self.state_tokens,
+            self.csrf,
+            self.error_pages.clone(),
+            self.mount_hooks.clone(),
+            self.title.clone(),
+        )
+        .with_static_assets(self.static_assets.clone());
+        // Pages reused from the cache would share their session, so they'd
+        // count towards the same per-session limit.
+        match &self.limits {
+            Some(limits) if limits.per_session.is_some() => manager.without_etags(),
+            _ => manager,
+        }
+    }
+}
+
+/// Handles a websocket connection, from joining until the client leaves.
+fn handle_connection<T>(conn: WebSocketConnection, connection: Connection<T>)
+where
+    T: LiveView,
+{
+    let Connection {
+        live_view,
+        options,
+        csrf_cookie,
+        limits,
+        socket_options,
+    } = connection;
+
+    let mut conn = match acquire_ip(conn, &limits) {
+        Some(conn) => conn,
+        None => return,
+    };
+
+    // The read timeout applies to every handle of the connection, so waiting
+    // for heartbeats below replaces it.
+    set_read_timeout(&mut conn, socket_options.join_timeout);
+    let (mut socket, mut message) = match wait_for_join(conn, socket_options) {
+        Ok((socket, message)) => (socket, message),
+        Err(err) => {
+            error!("{err}");
+            LiveViewError::Join(err.to_string()).report(options.on_error);
+            return;
+        }
+    };
+
+    let join_event = message.take_join_event().unwrap();
+    socket.negotiate_serializer(join_event.params.serializer.as_deref());
+    // Navigating keeps the page, and its static assets, loaded by this handler.
+    let static_assets = live_view.static_assets().clone();
+    if reject_stale(&mut socket, &mut message, &join_event, &static_assets) {
+        return;
+    }
+    if !acquire_session(&join_event.session, &limits) {
+        let reply = message.reply_err(json!({ "reason": "too many connections" }));
+        socket.send_reply(reply).log_warn();
+        return;
+    }
+    let mut conn = socket.conn.clone();
+    let mut event_handler = EventHandler::spawn(socket.clone(), live_view, options);
+    let joined = join(
+        &mut socket,
+        &mut message,
+        join_event,
+        &event_handler,
+        csrf_cookie.clone(),
+        &options,
+        false,
+    );
+    if !joined {
+        return;
+    }
+
+    set_read_timeout(&mut conn, socket_options.heartbeat_timeout);
+    // Set when the client leaves the LiveView to navigate to another one,
+    // which is mounted when the client rejoins.
+    let mut navigation: Option<Navigation> = None;
+    let mut reason = TerminateReason::Closed;
+    loop {
+        match RawSocket::receive_from_conn(&mut conn) {
+            Ok(SocketMessage::Event(
+                mut message @ Message {
+                    event: ProtocolEvent::Join,
+                    ..
+                },
+            )) if navigation.is_some() && !message.is_upload() => {
+                let Ok(join_event) = message.take_join_event() else {
+                    break;
+                };
+                socket = socket.rejoin(message.ref1.clone(), message.topic.clone());
+                socket.negotiate_serializer(join_event.params.serializer.as_deref());
+                if reject_stale(&mut socket, &mut message, &join_event, &static_assets) {
+                    break;
                 }
-            })
-            .into_response()
-        } else {
-            live_view.handle_request(req)
+                let next = navigation
+                    .take()
+                    .and_then(|navigation| navigation.spawn(socket.clone()));
+                let Some(next) = next else {
+                    break;
+                };
+                event_handler = next;
+                let joined = join(
+                    &mut socket,
+                    &mut message,
+                    join_event,
+                    &event_handler,
+                    csrf_cookie.clone(),
+                    &options,
+                    true,
+                );
+                if !joined {
+                    break;
+                }
+            }
+            Ok(SocketMessage::Event(message)) => {
+                if !handle_message::<Manager<T>, T>(
+                    &mut socket,
+                    message,
+                    &event_handler,
+                    &mut navigation,
+                ) {
+                    break;
+                }
+            }
+            Ok(SocketMessage::Binary(message, chunk)) => {
+                event_handler.upload_chunk(message, chunk);
+            }
+            Ok(SocketMessage::Ping(_)) | Ok(SocketMessage::Pong(_)) => {}
+            Ok(SocketMessage::Close) => {
+                info!("Socket connection closed");
+                break;
+            }
+            Err(SocketError::WebsocketError(tungstenite::Error::AlreadyClosed))
+            | Err(SocketError::WebsocketError(tungstenite::Error::ConnectionClosed)) => {
+                info!("connection closed");
+                break;
+            }
+            Err(SocketError::WebsocketError(tungstenite::Error::Io(err)))
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+                ) =>
+            {
+                info!("closing connection: no heartbeat received");
+                reason = TerminateReason::Timeout;
+                socket.close(CloseCode::Away, "heartbeat timeout");
+                break;
+            }
+            Err(SocketError::WebsocketError(err)) => {
+                warn!("read message failed: {err}");
+                LiveViewError::Socket(err.to_string()).report(options.on_error);
+                reason = TerminateReason::Error;
+                break;
+            }
+            Err(SocketError::DeserializeError(err)) => {
+                warn!("deserialization failed: {err}");
+            }
         }
     }
+    // Otherwise the event handler keeps waiting for events of a connection
+    // which is gone.
+    event_handler.shutdown(reason);
 }
 
-fn wait_for_join(mut conn: WebSocketConnection) -> Result<(RawSocket, Message), SocketError> {
+/// Joins the event handler's LiveView, replying to the join message, and
+/// loads it once joined.
+///
+/// Returns false if joining failed.
+fn join(
+    socket: &mut RawSocket,
+    message: &mut Message,
+    mut join_event: JoinEvent,
+    event_handler: &EventHandler,
+    csrf_cookie: Option<String>,
+    options: &EventHandlerOptions,
+    navigating: bool,
+) -> bool {
+    join_event.csrf_cookie = csrf_cookie;
+    match event_handler.handle_join(join_event) {
+        Ok(reply) => {
+            socket
+                .send_reply(message.reply_ok(json!({ "rendered": reply })))
+                .unwrap();
+            event_handler.load();
+            true
+        }
+        Err(err) => {
+            error!("{err}");
+            // The client reloads the page after an error reply, rendering the
+            // error page if mounting failed. When navigating, this reason
+            // makes it load the page navigated to instead.
+            let reason = if navigating {
+                "unauthorized".to_string()
+            } else {
+                err.to_string()
+            };
+            let reply = message.reply_err(json!({ "reason": reason }));
+            socket.send_reply(reply).log_warn();
+            LiveViewError::Join(err.to_string()).report(options.on_error);
+            false
+        }
+    }
+}
+
+/// Replies to a join from a client which loaded older versions of the static
+/// assets with the `stale` reason, which makes it reload the page.
+///
+/// Returns true if the join was rejected.
+fn reject_stale(
+    socket: &mut RawSocket,
+    message: &mut Message,
+    join_event: &JoinEvent,
+    static_assets: &StaticAssets,
+) -> bool {
+    if !static_assets.is_stale(&join_event.params.track_static) {
+        return false;
+    }
+    info!("rejecting join: static assets changed");
+    let reply = message.reply_err(json!({ "reason": "stale" }));
+    socket.send_reply(reply).log_warn();
+    true
+}
+
+fn set_read_timeout(conn: &mut WebSocketConnection, timeout: Option<Duration>) {
+    conn.get_mut()
+        .set_read_timeout(timeout)
+        .expect("failed to set read timeout");
+}
+
+/// Registers the connection under its session, returning false if the limit
+/// is reached.
+fn acquire_session(session: &str, limits: &Limits) -> bool {
+    match limits {
+        Some((limits, registry, _)) => {
+            let key = ConnectionLimits::session_key(session);
+            let acquired = acquire_connection(registry, key, limits.per_session);
+            if !acquired {
+                warn!("rejecting join: too many connections for session");
+            }
+            acquired
+        }
+        None => true,
+    }
+}
+
+/// Registers the connection under `key`, returning false if the limit is
+/// reached.
+fn acquire_connection(registry: &ConnectionRegistry, key: String, max: Option<usize>) -> bool {
+    match max {
+        Some(max) => registry.acquire(key, max),
+        None => true,
+    }
+}
+
+/// Registers the connection under its client IP, closing it if the limit is
+/// reached.
+///
+/// The limit is checked before upgrading, but another connection may have been
+/// accepted since.
+fn acquire_ip(mut conn: WebSocketConnection, limits: &Limits) -> Option<WebSocketConnection> {
+    if let Some((limits, registry, Some(ip_key))) = limits {
+        if !acquire_connection(registry, ip_key.clone(), limits.per_ip) {
+            warn!("closing connection: too many connections from {ip_key}");
+            conn.close(Some(CloseFrame {
+                code: CloseCode::Policy,
+                reason: "too many connections".into(),
+            }))
+            .log_warn();
+            return None;
+        }
+    }
+    Some(conn)
+}
+
+fn wait_for_join(
+    mut conn: WebSocketConnection,
+    options: SocketOptions,
+) -> Result<(RawSocket, Message), SocketError> {
     loop {
         match RawSocket::receive_from_conn(&mut conn) {
             Ok(SocketMessage::Event(
@@ -160,14 +903,9 @@ fn wait_for_join(mut conn: WebSocketConnection) -> Result<(RawSocket, Message),
                     ..
                 },
             )) => {
-                return Ok((
-                    RawSocket {
-                        conn,
-                        ref1: message.ref1.clone(),
-                        topic: message.topic.clone(),
-                    },
-                    message,
-                ));
+                let ref1 = message.ref1.clone();
+                let topic = message.topic.clone();
+                return Ok((RawSocket::new(conn, ref1, topic, options), message));
             }
             Ok(SocketMessage::Event(Message {
                 event: ProtocolEvent::Close,
@@ -182,7 +920,12 @@ fn wait_for_join(mut conn: WebSocketConnection) -> Result<(RawSocket, Message),
                     tungstenite::Error::ConnectionClosed,
                 ));
             }
-            Ok(SocketMessage::Event(_) | SocketMessage::Ping(_) | SocketMessage::Pong(_)) => {}
+            Ok(
+                SocketMessage::Event(_)
+                | SocketMessage::Binary(..)
+                | SocketMessage::Ping(_)
+                | SocketMessage::Pong(_),
+            ) => {}
             Err(SocketError::WebsocketError(err @ tungstenite::Error::AlreadyClosed))
             | Err(SocketError::WebsocketError(err @ tungstenite::Error::ConnectionClosed))
             | Err(SocketError::WebsocketError(err)) => {
@@ -199,6 +942,7 @@ fn handle_message<L, T>(
     socket: &mut RawSocket,
     mut message: Message,
     event_handler: &EventHandler,
+    navigation: &mut Option<Navigation>,
 ) -> bool
 where
     L: LiveViewManager<T> + Serialize + for<'de> Deserialize<'de>,
@@ -214,39 +958,94 @@ where
         }
         ProtocolEvent::Diff => true,
         ProtocolEvent::Error => true,
-        ProtocolEvent::Event => match message.take_event() {
-            Ok(event) => {
-                info!("Received event {}", event.name);
-                match event_handler.handle_event(event) {
-                    Ok(Some(reply)) => {
-                        socket
-                            .send_reply(message.reply_ok(json!({ "diff": reply })))
-                            .log_warn();
-                    }
-                    Ok(None) => {
-                        socket.send_reply(message.reply_ok(json!({}))).log_warn();
-                    }
-                    Err(err) => {
-                        error!("{err}");
-                    }
-                }
-                true
-            }
-            Err(err) => {
-                error!("{err}");
-                true
-            }
-        },
+        ProtocolEvent::LiveRedirect => true,
+        ProtocolEvent::Redirect => true,
+        ProtocolEvent::Event => {
+            event_handler.push_event(message);
+            true
+        }
+        // Heartbeats are answered here rather than by the event handler, so a
+        // long running render or load can't delay them and cause the client to
+        // reconnect.
         ProtocolEvent::Heartbeat => {
             socket.send_reply(message.reply_ok(json!({}))).log_error();
             true
         }
+        ProtocolEvent::LivePatch => {
+            event_handler.patch(message);
+            true
+        }
+        ProtocolEvent::AuthAck => {
+            event_handler.acknowledge_auth();
+            socket.send_reply(message.reply_ok(json!({}))).log_error();
+            true
+        }
+        // Each uploaded file joins a channel of its own, which its chunks are
+        // sent on.
+        ProtocolEvent::Join if message.is_upload() => {
+            event_handler.upload(message);
+            true
+        }
         ProtocolEvent::Join => false,
-        ProtocolEvent::Leave => {
-            info!("Client left");
-            false
+        ProtocolEvent::Leave if message.is_upload() => {
+            socket.send_reply(message.reply_ok(json!({}))).log_warn();
+            true
         }
+        // The client leaves before rejoining when navigating to another
+        // LiveView, keeping the connection open.
+        ProtocolEvent::Leave => match event_handler.leave() {
+            Some(next) => {
+                *navigation = Some(next);
+                socket.send_reply(message.reply_ok(json!({}))).log_warn();
+                true
+            }
+            None => {
+                info!("Client left");
+                false
+            }
+        },
         ProtocolEvent::Reply => true,
+        ProtocolEvent::AllowUpload | ProtocolEvent::Progress => {
+            event_handler.upload(message);
+            true
+        }
+        // Chunks are binary messages, handled by the connection.
+        ProtocolEvent::Chunk => true,
+        // Components are only destroyed once the client confirms they were
+        // removed, as they may be rendered again meanwhile.
+        ProtocolEvent::CidsWillDestroy => {
+            socket.send_reply(message.reply_ok(json!({}))).log_warn();
+            true
+        }
+        ProtocolEvent::CidsDestroyed => {
+            event_handler.destroy_components(message);
+            true
+        }
+    }
+}
+
+impl LiveViewError {
+    /// Passes the error to the callback registered with
+    /// [`LiveViewHandler::on_error`], if any.
+    pub(crate) fn report(self, callback: Option<FuncRef<fn(LiveViewError)>>) {
+        if let Some(callback) = callback {
+            (callback.get())(self);
+        }
+    }
+}
+
+impl fmt::Display for LiveViewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiveViewError::Join(err) => write!(f, "join failed: {err}"),
+            LiveViewError::Event {
+                event: Some(event),
+                error,
+            } => write!(f, "event {event} failed: {error}"),
+            LiveViewError::Event { event: None, error } => write!(f, "event failed: {error}"),
+            LiveViewError::Render(err) => write!(f, "render failed: {err}"),
+            LiveViewError::Socket(err) => write!(f, "socket error: {err}"),
+        }
     }
 }
 
@@ -0,0 +1,416 @@
+//! Tags for the page's head, rendered by
+//! [`LiveView::head`](crate::LiveView::head).
+
+use serde::{Deserialize, Serialize};
+
+use crate::rendered::Rendered;
+use crate::{self as submillisecond_live_view, html};
+
+/// Open Graph metadata, describing the page when it's shared on social media.
+///
+/// Rendered as `meta` tags with a `property` attribute, such as
+/// `og:title`. Fields which are `None` are left out.
+///
+/// # Example
+///
+/// ```
+/// fn head(&self) -> Option<Rendered> {
+///     let url = format!("https://example.com/posts/{}", self.post.id);
+///     Some(html! {
+///         @(OpenGraph {
+///             title: self.post.title.clone(),
+///             description: Some(self.post.summary.clone()),
+///             image: self.post.cover_url.clone(),
+///             url: Some(url.clone()),
+///         }.render())
+///         @(canonical_link(&url))
+///     })
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenGraph {
+    /// Title of the page, as `og:title`.
+    pub title: String,
+    /// Short description of the page, as `og:description`.
+    pub description: Option<String>,
+    /// Absolute URL of an image representing the page, as `og:image`.
+    pub image: Option<String>,
+    /// Canonical absolute URL of the page, as `og:url`.
+    pub url: Option<String>,
+}
+
+impl OpenGraph {
+    /// Renders the `meta` tags.
+    pub fn render(&self) -> Rendered {
+        html! {
+            meta property="og:title" content=(self.title);
+            @if let Some(description) = &self.description {
+                meta property="og:description" content=(description);
+            }
+            @if let Some(image) = &self.image {
+                meta property="og:image" content=(image);
+            }
+            @if let Some(url) = &self.url {
+                meta property="og:url" content=(url);
+            }
+        }
+    }
+}
+
+/// A `link` tag, such as a favicon, a web app manifest or a preconnect hint.
+///
+/// # Example
+///
+/// ```
+/// fn head(&self) -> Option<Rendered> {
+///     Some(html! {
+///         @(Link::icon("/favicon-32.png").mime_type("image/png").sizes("32x32").render())
+///         @(Link::manifest("/site.webmanifest").render())
+///         @(Link::new("preconnect", "https://fonts.gstatic.com").render())
+///     })
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Link {
+    rel: String,
+    href: String,
+    mime_type: Option<String>,
+    sizes: Option<String>,
+}
+
+impl Link {
+    /// Creates a link with a `rel` and `href` attribute.
+    pub fn new(rel: impl Into<String>, href: impl Into<String>) -> Self {
+        Link {
+            rel: rel.into(),
+            href: href.into(),
+            mime_type: None,
+            sizes: None,
+        }
+    }
+
+    /// Creates a favicon link.
+    pub fn icon(href: impl Into<String>) -> Self {
+        Link::new("icon", href)
+    }
+
+    /// Creates a link to a web app manifest.
+    pub fn manifest(href: impl Into<String>) -> Self {
+        Link::new("manifest", href)
+    }
+
+    /// Sets the MIME type of the linked resource, as the `type` attribute.
+    pub fn mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+
+    /// Sets the sizes of an icon, such as `32x32`.
+    pub fn sizes(mut self, sizes: impl Into<String>) -> Self {
+        self.sizes = Some(sizes.into());
+        self
+    }
+
+    /// Renders the `link` tag.
+    pub fn render(&self) -> Rendered {
+        html! {
+            link rel=(self.rel) href=(self.href) type=[self.mime_type.as_ref()] sizes=[self.sizes.as_ref()];
+        }
+    }
+}
+
+/// A `script` tag loading a script, such as a bundle or a script from a CDN.
+///
+/// # Example
+///
+/// ```
+/// fn head(&self) -> Option<Rendered> {
+///     Some(html! {
+///         @(Script::new("/assets/app.js").module().render())
+///         @(Script::new("/assets/legacy.js").nomodule().defer().render())
+///         @(Script::new("https://cdn.example.com/lib.js")
+///             .integrity("sha384-...")
+///             .crossorigin("anonymous")
+///             .render())
+///     })
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Script {
+    src: String,
+    module: bool,
+    nomodule: bool,
+    asynchronous: bool,
+    defer: bool,
+    integrity: Option<String>,
+    crossorigin: Option<String>,
+}
+
+impl Script {
+    /// Creates a script loaded from `src`.
+    pub fn new(src: impl Into<String>) -> Self {
+        Script {
+            src: src.into(),
+            module: false,
+            nomodule: false,
+            asynchronous: false,
+            defer: false,
+            integrity: None,
+            crossorigin: None,
+        }
+    }
+
+    /// Loads the script as an ES module, with `type="module"`.
+    pub fn module(mut self) -> Self {
+        self.module = true;
+        self
+    }
+
+    /// Only runs the script in browsers without ES module support, with the
+    /// `nomodule` attribute.
+    pub fn nomodule(mut self) -> Self {
+        self.nomodule = true;
+        self
+    }
+
+    /// Runs the script as soon as it's loaded, with the `async` attribute.
+    pub fn asynchronous(mut self) -> Self {
+        self.asynchronous = true;
+        self
+    }
+
+    /// Runs the script once the page is parsed, with the `defer` attribute.
+    pub fn defer(mut self) -> Self {
+        self.defer = true;
+        self
+    }
+
+    /// Sets the hash the script must match, such as `sha384-...`.
+    pub fn integrity(mut self, integrity: impl Into<String>) -> Self {
+        self.integrity = Some(integrity.into());
+        self
+    }
+
+    /// Sets the CORS mode of the request, such as `anonymous`.
+    pub fn crossorigin(mut self, crossorigin: impl Into<String>) -> Self {
+        self.crossorigin = Some(crossorigin.into());
+        self
+    }
+
+    /// Renders the `script` tag.
+    pub fn render(&self) -> Rendered {
+        html! {
+            script
+                src=(self.src)
+                type=[self.module.then_some("module")]
+                nomodule[self.nomodule]
+                async[self.asynchronous]
+                defer[self.defer]
+                integrity=[self.integrity.as_ref()]
+                crossorigin=[self.crossorigin.as_ref()] {}
+        }
+    }
+}
+
+/// The `onload` handler applying a preloaded stylesheet.
+pub(crate) const PRELOAD_ONLOAD: &str = "this.onload=null;this.rel='stylesheet'";
+
+/// A linked stylesheet, optionally for a media query, or preloaded so it
+/// doesn't block rendering.
+///
+/// Stylesheets from a CDN can be checked with [`Stylesheet::integrity`], which
+/// applies to both links of a preloaded stylesheet.
+///
+/// Preloaded stylesheets are applied once loaded by an inline `onload`
+/// handler, which a
+/// [`ContentSecurityPolicy`](crate::csp::ContentSecurityPolicy) allows by its
+/// hash. Clients without JavaScript load them from a `noscript` fallback.
+///
+/// # Example
+///
+/// ```
+/// fn head(&self) -> Option<Rendered> {
+///     Some(html! {
+///         @(Stylesheet::new("/assets/app.css").render())
+///         @(Stylesheet::new("/assets/dark.css").media("(prefers-color-scheme: dark)").render())
+///         @(Stylesheet::new("/assets/print.css").media("print").preload().render())
+///         @(Stylesheet::new("https://cdn.example.com/lib.css")
+///             .integrity("sha384-...")
+///             .crossorigin("anonymous")
+///             .render())
+///     })
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stylesheet {
+    href: String,
+    media: Option<String>,
+    preload: bool,
+    integrity: Option<String>,
+    crossorigin: Option<String>,
+}
+
+impl Stylesheet {
+    /// Creates a stylesheet loaded from `href`.
+    pub fn new(href: impl Into<String>) -> Self {
+        Stylesheet {
+            href: href.into(),
+            media: None,
+            preload: false,
+            integrity: None,
+            crossorigin: None,
+        }
+    }
+
+    /// Only applies the stylesheet to a media query, such as `print`.
+    pub fn media(mut self, media: impl Into<String>) -> Self {
+        self.media = Some(media.into());
+        self
+    }
+
+    /// Preloads the stylesheet with `rel="preload"`, applying it once loaded
+    /// instead of blocking rendering until then.
+    pub fn preload(mut self) -> Self {
+        self.preload = true;
+        self
+    }
+
+    /// Sets the hash the stylesheet must match, such as `sha384-...`.
+    pub fn integrity(mut self, integrity: impl Into<String>) -> Self {
+        self.integrity = Some(integrity.into());
+        self
+    }
+
+    /// Sets the CORS mode of the request, such as `anonymous`.
+    pub fn crossorigin(mut self, crossorigin: impl Into<String>) -> Self {
+        self.crossorigin = Some(crossorigin.into());
+        self
+    }
+
+    /// Renders the `link` tag.
+    pub fn render(&self) -> Rendered {
+        html! {
+            @if self.preload {
+                link
+                    rel="preload"
+                    href=(self.href)
+                    as="style"
+                    media=[self.media.as_ref()]
+                    integrity=[self.integrity.as_ref()]
+                    crossorigin=[self.crossorigin.as_ref()]
+                    onload=(PRELOAD_ONLOAD);
+                noscript {
+                    link
+                        rel="stylesheet"
+                        href=(self.href)
+                        media=[self.media.as_ref()]
+                        integrity=[self.integrity.as_ref()]
+                        crossorigin=[self.crossorigin.as_ref()];
+                }
+            } @else {
+                link
+                    rel="stylesheet"
+                    href=(self.href)
+                    media=[self.media.as_ref()]
+                    integrity=[self.integrity.as_ref()]
+                    crossorigin=[self.crossorigin.as_ref()];
+            }
+        }
+    }
+}
+
+/// Renders a `link` tag with the canonical URL of the page, so search engines
+/// index pages reachable from several URLs under one.
+pub fn canonical_link(url: &str) -> Rendered {
+    html! {
+        link rel="canonical" href=(url);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonical_link, Link, OpenGraph, Script, Stylesheet};
+
+    #[test]
+    fn open_graph_tags_are_escaped() {
+        let tags = OpenGraph {
+            title: "Tom & Jerry".to_string(),
+            url: Some("https://example.com/?a=1&b=\"2\"".to_string()),
+            ..Default::default()
+        }
+        .render()
+        .to_string();
+        assert!(tags.contains(r#"property="og:title" content="Tom &amp; Jerry""#));
+        assert!(tags.contains(r#"content="https://example.com/?a=1&amp;b=&quot;2&quot;""#));
+        assert!(!tags.contains("og:description"));
+        assert!(!tags.contains("og:image"));
+
+        let link = canonical_link("https://example.com/posts").to_string();
+        assert!(link.contains(r#"rel="canonical" href="https://example.com/posts""#));
+    }
+
+    #[test]
+    fn link_attributes_are_optional() {
+        let icon = Link::icon("/favicon.png")
+            .mime_type("image/png")
+            .sizes("32x32")
+            .render()
+            .to_string();
+        assert!(icon.contains(r#"rel="icon" href="/favicon.png" type="image/png" sizes="32x32""#));
+
+        let manifest = Link::manifest("/site.webmanifest").render().to_string();
+        assert!(manifest.contains(r#"rel="manifest" href="/site.webmanifest""#));
+        assert!(!manifest.contains("type="));
+        assert!(!manifest.contains("sizes="));
+    }
+
+    #[test]
+    fn script_attributes_are_toggled() {
+        let module = Script::new("/app.js").module().render().to_string();
+        assert!(module.contains(r#"src="/app.js" type="module""#));
+        assert!(!module.contains("async"));
+        assert!(!module.contains("defer"));
+
+        let cdn = Script::new("https://cdn.example.com/lib.js")
+            .asynchronous()
+            .integrity("sha384-abc")
+            .crossorigin("anonymous")
+            .render()
+            .to_string();
+        assert!(cdn.contains(" async"));
+        assert!(cdn.contains(r#"integrity="sha384-abc" crossorigin="anonymous""#));
+        assert!(!cdn.contains("type="));
+    }
+
+    #[test]
+    fn preloaded_stylesheets_fall_back_to_noscript() {
+        let print = Stylesheet::new("/print.css")
+            .media("print")
+            .render()
+            .to_string();
+        assert!(print.contains(r#"rel="stylesheet" href="/print.css" media="print""#));
+
+        let preloaded = Stylesheet::new("/app.css").preload().render().to_string();
+        assert!(preloaded.contains(r#"rel="preload" href="/app.css" as="style""#));
+        assert!(preloaded.contains("onload="));
+        assert!(preloaded.contains(r#"<noscript><link rel="stylesheet" href="/app.css""#));
+    }
+
+    #[test]
+    fn stylesheet_integrity_covers_preload_and_noscript() {
+        let cdn = Stylesheet::new("https://cdn.example.com/lib.css")
+            .integrity("sha384-abc")
+            .crossorigin("anonymous");
+        let sri = r#"integrity="sha384-abc" crossorigin="anonymous""#;
+
+        let linked = cdn.clone().render().to_string();
+        assert!(linked.contains(sri));
+
+        let preloaded = cdn.preload().render().to_string();
+        assert_eq!(preloaded.matches(sri).count(), 2);
+        assert!(!Stylesheet::new("/app.css")
+            .render()
+            .to_string()
+            .contains("integrity"));
+    }
+}
@@ -0,0 +1,216 @@
+//! Undo and redo history of a value.
+
+use std::collections::VecDeque;
+use std::mem;
+use std::ops::Deref;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rendered::Rendered;
+use crate::{self as submillisecond_live_view, html};
+
+/// A value with undo and redo history.
+///
+/// Changes made with [`History::update`] or [`History::set`] are recorded
+/// only if they change the value, so handlers can update the value
+/// unconditionally. The number of changes kept is bounded by
+/// [`History::depth`].
+///
+/// # Example
+///
+/// ```
+/// impl LiveView for Editor {
+///     type Events = (Edit, Undo, Redo);
+///
+///     fn render(&self) -> Rendered {
+///         html! {
+///             textarea name="text" @change=(Edit) { (self.text.get()) }
+///             @(self.text.render_controls())
+///         }
+///     }
+///     // ...
+/// }
+///
+/// impl LiveViewEvent<Edit> for Editor {
+///     fn handle(state: &mut Self, event: Edit) {
+///         state.text.set(event.text);
+///     }
+/// }
+///
+/// impl LiveViewEvent<Undo> for Editor {
+///     fn handle(state: &mut Self, _event: Undo) {
+///         state.text.undo();
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct History<T> {
+    past: VecDeque<T>,
+    present: T,
+    future: Vec<T>,
+    depth: usize,
+}
+
+/// Event sent by the undo button of [`History::render_controls`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Undo {}
+
+/// Event sent by the redo button of [`History::render_controls`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Redo {}
+
+impl<T> History<T>
+where
+    T: Clone + PartialEq,
+{
+    /// Creates a history with an initial value, keeping up to 100 changes.
+    pub fn new(value: T) -> Self {
+        History {
+            past: VecDeque::new(),
+            present: value,
+            future: Vec::new(),
+            depth: 100,
+        }
+    }
+
+    /// Sets the number of changes which can be undone.
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self.trim();
+        self
+    }
+
+    /// Returns the current value.
+    pub fn get(&self) -> &T {
+        &self.present
+    }
+
+    /// Replaces the value, recording the change if it differs.
+    pub fn set(&mut self, value: T) {
+        if value != self.present {
+            let previous = mem::replace(&mut self.present, value);
+            self.record(previous);
+        }
+    }
+
+    /// Updates the value in place, recording the change if it differs.
+    pub fn update(&mut self, f: impl FnOnce(&mut T)) {
+        let mut value = self.present.clone();
+        f(&mut value);
+        self.set(value);
+    }
+
+    /// Restores the value before the last change, returning `false` if there
+    /// is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.past.pop_back() {
+            Some(previous) => {
+                let current = mem::replace(&mut self.present, previous);
+                self.future.push(current);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reapplies the last undone change, returning `false` if there is
+    /// nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.future.pop() {
+            Some(next) => {
+                let current = mem::replace(&mut self.present, next);
+                self.past.push_back(current);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns whether there is a change to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.past.is_empty()
+    }
+
+    /// Returns whether there is an undone change to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty()
+    }
+
+    /// Forgets all changes, keeping the current value.
+    pub fn clear(&mut self) {
+        self.past.clear();
+        self.future.clear();
+    }
+
+    /// Renders undo and redo buttons, which send [`Undo`] and [`Redo`]
+    /// events.
+    pub fn render_controls(&self) -> Rendered {
+        html! {
+            div.history-controls {
+                button.history-undo type="button" disabled[!self.can_undo()] @click=(Undo) {
+                    "Undo"
+                }
+                button.history-redo type="button" disabled[!self.can_redo()] @click=(Redo) {
+                    "Redo"
+                }
+            }
+        }
+    }
+
+    fn record(&mut self, previous: T) {
+        self.past.push_back(previous);
+        self.future.clear();
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        while self.past.len() > self.depth {
+            self.past.pop_front();
+        }
+    }
+}
+
+impl<T> Deref for History<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.present
+    }
+}
+
+impl<T> Default for History<T>
+where
+    T: Clone + Default + PartialEq,
+{
+    fn default() -> Self {
+        History::new(T::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::History;
+
+    #[test]
+    fn undo_and_redo_changes() {
+        let mut history = History::new(1).depth(2);
+        history.set(1);
+        assert!(!history.can_undo());
+
+        history.set(2);
+        history.update(|value| *value += 1);
+        history.set(4);
+        assert_eq!(*history, 4);
+
+        assert!(history.undo());
+        assert!(history.undo());
+        assert_eq!(*history, 2);
+        // Only 2 changes are kept.
+        assert!(!history.undo());
+
+        assert!(history.redo());
+        assert_eq!(*history, 3);
+        history.set(5);
+        assert!(!history.can_redo());
+    }
+}
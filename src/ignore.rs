@@ -0,0 +1,110 @@
+//! Regions of the page left to client-side JavaScript.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::mem;
+
+use crate::rendered::Rendered;
+use crate::{self as submillisecond_live_view, html, PreEscaped};
+
+thread_local! {
+    static IGNORED: RefCell<Ignored> = RefCell::new(Ignored::default());
+}
+
+/// Regions rendered by the LiveView, by id.
+#[derive(Default)]
+struct Ignored {
+    /// Regions of the last render, not yet used by the current one.
+    previous: HashMap<String, Rendered>,
+    /// Regions used by the current render.
+    current: HashMap<String, Rendered>,
+}
+
+/// Renders a `div` with `phx-update="ignore"`, so widgets managed by
+/// JavaScript, such as maps or rich text editors, aren't clobbered when the
+/// LiveView is patched.
+///
+/// The content is only rendered the first time the region is rendered, such
+/// as when mounting or joining, and the region is left out of the diffs of
+/// later renders. It's rendered again if the region is removed and rendered
+/// again later.
+///
+/// The `id` must be unique on the page.
+///
+/// # Example
+///
+/// ```
+/// html! {
+///     @(ignore_updates("map", || html! {
+///         div phx-hook="Map" data-lat=(self.lat) data-lng=(self.lng) {}
+///     }))
+/// }
+/// ```
+pub fn ignore_updates(id: &str, content: impl FnOnce() -> Rendered) -> Rendered {
+    let rendered = IGNORED.with(|ignored| {
+        let mut ignored = ignored.borrow_mut();
+        let Ignored { previous, current } = &mut *ignored;
+        current.get(id).cloned().or_else(|| previous.remove(id))
+    });
+    let rendered = rendered.unwrap_or_else(|| {
+        let content = content().to_string();
+        html! {
+            div id=(id) phx-update="ignore" {
+                (PreEscaped(content))
+            }
+        }
+    });
+    IGNORED.with(|ignored| {
+        ignored
+            .borrow_mut()
+            .current
+            .insert(id.to_string(), rendered.clone());
+    });
+    rendered
+}
+
+/// Drops all regions, so they're rendered again by a dead render or a join.
+pub(crate) fn reset() {
+    IGNORED.with(|ignored| *ignored.borrow_mut() = Ignored::default());
+}
+
+/// Drops the regions which weren't rendered by the LiveView's last render.
+pub(crate) fn end_render() {
+    IGNORED.with(|ignored| {
+        let mut ignored = ignored.borrow_mut();
+        ignored.previous = mem::take(&mut ignored.current);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{end_render, ignore_updates};
+    use crate::{self as submillisecond_live_view, html};
+
+    #[test]
+    fn content_is_only_rendered_once() {
+        let render = |count: usize| {
+            let rendered = ignore_updates("editor", || {
+                html! {
+                    div { "Edits: " (count) }
+                }
+            });
+            end_render();
+            rendered
+        };
+
+        let first = render(1);
+        assert_eq!(
+            first.to_string(),
+            r#"<div id="editor" phx-update="ignore"><div>Edits: 1</div></div>"#
+        );
+        assert_eq!(first.diff(render(2)), None);
+
+        // Regions which are removed are rendered again when they come back.
+        end_render();
+        assert_eq!(
+            render(3).to_string(),
+            r#"<div id="editor" phx-update="ignore"><div>Edits: 3</div></div>"#
+        );
+    }
+}
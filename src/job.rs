@@ -0,0 +1,282 @@
+//! Background jobs reporting progress to a LiveView.
+
+use lunatic::function::FuncRef;
+use lunatic::serializer::Json;
+use lunatic::{Mailbox, MessageSignal, Process, ProcessDiedSignal};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::event_handler::EventHandlerError;
+use crate::rendered::Rendered;
+use crate::socket::Socket;
+use crate::{self as submillisecond_live_view, html};
+
+/// Function run by a job's worker process.
+type JobFn<C> = FuncRef<fn(C, JobProgress) -> Result<(), String>>;
+
+/// Progress of a background job started with
+/// [`Socket::track_job`](crate::socket::Socket::track_job).
+///
+/// The job is kept in the LiveView's state, and updated by handling the
+/// [`JobUpdated`] events sent as the job progresses and finishes.
+///
+/// # Example
+///
+/// ```
+/// impl LiveViewEvent<Import> for Products {
+///     fn handle(state: &mut Self, event: Import) {
+///         if let Some(socket) = &state.socket {
+///             state.import = Some(socket.track_job(event.url, |url, mut progress| {
+///                 let rows = fetch_rows(&url)?;
+///                 for (i, row) in rows.iter().enumerate() {
+///                     save(row)?;
+///                     progress
+///                         .report(i as u64 + 1, rows.len() as u64)
+///                         .map_err(|err| err.to_string())?;
+///                 }
+///                 Ok(())
+///             }));
+///         }
+///     }
+/// }
+///
+/// impl LiveViewEvent<JobUpdated> for Products {
+///     fn handle(state: &mut Self, event: JobUpdated) {
+///         if let Some(import) = &mut state.import {
+///             import.handle(event);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Job {
+    id: u64,
+    done: u64,
+    total: Option<u64>,
+    status: JobStatus,
+}
+
+/// Status of a [`Job`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JobStatus {
+    /// The job is still running.
+    Running,
+    /// The job finished successfully.
+    Completed,
+    /// The job returned an error or panicked.
+    Failed(String),
+}
+
+/// Event sent to the LiveView as a [`Job`] progresses and finishes.
+///
+/// Only the server sends it, so clients pushing an event of the same name are
+/// rejected as an unknown event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobUpdated {
+    /// The id of the job.
+    pub id: u64,
+    /// Units of work done, if reported.
+    pub done: Option<u64>,
+    /// Total units of work, if reported.
+    pub total: Option<u64>,
+    /// The status of the job.
+    pub status: JobStatus,
+}
+
+/// Reports the progress of a running job to its LiveView.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JobProgress {
+    id: u64,
+    socket: Socket,
+}
+
+#[derive(Serialize, Deserialize)]
+enum TrackerMessage {
+    Finished(Result<(), String>),
+}
+
+impl Job {
+    /// Returns the id of the job, which [`JobUpdated`] events are sent with.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the status of the job.
+    pub fn status(&self) -> &JobStatus {
+        &self.status
+    }
+
+    /// Returns whether the job is still running.
+    pub fn is_running(&self) -> bool {
+        self.status == JobStatus::Running
+    }
+
+    /// Returns the units of work done and the total, if reported.
+    pub fn progress(&self) -> Option<(u64, u64)> {
+        self.total.map(|total| (self.done, total))
+    }
+
+    /// Returns the percentage of work done, if reported.
+    pub fn percent(&self) -> Option<f64> {
+        match self.progress() {
+            Some((_, 0)) => Some(100.0),
+            Some((done, total)) => Some(done as f64 * 100.0 / total as f64),
+            None => None,
+        }
+    }
+
+    /// Updates the job from an event.
+    ///
+    /// Events of other jobs are ignored.
+    pub fn handle(&mut self, event: JobUpdated) {
+        if event.id != self.id {
+            return;
+        }
+        if let Some(done) = event.done {
+            self.done = done;
+        }
+        if event.total.is_some() {
+            self.total = event.total;
+        }
+        self.status = event.status;
+    }
+
+    /// Renders a progress bar while running, and the error if the job failed.
+    pub fn render(&self) -> Rendered {
+        let done = self.done;
+        let total = self.total;
+        html! {
+            div.job {
+                @if self.is_running() {
+                    @if let Some(total) = total {
+                        progress.job-progress value=(done) max=(total) {}
+                    } @else {
+                        progress.job-progress {}
+                    }
+                } @else if let JobStatus::Failed(error) = &self.status {
+                    p.job-error { (error) }
+                }
+            }
+        }
+    }
+}
+
+impl JobProgress {
+    /// Reports the units of work done out of a total.
+    ///
+    /// Returns an error if the LiveView can no longer be updated, such as when
+    /// the client disconnected, so the job can stop early.
+    pub fn report(&mut self, done: u64, total: u64) -> Result<(), EventHandlerError> {
+        self.socket.send_event(JobUpdated {
+            id: self.id,
+            done: Some(done),
+            total: Some(total),
+            status: JobStatus::Running,
+        })
+    }
+}
+
+/// Starts a job in a worker process, tracked by a process which reports when
+/// it finishes and stops it if the LiveView goes away.
+pub(crate) fn track<C>(
+    socket: Socket,
+    context: C,
+    job: fn(C, JobProgress) -> Result<(), String>,
+) -> Job
+where
+    C: Serialize + DeserializeOwned,
+{
+    let id = rand::random();
+    Process::spawn((socket, id, context, FuncRef::new(job)), tracker::<C>);
+    Job {
+        id,
+        done: 0,
+        total: None,
+        status: JobStatus::Running,
+    }
+}
+
+fn tracker<C>(
+    (mut socket, id, context, job): (Socket, u64, C, JobFn<C>),
+    mailbox: Mailbox<TrackerMessage, Json>,
+) where
+    C: Serialize + DeserializeOwned,
+{
+    let mailbox = mailbox.monitorable();
+    let progress = JobProgress {
+        id,
+        socket: socket.clone(),
+    };
+    let worker = Process::spawn((mailbox.this(), progress, context, job), worker::<C>);
+    mailbox.monitor(worker);
+    mailbox.monitor(socket.event_handler.process());
+
+    let status = match mailbox.receive() {
+        MessageSignal::Message(TrackerMessage::Finished(Ok(()))) => JobStatus::Completed,
+        MessageSignal::Message(TrackerMessage::Finished(Err(err))) => JobStatus::Failed(err),
+        MessageSignal::Signal(ProcessDiedSignal(died)) if died == worker.id() => {
+            JobStatus::Failed("job panicked".to_string())
+        }
+        MessageSignal::Signal(ProcessDiedSignal(_)) => {
+            // The LiveView is gone, so there's no one to report to.
+            worker.kill();
+            return;
+        }
+    };
+    let _ = socket.send_event(JobUpdated {
+        id,
+        done: None,
+        total: None,
+        status,
+    });
+}
+
+fn worker<C>(
+    (tracker, progress, context, job): (Process<TrackerMessage, Json>, JobProgress, C, JobFn<C>),
+    _: Mailbox<(), Json>,
+) where
+    C: Serialize + DeserializeOwned,
+{
+    let result = (job.get())(context, progress);
+    tracker.send(TrackerMessage::Finished(result));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Job, JobStatus, JobUpdated};
+
+    #[test]
+    fn handle_updates_matching_job() {
+        let mut job = Job {
+            id: 1,
+            done: 0,
+            total: None,
+            status: JobStatus::Running,
+        };
+        assert_eq!(job.percent(), None);
+
+        job.handle(JobUpdated {
+            id: 1,
+            done: Some(1),
+            total: Some(4),
+            status: JobStatus::Running,
+        });
+        job.handle(JobUpdated {
+            id: 2,
+            done: Some(4),
+            total: Some(4),
+            status: JobStatus::Completed,
+        });
+        assert_eq!(job.percent(), Some(25.0));
+        assert!(job.is_running());
+
+        job.handle(JobUpdated {
+            id: 1,
+            done: None,
+            total: None,
+            status: JobStatus::Failed("timed out".to_string()),
+        });
+        assert_eq!(job.progress(), Some((1, 4)));
+        assert_eq!(job.status(), &JobStatus::Failed("timed out".to_string()));
+    }
+}
@@ -0,0 +1,200 @@
+//! Commands run by the client without a round trip to the server.
+
+use std::any::type_name;
+use std::fmt;
+
+use maud_live_view::Render;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// Commands run by the client when an event is triggered, such as showing or
+/// hiding an element, without waiting for the server.
+///
+/// Commands are used in place of an event name in `phx-*` attributes, and
+/// chained with [`JS::then`]. [`JS::push`] sends an event to the LiveView
+/// along with the other commands.
+///
+/// Options such as transitions apply to the last command.
+///
+/// # Example
+///
+/// ```
+/// html! {
+///     button phx-click=(JS::toggle("#menu").transition("ease-out", "opacity-0", "opacity-100")) {
+///         "Menu"
+///     }
+///     button phx-click=(JS::hide("#modal").then(JS::push::<CloseModal>())) { "Close" }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct JS {
+    commands: Vec<(String, Map<String, Value>)>,
+}
+
+impl JS {
+    /// Creates an empty list of commands.
+    pub fn new() -> Self {
+        JS::default()
+    }
+
+    /// Shows the elements matching a selector.
+    pub fn show(to: &str) -> Self {
+        JS::command("show", to)
+    }
+
+    /// Hides the elements matching a selector.
+    pub fn hide(to: &str) -> Self {
+        JS::command("hide", to)
+    }
+
+    /// Shows or hides the elements matching a selector.
+    pub fn toggle(to: &str) -> Self {
+        JS::command("toggle", to)
+    }
+
+    /// Adds classes, separated by spaces, to the elements matching a selector.
+    pub fn add_class(to: &str, names: &str) -> Self {
+        JS::command("add_class", to).arg("names", classes(names))
+    }
+
+    /// Removes classes, separated by spaces, from the elements matching a
+    /// selector.
+    pub fn remove_class(to: &str, names: &str) -> Self {
+        JS::command("remove_class", to).arg("names", classes(names))
+    }
+
+    /// Sets an attribute of the elements matching a selector.
+    pub fn set_attribute(to: &str, name: &str, value: &str) -> Self {
+        JS::command("set_attr", to).arg("attr", json!([name, value]))
+    }
+
+    /// Removes an attribute of the elements matching a selector.
+    pub fn remove_attribute(to: &str, name: &str) -> Self {
+        JS::command("remove_attr", to).arg("attr", json!(name))
+    }
+
+    /// Sets the transition of the last command, as the classes applied while
+    /// it runs, at its start and at its end.
+    pub fn transition(self, running: &str, start: &str, end: &str) -> Self {
+        let transition = json!([classes(running), classes(start), classes(end)]);
+        match self.last_kind() {
+            Some("toggle") => self.arg("ins", transition),
+            _ => self.arg("transition", transition),
+        }
+    }
+
+    /// Sets the transition of a toggle hiding the elements, while
+    /// [`JS::transition`] sets the one showing them.
+    pub fn transition_out(self, running: &str, start: &str, end: &str) -> Self {
+        self.arg(
+            "outs",
+            json!([classes(running), classes(start), classes(end)]),
+        )
+    }
+
+    /// Sets the duration of the transition, in milliseconds.
+    pub fn time(self, time: u32) -> Self {
+        self.arg("time", json!(time))
+    }
+
+    /// Sets the display value of shown elements, `block` by default.
+    pub fn display(self, display: &str) -> Self {
+        self.arg("display", json!(display))
+    }
+
+    /// Dispatches a DOM event on the elements matching a selector.
+    pub fn dispatch(to: &str, event: &str) -> Self {
+        JS::command("dispatch", to).arg("event", json!(event))
+    }
+
+    /// Pushes an event to the LiveView.
+    pub fn push<E>() -> Self {
+        JS::from_command("push", json!({ "event": type_name::<E>() }))
+    }
+
+    /// Runs `other` after these commands.
+    pub fn then(mut self, mut other: JS) -> Self {
+        self.commands.append(&mut other.commands);
+        self
+    }
+
+    fn command(kind: &str, to: &str) -> Self {
+        JS::from_command(kind, json!({ "to": to }))
+    }
+
+    fn from_command(kind: &str, args: Value) -> Self {
+        let Value::Object(args) = args else {
+            unreachable!("command arguments are an object");
+        };
+        JS {
+            commands: vec![(kind.to_string(), args)],
+        }
+    }
+
+    /// Sets an argument of the last command.
+    fn arg(mut self, name: &str, value: Value) -> Self {
+        if let Some((_, args)) = self.commands.last_mut() {
+            args.insert(name.to_string(), value);
+        }
+        self
+    }
+
+    fn last_kind(&self) -> Option<&str> {
+        self.commands.last().map(|(kind, _)| kind.as_str())
+    }
+}
+
+impl fmt::Display for JS {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let commands = serde_json::to_string(&self.commands).map_err(|_| fmt::Error)?;
+        f.write_str(&commands)
+    }
+}
+
+impl Render for JS {
+    fn render_to(&self, buffer: &mut String) {
+        self.to_string().render_to(buffer);
+    }
+}
+
+fn classes(names: &str) -> Value {
+    json!(names.split_whitespace().collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::{json, Value};
+
+    use super::JS;
+    use crate::{self as submillisecond_live_view, html};
+
+    #[test]
+    fn commands_are_rendered_as_json() {
+        let js = JS::toggle("#menu")
+            .transition("fade", "opacity-0", "opacity-100")
+            .time(200)
+            .then(JS::add_class("#menu", "open  shown"))
+            .then(JS::push::<String>());
+        let commands: Value = serde_json::from_str(&js.to_string()).unwrap();
+        assert_eq!(
+            commands,
+            json!([
+                [
+                    "toggle",
+                    {
+                        "to": "#menu",
+                        "ins": [["fade"], ["opacity-0"], ["opacity-100"]],
+                        "time": 200
+                    }
+                ],
+                ["add_class", { "to": "#menu", "names": ["open", "shown"] }],
+                ["push", { "event": "alloc::string::String" }]
+            ])
+        );
+        assert_eq!(
+            html! { button phx-click=(JS::show("#menu")) {} }.to_string(),
+            r##"<button phx-click="[[&quot;show&quot;,{&quot;to&quot;:&quot;#menu&quot;}]]"></button>"##
+        );
+    }
+}
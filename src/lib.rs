@@ -51,9 +51,10 @@
 //!
 //! impl LiveView for Counter {
 //!     type Events = (Increment, Decrement);
+//!     type Message = ();
 //!
-//!     fn mount(_uri: Uri, _socket: Option<Socket>) -> Self {
-//!         Counter { count: 0 }
+//!     fn mount(_uri: Uri, _socket: Option<Socket>) -> Result<Self, MountError> {
+//!         Ok(Counter { count: 0 })
 //!     }
 //!
 //!     fn render(&self) -> Rendered {
@@ -91,7 +92,7 @@
 //!
 //! fn main() -> std::io::Result<()> {
 //!     Application::new(router! {
-//!         GET "/" => Counter::handler("index.html", "#app")
+//!         GET "/" => Counter::handler().layout("index.html", "#app")
 //!     })
 //!     .serve("127.0.0.1:3000")
 //! }
@@ -126,6 +127,9 @@
 //!
 //! See <https://hexdocs.pm/phoenix_live_view/bindings.html#click-events>.
 //!
+//! Events defined as the variants of an enum are rendered with [`Dispatch`]
+//! instead, such as `phx-click=(Dispatch(Msg::Increment))`.
+//!
 //! #### Values
 //!
 //! Values can be added to events with the `:name=(value)` syntax.
@@ -144,6 +148,83 @@
 //!
 //! See <https://hexdocs.pm/phoenix_live_view/bindings.html#click-events>.
 //!
+//! Values are sent back exactly as they appear in the DOM, so can be altered by
+//! the client. Wrap values which must not be tampered with in [`Signed`], and
+//! use `Signed` for the field in the event.
+//!
+//! ```rust
+//! html! {
+//!   button :product_id=(Signed(product.id)) @click=(Buy) { "Buy" }
+//! }
+//! ```
+//!
+//! #### No-reply Events
+//!
+//! High frequency events, such as `keyup` or hook events, can skip the reply
+//! sent by the server for every event by adding the `phx-no-reply` attribute.
+//! The client acknowledges these events immediately, and the server only sends
+//! a diff if the render changed.
+//!
+//! **Example**
+//!
+//! ```rust
+//! html! {
+//!   input phx-no-reply @keyup=(Search) {}
+//! }
+//! ```
+//!
+//! #### Optimistic Updates
+//!
+//! Elements with a `phx-click` event can predict its result with the
+//! `data-optimistic-class` attribute, toggling the listed classes as soon as
+//! they're clicked, and the `data-optimistic-text` attribute, replacing their
+//! text. The prediction is reverted unless the event changes the render, or is
+//! acknowledged with [`acknowledge_optimistic`]. Handlers can revert it with
+//! [`deny_optimistic`].
+//!
+//! **Example**
+//!
+//! ```rust
+//! html! {
+//!   button.liked[self.liked] data-optimistic-class="liked" @click=(Like) { "Like" }
+//! }
+//! ```
+//!
+//! Predictions on `phx-no-reply` events are kept until the next render.
+//!
+//! #### Hooks
+//!
+//! Client hooks are bound with `@hook=(Type)`, which renders the `phx-hook`
+//! attribute with the type's name. Hooks are registered on
+//! `window.liveViewHooks` by that name before the LiveView script loads.
+//! Events pushed by a hook with `this.pushEvent` are handled like other
+//! events, and can be named by their type's name alone.
+//!
+//! **Example**
+//!
+//! ```rust
+//! struct Chart;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Zoomed {
+//!   level: u32,
+//! }
+//!
+//! html! {
+//!   canvas #chart @hook=(Chart) {}
+//! }
+//! ```
+//!
+//! ```js
+//! window.liveViewHooks = {
+//!   "my_app::Chart": {
+//!     mounted() {
+//!       this.el.addEventListener("wheel", () => this.pushEvent("Zoomed", { level: 2 }));
+//!     },
+//!   },
+//! };
+//! ```
+//!
 //! #### Nesting Html
 //!
 //! Maud supports [partials], but there is a different syntax for nesting
@@ -171,32 +252,156 @@
 //! ```
 //!
 //! [partials]: https://maud.lambda.xyz/partials.html
+//!
+//! #### Conditional Classes
+//!
+//! Classes which depend on the state can be joined into one `class` attribute
+//! with [`classes!`], which only includes the classes whose condition is true.
+//!
+//! **Example**
+//!
+//! ```rust
+//! html! {
+//!   li class=(classes![("completed", todo.completed), ("editing", todo.editing)]) {
+//!     (todo.title)
+//!   }
+//! }
+//! ```
+//!
+//! ### Secrets
+//!
+//! Sessions, [`Signed`] values and state snapshots are signed with the
+//! `LIVE_VIEW_SECRET` environment variable, which should be set to a long
+//! random string in production.
+//!
+//! Changing the secret invalidates pages signed with the old one. To rotate it
+//! without reloading open pages, set `LIVE_VIEW_PREVIOUS_SECRET` to the old
+//! secret and `LIVE_VIEW_PREVIOUS_SECRET_EXPIRES_AT` to a unix timestamp ending
+//! the grace period. Data signed with the previous secret is accepted until
+//! then, while new data is signed with the current secret.
+//!
+//! ### Wire Format
+//!
+//! Messages are sent to the client as JSON text frames. With the `msgpack`
+//! feature enabled, clients which ask for it when joining, such as the bundled
+//! client, are sent MessagePack binary frames instead, which are smaller and
+//! faster to encode for large diffs.
 
 #![warn(missing_docs)]
 
+pub mod csp;
 pub mod handler;
+pub mod js;
 pub mod rendered;
 pub mod socket;
 
+mod activity;
+mod assets;
+mod audit;
+mod auth;
+mod classes;
+mod collab;
+mod component;
+mod context;
 mod csrf;
+mod download;
 mod event_handler;
+mod export;
+mod flags;
+mod form;
+mod head;
+mod history;
+mod ignore;
+mod job;
+mod limits;
 mod live_view;
 mod manager;
+#[cfg(feature = "markdown")]
+mod markdown;
 mod maud;
+mod metrics;
+mod modal;
+mod navigation;
+mod optimistic;
+mod paginator;
+mod policy;
+mod pubsub;
+mod sanitize;
+mod schedule;
+mod session;
+mod signed;
+mod state_token;
+mod store;
+mod stream;
 mod template;
+mod temporary;
+#[cfg(feature = "timezone")]
+mod timezone;
+mod timings;
+mod upload;
+mod wizard;
 
 #[doc(hidden)]
 pub use maud_live_view;
-pub use maud_live_view::html;
+pub use maud_live_view::{html, PreEscaped};
+pub use submillisecond_live_view_macros::{LiveForm, LiveViewEvent};
 
+pub use crate::activity::{Activity, ActivityChanged, ActivityTopic, UserActivity};
+pub use crate::assets::{static_path, StaticAssets};
+pub use crate::audit::{AuditEvent, AuditResult};
+pub use crate::auth::{Identity, TokenAuth};
+pub use crate::collab::{Document, DocumentChange, DocumentTopic, DocumentUpdate, Replica};
+pub use crate::component::{live_component, myself, LiveComponent};
+pub use crate::context::{AppContext, ContextError, ContextRequest};
+pub use crate::download::Download;
+pub use crate::flags::{feature_enabled, FeatureFlags};
+pub use crate::form::{
+    Changeset, FormChanged, FormErrors, FormField, FormParams, FormSubmitted, FormValue, LiveForm,
+};
+pub use crate::head::{canonical_link, Link, OpenGraph, Script, Stylesheet};
+pub use crate::history::{History, Redo, Undo};
+pub use crate::ignore::ignore_updates;
+pub use crate::job::{Job, JobProgress, JobStatus, JobUpdated};
+pub use crate::limits::ConnectionLimits;
 pub use crate::live_view::*;
+#[cfg(feature = "markdown")]
+pub use crate::markdown::markdown;
+pub use crate::modal::{Modal, ModalClosed};
+pub use crate::optimistic::{acknowledge_optimistic, deny_optimistic};
+pub use crate::paginator::{PageChanged, PageLink, Paginator};
+pub use crate::policy::Policy;
+pub use crate::pubsub::{Broadcast, PubSub};
+pub use crate::sanitize::{html_sanitize, Sanitizer};
+pub use crate::schedule::{Cron, CronError, Schedule, Scheduled};
+pub use crate::session::session_claims;
+pub use crate::signed::Signed;
+pub use crate::store::{Store, StoreChanged};
+pub use crate::stream::Stream;
+pub use crate::temporary::TemporaryAssign;
+#[cfg(feature = "timezone")]
+pub use crate::timezone::{local_time, viewer_timezone};
+#[cfg(feature = "timings")]
+pub use crate::timings::Timings;
+pub use crate::upload::{
+    allow_upload, cancel_upload, consume_uploaded_entries, live_file_input, uploaded_entries,
+    UploadConfig, UploadEntry, UploadEntryError,
+};
+pub use crate::wizard::Wizard;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+
+    pub use crate::auth::identity;
+}
 
 /// Prelude
 pub mod prelude {
     pub use submillisecond::http::Uri;
 
     pub use crate::handler::LiveViewRouter;
+    pub use crate::js::JS;
     pub use crate::rendered::Rendered;
-    pub use crate::socket::Socket;
+    pub use crate::socket::{InfoSender, NotifyLevel, Socket};
     pub use crate::*;
 }
@@ -0,0 +1,215 @@
+//! Limits on concurrent live socket connections.
+
+use std::collections::HashMap;
+
+use lunatic::serializer::Json;
+use lunatic::{Mailbox, MessageSignal, Process, ProcessDiedSignal, Tag};
+use serde::{Deserialize, Serialize};
+use submillisecond::RequestContext;
+
+use crate::maud::hash;
+
+const REGISTRY_PROCESS_ID: &str = "1f5c2a0e-9b1d-4d8e-a7c3-6e2b8f4d9a17";
+
+/// Caps on concurrent live socket connections, bounding the resources used by
+/// misbehaving clients.
+///
+/// Connections exceeding the limit per IP are rejected with
+/// `429 Too Many Requests` before upgrading to a websocket. Connections
+/// exceeding the limit per session are rejected when joining.
+///
+/// # Example
+///
+/// ```
+/// router! {
+///     GET "/" => MyLiveView::handler().layout("index.html", "#app")
+///         .connection_limits(ConnectionLimits::new().per_ip(20).per_session(2))
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionLimits {
+    pub(crate) per_ip: Option<usize>,
+    pub(crate) per_session: Option<usize>,
+    ip_header: String,
+    trusted_proxies: usize,
+}
+
+/// Process counting connections, shared by all handlers.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(crate) struct ConnectionRegistry {
+    process: Process<RegistryMessage, Json>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum RegistryMessage {
+    Count(Process<usize, Json>, Tag, String),
+    Acquire(Process<bool, Json>, Tag, String, usize),
+}
+
+impl ConnectionLimits {
+    /// Creates connection limits without any caps.
+    pub fn new() -> Self {
+        ConnectionLimits::default()
+    }
+
+    /// Sets the maximum number of connections from a single IP.
+    pub fn per_ip(mut self, max: usize) -> Self {
+        self.per_ip = Some(max);
+        self
+    }
+
+    /// Sets the maximum number of connections joined with the same session.
+    ///
+    /// A session is issued with each dead render, so this caps connections
+    /// replaying a single page. Pages are sent without an ETag when this is
+    /// set, so reloads aren't served the cached page and its session.
+    pub fn per_session(mut self, max: usize) -> Self {
+        self.per_session = Some(max);
+        self
+    }
+
+    /// Sets the header the client IP is read from.
+    ///
+    /// The peer address of the connection isn't available to handlers, so the
+    /// IP is read from a header set by a reverse proxy. Requests without the
+    /// header are not limited per IP. Defaults to `X-Forwarded-For`.
+    pub fn ip_header(mut self, header: impl Into<String>) -> Self {
+        self.ip_header = header.into();
+        self
+    }
+
+    /// Sets the number of reverse proxies in front of the server, each
+    /// appending the address it received the request from to the IP header.
+    ///
+    /// The client IP is the address appended by the outermost trusted proxy,
+    /// counting from the end of the list, since addresses before it are sent
+    /// by the client and can be forged. Defaults to 1.
+    pub fn trusted_proxies(mut self, count: usize) -> Self {
+        self.trusted_proxies = count.max(1);
+        self
+    }
+
+    /// Returns the registry key for the client IP of a request.
+    pub(crate) fn ip_key(&self, req: &RequestContext) -> Option<String> {
+        req.headers()
+            .get(self.ip_header.as_str())
+            .and_then(|header| header.to_str().ok())
+            .and_then(|header| client_ip(header, self.trusted_proxies))
+            .map(|ip| format!("ip:{ip}"))
+    }
+
+    /// Returns the registry key for a session.
+    pub(crate) fn session_key(session: &str) -> String {
+        format!("session:{}", hash([session.as_bytes()]))
+    }
+}
+
+impl Default for ConnectionLimits {
+    fn default() -> Self {
+        ConnectionLimits {
+            per_ip: None,
+            per_session: None,
+            ip_header: "x-forwarded-for".to_string(),
+            trusted_proxies: 1,
+        }
+    }
+}
+
+impl ConnectionRegistry {
+    /// Starts the registry if it's not already running.
+    pub(crate) fn start() {
+        if Self::lookup().is_none() {
+            let process = Process::spawn_link((), registry);
+            process.register(&REGISTRY_PROCESS_ID);
+        }
+    }
+
+    pub(crate) fn lookup() -> Option<Self> {
+        Process::lookup(&REGISTRY_PROCESS_ID).map(|process| ConnectionRegistry { process })
+    }
+
+    /// Returns the number of connections registered under a key.
+    pub(crate) fn count(&self, key: String) -> usize {
+        let tag = Tag::new();
+        self.process
+            .send(RegistryMessage::Count(unsafe { Process::this() }, tag, key));
+        let mailbox: Mailbox<usize, Json> = unsafe { Mailbox::new() };
+        mailbox.tag_receive(&[tag])
+    }
+
+    /// Registers the current process under a key if fewer than `max`
+    /// connections are registered, returning false otherwise.
+    ///
+    /// The connection is released when the current process exits.
+    pub(crate) fn acquire(&self, key: String, max: usize) -> bool {
+        let tag = Tag::new();
+        self.process.send(RegistryMessage::Acquire(
+            unsafe { Process::this() },
+            tag,
+            key,
+            max,
+        ));
+        let mailbox: Mailbox<bool, Json> = unsafe { Mailbox::new() };
+        mailbox.tag_receive(&[tag])
+    }
+}
+
+/// Returns the address appended by the outermost of `trusted_proxies`
+/// proxies, or the first address if the list is shorter.
+fn client_ip(header: &str, trusted_proxies: usize) -> Option<&str> {
+    let addresses: Vec<_> = header.split(',').map(str::trim).collect();
+    let ip = addresses[addresses.len().saturating_sub(trusted_proxies)];
+    (!ip.is_empty()).then_some(ip)
+}
+
+fn registry(_: (), mailbox: Mailbox<RegistryMessage, Json>) {
+    let mailbox = mailbox.monitorable();
+    let mut connections: HashMap<String, usize> = HashMap::new();
+    let mut keys_by_process: HashMap<u64, Vec<String>> = HashMap::new();
+
+    loop {
+        match mailbox.receive() {
+            MessageSignal::Message(RegistryMessage::Count(parent, tag, key)) => {
+                parent.tag_send(tag, connections.get(&key).copied().unwrap_or(0));
+            }
+            MessageSignal::Message(RegistryMessage::Acquire(parent, tag, key, max)) => {
+                let count = connections.entry(key.clone()).or_default();
+                let acquired = *count < max;
+                if acquired {
+                    *count += 1;
+                    let keys = keys_by_process.entry(parent.id()).or_default();
+                    if keys.is_empty() {
+                        mailbox.monitor(parent);
+                    }
+                    keys.push(key);
+                }
+                parent.tag_send(tag, acquired);
+            }
+            MessageSignal::Signal(ProcessDiedSignal(id)) => {
+                for key in keys_by_process.remove(&id).unwrap_or_default() {
+                    if let Some(count) = connections.get_mut(&key) {
+                        *count -= 1;
+                        if *count == 0 {
+                            connections.remove(&key);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::client_ip;
+
+    #[test]
+    fn client_ip_skips_forged_addresses() {
+        let header = "6.6.6.6, 203.0.113.7, 10.0.0.2";
+        assert_eq!(client_ip(header, 1), Some("10.0.0.2"));
+        assert_eq!(client_ip(header, 2), Some("203.0.113.7"));
+        assert_eq!(client_ip(header, 5), Some("6.6.6.6"));
+        assert_eq!(client_ip(" 203.0.113.7 ", 1), Some("203.0.113.7"));
+        assert_eq!(client_ip("", 1), None);
+    }
+}
@@ -1,9 +1,22 @@
+use std::any::type_name;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+use maud_live_view::Render;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use submillisecond::http::Uri;
+use serde_json::{Map, Value};
+use submillisecond::http::{StatusCode, Uri};
 use thiserror::Error;
 
 use crate::rendered::Rendered;
 use crate::socket::{Event, Socket};
+use crate::upload::UploadEntry;
+
+thread_local! {
+    /// Reply returned by the last event handled.
+    static REPLY: RefCell<Option<Value>> = const { RefCell::new(None) };
+}
 
 /// Html input checkbox value.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -27,28 +40,339 @@ pub enum DeserializeEventError {
     Json(#[from] serde_json::Error),
 }
 
+/// An error mounting a LiveView, such as a missing record.
+///
+/// The dead render responds with the error's status, rendering the page
+/// configured with [`LiveViewHandler::error_page`] or
+/// [`LiveViewHandler::error_view`] if any.
+///
+/// [`LiveViewHandler::error_page`]: crate::handler::LiveViewHandler::error_page
+/// [`LiveViewHandler::error_view`]: crate::handler::LiveViewHandler::error_view
+#[derive(Clone, Debug, PartialEq, Eq, Error, Serialize, Deserialize)]
+#[error("{message}")]
+pub struct MountError {
+    status: u16,
+    message: String,
+}
+
 /// A live view.
 pub trait LiveView: Sized {
     /// Events registered with this liveview.
+    ///
+    /// A tuple of up to 12 events, or a list of any length built with the
+    /// [`events!`](crate::events) macro.
     type Events: EventList<Self>;
 
+    /// Messages sent to the LiveView by other processes, such as background
+    /// jobs or timers, with an [`InfoSender`](crate::socket::InfoSender), and
+    /// handled by [`LiveView::handle_info`].
+    ///
+    /// Use `()` for LiveViews which don't receive messages.
+    type Message: Serialize + for<'de> Deserialize<'de>;
+
     /// The LiveView entry-point.
     ///
     /// Mount is invoked twice: once to do the initial page load, and again to
     /// establish the live socket.
-    fn mount(uri: Uri, socket: Option<Socket>) -> Self;
+    ///
+    /// Returning an error, such as [`MountError::not_found`] for a missing
+    /// record, responds with an error page instead of the LiveView.
+    fn mount(uri: Uri, socket: Option<Socket>) -> Result<Self, MountError>;
+
+    /// Invoked once after the live socket has joined, before
+    /// [`LiveView::load`].
+    ///
+    /// Unlike `mount`, which is also invoked for the initial page load, the
+    /// socket is always available here, making it the place to subscribe to
+    /// topics or start timers. Events should be sent with
+    /// [`Socket::spawn_send_event`], as the LiveView is busy until this
+    /// returns.
+    fn after_join(&mut self, _socket: &mut Socket) {}
+
+    /// Invoked when the URL is patched without remounting, such as by
+    /// following a `data-phx-link="patch"` link or with
+    /// [`Socket::push_patch`].
+    ///
+    /// The new URI is also available from [`Socket::uri`].
+    fn handle_params(&mut self, _uri: Uri) {}
+
+    /// Invoked when the client reports the progress of an uploaded file,
+    /// before rendering.
+    ///
+    /// `name` is the name of the upload, as allowed with
+    /// [`allow_upload`](crate::allow_upload). The entry is done once the file
+    /// has been received, and can be consumed with
+    /// [`consume_uploaded_entries`](crate::consume_uploaded_entries), such as
+    /// to save files uploaded with
+    /// [`UploadConfig::auto_upload`](crate::UploadConfig::auto_upload).
+    fn handle_progress(&mut self, _name: &str, _entry: &UploadEntry) {}
+
+    /// Returns the metadata of a file uploaded externally, with
+    /// [`UploadConfig::external`](crate::UploadConfig::external).
+    ///
+    /// Invoked for each file before it's uploaded. The metadata is sent to the
+    /// client uploader named by its `uploader` field, along with anything it
+    /// needs to send the file, such as a presigned URL. Returning an error
+    /// rejects the file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// fn presign_upload(&mut self, _name: &str, entry: &UploadEntry) -> Result<Value, String> {
+    ///     let key = format!("avatars/{}", entry.entry_ref);
+    ///     let url = self.bucket.presign_put(&key).map_err(|err| err.to_string())?;
+    ///     Ok(json!({ "uploader": "S3", "key": key, "url": url }))
+    /// }
+    /// ```
+    fn presign_upload(&mut self, _name: &str, _entry: &UploadEntry) -> Result<Value, String> {
+        Err("external uploads are not supported".to_string())
+    }
+
+    /// Loads data after the live socket has joined.
+    ///
+    /// Invoked once the join reply has been sent to the client, and before any
+    /// events are handled. Heavy data loading can be deferred from `mount` to
+    /// here, letting `mount` return a lightweight skeleton which is displayed
+    /// until the loaded content is pushed as a diff.
+    ///
+    /// While the socket is joining, the LiveView container has the
+    /// `phx-loading` class, and `phx-connected` once joined, which can be used
+    /// to style the skeleton.
+    fn load(&mut self) {}
 
     /// Renders a template.
     ///
     /// This callback is invoked whenever LiveView detects new content must be
     /// rendered and sent to the client.
     fn render(&self) -> Rendered;
+
+    /// Returns the title of the page, replacing the template's title.
+    ///
+    /// The title is checked after each render, and updated in the browser when
+    /// it changes, such as to show a count of unread messages. It's combined
+    /// with the prefix and suffix set with
+    /// [`LiveViewHandler::title_prefix`](crate::handler::LiveViewHandler::title_prefix)
+    /// and
+    /// [`LiveViewHandler::title_suffix`](crate::handler::LiveViewHandler::title_suffix).
+    fn title(&self) -> Option<String> {
+        None
+    }
+
+    /// Returns content added to the page's head, such as meta tags or a
+    /// canonical link depending on the mounted state.
+    ///
+    /// The head is only rendered with the initial HTTP response, and isn't
+    /// updated once the page is loaded. [`OpenGraph`](crate::OpenGraph) and
+    /// [`canonical_link`](crate::canonical_link) render common tags, and
+    /// markup such as an analytics snippet can be added unescaped with
+    /// [`PreEscaped`](crate::PreEscaped).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// const ANALYTICS: &str = r#"<script async src="https://example.com/analytics.js"></script>"#;
+    ///
+    /// fn head(&self) -> Option<Rendered> {
+    ///     Some(html! {
+    ///         link rel="canonical" href=(format!("https://example.com/users/{}", self.user.id));
+    ///         meta name="description" content=(self.user.bio);
+    ///         (PreEscaped(ANALYTICS))
+    ///     })
+    /// }
+    /// ```
+    fn head(&self) -> Option<Rendered> {
+        None
+    }
+
+    /// Returns a snapshot of state to restore if the socket reconnects.
+    ///
+    /// The snapshot is encrypted into the page and sent back by the client when
+    /// rejoining, so state survives reconnecting to a different node without a
+    /// shared store. Snapshots should be small, as they are sent to the client
+    /// whenever they change, and snapshots exceeding the handler's limit are
+    /// dropped.
+    fn snapshot(&self) -> Option<Value> {
+        None
+    }
+
+    /// Restores a snapshot returned by [`LiveView::snapshot`].
+    ///
+    /// Invoked after mounting the live socket if the client has an unexpired
+    /// snapshot. Snapshots taken before the LiveView's root template changed,
+    /// such as by deploying a new version, aren't restored, so the LiveView is
+    /// only mounted.
+    fn restore(&mut self, _snapshot: Value) {}
+
+    /// Handles a message sent by another process, re-rendering the LiveView
+    /// afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// impl LiveView for Report {
+    ///     type Message = ReportProgress;
+    ///
+    ///     fn after_join(&mut self, socket: &mut Socket) {
+    ///         let sender = socket.info_sender::<Self>();
+    ///         Process::spawn(sender, |sender, _: Mailbox<()>| {
+    ///             for percent in generate_report() {
+    ///                 sender.send(ReportProgress { percent });
+    ///             }
+    ///         });
+    ///     }
+    ///
+    ///     fn handle_info(&mut self, msg: ReportProgress) {
+    ///         self.percent = msg.percent;
+    ///     }
+    ///     // ...
+    /// }
+    /// ```
+    fn handle_info(&mut self, _msg: Self::Message) {}
+
+    /// Invoked when the LiveView stops, such as when the client leaves or the
+    /// connection closes, to release resources like spawned processes.
+    ///
+    /// Not invoked if the LiveView's process fails.
+    fn terminate(&mut self, _reason: TerminateReason) {}
+
+    /// Returns the debounce and throttle applied to the change events of inputs
+    /// without their own `phx-debounce` or `phx-throttle` attribute.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// fn event_config() -> EventConfig {
+    ///     EventConfig::new().debounce(300)
+    /// }
+    /// ```
+    fn event_config() -> EventConfig {
+        EventConfig::default()
+    }
+
+    /// Recovers the params of a form after the socket reconnects.
+    ///
+    /// Forms with an `id` and a `phx-change` event send their event again when
+    /// the client rejoins a freshly mounted LiveView, so typed input isn't
+    /// lost. Forms with `phx-auto-recover` set to [`RECOVER_EVENT`] are sent
+    /// here instead, with their params by input name, and aren't recovered
+    /// with `phx-auto-recover="ignore"`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// html! {
+    ///     form #message phx-auto-recover=(RECOVER_EVENT) @change=(Typing) @submit=(Send) {
+    ///         input name="text" {}
+    ///     }
+    /// }
+    ///
+    /// fn handle_recovery(&mut self, params: Value) {
+    ///     self.draft = params["text"].as_str().unwrap_or_default().to_string();
+    /// }
+    /// ```
+    fn handle_recovery(&mut self, _params: Value) {}
+}
+
+/// Why a LiveView stopped, passed to [`LiveView::terminate`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TerminateReason {
+    /// The client left the LiveView, such as to navigate to another one.
+    Left,
+    /// The connection was closed.
+    Closed,
+    /// The client stopped sending heartbeats, such as after losing its
+    /// network, or stopped reading the messages sent to it.
+    Timeout,
+    /// Reading from the connection failed.
+    Error,
+}
+
+/// Defaults for the events of a LiveView, returned by
+/// [`LiveView::event_config`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EventConfig {
+    debounce: Option<u32>,
+    throttle: Option<u32>,
+}
+
+impl EventConfig {
+    /// Creates a config without debounce or throttle.
+    pub fn new() -> Self {
+        EventConfig::default()
+    }
+
+    /// Waits for inputs to stop changing for `ms` milliseconds before sending
+    /// their change events.
+    pub fn debounce(mut self, ms: u32) -> Self {
+        self.debounce = Some(ms);
+        self
+    }
+
+    /// Sends the change events of inputs at most once every `ms`
+    /// milliseconds.
+    pub fn throttle(mut self, ms: u32) -> Self {
+        self.throttle = Some(ms);
+        self
+    }
+
+    /// Returns the attributes of the LiveView's container holding the
+    /// defaults, read by the client.
+    pub(crate) fn container_attributes(&self) -> String {
+        let mut attributes = String::new();
+        if let Some(debounce) = self.debounce {
+            attributes.push_str(&format!(r#" data-default-debounce="{debounce}""#));
+        }
+        if let Some(throttle) = self.throttle {
+            attributes.push_str(&format!(r#" data-default-throttle="{throttle}""#));
+        }
+        attributes
+    }
 }
 
+/// Name of the event sending forms with `phx-auto-recover` to
+/// [`LiveView::handle_recovery`].
+pub const RECOVER_EVENT: &str = "recover";
+
 /// Live view event handler.
+///
+/// Implement [`LiveViewEvent::handle`], and also
+/// [`LiveViewEvent::handle_with_reply`] to reply to the client.
 pub trait LiveViewEvent<E> {
     /// Handler for the live view, typically used in the router.
     fn handle(state: &mut Self, event: E);
+
+    /// Handles the event, returning a reply sent back to the element which
+    /// pushed it, if any.
+    ///
+    /// Hooks receive the reply in the callback passed to `this.pushEvent`,
+    /// such as validation results or the id of a created record. Events are
+    /// always handled by this method, which defaults to calling
+    /// [`LiveViewEvent::handle`] without a reply.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// impl LiveViewEvent<CreateTodo> for Todos {
+    ///     fn handle(state: &mut Self, event: CreateTodo) {
+    ///         Self::handle_with_reply(state, event);
+    ///     }
+    ///
+    ///     fn handle_with_reply(state: &mut Self, event: CreateTodo) -> Option<Value> {
+    ///         let id = state.add(event.title);
+    ///         Some(json!({ "id": id }))
+    ///     }
+    /// }
+    /// ```
+    fn handle_with_reply(state: &mut Self, event: E) -> Option<Value> {
+        Self::handle(state, event);
+        None
+    }
+}
+
+/// Takes the reply returned by the last event handled, clearing it for the
+/// next event.
+pub(crate) fn take_reply() -> Option<Value> {
+    REPLY.with(RefCell::take)
 }
 
 /// Event list is a trait to handle an incoming live view events and route them
@@ -57,12 +381,20 @@ pub trait EventList<T> {
     /// Handles an event, returning a Result, with a bool indicating if the
     /// event was handled or not.
     fn handle_event(state: &mut T, event: Event) -> Result<bool, DeserializeEventError>;
+
+    /// Returns the names of the events in the list, which are the only event
+    /// names accepted from the client.
+    fn names() -> Vec<&'static str>;
 }
 
 impl<T> EventList<T> for () {
     fn handle_event(_state: &mut T, _event: Event) -> Result<bool, DeserializeEventError> {
         Ok(false)
     }
+
+    fn names() -> Vec<&'static str> {
+        Vec::new()
+    }
 }
 
 #[cfg(debug_assertions)]
@@ -73,7 +405,7 @@ where
     if serde_json::from_str::<T>("null").is_ok() {
         lunatic_log::error!(
             "unit structs are not supported as events. Change your event struct to be `{} {{}}`",
-            std::any::type_name::<T>()
+            type_name::<T>()
         );
     }
 }
@@ -81,6 +413,41 @@ where
 #[cfg(not(debug_assertions))]
 fn check_for_unit_struct<T>() {}
 
+/// Deserializes the value of an event matching `E`, and handles it.
+fn handle_matched_event<T, E>(state: &mut T, event: Event) -> Result<(), DeserializeEventError>
+where
+    T: LiveViewEvent<E>,
+    E: for<'de> Deserialize<'de>,
+{
+    let value: E = if event.ty == "form" {
+        match event.value.as_str() {
+            Some(value) => match serde_qs::from_str(value) {
+                Ok(value) => value,
+                Err(err) => {
+                    check_for_unit_struct::<E>();
+                    return Err(DeserializeEventError::Form(err));
+                }
+            },
+            None => {
+                return Err(DeserializeEventError::Form(serde_qs::Error::Custom(
+                    "expected value to be string in form event".to_string(),
+                )));
+            }
+        }
+    } else {
+        match serde_json::from_value(event.value) {
+            Ok(value) => value,
+            Err(err) => {
+                check_for_unit_struct::<E>();
+                return Err(DeserializeEventError::Json(err));
+            }
+        }
+    };
+    let reply = T::handle_with_reply(state, value);
+    REPLY.with(|cell| *cell.borrow_mut() = reply);
+    Ok(())
+}
+
 macro_rules! impl_event_list {
     ($( $t: ident ),*) => {
         impl<T, $( $t ),*> EventList<T> for ($( $t, )*)
@@ -92,38 +459,18 @@ macro_rules! impl_event_list {
         {
             fn handle_event(state: &mut T, event: Event) -> Result<bool, DeserializeEventError> {
                 $(
-                    if std::any::type_name::<$t>() == event.name {
-                        let value: $t = if event.ty == "form" {
-                            match event.value.as_str() {
-                                Some(value) => match serde_qs::from_str(value) {
-                                    Ok(value) => value,
-                                    Err(err) => {
-                                        check_for_unit_struct::<$t>();
-                                        return Err(DeserializeEventError::Form(err));
-                                    }
-                                }
-                                None => {
-                                    return Err(DeserializeEventError::Form(serde_qs::Error::Custom(
-                                        "expected value to be string in form event".to_string(),
-                                    )));
-                                }
-                            }
-                        } else {
-                            match serde_json::from_value(event.value) {
-                                Ok(value) => value,
-                                Err(err) => {
-                                    check_for_unit_struct::<$t>();
-                                    return Err(DeserializeEventError::Json(err));
-                                }
-                            }
-                        };
-                        T::handle(state, value);
+                    if event.matches(type_name::<$t>()) {
+                        handle_matched_event::<T, $t>(state, event)?;
                         return Ok(true);
                     }
                 )*
 
                 Ok(false)
             }
+
+            fn names() -> Vec<&'static str> {
+                vec![$( type_name::<$t>() ),*]
+            }
         }
     };
 }
@@ -141,6 +488,201 @@ impl_event_list!(A, B, C, D, E, F, G, H, I, J);
 impl_event_list!(A, B, C, D, E, F, G, H, I, J, K);
 impl_event_list!(A, B, C, D, E, F, G, H, I, J, K, L);
 
+/// A list of events of any length, built with the [`events!`](crate::events)
+/// macro.
+///
+/// Tuples of events are limited to 12 events, while an event chain holds the
+/// event `E` followed by the chain of the remaining events.
+pub struct EventChain<E, Rest>(PhantomData<(E, Rest)>);
+
+impl<T, E, Rest> EventList<T> for EventChain<E, Rest>
+where
+    T: LiveViewEvent<E>,
+    E: for<'de> Deserialize<'de>,
+    Rest: EventList<T>,
+{
+    fn handle_event(state: &mut T, event: Event) -> Result<bool, DeserializeEventError> {
+        if event.matches(type_name::<E>()) {
+            handle_matched_event::<T, E>(state, event)?;
+            return Ok(true);
+        }
+        Rest::handle_event(state, event)
+    }
+
+    fn names() -> Vec<&'static str> {
+        let mut names = Rest::names();
+        names.insert(0, type_name::<E>());
+        names
+    }
+}
+
+/// Lists the events of a LiveView, for views with more events than fit in a
+/// tuple.
+///
+/// # Example
+///
+/// ```
+/// impl LiveView for Spreadsheet {
+///     type Events = events![
+///         SelectCell, EditCell, CopyCells, PasteCells, InsertRow, DeleteRow, InsertColumn,
+///         DeleteColumn, SortColumn, FilterColumn, Undo, Redo, Export,
+///     ];
+///     // ...
+/// }
+/// ```
+#[macro_export]
+macro_rules! events {
+    () => { () };
+    ($event: ty $(, $rest: ty )* $(,)?) => {
+        $crate::EventChain<$event, $crate::events!($( $rest ),*)>
+    };
+}
+
+/// Events defined as the variants of an enum, handled by a single
+/// [`LiveViewEvent`] implementation.
+///
+/// Used as [`LiveView::Events`], any variant of `M` is accepted from the
+/// client. Rendered in a `phx-*` attribute, it names the event sending the
+/// variant back to the server, as `@click=(..)` only accepts types.
+///
+/// Fields of struct variants are rendered along with the variant, and
+/// overridden by the event's values, such as `:name=(..)` values or the fields
+/// of a form. Form fields are sent as strings.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Serialize, Deserialize)]
+/// enum Msg {
+///     Increment,
+///     Decrement,
+///     SetName { name: String },
+/// }
+///
+/// impl LiveView for Counter {
+///     type Events = Dispatch<Msg>;
+///
+///     fn render(&self) -> Rendered {
+///         html! {
+///             button phx-click=(Dispatch(Msg::Increment)) { "Increment" }
+///             button phx-click=(Dispatch(Msg::Decrement)) { "Decrement" }
+///             form phx-submit=(Dispatch(Msg::SetName { name: String::new() })) {
+///                 input name="name" {}
+///             }
+///         }
+///     }
+///     // ...
+/// }
+///
+/// impl LiveViewEvent<Msg> for Counter {
+///     fn handle(state: &mut Self, msg: Msg) {
+///         match msg {
+///             Msg::Increment => state.count += 1,
+///             Msg::Decrement => state.count -= 1,
+///             Msg::SetName { name } => state.name = name,
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dispatch<M>(pub M);
+
+impl<M> Dispatch<M>
+where
+    M: Serialize,
+{
+    /// Returns the name of the event, as the enum's type name followed by `#`
+    /// and the variant encoded as JSON.
+    pub fn event_name(&self) -> String {
+        let variant = serde_json::to_string(&self.0).unwrap_or_default();
+        format!("{}#{variant}", type_name::<M>())
+    }
+}
+
+impl<M> Render for Dispatch<M>
+where
+    M: Serialize,
+{
+    fn render_to(&self, buffer: &mut String) {
+        self.event_name().render_to(buffer);
+    }
+}
+
+impl<T, M> EventList<T> for Dispatch<M>
+where
+    T: LiveViewEvent<M>,
+    M: DeserializeOwned,
+{
+    fn handle_event(state: &mut T, event: Event) -> Result<bool, DeserializeEventError> {
+        let Some(variant) = event
+            .name
+            .strip_prefix(type_name::<M>())
+            .and_then(|rest| rest.strip_prefix('#'))
+        else {
+            return Ok(false);
+        };
+        let mut variant: Value = serde_json::from_str(variant)?;
+        let values: Map<String, Value> = if event.ty == "form" {
+            serde_qs::from_str(event.value.as_str().unwrap_or_default())?
+        } else {
+            match event.value {
+                Value::Object(values) => values,
+                _ => Map::new(),
+            }
+        };
+        // Unit and tuple variants have no fields to override.
+        if let Some(Value::Object(fields)) = variant
+            .as_object_mut()
+            .and_then(|variant| variant.values_mut().next())
+        {
+            fields.extend(values);
+        }
+        let msg = serde_json::from_value(variant)?;
+        let reply = T::handle_with_reply(state, msg);
+        REPLY.with(|cell| *cell.borrow_mut() = reply);
+        Ok(true)
+    }
+
+    fn names() -> Vec<&'static str> {
+        vec![type_name::<M>()]
+    }
+}
+
+impl MountError {
+    /// Creates a mount error with a status code and message.
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        MountError {
+            status: status.as_u16(),
+            message: message.into(),
+        }
+    }
+
+    /// Creates a `404 Not Found` mount error.
+    pub fn not_found() -> Self {
+        MountError::new(StatusCode::NOT_FOUND, "not found")
+    }
+
+    /// Creates a `403 Forbidden` mount error.
+    pub fn forbidden() -> Self {
+        MountError::new(StatusCode::FORBIDDEN, "forbidden")
+    }
+
+    /// Creates a `401 Unauthorized` mount error.
+    pub fn unauthorized() -> Self {
+        MountError::new(StatusCode::UNAUTHORIZED, "unauthorized")
+    }
+
+    /// Returns the status code of the error response.
+    pub fn status(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
 impl CheckboxValue {
     /// Returns a bool indicating if checkbox is checked.
     pub fn is_checked(&self) -> bool {
@@ -156,3 +698,164 @@ impl Default for CheckboxValue {
         CheckboxValue::Unchecked
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json::{json, Value};
+
+    use super::{take_reply, Dispatch, EventList, LiveViewEvent};
+    use crate as submillisecond_live_view;
+    use crate::socket::Event;
+
+    #[derive(Serialize, Deserialize)]
+    struct Zoomed {
+        level: u32,
+    }
+
+    #[derive(Default)]
+    struct Chart {
+        level: u32,
+    }
+
+    impl LiveViewEvent<Zoomed> for Chart {
+        fn handle(state: &mut Self, event: Zoomed) {
+            state.level = event.level;
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Reset {}
+
+    impl LiveViewEvent<Reset> for Chart {
+        fn handle(state: &mut Self, event: Reset) {
+            Self::handle_with_reply(state, event);
+        }
+
+        fn handle_with_reply(state: &mut Self, _event: Reset) -> Option<Value> {
+            let level = state.level;
+            state.level = 0;
+            Some(json!({ "previous": level }))
+        }
+    }
+
+    #[derive(crate::LiveViewEvent)]
+    #[event(live_view = Chart)]
+    struct Clear;
+
+    impl Chart {
+        fn clear(&mut self, _event: Clear) {
+            self.level = 0;
+        }
+    }
+
+    fn event(name: &str, ty: &str) -> Event {
+        Event {
+            name: name.to_string(),
+            ty: ty.to_string(),
+            value: json!({ "level": 2 }),
+            cid: None,
+        }
+    }
+
+    #[test]
+    fn hook_events_are_matched_by_type_name() {
+        let mut chart = Chart::default();
+        let handled = <(Zoomed,)>::handle_event(&mut chart, event("Zoomed", "click"));
+        assert!(!handled.unwrap());
+
+        let handled = <(Zoomed,)>::handle_event(&mut chart, event("Zoomed", "hook"));
+        assert!(handled.unwrap());
+        assert_eq!(chart.level, 2);
+
+        let name = std::any::type_name::<Zoomed>();
+        let handled = <(Zoomed,)>::handle_event(&mut chart, event(name, "hook"));
+        assert!(handled.unwrap());
+    }
+
+    #[test]
+    fn replies_are_taken_once() {
+        let mut chart = Chart { level: 3 };
+        let name = std::any::type_name::<Reset>();
+        let handled = <(Zoomed, Reset)>::handle_event(&mut chart, event(name, "hook"));
+        assert!(handled.unwrap());
+        assert_eq!(take_reply(), Some(json!({ "previous": 3 })));
+        assert_eq!(take_reply(), None);
+
+        let name = std::any::type_name::<Zoomed>();
+        <(Zoomed, Reset)>::handle_event(&mut chart, event(name, "click")).unwrap();
+        assert_eq!(take_reply(), None);
+    }
+
+    #[derive(Serialize, Deserialize)]
+    enum Msg {
+        Zoom { level: u32, animate: bool },
+        Reset,
+    }
+
+    impl LiveViewEvent<Msg> for Chart {
+        fn handle(state: &mut Self, msg: Msg) {
+            match msg {
+                Msg::Zoom { level, .. } => state.level = level,
+                Msg::Reset => state.level = 0,
+            }
+        }
+    }
+
+    #[test]
+    fn enum_events_are_dispatched_by_variant() {
+        let mut chart = Chart::default();
+        let name = Dispatch(Msg::Zoom {
+            level: 1,
+            animate: true,
+        })
+        .event_name();
+        let handled = Dispatch::<Msg>::handle_event(&mut chart, event(&name, "click"));
+        assert!(handled.unwrap());
+        assert_eq!(chart.level, 2);
+
+        let name = Dispatch(Msg::Reset).event_name();
+        assert!(event(&name, "click").matches(std::any::type_name::<Msg>()));
+        let handled = Dispatch::<Msg>::handle_event(&mut chart, event(&name, "click"));
+        assert!(handled.unwrap());
+        assert_eq!(chart.level, 0);
+
+        let name = std::any::type_name::<Zoomed>();
+        let handled = Dispatch::<Msg>::handle_event(&mut chart, event(name, "click"));
+        assert!(!handled.unwrap());
+    }
+
+    #[test]
+    fn event_chains_handle_each_event() {
+        type Events = crate::events![Zoomed, Reset, Clear];
+        assert_eq!(
+            <Events as EventList<Chart>>::names(),
+            [
+                std::any::type_name::<Zoomed>(),
+                std::any::type_name::<Reset>(),
+                std::any::type_name::<Clear>(),
+            ]
+        );
+
+        let mut chart = Chart::default();
+        let name = std::any::type_name::<Zoomed>();
+        assert!(Events::handle_event(&mut chart, event(name, "click")).unwrap());
+        assert_eq!(chart.level, 2);
+
+        let name = std::any::type_name::<Clear>();
+        assert!(Events::handle_event(&mut chart, event(name, "click")).unwrap());
+        assert_eq!(chart.level, 0);
+
+        let name = std::any::type_name::<Msg>();
+        assert!(!Events::handle_event(&mut chart, event(name, "click")).unwrap());
+    }
+
+    #[test]
+    fn derived_events_call_the_handler() {
+        let mut chart = Chart { level: 3 };
+        let name = std::any::type_name::<Clear>();
+        let handled = <(Clear,)>::handle_event(&mut chart, event(name, "click"));
+        assert!(handled.unwrap());
+        assert_eq!(chart.level, 0);
+    }
+}
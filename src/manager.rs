@@ -35,6 +35,27 @@ where
         state: &mut Self::State,
         live_view: &T,
     ) -> LiveViewManagerResult<Option<Value>, Self::Error>;
+
+    /// Re-render the live view outside of an event, returning a diff if
+    /// anything changed.
+    fn handle_render(
+        &self,
+        state: &mut Self::State,
+        live_view: &T,
+    ) -> LiveViewManagerResult<Option<Value>, Self::Error>;
+
+    /// Re-renders components alone, such as after handling their events,
+    /// returning a diff if any changed.
+    fn handle_component_render(
+        &self,
+        state: &mut Self::State,
+        live_view: &T,
+        cids: &[u32],
+    ) -> LiveViewManagerResult<Option<Value>, Self::Error>;
+
+    /// Destroys components removed from the page, returning the ids of those
+    /// destroyed.
+    fn handle_destroy_components(&self, state: &mut Self::State, cids: &[u32]) -> Vec<u32>;
 }
 
 /// Live view socket result for returning a response with a recoverable error,
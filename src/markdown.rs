@@ -0,0 +1,42 @@
+//! Rendering of markdown, such as user comments.
+
+use maud_live_view::PreEscaped;
+use pulldown_cmark::{html, Options, Parser};
+
+use crate::html_sanitize;
+
+/// Renders CommonMark to HTML, sanitized with the default
+/// [`Sanitizer`](crate::Sanitizer) policy.
+///
+/// Tables and strikethrough are supported. The result is rendered
+/// as a single dynamic value, only sent again when the content changes.
+///
+/// # Example
+///
+/// ```
+/// html! {
+///     div.comment { (markdown(&comment.body)) }
+/// }
+/// ```
+pub fn markdown(content: &str) -> PreEscaped<String> {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let mut rendered = String::new();
+    html::push_html(&mut rendered, Parser::new_ext(content, options));
+    html_sanitize(&rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use maud_live_view::PreEscaped;
+
+    use super::markdown;
+
+    #[test]
+    fn markdown_is_sanitized() {
+        let PreEscaped(rendered) =
+            markdown("**bold** <script>alert(1)</script> [link](javascript:alert(1))");
+        assert!(rendered.contains("<strong>bold</strong>"));
+        assert!(!rendered.contains("<script>"));
+        assert!(!rendered.contains("javascript:"));
+    }
+}
@@ -1,39 +1,109 @@
+use std::any::type_name;
 use std::borrow::Cow;
-use std::env;
 use std::marker::PhantomData;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{env, fs, io};
 
 pub use ::maud_live_view::*;
+use base64::engine::general_purpose;
+use base64::Engine;
 use hmac::{Hmac, Mac};
-use jwt::VerifyWithKey;
-use lunatic::ap::ProcessRef;
+use jwt::{SignWithKey, VerifyWithKey};
+use lunatic::function::FuncRef;
 use lunatic_log::error;
+use percent_encoding::percent_decode_str;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use sha2::Sha256;
-use submillisecond::http::Uri;
-use submillisecond::response::Response;
+use sha2::{Digest, Sha256};
+use submillisecond::http::{header, StatusCode, Uri};
+use submillisecond::response::{IntoResponse, Response};
 use submillisecond::RequestContext;
 use thiserror::Error;
 
+use crate::assets::{set_static_assets, StaticAssets};
+use crate::csrf::{cookie_value, CsrfToken, CSRF_COOKIE};
+use crate::handler::CsrfProtection;
 use crate::manager::{Join, LiveViewManager, LiveViewManagerResult};
-use crate::rendered::{IntoJson, Rendered};
-use crate::socket::{Event, JoinEvent, Socket};
-use crate::template::{TemplateProcess, TemplateProcessRequests};
-use crate::LiveView;
+use crate::rendered::{intern, memo, statics, Rendered};
+use crate::session::{session_id, set_session, RevokedSessions, SESSION_MAX_AGE};
+use crate::socket::{set_timezone, set_uri, Event, JoinEvent, Socket, TIMEZONE_COOKIE};
+use crate::state_token::StateTokenOptions;
+#[cfg(debug_assertions)]
+use crate::template::TemplateProcessMessages;
+use crate::template::{Layout, Template};
+use crate::timings::{self, Phase};
+use crate::{component, flags, ignore, LiveView, MountError};
 
 #[derive(Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct LiveViewMaud<T> {
     phantom: PhantomData<T>,
-    template_process: ProcessRef<TemplateProcess>,
+    layout: Layout,
+    diff_options: DiffOptions,
+    state_tokens: StateTokenOptions,
+    csrf: CsrfProtection,
+    error_pages: ErrorPages,
+    mount_hooks: Vec<MountHook>,
+    title: TitleOptions,
+    static_assets: StaticAssets,
+    /// Whether dead renders are sent with an ETag, letting clients reuse the
+    /// page and its session.
+    etags: bool,
+}
+
+/// A hook invoked with the URI before mounting, which can reject the mount.
+pub(crate) type MountHook = FuncRef<fn(Uri) -> Result<(), MountError>>;
+
+/// A view rendering the error page of a failed mount.
+pub(crate) type ErrorView = FuncRef<fn(MountError) -> Rendered>;
+
+/// Pages rendered by dead renders when mounting fails.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct ErrorPages {
+    /// Paths of static html pages by status code.
+    pub(crate) pages: Vec<(u16, String)>,
+    pub(crate) view: Option<ErrorView>,
+}
+
+/// Options controlling how diffs are sent to the client.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(crate) struct DiffOptions {
+    pub(crate) max_diff_ratio: f32,
+    pub(crate) intern_strings: bool,
+}
+
+/// Text around the titles returned by [`LiveView::title`].
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct TitleOptions {
+    pub(crate) prefix: String,
+    pub(crate) suffix: String,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// Rendered state of a LiveView, kept to diff subsequent renders against.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct MaudState {
+    rendered: Rendered,
+    /// Snapshot held by the client as a state token.
+    snapshot: Option<Value>,
+    /// Id of the session the client's state token was issued to.
+    snapshot_session: Option<String>,
+    /// Title of the page last sent to the client.
+    title: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) struct Session {
     pub(crate) csrf_token: String,
+    /// Unique id of the session, revoked when the session is rotated.
+    pub(crate) id: String,
+    /// When the session was issued, in seconds since the Unix epoch.
+    pub(crate) issued_at: u64,
+    /// Claims set when the session was rotated, such as the id of the user who
+    /// logged in.
+    pub(crate) claims: Value,
 }
 
-#[derive(Clone, Copy, Debug, Error, Serialize, Deserialize)]
+#[derive(Clone, Debug, Error, Serialize, Deserialize)]
 pub(crate) enum LiveViewMaudError {
     #[error("invalid csrf token")]
     InvalidCsrfToken,
@@ -41,22 +111,101 @@ pub(crate) enum LiveViewMaudError {
     InvalidUrl,
     #[error("missing url")]
     MissingUrl,
+    #[error("mount failed: {0}")]
+    Mount(MountError),
+}
+
+impl Session {
+    /// Issues a session with a new CSRF token and claims, returning the session
+    /// and its signed encoding.
+    pub(crate) fn issue(claims: Value) -> (Session, String) {
+        let key: Hmac<Sha256> = Hmac::new_from_slice(&secret()).expect("unable to encode secret");
+        let session = Session {
+            csrf_token: CsrfToken::generate().masked,
+            id: format!("{:032x}", rand::random::<u128>()),
+            issued_at: unix_now(),
+            claims,
+        };
+        let signed = session
+            .clone()
+            .sign_with_key(&key)
+            .expect("failed to sign session");
+        (session, signed)
+    }
+
+    /// Returns whether a session issued at a time, in seconds since the Unix
+    /// epoch, is older than [`SESSION_MAX_AGE`].
+    pub(crate) fn is_expired(issued_at: u64) -> bool {
+        unix_now().saturating_sub(issued_at) > SESSION_MAX_AGE.as_secs()
+    }
 }
 
 impl<T> LiveViewMaud<T> {
-    pub(crate) fn new(template_process: ProcessRef<TemplateProcess>) -> Self {
+    pub(crate) fn new(
+        layout: Layout,
+        diff_options: DiffOptions,
+        state_tokens: StateTokenOptions,
+        csrf: CsrfProtection,
+        error_pages: ErrorPages,
+        mount_hooks: Vec<MountHook>,
+        title: TitleOptions,
+    ) -> Self {
         LiveViewMaud {
             phantom: PhantomData,
-            template_process,
+            layout,
+            diff_options,
+            state_tokens,
+            csrf,
+            error_pages,
+            mount_hooks,
+            title,
+            static_assets: StaticAssets::default(),
+            etags: true,
         }
     }
+
+    /// Sets the static assets whose URLs are returned by
+    /// [`static_path`](crate::static_path).
+    pub(crate) fn with_static_assets(mut self, static_assets: StaticAssets) -> Self {
+        self.static_assets = static_assets;
+        self
+    }
+
+    /// Sends dead renders without an ETag, so every page load is issued its
+    /// own session.
+    pub(crate) fn without_etags(mut self) -> Self {
+        self.etags = false;
+        self
+    }
+
+    /// Returns the static assets tracked by clients.
+    pub(crate) fn static_assets(&self) -> &StaticAssets {
+        &self.static_assets
+    }
 }
 
 impl<T> Clone for LiveViewMaud<T> {
     fn clone(&self) -> Self {
         Self {
             phantom: self.phantom,
-            template_process: self.template_process.clone(),
+            layout: self.layout.clone(),
+            diff_options: self.diff_options,
+            state_tokens: self.state_tokens,
+            csrf: self.csrf,
+            error_pages: self.error_pages.clone(),
+            mount_hooks: self.mount_hooks.clone(),
+            title: self.title.clone(),
+            static_assets: self.static_assets.clone(),
+            etags: self.etags,
+        }
+    }
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        DiffOptions {
+            max_diff_ratio: 1.0,
+            intern_strings: cfg!(feature = "liveview_js"),
         }
     }
 }
@@ -65,15 +214,94 @@ impl<T> LiveViewManager<T> for LiveViewMaud<T>
 where
     T: LiveView,
 {
-    type State = Rendered;
+    type State = MaudState;
     // type Reply = Value;
     type Error = LiveViewMaudError;
 
     fn handle_request(&self, req: RequestContext) -> Response {
-        let content = T::mount(req.uri().clone(), None).render().to_string();
-        let html = self.template_process.render(content);
+        let template = self.layout.template();
+        let live_view = match mount_request::<T>(&req, &self.mount_hooks, &self.static_assets) {
+            Ok(live_view) => live_view,
+            Err(err) => return self.error_pages.render(err, &template),
+        };
+        ignore::reset();
+        let rendered = live_view.render();
+        let content = rendered.to_string();
+        let title = self.title(&live_view);
+        let head = live_view.head().map(|head| head.to_string());
+        // Pages embed a session signed with the secret, which can't be reused
+        // from the cache once the secret changes, or once it's close to
+        // expiring.
+        let session_period = unix_now() / (SESSION_MAX_AGE.as_secs() / 2);
+        // The snapshot is sent in the page as a state token, restored when
+        // joining, so it's part of the page too.
+        let snapshot = live_view.snapshot();
+        let snapshot_json = snapshot.as_ref().map(Value::to_string);
+        let etag = self.etags.then(|| {
+            format!(
+                r#"W/"{}""#,
+                hash([
+                    secret_key_id().as_bytes(),
+                    &session_period.to_le_bytes(),
+                    template.fingerprint().as_bytes(),
+                    content.as_bytes(),
+                    title.as_deref().unwrap_or_default().as_bytes(),
+                    head.as_deref().unwrap_or_default().as_bytes(),
+                    snapshot_json.as_deref().unwrap_or_default().as_bytes(),
+                ])
+            )
+        });
 
-        Response::builder()
+        let not_modified = etag.as_deref().is_some_and(|etag| {
+            req.headers()
+                .get(header::IF_NONE_MATCH)
+                .and_then(|if_none_match| if_none_match.to_str().ok())
+                .is_some_and(|if_none_match| etag_matches(if_none_match, etag))
+        });
+        // Cached pages rely on the cookie too, so it's set on every response
+        // until the client has one.
+        let set_cookie = match self.csrf {
+            CsrfProtection::Cookie if !has_csrf_cookie(&req) => Some(format!(
+                "{CSRF_COOKIE}={}; Path=/; SameSite=Strict",
+                CsrfToken::generate().masked
+            )),
+            _ => None,
+        };
+        let mut builder = Response::builder();
+        if let Some(set_cookie) = set_cookie {
+            builder = builder.header(header::SET_COOKIE, set_cookie);
+        }
+        builder = match etag {
+            Some(etag) => builder
+                .header(header::ETAG, etag)
+                .header(header::CACHE_CONTROL, "private, no-cache"),
+            // The page's session can't be reused, so it isn't stored either.
+            None => builder.header(header::CACHE_CONTROL, "no-store"),
+        };
+
+        if not_modified {
+            return builder
+                .status(StatusCode::NOT_MODIFIED)
+                .body(Vec::new())
+                .unwrap();
+        }
+
+        let session = Session::issue(Value::Null);
+        let static_token = snapshot.and_then(|snapshot| {
+            self.state_tokens
+                .encrypt::<T>(snapshot, &fingerprint::<T>(&rendered), &session.0.id)
+        });
+        let html = template.render(
+            &content,
+            static_token.as_deref().unwrap_or_default(),
+            session,
+            self.csrf,
+            T::event_config(),
+            title.as_deref(),
+            head.as_deref(),
+        );
+
+        builder
             .header("Content-Type", "text/html; charset=UTF-8")
             .body(html.into_bytes())
             .unwrap()
@@ -84,16 +312,24 @@ where
         socket: Socket,
         event: JoinEvent,
     ) -> LiveViewManagerResult<Join<T, Self::State, Value>, Self::Error> {
-        let key: Hmac<Sha256> = Hmac::new_from_slice(&secret()).expect("unable to encode secret");
-        let session: Result<Session, _> = event.session.verify_with_key(&key);
+        let session = verify_session(&event.session);
 
         // Verify csrf token
-        if !session
-            .map(|session| session.csrf_token == event.params.csrf_token)
-            .unwrap_or(false)
-        {
+        let csrf_valid = match (self.csrf, &session) {
+            (CsrfProtection::MetaTag, Some(session)) => {
+                session.csrf_token == event.params.csrf_token
+            }
+            (CsrfProtection::Cookie, Some(_)) => {
+                !event.params.csrf_token.is_empty()
+                    && event.csrf_cookie.as_deref() == Some(event.params.csrf_token.as_str())
+            }
+            (_, None) => false,
+        };
+        let Some(session) = session.filter(|_| csrf_valid) else {
             return LiveViewManagerResult::FatalError(LiveViewMaudError::InvalidCsrfToken);
-        }
+        };
+        let joined_session = session.id.clone();
+        set_session(session);
 
         macro_rules! tri_fatal {
             ($e: expr) => {
@@ -110,9 +346,53 @@ where
             .parse()
             .map_err(|_| LiveViewMaudError::InvalidUrl));
 
-        let live_view = T::mount(uri, Some(socket));
-        let state = live_view.render();
-        let reply = state.clone().into_json();
+        set_uri(uri.clone());
+        set_timezone(event.params.timezone.clone());
+        set_static_assets(self.static_assets.clone());
+        flags::load();
+        // Pages are reloaded when their template is edited during development.
+        #[cfg(debug_assertions)]
+        self.layout
+            .process()
+            .subscribe(socket.event_handler.process());
+        let mut live_view = tri_fatal!(self
+            .mount(uri, Some(socket))
+            .map_err(LiveViewMaudError::Mount));
+        let snapshot = event.static_token.as_deref().and_then(|token| {
+            let fingerprint = fingerprint::<T>(&live_view.render());
+            memo::end_render();
+            self.state_tokens
+                .decrypt::<T>(token, &fingerprint, &joined_session)
+        });
+        if let Some(snapshot) = snapshot.clone() {
+            live_view.restore(snapshot);
+        }
+
+        ignore::reset();
+        let rendered = live_view.render();
+        memo::end_render();
+        let mut reply = rendered.clone().into_root_json();
+        // A rejoining client already has the statics if they haven't changed.
+        let statics_fingerprint = statics::fingerprint(&reply);
+        if event.params.statics.as_ref() == Some(&statics_fingerprint) {
+            statics::strip_statics(&mut reply);
+        }
+        if let Value::Object(reply) = &mut reply {
+            reply.insert("sf".to_string(), Value::String(statics_fingerprint));
+        }
+        if self.diff_options.intern_strings {
+            intern::intern(&mut reply);
+        }
+        let mut state = MaudState {
+            rendered,
+            snapshot,
+            snapshot_session: Some(joined_session),
+            title: None,
+        };
+        let reply = self.attach_state_token(&mut state, &live_view, Some(reply));
+        let reply = self
+            .attach_title(&mut state, &live_view, reply)
+            .unwrap_or_default();
         LiveViewManagerResult::Ok(Join {
             live_view,
             state,
@@ -126,11 +406,171 @@ where
         state: &mut Self::State,
         live_view: &T,
     ) -> LiveViewManagerResult<Option<Value>, Self::Error> {
-        let rendered = live_view.render();
-        let diff = state.clone().diff(rendered.clone()); // TODO: Remove these clones
-        *state = rendered;
+        self.handle_render(state, live_view)
+    }
+
+    fn handle_render(
+        &self,
+        state: &mut Self::State,
+        live_view: &T,
+    ) -> LiveViewManagerResult<Option<Value>, Self::Error> {
+        let rendered = timings::measure(Phase::Render, || live_view.render());
+        memo::end_render();
+        let diff = timings::measure(Phase::Diff, || {
+            state
+                .rendered
+                .clone()
+                .diff(rendered.clone()) // TODO: Remove these clones
+                .map(|diff| diff_or_full_render(diff, &rendered, self.diff_options.max_diff_ratio))
+                .map(|mut diff| {
+                    if self.diff_options.intern_strings {
+                        intern::intern(&mut diff);
+                    }
+                    diff
+                })
+        });
+        state.rendered = rendered;
+
+        let diff = self.attach_state_token(state, live_view, diff);
+        LiveViewManagerResult::Ok(self.attach_title(state, live_view, diff))
+    }
+
+    fn handle_component_render(
+        &self,
+        state: &mut Self::State,
+        live_view: &T,
+        cids: &[u32],
+    ) -> LiveViewManagerResult<Option<Value>, Self::Error> {
+        let renders: Vec<_> = timings::measure(Phase::Render, || {
+            cids.iter()
+                .filter_map(|cid| Some((*cid, component::render(*cid)?)))
+                .collect()
+        });
+        let diff = timings::measure(Phase::Diff, || {
+            state.rendered.diff_components(renders).map(|mut diff| {
+                if self.diff_options.intern_strings {
+                    intern::intern(&mut diff);
+                }
+                diff
+            })
+        });
+
+        LiveViewManagerResult::Ok(self.attach_state_token(state, live_view, diff))
+    }
+
+    fn handle_destroy_components(&self, state: &mut Self::State, cids: &[u32]) -> Vec<u32> {
+        component::destroy(cids, |cid| state.rendered.has_component(cid))
+    }
+}
+
+impl<T> LiveViewMaud<T>
+where
+    T: LiveView,
+{
+    /// Mounts the LiveView after running the mount hooks.
+    fn mount(&self, uri: Uri, socket: Option<Socket>) -> Result<T, MountError> {
+        for hook in &self.mount_hooks {
+            (hook.get())(uri.clone())?;
+        }
+        T::mount(uri, socket)
+    }
+
+    /// Returns the LiveView's title with the configured prefix and suffix.
+    fn title(&self, live_view: &T) -> Option<String> {
+        live_view
+            .title()
+            .map(|title| format!("{}{title}{}", self.title.prefix, self.title.suffix))
+    }
 
-        LiveViewManagerResult::Ok(diff)
+    /// Adds the page's title to a render under the `t` key when it differs
+    /// from the one shown by the client.
+    fn attach_title(
+        &self,
+        state: &mut MaudState,
+        live_view: &T,
+        render: Option<Value>,
+    ) -> Option<Value> {
+        let title = self.title(live_view);
+        if title.is_none() || title == state.title {
+            return render;
+        }
+        state.title = title.clone();
+
+        let mut render = render.unwrap_or_else(|| Value::Object(Default::default()));
+        if let (Value::Object(render), Some(title)) = (&mut render, title) {
+            render.insert("t".to_string(), Value::String(title));
+        }
+        Some(render)
+    }
+
+    /// Adds a state token to a render when the LiveView's snapshot differs from
+    /// the one held by the client.
+    ///
+    /// The token is sent under the `st` key, which the client stores in the
+    /// `data-phx-static` attribute and removes before applying the render.
+    fn attach_state_token(
+        &self,
+        state: &mut MaudState,
+        live_view: &T,
+        render: Option<Value>,
+    ) -> Option<Value> {
+        // Tokens are re-issued when the session is rotated, as they're bound
+        // to the session they were issued to.
+        let snapshot = live_view.snapshot();
+        let session = session_id();
+        if snapshot == state.snapshot && session == state.snapshot_session {
+            return render;
+        }
+
+        // An empty token clears a stale snapshot if the new one can't be sent.
+        let token = snapshot
+            .clone()
+            .and_then(|snapshot| {
+                self.state_tokens.encrypt::<T>(
+                    snapshot,
+                    &fingerprint::<T>(&state.rendered),
+                    session.as_deref().unwrap_or_default(),
+                )
+            })
+            .unwrap_or_default();
+        state.snapshot = snapshot;
+        state.snapshot_session = session;
+
+        let mut render = render.unwrap_or_else(|| Value::Object(Default::default()));
+        if let Value::Object(render) = &mut render {
+            render.insert("st".to_string(), Value::String(token));
+        }
+        Some(render)
+    }
+}
+
+impl ErrorPages {
+    /// Responds with the error page for a mount error's status, falling back
+    /// to the error view rendered in the template, or the error message.
+    fn render(&self, err: MountError, template: &Template) -> Response {
+        let status = err.status();
+        let page = self
+            .pages
+            .iter()
+            .find(|(page_status, _)| *page_status == status.as_u16())
+            .and_then(|(_, path)| match fs::read_to_string(path) {
+                Ok(html) => Some(html),
+                Err(read_err) => {
+                    error!("failed to read error page {path}: {read_err}");
+                    None
+                }
+            });
+        let html = match (page, self.view) {
+            (Some(html), _) => html,
+            (None, Some(view)) => template.render_static(&(view.get())(err).to_string()),
+            (None, None) => return (status, err.to_string()).into_response(),
+        };
+
+        Response::builder()
+            .status(status)
+            .header("Content-Type", "text/html; charset=UTF-8")
+            .body(html.into_bytes())
+            .unwrap()
     }
 }
 
@@ -140,9 +580,198 @@ const SECRET_DEFAULT: [u8; 32] = *b"liveview-debug-secret-csrf-token";
 #[cfg(not(debug_assertions))]
 const SECRET_DEFAULT: [u8; 32] = const_random::const_random!([u8; 32]);
 
+/// Returns the secret used to sign and encrypt data sent to the client.
 pub(crate) fn secret() -> Cow<'static, [u8]> {
     match env::var("LIVE_VIEW_SECRET") {
         Ok(secret) => Cow::Owned(secret.into_bytes()),
         Err(_) => Cow::Borrowed(&SECRET_DEFAULT),
     }
 }
+
+/// Returns an id of the current secret, which doesn't reveal the secret.
+fn secret_key_id() -> String {
+    hash([b"submillisecond-live-view:key-id:".as_slice(), &secret()])
+}
+
+/// Returns the secrets data from the client is verified with: the current
+/// secret, followed by the previous secret until its grace period ends.
+pub(crate) fn secrets() -> Vec<Cow<'static, [u8]>> {
+    let mut secrets = vec![secret()];
+    secrets.extend(previous_secret().map(Cow::Owned));
+    secrets
+}
+
+fn previous_secret() -> Option<Vec<u8>> {
+    let secret = env::var("LIVE_VIEW_PREVIOUS_SECRET").ok()?;
+    let expires_at: u64 = env::var("LIVE_VIEW_PREVIOUS_SECRET_EXPIRES_AT")
+        .ok()?
+        .parse()
+        .ok()?;
+    (unix_now() < expires_at).then(|| secret.into_bytes())
+}
+
+/// Returns the current time in seconds since the Unix epoch.
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Mounts a LiveView for a request without a live socket, such as a dead
+/// render, after running the mount hooks and setting up the process with the
+/// request's timezone, the static assets and the feature flags.
+pub(crate) fn mount_request<T>(
+    req: &RequestContext,
+    mount_hooks: &[MountHook],
+    static_assets: &StaticAssets,
+) -> Result<T, MountError>
+where
+    T: LiveView,
+{
+    let timezone = req
+        .headers()
+        .get(header::COOKIE)
+        .and_then(|cookie| cookie.to_str().ok())
+        .and_then(|cookie| cookie_value(cookie, TIMEZONE_COOKIE))
+        .map(|timezone| {
+            percent_decode_str(timezone)
+                .decode_utf8_lossy()
+                .into_owned()
+        });
+    set_timezone(timezone);
+    set_static_assets(static_assets.clone());
+    flags::load();
+    let uri = req.uri().clone();
+    for hook in mount_hooks {
+        (hook.get())(uri.clone())?;
+    }
+    T::mount(uri, None)
+}
+
+/// Verifies a session signed with any of the current secrets, which hasn't
+/// expired or been revoked by a rotation.
+pub(crate) fn verify_session(session: &str) -> Option<Session> {
+    let session: Session = secrets().iter().find_map(|secret| {
+        let key: Hmac<Sha256> = Hmac::new_from_slice(secret).expect("unable to encode secret");
+        session.verify_with_key(&key).ok()
+    })?;
+    if Session::is_expired(session.issued_at) {
+        return None;
+    }
+    let revoked =
+        RevokedSessions::lookup().is_some_and(|revoked| revoked.is_revoked(session.id.clone()));
+    (!revoked).then_some(session)
+}
+
+/// Returns a fingerprint of a LiveView's templates, which differs between
+/// LiveViews and changes when the root template changes.
+fn fingerprint<T>(rendered: &Rendered) -> String {
+    hash([
+        type_name::<T>().as_bytes(),
+        rendered.fingerprint().as_bytes(),
+    ])
+}
+
+/// Hashes a sequence of byte slices into a short url-safe base64 string.
+pub(crate) fn hash<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    general_purpose::URL_SAFE_NO_PAD.encode(&hasher.finalize()[..16])
+}
+
+/// Returns the full render in place of a diff if the diff is larger than the
+/// full render multiplied by `max_ratio`.
+///
+/// A diff containing statics replaces the client's rendered state, so the full
+/// render can be sent as a diff. The full render is only built when the diff
+/// is larger than a lower bound of its length.
+fn diff_or_full_render(diff: Value, rendered: &Rendered, max_ratio: f32) -> Value {
+    let diff_len = json_len(&diff) as f32;
+    if diff_len <= rendered.min_json_len() as f32 * max_ratio {
+        return diff;
+    }
+
+    let full = rendered.clone().into_root_json();
+    if diff_len > json_len(&full) as f32 * max_ratio {
+        full
+    } else {
+        diff
+    }
+}
+
+/// Returns the length of a value serialized as json, without allocating.
+fn json_len(value: &Value) -> usize {
+    struct Counter(usize);
+
+    impl io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    serde_json::to_writer(&mut counter, value).expect("value should serialize");
+    counter.0
+}
+
+fn has_csrf_cookie(req: &RequestContext) -> bool {
+    req.headers()
+        .get(header::COOKIE)
+        .and_then(|cookie| cookie.to_str().ok())
+        .and_then(|cookie| cookie_value(cookie, CSRF_COOKIE))
+        .map(|token| !token.is_empty())
+        .unwrap_or(false)
+}
+
+/// Checks if an `If-None-Match` header value matches an etag using the weak
+/// comparison function.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let etag = etag.trim_start_matches("W/");
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::rendered::IntoJson;
+    use crate::{self as submillisecond_live_view, html};
+
+    #[test]
+    fn etag_matches_weak() {
+        assert!(etag_matches(r#"W/"abc""#, r#"W/"abc""#));
+        assert!(etag_matches(r#""abc""#, r#"W/"abc""#));
+        assert!(etag_matches(r#""xyz", W/"abc""#, r#"W/"abc""#));
+        assert!(etag_matches("*", r#"W/"abc""#));
+        assert!(!etag_matches(r#"W/"xyz""#, r#"W/"abc""#));
+        assert!(!etag_matches("", r#"W/"abc""#));
+    }
+
+    #[lunatic::test]
+    fn oversized_diff_falls_back_to_full_render() {
+        let rendered = html! { p { "Count is " (1) } };
+        let full = rendered.clone().into_json();
+        assert!(rendered.min_json_len() <= json_len(&full));
+
+        let diff = json!({ "0": "1" });
+        assert_eq!(diff_or_full_render(diff.clone(), &rendered, 1.0), diff);
+
+        let diff = json!({ "0": "1".repeat(100) });
+        assert_eq!(diff_or_full_render(diff, &rendered, 1.0), full);
+
+        let diff = json!({ "0": "1" });
+        assert_eq!(diff_or_full_render(diff, &rendered, 0.0), full);
+    }
+}
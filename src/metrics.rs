@@ -0,0 +1,163 @@
+//! Resource metrics of connections, reported through lunatic's metrics.
+//!
+//! Metrics are aggregated per LiveView, with names holding the LiveView's type
+//! name reduced to alphanumeric characters and underscores, so leaks and hot
+//! spots can be attributed to a particular view without a series per
+//! connection. They're exported by the lunatic runtime, such as to
+//! Prometheus, rather than shown in a dashboard of their own.
+//!
+//! Metrics are only reported when the `metrics` feature is enabled.
+
+use std::any::type_name;
+use std::time::{Duration, Instant};
+
+use lunatic::serializer::Json;
+use lunatic::{metrics, Process};
+
+use crate::event_handler::EventHandlerMessage;
+
+/// How often the render rate and memory of a connection are reported while
+/// it renders.
+pub(crate) const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+pub(crate) struct Metrics {
+    prefix: String,
+    /// Event handler of the connection, which reports are scheduled on.
+    event_handler: Process<EventHandlerMessage, Json>,
+    /// Whether a report is scheduled.
+    scheduled: bool,
+    renders: u64,
+    window_start: Instant,
+    /// Values last added to the LiveView's gauges by this connection.
+    renders_per_sec: f64,
+    memory_bytes: f64,
+}
+
+impl Metrics {
+    /// Creates the metrics of the connection of the event handler.
+    pub(crate) fn new<T>(event_handler: Process<EventHandlerMessage, Json>) -> Self {
+        Metrics {
+            prefix: format!("submillisecond_live_view.{}", metric_name(type_name::<T>())),
+            event_handler,
+            scheduled: false,
+            renders: 0,
+            window_start: Instant::now(),
+            renders_per_sec: 0.0,
+            memory_bytes: 0.0,
+        }
+    }
+
+    /// Returns whether metrics are reported.
+    pub(crate) fn enabled() -> bool {
+        cfg!(feature = "metrics")
+    }
+
+    /// Records the number of client events handled together with a single
+    /// render.
+    pub(crate) fn record_coalesced_events(&self, len: usize) {
+        if !Self::enabled() {
+            return;
+        }
+
+        metrics::histogram(&format!("{}.coalesced_events", self.prefix), len as f64);
+    }
+
+    /// Records a render, scheduling a report if there's none, so idle
+    /// connections aren't woken up to report.
+    pub(crate) fn record_render(&mut self) {
+        if !Self::enabled() {
+            return;
+        }
+
+        metrics::increment_counter(&format!("{}.renders", self.prefix));
+        if !self.scheduled {
+            self.schedule();
+            self.window_start = Instant::now();
+        }
+        self.renders += 1;
+    }
+
+    /// Reports the render rate since the last report, the memory used by the
+    /// process and the number of messages waiting in its mailbox.
+    ///
+    /// Another report is scheduled if the LiveView rendered since the last
+    /// one, so the render rate drops back to zero once it stops rendering.
+    pub(crate) fn report(&mut self, mailbox_len: usize) {
+        self.scheduled = false;
+        if !Self::enabled() {
+            return;
+        }
+
+        let elapsed = self.window_start.elapsed();
+        let renders_per_sec = self.renders as f64 / elapsed.as_secs_f64();
+        self.renders_per_sec =
+            self.add_to_gauge("renders_per_sec", self.renders_per_sec, renders_per_sec);
+        self.memory_bytes =
+            self.add_to_gauge("memory_bytes", self.memory_bytes, memory_bytes() as f64);
+        metrics::histogram(&format!("{}.mailbox_len", self.prefix), mailbox_len as f64);
+        if self.renders > 0 {
+            self.schedule();
+        }
+        self.renders = 0;
+        self.window_start = Instant::now();
+    }
+
+    fn schedule(&mut self) {
+        self.event_handler
+            .send_after(EventHandlerMessage::ReportMetrics, REPORT_INTERVAL);
+        self.scheduled = true;
+    }
+
+    /// Replaces the connection's share of a gauge of the LiveView, returning
+    /// the new share.
+    fn add_to_gauge(&self, name: &str, previous: f64, value: f64) -> f64 {
+        metrics::increment_gauge(&format!("{}.{name}", self.prefix), value - previous);
+        value
+    }
+}
+
+impl Drop for Metrics {
+    /// Removes the connection's share of the gauges once it's gone, so it
+    /// doesn't look like it's still holding on to memory.
+    fn drop(&mut self) {
+        if !Self::enabled() {
+            return;
+        }
+
+        self.add_to_gauge("renders_per_sec", self.renders_per_sec, 0.0);
+        self.add_to_gauge("memory_bytes", self.memory_bytes, 0.0);
+    }
+}
+
+/// Reduces a type name to alphanumeric characters and underscores, which
+/// metric exporters accept in names.
+fn metric_name(type_name: &str) -> String {
+    type_name
+        .replace("::", "_")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Returns the size of the process' linear memory, which is an upper bound
+/// estimate of its heap usage.
+#[cfg(target_arch = "wasm32")]
+fn memory_bytes() -> usize {
+    core::arch::wasm32::memory_size(0) * 65536
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn memory_bytes() -> usize {
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::metric_name;
+
+    #[test]
+    fn metric_names_are_alphanumeric() {
+        assert_eq!(metric_name("app::views::Counter"), "app_views_Counter");
+        assert_eq!(metric_name("app::Table<app::Row>"), "app_Table_app_Row_");
+    }
+}
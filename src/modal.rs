@@ -0,0 +1,187 @@
+//! Modal dialogs.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::rendered::Rendered;
+use crate::{self as submillisecond_live_view, html};
+
+/// A modal dialog, opened and closed by the LiveView's state.
+///
+/// The modal is kept in the LiveView's state and opened with [`Modal::open`].
+/// Pressing escape, clicking outside the dialog or clicking its close button
+/// hides it immediately on the client, and sends a [`ModalClosed`] event to
+/// keep the server in sync. While open, keyboard focus is kept within the
+/// dialog.
+///
+/// # Example
+///
+/// ```
+/// impl LiveView for Settings {
+///     type Events = (OpenSettings, ModalClosed);
+///
+///     fn render(&self) -> Rendered {
+///         html! {
+///             button @click=(OpenSettings) { "Settings" }
+///             @(self.modal.render(html! {
+///                 p { "Are you sure?" }
+///                 button phx-click=(self.modal.close_js()) { "Cancel" }
+///             }))
+///         }
+///     }
+///     // ...
+/// }
+///
+/// impl LiveViewEvent<OpenSettings> for Settings {
+///     fn handle(state: &mut Self, _event: OpenSettings) {
+///         state.modal.open();
+///     }
+/// }
+///
+/// impl LiveViewEvent<ModalClosed> for Settings {
+///     fn handle(state: &mut Self, event: ModalClosed) {
+///         state.modal.handle(event);
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Modal {
+    id: String,
+    title: Option<String>,
+    open: bool,
+}
+
+/// Event sent when a [`Modal`] is closed on the client.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModalClosed {
+    /// The id of the closed modal.
+    pub id: String,
+}
+
+impl Modal {
+    /// Creates a closed modal.
+    ///
+    /// The id is used as the dialog element's id, and must be unique within
+    /// the page.
+    pub fn new(id: impl Into<String>) -> Self {
+        Modal {
+            id: id.into(),
+            title: None,
+            open: false,
+        }
+    }
+
+    /// Sets a title rendered as the dialog's heading and accessible name.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Returns the id of the dialog element.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns whether the modal is open.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Opens the modal.
+    pub fn open(&mut self) {
+        self.open = true;
+    }
+
+    /// Closes the modal.
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// Handles the modal being closed on the client.
+    ///
+    /// Events from other modals on the page are ignored.
+    pub fn handle(&mut self, event: ModalClosed) {
+        if event.id == self.id {
+            self.close();
+        }
+    }
+
+    /// Returns JS commands which hide the modal and send [`ModalClosed`], for
+    /// use as the `phx-click` of other buttons in the dialog.
+    pub fn close_js(&self) -> String {
+        json!([
+            ["hide", { "to": format!("#{}", self.id) }],
+            [
+                "push",
+                {
+                    "event": std::any::type_name::<ModalClosed>(),
+                    "value": { "id": self.id },
+                }
+            ],
+        ])
+        .to_string()
+    }
+
+    /// Renders the dialog with its content if open, or nothing if closed.
+    pub fn render(&self, content: Rendered) -> Rendered {
+        let close = self.close_js();
+        let title_id = self.title.as_ref().map(|_| format!("{}-title", self.id));
+        html! {
+            @if self.open {
+                div.modal
+                    id=(self.id)
+                    role="dialog"
+                    aria-modal="true"
+                    aria-labelledby=[title_id.as_ref()]
+                    data-focus-trap
+                    phx-window-keydown=(close)
+                    phx-key="Escape"
+                {
+                    div.modal-backdrop {}
+                    div.modal-content phx-click-away=(close) {
+                        button.modal-close type="button" aria-label="Close" phx-click=(close) {
+                            "×"
+                        }
+                        @if let (Some(title), Some(title_id)) = (&self.title, &title_id) {
+                            h2.modal-title id=(title_id) { (title) }
+                        }
+                        @(content)
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::{Modal, ModalClosed};
+
+    #[test]
+    fn close_js_pushes_modal_closed() {
+        let modal = Modal::new("confirm");
+        let commands: Value = serde_json::from_str(&modal.close_js()).unwrap();
+        assert_eq!(commands[0][1]["to"], "#confirm");
+        assert_eq!(
+            commands[1][1]["event"],
+            std::any::type_name::<ModalClosed>()
+        );
+        assert_eq!(commands[1][1]["value"]["id"], "confirm");
+    }
+
+    #[test]
+    fn handle_ignores_other_modals() {
+        let mut modal = Modal::new("confirm");
+        modal.open();
+        modal.handle(ModalClosed {
+            id: "other".to_string(),
+        });
+        assert!(modal.is_open());
+        modal.handle(ModalClosed {
+            id: "confirm".to_string(),
+        });
+        assert!(!modal.is_open());
+    }
+}
@@ -0,0 +1,100 @@
+//! Live navigation between LiveViews over the same websocket.
+
+use std::any::type_name;
+
+use lunatic::function::FuncRef;
+use lunatic::serializer::Json;
+use lunatic::{Mailbox, Process, Tag};
+use serde::{Deserialize, Serialize};
+
+use crate::event_handler::{EventHandler, EventHandlerOptions};
+use crate::maud::LiveViewMaud;
+use crate::socket::RawSocket;
+use crate::LiveView;
+
+const ROUTE_PROCESS_ID: &str = "7b2e9d41-3c6f-4a85-9e1d-0f4a6c8b5d23";
+
+/// Options of a LiveView's handler, used to mount it when navigated to.
+type RouteOptions<T> = (LiveViewMaud<T>, EventHandlerOptions);
+
+type RouteRequest<T> = (Process<RouteOptions<T>, Json>, Tag);
+
+/// A navigation to another LiveView, mounted once the client leaves the
+/// current one and rejoins.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct Navigation {
+    /// Type name of the LiveView navigated to.
+    live_view: String,
+    spawn: FuncRef<fn(RawSocket) -> Option<EventHandler>>,
+}
+
+impl Navigation {
+    /// Returns a navigation to a LiveView, if it's served by a handler.
+    pub(crate) fn to<T>() -> Option<Self>
+    where
+        T: LiveView,
+    {
+        route::<T>()?;
+        Some(Navigation {
+            live_view: type_name::<T>().to_string(),
+            spawn: FuncRef::new(spawn_event_handler::<T>),
+        })
+    }
+
+    /// Spawns the event handler of the LiveView navigated to, linked to the
+    /// current process.
+    pub(crate) fn spawn(self, socket: RawSocket) -> Option<EventHandler> {
+        (self.spawn.get())(socket)
+    }
+}
+
+impl PartialEq for Navigation {
+    fn eq(&self, other: &Self) -> bool {
+        self.live_view == other.live_view
+    }
+}
+
+impl Eq for Navigation {}
+
+/// Makes a handler's LiveView available to navigate to.
+///
+/// If the LiveView is served by several handlers, the options of the first
+/// one registered are used.
+pub(crate) fn register<T>(live_view: LiveViewMaud<T>, options: EventHandlerOptions)
+where
+    T: LiveView,
+{
+    if route::<T>().is_none() {
+        let process = Process::spawn_link((live_view, options), serve_route::<T>);
+        process.register(&route_name::<T>());
+    }
+}
+
+fn route<T>() -> Option<Process<RouteRequest<T>, Json>> {
+    Process::lookup(&route_name::<T>())
+}
+
+fn route_name<T>() -> String {
+    format!("{ROUTE_PROCESS_ID}-{}", type_name::<T>())
+}
+
+fn spawn_event_handler<T>(socket: RawSocket) -> Option<EventHandler>
+where
+    T: LiveView,
+{
+    let tag = Tag::new();
+    route::<T>()?.send((unsafe { Process::this() }, tag));
+    let mailbox: Mailbox<RouteOptions<T>, Json> = unsafe { Mailbox::new() };
+    let (live_view, options) = mailbox.tag_receive(&[tag]);
+    Some(EventHandler::spawn(socket, live_view, options))
+}
+
+fn serve_route<T>((live_view, options): RouteOptions<T>, mailbox: Mailbox<RouteRequest<T>, Json>)
+where
+    T: LiveView,
+{
+    loop {
+        let (parent, tag) = mailbox.receive();
+        parent.tag_send(tag, (live_view.clone(), options));
+    }
+}
@@ -0,0 +1,54 @@
+//! Acknowledging and denying optimistic updates made by the client.
+
+use std::cell::Cell;
+
+use serde::Serialize;
+
+thread_local! {
+    /// Verdict on the optimistic update of the event being handled, if given.
+    static VERDICT: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Verdict on an optimistic update, sent in the reply to its event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct OptimisticReply {
+    #[serde(rename = "ref")]
+    pub(crate) optimistic_ref: u64,
+    pub(crate) accepted: bool,
+}
+
+/// Keeps the client's optimistic update for the event being handled, even if
+/// the render doesn't change.
+///
+/// By default, an optimistic update is kept only if handling its event
+/// changed the render, which then replaces the predicted change. Acknowledge
+/// updates whose result is rendered later, such as after a background job.
+pub fn acknowledge_optimistic() {
+    VERDICT.with(|verdict| verdict.set(Some(true)));
+}
+
+/// Reverts the client's optimistic update for the event being handled, such
+/// as when the action it predicted wasn't allowed.
+///
+/// # Example
+///
+/// ```
+/// impl LiveViewEvent<Like> for Post {
+///     fn handle(state: &mut Self, _event: Like) {
+///         if state.likes.contains(&state.user_id) {
+///             deny_optimistic();
+///             return;
+///         }
+///         state.likes.push(state.user_id);
+///     }
+/// }
+/// ```
+pub fn deny_optimistic() {
+    VERDICT.with(|verdict| verdict.set(Some(false)));
+}
+
+/// Takes the verdict given while handling an event, clearing it for the next
+/// event.
+pub(crate) fn take_verdict() -> Option<bool> {
+    VERDICT.with(Cell::take)
+}
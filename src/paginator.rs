@@ -0,0 +1,288 @@
+//! Pagination of large collections.
+
+use std::ops::Range;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::rendered::Rendered;
+use crate::{self as submillisecond_live_view, html};
+
+/// Page state of a paginated collection, with windowed page links.
+///
+/// The paginator is kept in the LiveView's state, and handles the
+/// [`PageChanged`] event sent by the links of [`Paginator::render`]. Large
+/// collections can be loaded a page at a time using [`Paginator::range`].
+///
+/// # Example
+///
+/// ```
+/// impl LiveView for Posts {
+///     type Events = (PageChanged,);
+///
+///     fn render(&self) -> Rendered {
+///         html! {
+///             @for post in self.paginator.paginate(&self.posts) {
+///                 article { (post.title) }
+///             }
+///             @(self.paginator.render())
+///         }
+///     }
+///     // ...
+/// }
+///
+/// impl LiveViewEvent<PageChanged> for Posts {
+///     fn handle(state: &mut Self, event: PageChanged) {
+///         state.paginator.handle(event);
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Paginator {
+    page: usize,
+    per_page: usize,
+    total: usize,
+    window: usize,
+}
+
+/// Event sent by the page links of a [`Paginator`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageChanged {
+    /// The requested page, starting from 1.
+    #[serde(deserialize_with = "deserialize_page")]
+    pub page: usize,
+}
+
+/// A link rendered by a [`Paginator`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PageLink {
+    /// A link to a page.
+    Page {
+        /// The page number, starting from 1.
+        number: usize,
+        /// Whether this is the current page.
+        current: bool,
+    },
+    /// Pages left out between the window and the first or last page.
+    Gap,
+}
+
+impl Paginator {
+    /// Creates a paginator on the first page, with a number of items per page.
+    pub fn new(per_page: usize) -> Self {
+        Paginator {
+            page: 1,
+            per_page: per_page.max(1),
+            total: 0,
+            window: 2,
+        }
+    }
+
+    /// Sets the number of pages linked either side of the current page.
+    ///
+    /// Defaults to 2.
+    pub fn window(mut self, window: usize) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Returns the current page, starting from 1.
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    /// Returns the number of items per page.
+    pub fn per_page(&self) -> usize {
+        self.per_page
+    }
+
+    /// Returns the total number of items.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns the number of pages, which is at least 1.
+    pub fn total_pages(&self) -> usize {
+        self.total.div_ceil(self.per_page).max(1)
+    }
+
+    /// Sets the total number of items, moving to the last page if the current
+    /// page no longer exists.
+    ///
+    /// The total should be set whenever the collection changes, and
+    /// determines the links rendered.
+    pub fn set_total(&mut self, total: usize) {
+        self.total = total;
+        self.page = self.page.min(self.total_pages());
+    }
+
+    /// Moves to a page, clamped to the existing pages.
+    pub fn set_page(&mut self, page: usize) {
+        self.page = page.clamp(1, self.total_pages());
+    }
+
+    /// Handles a page link being clicked.
+    pub fn handle(&mut self, event: PageChanged) {
+        self.set_page(event.page);
+    }
+
+    /// Returns the range of item indexes on the current page, such as for a
+    /// database query's offset and limit.
+    pub fn range(&self) -> Range<usize> {
+        let start = (self.page - 1) * self.per_page;
+        start..start + self.per_page
+    }
+
+    /// Returns the items on the current page.
+    pub fn paginate<'a, T>(&self, items: &'a [T]) -> &'a [T] {
+        let range = self.range();
+        &items[range.start.min(items.len())..range.end.min(items.len())]
+    }
+
+    /// Returns the links to render: the first and last pages, and the pages
+    /// within the window of the current page, with gaps between them.
+    pub fn links(&self) -> Vec<PageLink> {
+        let total_pages = self.total_pages();
+        let start = self.page.saturating_sub(self.window).max(1);
+        let end = (self.page + self.window).min(total_pages);
+
+        let mut numbers = Vec::with_capacity(end - start + 3);
+        if start > 1 {
+            numbers.push(1);
+        }
+        numbers.extend(start..=end);
+        if end < total_pages {
+            numbers.push(total_pages);
+        }
+
+        let mut links = Vec::with_capacity(numbers.len() + 2);
+        for (i, &number) in numbers.iter().enumerate() {
+            if i > 0 && number > numbers[i - 1] + 1 {
+                links.push(PageLink::Gap);
+            }
+            links.push(PageLink::Page {
+                number,
+                current: number == self.page,
+            });
+        }
+        links
+    }
+
+    /// Renders the previous and next buttons and page links, which send
+    /// [`PageChanged`] events.
+    pub fn render(&self) -> Rendered {
+        let total_pages = self.total_pages();
+        let previous = self.page.saturating_sub(1);
+        let next = self.page + 1;
+        html! {
+            nav.pagination aria-label="Pagination" {
+                button.pagination-previous
+                    type="button"
+                    disabled[self.page <= 1]
+                    :page=(previous)
+                    @click=(PageChanged)
+                {
+                    "Previous"
+                }
+                @for link in self.links() {
+                    @if let Some(number) = link.number() {
+                        @if number == self.page {
+                            button.pagination-page.current type="button" aria-current="page" disabled {
+                                (number)
+                            }
+                        } @else {
+                            button.pagination-page type="button" :page=(number) @click=(PageChanged) {
+                                (number)
+                            }
+                        }
+                    } @else {
+                        span.pagination-gap { "…" }
+                    }
+                }
+                button.pagination-next
+                    type="button"
+                    disabled[self.page >= total_pages]
+                    :page=(next)
+                    @click=(PageChanged)
+                {
+                    "Next"
+                }
+            }
+        }
+    }
+}
+
+impl PageLink {
+    /// Returns the page number, or `None` for a gap.
+    pub fn number(&self) -> Option<usize> {
+        match self {
+            PageLink::Page { number, .. } => Some(*number),
+            PageLink::Gap => None,
+        }
+    }
+}
+
+/// Deserializes a page sent as a `phx-value-page` string, or as a number.
+fn deserialize_page<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Page {
+        Number(usize),
+        String(String),
+    }
+
+    match Page::deserialize(deserializer)? {
+        Page::Number(page) => Ok(page),
+        Page::String(page) => page.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PageChanged, PageLink, Paginator};
+
+    fn numbers(links: &[PageLink]) -> Vec<Option<usize>> {
+        links.iter().map(PageLink::number).collect()
+    }
+
+    #[test]
+    fn links_are_windowed() {
+        let mut paginator = Paginator::new(10).window(1);
+        paginator.set_total(200);
+        assert_eq!(
+            numbers(&paginator.links()),
+            [Some(1), Some(2), None, Some(20)]
+        );
+
+        paginator.set_page(10);
+        assert_eq!(
+            numbers(&paginator.links()),
+            [Some(1), None, Some(9), Some(10), Some(11), None, Some(20)]
+        );
+
+        paginator.handle(serde_json::from_str::<PageChanged>(r#"{"page":"19"}"#).unwrap());
+        assert_eq!(
+            numbers(&paginator.links()),
+            [Some(1), None, Some(18), Some(19), Some(20)]
+        );
+    }
+
+    #[test]
+    fn paginate_clamps_to_total() {
+        let items: Vec<_> = (0..25).collect();
+        let mut paginator = Paginator::new(10);
+        paginator.set_total(items.len());
+        paginator.set_page(3);
+        assert_eq!(paginator.paginate(&items), &[20, 21, 22, 23, 24]);
+
+        paginator.handle(PageChanged { page: 9 });
+        assert_eq!(paginator.page(), 3);
+        assert_eq!(paginator.paginate(&items[..5]), &[] as &[i32]);
+
+        paginator.set_total(5);
+        assert_eq!(paginator.page(), 1);
+        assert_eq!(paginator.paginate(&items[..5]), &[0, 1, 2, 3, 4]);
+    }
+}
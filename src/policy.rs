@@ -0,0 +1,96 @@
+//! Authorization policies shared by rendering and event handling.
+
+use crate::Identity;
+
+/// Authorization rules deciding which actions an identity can perform.
+///
+/// Checking the same policy with [`can!`](crate::can) when rendering and when
+/// handling events keeps what's shown consistent with what's allowed.
+///
+/// Functions and closures taking the identity and action implement `Policy`.
+///
+/// # Example
+///
+/// ```
+/// enum Action {
+///     EditPost(u64),
+/// }
+///
+/// struct Posts;
+///
+/// impl Policy<Action> for Posts {
+///     fn can(&self, identity: Option<&Identity>, action: &Action) -> bool {
+///         match action {
+///             Action::EditPost(id) => identity.map_or(false, |user| is_author(user, *id)),
+///         }
+///     }
+/// }
+///
+/// // In render
+/// html! {
+///     @if can!(Posts, Action::EditPost(post.id)) {
+///         button @click=(Edit) { "Edit" }
+///     }
+/// }
+///
+/// // In the event handler
+/// impl LiveViewEvent<Edit> for Blog {
+///     fn handle(state: &mut Self, event: Edit) {
+///         if !can!(Posts, Action::EditPost(event.id)) {
+///             return;
+///         }
+///         // ...
+///     }
+/// }
+/// ```
+pub trait Policy<A: ?Sized> {
+    /// Returns true if the identity can perform the action.
+    ///
+    /// The identity is `None` for connections which aren't authenticated.
+    fn can(&self, identity: Option<&Identity>, action: &A) -> bool;
+}
+
+impl<A, F> Policy<A> for F
+where
+    A: ?Sized,
+    F: Fn(Option<&Identity>, &A) -> bool,
+{
+    fn can(&self, identity: Option<&Identity>, action: &A) -> bool {
+        self(identity, action)
+    }
+}
+
+/// Checks a [`Policy`] for the identity of the current connection.
+///
+/// The identity is the one authenticated with
+/// [`TokenAuth`](crate::TokenAuth), and is only available from within the
+/// LiveView's process, such as in `mount`, event handlers and `render`.
+#[macro_export]
+macro_rules! can {
+    ($policy: expr, $action: expr $(,)?) => {
+        $crate::Policy::can(&$policy, $crate::__private::identity().as_ref(), &$action)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Identity;
+
+    enum Action {
+        Read,
+        Delete,
+    }
+
+    fn policy(identity: Option<&Identity>, action: &Action) -> bool {
+        match action {
+            Action::Read => true,
+            Action::Delete => identity.is_some(),
+        }
+    }
+
+    #[test]
+    fn policy_is_checked_without_identity() {
+        assert!(can!(policy, Action::Read));
+        assert!(!can!(policy, Action::Delete));
+    }
+}
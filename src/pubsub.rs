@@ -0,0 +1,358 @@
+//! Publishing messages to the LiveViews subscribed to a topic.
+
+use std::any::type_name;
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+use lunatic::serializer::Json;
+use lunatic::{distributed, Mailbox, MailboxError, MessageSignal, Process, ProcessDiedSignal};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::socket::Socket;
+
+const PUBSUB_PROCESS_ID: &str = "c81f5a2e-6d07-4b93-a4e1-93b7d0c6f258";
+
+/// How often connected nodes are checked, to connect to new nodes and drop
+/// disconnected ones.
+const PEER_REFRESH: Duration = Duration::from_secs(5);
+
+/// Publishes messages to the LiveViews subscribed to a topic, through a named
+/// process started by the handler.
+///
+/// LiveViews subscribe with [`PubSub::subscribe`], typically in
+/// [`LiveView::after_join`](crate::LiveView::after_join), and receive a
+/// [`Broadcast`] event for each message broadcast to the topic, from any
+/// process. Subscriptions end when the LiveView's connection closes.
+///
+/// When running distributed, broadcasts also reach the LiveViews on the other
+/// lunatic nodes running the application. Nodes are connected to as they join
+/// the cluster, and dropped once they leave it, so a failed node only misses
+/// the broadcasts sent while it's down.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct ChatMessage {
+///     author: String,
+///     text: String,
+/// }
+///
+/// impl LiveView for Chat {
+///     type Events = (SendMessage, Broadcast<ChatMessage>);
+///
+///     fn after_join(&mut self, socket: &mut Socket) {
+///         PubSub::<ChatMessage>::subscribe(socket, &self.room);
+///     }
+///     // ...
+/// }
+///
+/// impl LiveViewEvent<SendMessage> for Chat {
+///     fn handle(state: &mut Self, event: SendMessage) {
+///         let message = ChatMessage { author: state.name.clone(), text: event.text };
+///         PubSub::broadcast(&state.room, message);
+///     }
+/// }
+///
+/// impl LiveViewEvent<Broadcast<ChatMessage>> for Chat {
+///     fn handle(state: &mut Self, event: Broadcast<ChatMessage>) {
+///         state.messages.push(event.message);
+///     }
+/// }
+/// ```
+pub struct PubSub<M> {
+    phantom: PhantomData<M>,
+}
+
+/// Path of [`Broadcast`], which its events are named by followed by their
+/// message type.
+pub(crate) const BROADCAST_EVENT: &str = concat!(module_path!(), "::Broadcast");
+
+/// Event sent to the LiveViews subscribed to a topic with [`PubSub`] when a
+/// message is broadcast to it.
+///
+/// Only the server sends it, so clients pushing an event of the same name are
+/// rejected as an unknown event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Broadcast<M> {
+    /// The topic the message was broadcast to.
+    pub topic: String,
+    /// The message.
+    pub message: M,
+}
+
+/// Messages of the process, holding the name of the [`Broadcast`] event of
+/// their message type, so a single process serves every message type.
+#[derive(Serialize, Deserialize)]
+enum PubSubMessage {
+    Subscribe(Box<Socket>, String, String),
+    Unsubscribe(u64, String, String),
+    Broadcast {
+        /// Id of the event handler excluded from the broadcast, if any.
+        sender: Option<u64>,
+        event: String,
+        topic: String,
+        message: Value,
+    },
+    /// A broadcast from another node, only delivered to local subscribers.
+    Relay {
+        event: String,
+        topic: String,
+        message: Value,
+    },
+    /// The process of another node.
+    Peer(u64, Process<PubSubMessage, Json>),
+}
+
+struct Subscriber {
+    topics: HashSet<String>,
+    forwarder: Process<(String, Broadcast<Value>), Json>,
+}
+
+impl<M> PubSub<M>
+where
+    M: Clone + Serialize + DeserializeOwned,
+{
+    /// Subscribes a LiveView to a topic, until its connection closes.
+    pub fn subscribe(socket: &Socket, topic: &str) {
+        process().send(PubSubMessage::Subscribe(
+            Box::new(socket.clone()),
+            Self::event(),
+            topic.to_string(),
+        ));
+    }
+
+    /// Unsubscribes a LiveView from a topic.
+    pub fn unsubscribe(socket: &Socket, topic: &str) {
+        process().send(PubSubMessage::Unsubscribe(
+            socket.event_handler.process().id(),
+            Self::event(),
+            topic.to_string(),
+        ));
+    }
+
+    /// Broadcasts a message to the LiveViews subscribed to a topic.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the message can't be serialized.
+    pub fn broadcast(topic: &str, message: M) {
+        Self::send_broadcast(None, topic, message);
+    }
+
+    /// Broadcasts a message to the LiveViews subscribed to a topic, except the
+    /// LiveView of `socket`, such as when it already shows the change.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the message can't be serialized.
+    pub fn broadcast_from(socket: &Socket, topic: &str, message: M) {
+        Self::send_broadcast(Some(socket.event_handler.process().id()), topic, message);
+    }
+
+    fn send_broadcast(sender: Option<u64>, topic: &str, message: M) {
+        let message = serde_json::to_value(message).expect("failed to serialize message");
+        process().send(PubSubMessage::Broadcast {
+            sender,
+            event: Self::event(),
+            topic: topic.to_string(),
+            message,
+        });
+    }
+
+    /// Returns the name of the event subscribers receive broadcasts as.
+    fn event() -> String {
+        type_name::<Broadcast<M>>().to_string()
+    }
+}
+
+/// Starts the process if it's not already running.
+pub(crate) fn start() {
+    if Process::<PubSubMessage, Json>::lookup(&PUBSUB_PROCESS_ID).is_none() {
+        let process = Process::spawn_link((), pubsub);
+        process.register(&PUBSUB_PROCESS_ID);
+    }
+}
+
+fn process() -> Process<PubSubMessage, Json> {
+    Process::lookup(&PUBSUB_PROCESS_ID).expect("PubSub should be started")
+}
+
+fn pubsub(_: (), mailbox: Mailbox<PubSubMessage, Json>) {
+    let mailbox = mailbox.monitorable();
+    // Subscribers by the id of their event handler, and their ids by topic
+    // key.
+    let mut subscribers: HashMap<u64, Subscriber> = HashMap::new();
+    let mut topics: HashMap<String, HashSet<u64>> = HashMap::new();
+    // Processes of the other nodes, by node id.
+    let mut peers: HashMap<u64, Process<PubSubMessage, Json>> = HashMap::new();
+    let mut next_refresh = Instant::now();
+
+    loop {
+        if Instant::now() >= next_refresh {
+            refresh_peers(&mut peers, mailbox.this());
+            next_refresh = Instant::now() + PEER_REFRESH;
+        }
+        let message =
+            mailbox.receive_timeout(next_refresh.saturating_duration_since(Instant::now()));
+        match message {
+            Ok(MessageSignal::Message(PubSubMessage::Subscribe(socket, event, topic))) => {
+                let event_handler = socket.event_handler.process();
+                let subscriber = subscribers.entry(event_handler.id()).or_insert_with(|| {
+                    mailbox.monitor(event_handler);
+                    Subscriber {
+                        topics: HashSet::new(),
+                        forwarder: Process::spawn(*socket, forward),
+                    }
+                });
+                let key = topic_key(&event, &topic);
+                subscriber.topics.insert(key.clone());
+                topics.entry(key).or_default().insert(event_handler.id());
+            }
+            Ok(MessageSignal::Message(PubSubMessage::Unsubscribe(id, event, topic))) => {
+                let key = topic_key(&event, &topic);
+                if let Some(subscriber) = subscribers.get_mut(&id) {
+                    subscriber.topics.remove(&key);
+                }
+                remove_from_topic(&mut topics, &key, id);
+            }
+            Ok(MessageSignal::Message(PubSubMessage::Broadcast {
+                sender,
+                event,
+                topic,
+                message,
+            })) => {
+                for peer in peers.values() {
+                    peer.send(PubSubMessage::Relay {
+                        event: event.clone(),
+                        topic: topic.clone(),
+                        message: message.clone(),
+                    });
+                }
+                deliver(&subscribers, &topics, sender, event, topic, message);
+            }
+            Ok(MessageSignal::Message(PubSubMessage::Relay {
+                event,
+                topic,
+                message,
+            })) => {
+                deliver(&subscribers, &topics, None, event, topic, message);
+            }
+            Ok(MessageSignal::Message(PubSubMessage::Peer(node, peer))) => {
+                peers.insert(node, peer);
+            }
+            Ok(MessageSignal::Signal(ProcessDiedSignal(id))) => {
+                if let Some(subscriber) = subscribers.remove(&id) {
+                    for key in &subscriber.topics {
+                        remove_from_topic(&mut topics, key, id);
+                    }
+                    subscriber.forwarder.kill();
+                }
+            }
+            Err(MailboxError::TimedOut) => {}
+            Err(_) => continue,
+        }
+    }
+}
+
+/// Returns the key of a topic, as topics of different message types are
+/// separate.
+fn topic_key(event: &str, topic: &str) -> String {
+    format!("{event}/{topic}")
+}
+
+/// Sends a broadcast to the local subscribers of a topic, except `sender`.
+fn deliver(
+    subscribers: &HashMap<u64, Subscriber>,
+    topics: &HashMap<String, HashSet<u64>>,
+    sender: Option<u64>,
+    event: String,
+    topic: String,
+    message: Value,
+) {
+    let ids = topics.get(&topic_key(&event, &topic)).into_iter().flatten();
+    for id in ids.filter(|id| Some(**id) != sender) {
+        if let Some(subscriber) = subscribers.get(id) {
+            subscriber.forwarder.send((
+                event.clone(),
+                Broadcast {
+                    topic: topic.clone(),
+                    message: message.clone(),
+                },
+            ));
+        }
+    }
+}
+
+/// Drops the peers of disconnected nodes, and connects to new nodes.
+fn refresh_peers(
+    peers: &mut HashMap<u64, Process<PubSubMessage, Json>>,
+    this: Process<PubSubMessage, Json>,
+) {
+    let nodes = distributed::nodes();
+    peers.retain(|node, _| nodes.contains(node));
+    let this_node = distributed::node_id();
+    for node in nodes {
+        if node != this_node && !peers.contains_key(&node) {
+            Process::spawn((node, this), connect);
+        }
+    }
+}
+
+/// Connects to the process of another node, in a separate process as spawning
+/// on a node which just left panics.
+fn connect((node, origin): (u64, Process<PubSubMessage, Json>), _: Mailbox<(), Json>) {
+    Process::spawn_node(node, origin, introduce);
+}
+
+/// Introduces the processes of two nodes to each other, running on the node
+/// connected to.
+fn introduce(origin: Process<PubSubMessage, Json>, _: Mailbox<(), Json>) {
+    let Some(local) = Process::<PubSubMessage, Json>::lookup(&PUBSUB_PROCESS_ID) else {
+        // The node isn't serving LiveViews.
+        return;
+    };
+    local.send(PubSubMessage::Peer(origin.node_id(), origin));
+    origin.send(PubSubMessage::Peer(distributed::node_id(), local));
+}
+
+fn remove_from_topic(topics: &mut HashMap<String, HashSet<u64>>, topic: &str, id: u64) {
+    if let Some(ids) = topics.get_mut(topic) {
+        ids.remove(&id);
+        if ids.is_empty() {
+            topics.remove(topic);
+        }
+    }
+}
+
+/// Sends broadcasts to a subscriber in order, without blocking the process
+/// while the LiveView re-renders.
+fn forward(mut socket: Socket, mailbox: Mailbox<(String, Broadcast<Value>), Json>) {
+    loop {
+        let (event, broadcast) = mailbox.receive();
+        let value = serde_json::to_value(broadcast).expect("broadcasts serialize");
+        if socket.send_event_value(event, value).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use super::remove_from_topic;
+
+    #[test]
+    fn empty_topics_are_removed() {
+        let mut topics = HashMap::from([("lobby".to_string(), HashSet::from([1, 2]))]);
+        remove_from_topic(&mut topics, "lobby", 1);
+        assert_eq!(topics["lobby"], HashSet::from([2]));
+        remove_from_topic(&mut topics, "unknown", 2);
+        remove_from_topic(&mut topics, "lobby", 2);
+        assert!(topics.is_empty());
+    }
+}
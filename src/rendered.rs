@@ -8,9 +8,15 @@
 // const TITLE: &str = "t";
 // const TEMPLATES: &str = "p";
 
+mod boundary;
 mod builder;
+mod component;
 mod diff;
 mod dynamic;
+pub(crate) mod intern;
+pub(crate) mod memo;
+mod spawn;
+pub(crate) mod statics;
 mod strip;
 
 use core::fmt;
@@ -19,6 +25,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::{map::Entry, Map, Value};
 
 pub use self::builder::*;
+pub use self::spawn::*;
 use self::{
     dynamic::{Dynamic, DynamicItems, DynamicList, Dynamics},
     strip::Strip,
@@ -61,10 +68,26 @@ impl Rendered {
         builder::RenderedBuilder::new()
     }
 
+    /// Returns a hash of the root's statics, which only change along with the
+    /// template.
+    pub(crate) fn fingerprint(&self) -> String {
+        crate::maud::hash(self.statics.iter().map(String::as_bytes))
+    }
+
+    /// Returns a lower bound of the length of the render serialized as json,
+    /// counting the quoted statics and templates it always contains.
+    pub(crate) fn min_json_len(&self) -> usize {
+        self.statics
+            .iter()
+            .chain(self.templates.iter().flatten())
+            .map(|s| s.len() + 2)
+            .sum()
+    }
+
     /// Diffs self with another [`Rendered`] and returns diff as [`serde_json::Value`].
     pub fn diff(self, other: Rendered) -> Option<Value> {
-        let a = self.into_json();
-        let b = other.into_json();
+        let a = self.into_root_json();
+        let b = other.into_root_json();
         let diff = diff::diff(&a, &b).unwrap_or_default();
         match diff {
             Value::Object(_) => strip::strip(Strip::Nulls.into(), diff),
@@ -153,6 +176,9 @@ fn fmt_dynamic_list_item(
         Dynamic::String(s) => {
             write!(f, "{s}")?;
         }
+        Dynamic::Component(_, rendered) => {
+            write!(f, "{rendered}")?;
+        }
         Dynamic::Nested(n) => {
             let statics = templates.get(n.statics).unwrap();
             for (s, d) in statics.iter().zip(n.dynamics.iter()) {
@@ -284,6 +310,7 @@ where
         match self {
             Dynamic::String(s) => s.into(),
             Dynamic::Nested(n) => n.into_json(),
+            Dynamic::Component(cid, _) => cid.into(),
         }
     }
 }
@@ -0,0 +1,127 @@
+//! Error boundaries around nested renders.
+
+use std::fmt;
+
+use lunatic::function::FuncRef;
+use lunatic::{Mailbox, MessageSignal, Process, Tag};
+use lunatic_log::error;
+use serde::{Deserialize, Serialize};
+
+use super::Rendered;
+
+/// Function rendering a fragment inside an error boundary.
+type BoundaryFn<C, E> = FuncRef<fn(C) -> Result<Rendered, E>>;
+
+/// Result of a render inside an error boundary, with the error as a message.
+type BoundaryResult = Result<Rendered, String>;
+
+impl Rendered {
+    /// Renders in a separate process, replacing the render with `fallback` if
+    /// it returns an error or panics.
+    ///
+    /// A panic while rendering otherwise stops the LiveView's process, closing
+    /// its connection. Errors are logged, and the rest of the LiveView renders
+    /// as usual.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// fn render_feed(posts: Vec<Post>) -> Result<Rendered, FeedError> {
+    ///     // ...
+    /// }
+    ///
+    /// fn render(&self) -> Rendered {
+    ///     let fallback = html! { p.error { "The feed couldn't be shown." } };
+    ///     html! {
+    ///         @(Rendered::boundary(self.posts.clone(), render_feed, fallback))
+    ///     }
+    /// }
+    /// ```
+    pub fn boundary<C, E>(
+        captures: C,
+        render: fn(C) -> Result<Rendered, E>,
+        fallback: Rendered,
+    ) -> Rendered
+    where
+        C: Serialize + for<'de> Deserialize<'de>,
+        E: fmt::Display,
+    {
+        let tag = Tag::new();
+        Process::spawn_link(
+            (
+                captures,
+                FuncRef::new(render),
+                unsafe { Process::<BoundaryResult>::this() },
+                tag,
+            ),
+            boundary::<C, E>,
+        );
+        let mailbox: Mailbox<BoundaryResult> = unsafe { Mailbox::new() };
+        match mailbox.tag_receive(&[tag]) {
+            Ok(rendered) => rendered,
+            Err(err) => {
+                error!("render failed, showing fallback: {err}");
+                fallback
+            }
+        }
+    }
+}
+
+/// Watches the render in an unlinked process, so a panic is reported to the
+/// parent rather than stopping it.
+fn boundary<C, E>(
+    (captures, render, parent, tag): (C, BoundaryFn<C, E>, Process<BoundaryResult>, Tag),
+    mailbox: Mailbox<BoundaryResult>,
+) where
+    C: Serialize + for<'de> Deserialize<'de>,
+    E: fmt::Display,
+{
+    let mailbox = mailbox.monitorable();
+    let worker = Process::spawn((captures, render, mailbox.this()), worker::<C, E>);
+    mailbox.monitor(worker);
+    let result = match mailbox.receive() {
+        MessageSignal::Message(result) => result,
+        MessageSignal::Signal(_) => Err("render panicked".to_string()),
+    };
+    parent.tag_send(tag, result);
+}
+
+fn worker<C, E>(
+    (captures, render, boundary): (C, BoundaryFn<C, E>, Process<BoundaryResult>),
+    _: Mailbox<()>,
+) where
+    C: Serialize + for<'de> Deserialize<'de>,
+    E: fmt::Display,
+{
+    let result = (render.get())(captures).map_err(|err| err.to_string());
+    boundary.send(result);
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::rendered::Rendered;
+    use crate::{self as submillisecond_live_view, html};
+
+    fn render_count(count: i32) -> Result<Rendered, String> {
+        if count < 0 {
+            return Err("negative count".to_string());
+        }
+        if count == 0 {
+            panic!("zero count");
+        }
+        Ok(html! { span { (count) } })
+    }
+
+    #[lunatic::test]
+    fn failed_renders_are_replaced_by_fallback() {
+        let fallback = || html! { span.error { "Unavailable" } };
+        assert_eq!(
+            Rendered::boundary(1, render_count, fallback()),
+            render_count(1).unwrap()
+        );
+        assert_eq!(Rendered::boundary(-1, render_count, fallback()), fallback());
+        assert_eq!(Rendered::boundary(0, render_count, fallback()), fallback());
+    }
+}
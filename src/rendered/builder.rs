@@ -98,6 +98,9 @@ impl RenderedBuilder {
     }
 
     /// Pushes an if frame.
+    ///
+    /// `@match` expressions are built in an if frame too, so switching arms
+    /// only replaces the frame's nested [`Rendered`].
     pub fn push_if_frame(&mut self) {
         self.push_dynamic_node(NodeValue::Items(ItemsNode::default()));
     }
@@ -417,6 +420,7 @@ fn vecs_match<T: PartialEq>(a: &Vec<T>, b: &Vec<T>) -> bool {
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
+    use serde_json::json;
 
     use crate::maud::DOCTYPE;
     use crate::rendered::dynamic::{Dynamic, DynamicItems, DynamicList, Dynamics};
@@ -661,6 +665,75 @@ mod tests {
         );
     }
 
+    #[lunatic::test]
+    fn match_statement() {
+        enum Status {
+            Active,
+            Pending(usize),
+            Closed,
+        }
+
+        let render = |status: Status| {
+            html! {
+                "Status: "
+                @match status {
+                    Status::Active => { "active" }
+                    Status::Pending(count) => { (count) " pending" }
+                    Status::Closed => {}
+                }
+                "."
+            }
+        };
+
+        assert_eq!(
+            render(Status::Active),
+            Rendered {
+                statics: vec!["Status: ".to_string(), ".".to_string()],
+                dynamics: Dynamics::Items(DynamicItems(vec![Dynamic::Nested(Rendered {
+                    statics: vec!["active".to_string()],
+                    dynamics: Dynamics::Items(DynamicItems(vec![])),
+                    templates: vec![],
+                })])),
+                templates: vec![],
+            }
+        );
+
+        assert_eq!(
+            render(Status::Pending(2)),
+            Rendered {
+                statics: vec!["Status: ".to_string(), ".".to_string()],
+                dynamics: Dynamics::Items(DynamicItems(vec![Dynamic::Nested(Rendered {
+                    statics: vec!["".to_string(), " pending".to_string()],
+                    dynamics: Dynamics::Items(DynamicItems(vec![Dynamic::String("2".to_string())])),
+                    templates: vec![],
+                })])),
+                templates: vec![],
+            }
+        );
+
+        assert_eq!(
+            render(Status::Closed),
+            Rendered {
+                statics: vec!["Status: ".to_string(), ".".to_string()],
+                dynamics: Dynamics::Items(DynamicItems(vec![Dynamic::String("".to_string())])),
+                templates: vec![],
+            }
+        );
+
+        assert_eq!(
+            render(Status::Active).diff(render(Status::Pending(2))),
+            Some(json!({ "0": { "s": ["", " pending"], "0": "2" } }))
+        );
+        assert_eq!(
+            render(Status::Pending(2)).diff(render(Status::Pending(3))),
+            Some(json!({ "0": { "0": "3" } }))
+        );
+        assert_eq!(
+            render(Status::Pending(3)).diff(render(Status::Closed)),
+            Some(json!({ "0": "" }))
+        );
+    }
+
     #[lunatic::test]
     fn for_loop_empty() {
         #[allow(clippy::reversed_empty_ranges)]
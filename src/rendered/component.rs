@@ -0,0 +1,213 @@
+//! Components rendered within a [`Rendered`], sent under the `c` key of the
+//! diff protocol.
+
+use serde_json::{Map, Value};
+
+use super::dynamic::{Dynamic, DynamicItems, Dynamics};
+use super::strip::{self, Strip};
+use super::{diff, IntoJson, Rendered, RenderedListItem};
+
+/// Key of the components in a render.
+const COMPONENTS: &str = "c";
+
+/// Walks the components nested in a render.
+trait Components {
+    /// Collects the components nested in a render, including those nested in
+    /// other components.
+    fn components<'a>(&'a self, components: &mut Vec<(u32, &'a Rendered)>);
+
+    /// Returns the render of a nested component.
+    fn component_mut(&mut self, cid: u32) -> Option<&mut Rendered>;
+}
+
+impl Rendered {
+    /// Renders a component by its id, with its render sent separately to the
+    /// client.
+    pub(crate) fn component(cid: u32, rendered: Rendered) -> Rendered {
+        Rendered {
+            statics: vec![String::new(), String::new()],
+            dynamics: Dynamics::Items(DynamicItems(vec![Dynamic::Component(cid, rendered)])),
+            templates: Vec::new(),
+        }
+    }
+
+    /// Converts the render into JSON, with the renders of its components under
+    /// the `c` key.
+    pub(crate) fn into_root_json(self) -> Value {
+        let components = self.components_json();
+        let mut json = self.into_json();
+        if !components.is_empty() {
+            json[COMPONENTS] = Value::Object(components);
+        }
+        json
+    }
+
+    /// Replaces the renders of components, returning the diff of the
+    /// components which changed.
+    ///
+    /// Only the components are diffed, rather than the whole render.
+    /// Components which aren't rendered are ignored.
+    pub(crate) fn diff_components(
+        &mut self,
+        renders: impl IntoIterator<Item = (u32, Rendered)>,
+    ) -> Option<Value> {
+        let old = self.components_json();
+        for (cid, rendered) in renders {
+            if let Some(component) = self.component_mut(cid) {
+                *component = rendered;
+            }
+        }
+        let new = self.components_json();
+        let diff = diff::diff(&Value::Object(old), &Value::Object(new))?;
+        let diff = strip::strip(Strip::Nulls.into(), diff)?;
+        let mut components = Map::new();
+        components.insert(COMPONENTS.to_string(), diff);
+        Some(Value::Object(components))
+    }
+
+    /// Returns whether a component is rendered.
+    pub(crate) fn has_component(&self, cid: u32) -> bool {
+        let mut components = Vec::new();
+        Components::components(self, &mut components);
+        components
+            .iter()
+            .any(|(component_cid, _)| *component_cid == cid)
+    }
+
+    fn components_json(&self) -> Map<String, Value> {
+        let mut components = Vec::new();
+        Components::components(self, &mut components);
+        components
+            .into_iter()
+            .map(|(cid, rendered)| (cid.to_string(), rendered.clone().into_json()))
+            .collect()
+    }
+}
+
+impl Components for Rendered {
+    fn components<'a>(&'a self, components: &mut Vec<(u32, &'a Rendered)>) {
+        self.dynamics.components(components);
+    }
+
+    fn component_mut(&mut self, cid: u32) -> Option<&mut Rendered> {
+        self.dynamics.component_mut(cid)
+    }
+}
+
+impl Components for RenderedListItem {
+    fn components<'a>(&'a self, components: &mut Vec<(u32, &'a Rendered)>) {
+        for dynamics in &self.dynamics {
+            dynamics.components(components);
+        }
+    }
+
+    fn component_mut(&mut self, cid: u32) -> Option<&mut Rendered> {
+        self.dynamics
+            .iter_mut()
+            .find_map(|dynamics| dynamics.component_mut(cid))
+    }
+}
+
+impl<N, L> Components for Dynamics<N, L>
+where
+    N: Components,
+    L: Components,
+{
+    fn components<'a>(&'a self, components: &mut Vec<(u32, &'a Rendered)>) {
+        match self {
+            Dynamics::Items(items) => {
+                for dynamic in items.iter() {
+                    dynamic.components(components);
+                }
+            }
+            Dynamics::List(list) => {
+                for dynamic in list.iter().flatten() {
+                    dynamic.components(components);
+                }
+            }
+        }
+    }
+
+    fn component_mut(&mut self, cid: u32) -> Option<&mut Rendered> {
+        match self {
+            Dynamics::Items(items) => items
+                .iter_mut()
+                .find_map(|dynamic| dynamic.component_mut(cid)),
+            Dynamics::List(list) => list
+                .iter_mut()
+                .flatten()
+                .find_map(|dynamic| dynamic.component_mut(cid)),
+        }
+    }
+}
+
+impl<N> Components for Dynamic<N>
+where
+    N: Components,
+{
+    fn components<'a>(&'a self, components: &mut Vec<(u32, &'a Rendered)>) {
+        match self {
+            Dynamic::String(_) => {}
+            Dynamic::Nested(nested) => nested.components(components),
+            Dynamic::Component(cid, rendered) => {
+                components.push((*cid, rendered));
+                rendered.components(components);
+            }
+        }
+    }
+
+    fn component_mut(&mut self, cid: u32) -> Option<&mut Rendered> {
+        match self {
+            Dynamic::String(_) => None,
+            Dynamic::Nested(nested) => nested.component_mut(cid),
+            Dynamic::Component(component_cid, rendered) => {
+                if *component_cid == cid {
+                    Some(rendered)
+                } else {
+                    rendered.component_mut(cid)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use crate::rendered::Rendered;
+    use crate::{self as submillisecond_live_view, html};
+
+    fn counter(count: i32) -> Rendered {
+        html! { button { (count) } }
+    }
+
+    #[test]
+    fn components_are_sent_separately() {
+        let component = Rendered::component(1, counter(0));
+        let rendered = html! { div { @(component) } };
+        assert_eq!(rendered.to_string(), "<div><button>0</button></div>");
+        assert_eq!(
+            rendered.into_root_json(),
+            json!({
+                "s": ["<div>", "</div>"],
+                "0": { "s": ["", ""], "0": 1 },
+                "c": { "1": { "s": ["<button>", "</button>"], "0": "0" } }
+            })
+        );
+    }
+
+    #[test]
+    fn component_renders_are_diffed_alone() {
+        let component = Rendered::component(1, counter(0));
+        let mut rendered = html! { div { @(component) } };
+        assert_eq!(
+            rendered.diff_components([(1, counter(1))]),
+            Some(json!({ "c": { "1": { "0": "1" } } }))
+        );
+        assert_eq!(rendered.diff_components([(1, counter(1))]), None);
+        assert_eq!(rendered.diff_components([(2, counter(2))]), None);
+        assert_eq!(rendered.to_string(), "<div><button>1</button></div>");
+    }
+}
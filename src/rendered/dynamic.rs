@@ -2,6 +2,8 @@ use std::{fmt, ops};
 
 use serde::{Deserialize, Serialize};
 
+use super::Rendered;
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum Dynamics<N, L> {
     Items(DynamicItems<N>),
@@ -18,6 +20,8 @@ pub(crate) struct DynamicList<L>(pub Vec<Vec<Dynamic<L>>>);
 pub(crate) enum Dynamic<N> {
     String(String),
     Nested(N),
+    /// A component by its id, sent separately in the diff's components.
+    Component(u32, Rendered),
 }
 
 impl<N> ops::Deref for DynamicItems<N> {
@@ -60,6 +64,9 @@ where
             Dynamic::Nested(n) => {
                 write!(f, "{n}")
             }
+            Dynamic::Component(_, rendered) => {
+                write!(f, "{rendered}")
+            }
         }
     }
 }
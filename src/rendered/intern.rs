@@ -0,0 +1,145 @@
+//! String interning within a diff payload.
+//!
+//! Strings repeated within a payload are emitted once in an `"i"` table on the
+//! root object, and each occurrence is replaced with a `{"i": index}`
+//! reference, which is expanded by the client before the diff is applied.
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+const INTERNED: &str = "i";
+
+/// Interns strings repeated within a value, if doing so shrinks the payload.
+pub(crate) fn intern(value: &mut Value) {
+    let Value::Object(map) = value else {
+        return;
+    };
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut order = Vec::new();
+    for value in map.values() {
+        count_strings(value, &mut counts, &mut order);
+    }
+
+    let mut table = Vec::new();
+    let mut indexes = HashMap::new();
+    for s in order {
+        let count = counts[&s];
+        if count > 1 && saves_space(&s, count, table.len()) {
+            indexes.insert(s.clone(), table.len());
+            table.push(Value::String(s));
+        }
+    }
+
+    if table.is_empty() {
+        return;
+    }
+
+    for value in map.values_mut() {
+        replace_strings(value, &indexes);
+    }
+    map.insert(INTERNED.to_string(), Value::Array(table));
+}
+
+fn count_strings(value: &Value, counts: &mut HashMap<String, usize>, order: &mut Vec<String>) {
+    match value {
+        Value::String(s) => {
+            let count = counts.entry(s.clone()).or_insert_with(|| {
+                order.push(s.clone());
+                0
+            });
+            *count += 1;
+        }
+        Value::Array(array) => {
+            for value in array {
+                count_strings(value, counts, order);
+            }
+        }
+        Value::Object(map) => {
+            for value in map.values() {
+                count_strings(value, counts, order);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn replace_strings(value: &mut Value, indexes: &HashMap<String, usize>) {
+    match value {
+        Value::String(s) => {
+            if let Some(index) = indexes.get(s) {
+                *value = reference(*index);
+            }
+        }
+        Value::Array(array) => {
+            for value in array {
+                replace_strings(value, indexes);
+            }
+        }
+        Value::Object(map) => {
+            for value in map.values_mut() {
+                replace_strings(value, indexes);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn reference(index: usize) -> Value {
+    let mut map = Map::new();
+    map.insert(INTERNED.to_string(), index.into());
+    Value::Object(map)
+}
+
+/// Checks if interning a string repeated `count` times is smaller than
+/// sending each occurrence.
+fn saves_space(s: &str, count: usize, index: usize) -> bool {
+    let len = Value::String(s.to_string()).to_string().len();
+    let reference_len = reference(index).to_string().len();
+    // The table entry is followed by a comma.
+    len + 1 + reference_len * count < len * count
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn interns_repeated_strings() {
+        let mut value = json!({
+            "0": {
+                "d": [
+                    ["todo completed", "Buy milk"],
+                    ["todo completed", "Walk dog"],
+                    ["todo completed", "Wash car"],
+                ]
+            }
+        });
+        intern(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "0": {
+                    "d": [
+                        [{ "i": 0 }, "Buy milk"],
+                        [{ "i": 0 }, "Walk dog"],
+                        [{ "i": 0 }, "Wash car"],
+                    ]
+                },
+                "i": ["todo completed"]
+            })
+        );
+    }
+
+    #[test]
+    fn skips_strings_not_worth_interning() {
+        let original = json!({ "0": "a", "1": "a", "2": "unique string" });
+        let mut value = original.clone();
+        intern(&mut value);
+        assert_eq!(value, original);
+    }
+}
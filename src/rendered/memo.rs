@@ -0,0 +1,158 @@
+//! Function components, re-rendered only when their props change.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::mem;
+
+use super::Rendered;
+
+thread_local! {
+    static MEMOS: RefCell<Memos> = RefCell::new(Memos::default());
+}
+
+/// Renders of function components by the address of their function, with the
+/// props they were rendered with.
+type Renders = HashMap<usize, Vec<(Box<dyn Any>, Rendered)>>;
+
+/// Renders of function components, kept for one render of the LiveView.
+#[derive(Default)]
+struct Memos {
+    /// Renders of the last render, not yet used by the current one.
+    previous: Renders,
+    /// Renders used by the current render.
+    current: Renders,
+}
+
+impl Rendered {
+    /// Renders a function component, reusing its last render if the props
+    /// didn't change.
+    ///
+    /// Nesting with `@(render(props))` builds the statics and dynamics of the
+    /// nested render every time the LiveView renders. A function component is
+    /// only rendered again when its props differ from the last render, and
+    /// otherwise reuses the statics and dynamics rendered before.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #[derive(Clone, PartialEq)]
+    /// struct AvatarProps {
+    ///     name: String,
+    ///     url: String,
+    /// }
+    ///
+    /// fn avatar(props: &AvatarProps) -> Rendered {
+    ///     html! {
+    ///         figure.avatar {
+    ///             img src=(props.url) alt=(props.name);
+    ///             figcaption { (props.name) }
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// fn render(&self) -> Rendered {
+    ///     html! {
+    ///         @(Rendered::memo(self.author.clone(), avatar))
+    ///         p { (self.comment) }
+    ///     }
+    /// }
+    /// ```
+    pub fn memo<P>(props: P, render: fn(&P) -> Rendered) -> Rendered
+    where
+        P: Clone + PartialEq + 'static,
+    {
+        let key = render as usize;
+        let memoized = MEMOS.with(|memos| {
+            let mut memos = memos.borrow_mut();
+            let Memos { previous, current } = &mut *memos;
+            if let Some((_, rendered)) = find(current, key, &props) {
+                return Some(rendered.clone());
+            }
+            let renders = previous.get_mut(&key)?;
+            let index = renders
+                .iter()
+                .position(|(memo_props, _)| memo_props.downcast_ref() == Some(&props))?;
+            let (memo_props, rendered) = renders.swap_remove(index);
+            current
+                .entry(key)
+                .or_default()
+                .push((memo_props, rendered.clone()));
+            Some(rendered)
+        });
+        if let Some(rendered) = memoized {
+            return rendered;
+        }
+
+        // The render isn't borrowing the memos, as it can render other function
+        // components.
+        let rendered = render(&props);
+        MEMOS.with(|memos| {
+            memos
+                .borrow_mut()
+                .current
+                .entry(key)
+                .or_default()
+                .push((Box::new(props), rendered.clone()));
+        });
+        rendered
+    }
+}
+
+/// Drops the renders of function components, and the regions ignoring
+/// updates, which weren't used by the LiveView's last render.
+pub(crate) fn end_render() {
+    MEMOS.with(|memos| {
+        let mut memos = memos.borrow_mut();
+        memos.previous = mem::take(&mut memos.current);
+    });
+    crate::ignore::end_render();
+}
+
+fn find<'a, P>(renders: &'a Renders, key: usize, props: &P) -> Option<&'a (Box<dyn Any>, Rendered)>
+where
+    P: PartialEq + 'static,
+{
+    renders
+        .get(&key)?
+        .iter()
+        .find(|(memo_props, _)| memo_props.downcast_ref() == Some(props))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use pretty_assertions::assert_eq;
+
+    use super::end_render;
+    use crate::rendered::Rendered;
+    use crate::{self as submillisecond_live_view, html};
+
+    thread_local! {
+        static RENDERS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn greeting(name: &String) -> Rendered {
+        RENDERS.with(|renders| renders.set(renders.get() + 1));
+        html! { p { "Hello, " (name) } }
+    }
+
+    #[test]
+    fn renders_are_reused_while_props_are_unchanged() {
+        let first = Rendered::memo("Ada".to_string(), greeting);
+        assert_eq!(first, greeting(&"Ada".to_string()));
+        Rendered::memo("Ada".to_string(), greeting);
+        Rendered::memo("Grace".to_string(), greeting);
+        assert_eq!(RENDERS.with(Cell::get), 3);
+
+        end_render();
+        assert_eq!(Rendered::memo("Ada".to_string(), greeting), first);
+        assert_eq!(RENDERS.with(Cell::get), 3);
+
+        // Grace wasn't rendered by the last render, so isn't kept.
+        end_render();
+        Rendered::memo("Grace".to_string(), greeting);
+        assert_eq!(RENDERS.with(Cell::get), 4);
+    }
+}
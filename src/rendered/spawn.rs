@@ -0,0 +1,109 @@
+//! Rendering in parallel lunatic processes.
+
+use lunatic::function::FuncRef;
+use lunatic::{Mailbox, Process, Tag};
+use serde::{Deserialize, Serialize};
+
+use super::Rendered;
+
+/// A [`Rendered`] being rendered in a separate process.
+///
+/// Created with [`Rendered::spawn`]. A render dropped without being joined is
+/// still waited for, so its result isn't left in the LiveView's mailbox.
+#[must_use = "a spawned render does nothing unless joined"]
+#[derive(Debug)]
+pub struct SpawnedRender {
+    /// Tag of the render's result, taken once it's received.
+    tag: Option<Tag>,
+}
+
+impl Rendered {
+    /// Renders in a separate linked process.
+    ///
+    /// Independent nested renders can be spawned up front and joined when
+    /// nesting, so expensive renders run in parallel.
+    ///
+    /// The render doesn't run in the LiveView's process, so it can't use
+    /// anything kept for the LiveView by its process: components and
+    /// [`myself`](crate::myself), [`Rendered::memo`],
+    /// [`ignore_updates`](crate::ignore_updates), the identity checked by
+    /// [`can!`](crate::can), [`feature_enabled`](crate::feature_enabled) and
+    /// the socket's timezone. Read what's needed before spawning and pass
+    /// it in the captures instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// fn render(&self) -> Rendered {
+    ///     let header = Rendered::spawn(self.user.clone(), render_header);
+    ///     let feed = Rendered::spawn(self.posts.clone(), render_feed);
+    ///     html! {
+    ///         @(header.join())
+    ///         @(feed.join())
+    ///     }
+    /// }
+    /// ```
+    pub fn spawn<C>(captures: C, render: fn(C) -> Rendered) -> SpawnedRender
+    where
+        C: Serialize + for<'de> Deserialize<'de>,
+    {
+        let tag = Tag::new();
+        Process::spawn_link(
+            (
+                captures,
+                FuncRef::new(render),
+                unsafe { Process::<Rendered>::this() },
+                tag,
+            ),
+            |(captures, render, parent, tag), _: Mailbox<()>| {
+                parent.tag_send(tag, render(captures));
+            },
+        );
+        SpawnedRender { tag: Some(tag) }
+    }
+}
+
+impl SpawnedRender {
+    /// Waits for the render to complete.
+    pub fn join(mut self) -> Rendered {
+        self.receive().expect("render already received")
+    }
+
+    fn receive(&mut self) -> Option<Rendered> {
+        let tag = self.tag.take()?;
+        let mailbox: Mailbox<Rendered> = unsafe { Mailbox::new() };
+        Some(mailbox.tag_receive(&[tag]))
+    }
+}
+
+impl Drop for SpawnedRender {
+    fn drop(&mut self) {
+        // The result is sent to the LiveView's mailbox, which can't decode it.
+        self.receive();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::rendered::Rendered;
+    use crate::{self as submillisecond_live_view, html};
+
+    fn render_item(name: String) -> Rendered {
+        html! { li { (name) } }
+    }
+
+    #[lunatic::test]
+    fn spawned_renders_match_inline() {
+        let a = Rendered::spawn("a".to_string(), render_item);
+        let b = Rendered::spawn("b".to_string(), render_item);
+        let spawned = html! { ul { @(a.join()) @(b.join()) } };
+
+        let inline = html! {
+            ul { @(render_item("a".to_string())) @(render_item("b".to_string())) }
+        };
+
+        assert_eq!(spawned, inline);
+    }
+}
@@ -0,0 +1,87 @@
+//! Statics of a join reply, which a rejoining client already has.
+//!
+//! A client rejoining a LiveView sends the fingerprint of the statics it was
+//! last joined with. If the statics of the new render are the same, the join
+//! reply is sent without them, and the client restores them from its own
+//! render before applying it.
+
+use serde_json::Value;
+
+use crate::maud::hash;
+
+const STATICS: &str = "s";
+const TEMPLATES: &str = "p";
+
+/// Returns a fingerprint of the statics of a render, and the positions of the
+/// nested renders holding them.
+pub(crate) fn fingerprint(value: &Value) -> String {
+    let shape = shape(value).to_string();
+    hash([shape.as_bytes()])
+}
+
+/// Removes the statics of a render.
+pub(crate) fn strip_statics(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.remove(STATICS);
+            map.remove(TEMPLATES);
+            map.values_mut().for_each(strip_statics);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_statics),
+        _ => {}
+    }
+}
+
+/// Returns the render with its dynamic strings replaced with `null`, keeping
+/// the statics.
+fn shape(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let value = if key == STATICS || key == TEMPLATES {
+                        value.clone()
+                    } else {
+                        shape(value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(shape).collect()),
+        _ => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn fingerprints_ignore_dynamic_strings() {
+        let render = json!({
+            "0": "3",
+            "1": { "0": "Buy milk", "s": ["<li>", "</li>"] },
+            "s": ["<p>", "</p>", ""]
+        });
+        let changed = json!({
+            "0": "4",
+            "1": { "0": "Walk dog", "s": ["<li>", "</li>"] },
+            "s": ["<p>", "</p>", ""]
+        });
+        let nested = json!({
+            "0": "4",
+            "1": "",
+            "s": ["<p>", "</p>", ""]
+        });
+        assert_eq!(fingerprint(&render), fingerprint(&changed));
+        assert_ne!(fingerprint(&render), fingerprint(&nested));
+
+        let mut stripped = render;
+        strip_statics(&mut stripped);
+        assert_eq!(stripped, json!({ "0": "3", "1": { "0": "Buy milk" } }));
+    }
+}
@@ -0,0 +1,156 @@
+//! Sanitization of untrusted HTML.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+
+use maud_live_view::PreEscaped;
+
+thread_local! {
+    static DEFAULT_SANITIZER: RefCell<Option<Sanitizer>> = const { RefCell::new(None) };
+}
+
+/// Sanitizes untrusted HTML, such as user comments or rendered markdown, with
+/// the default [`Sanitizer`] policy.
+///
+/// The result is safe to render as a dynamic value.
+///
+/// # Example
+///
+/// ```
+/// html! {
+///     div.comment { (html_sanitize(&comment.body)) }
+/// }
+/// ```
+pub fn html_sanitize(html: &str) -> PreEscaped<String> {
+    DEFAULT_SANITIZER.with(|sanitizer| {
+        sanitizer
+            .borrow_mut()
+            .get_or_insert_with(Sanitizer::new)
+            .sanitize(html)
+    })
+}
+
+/// A policy of tags, attributes and URL schemes allowed in untrusted HTML.
+///
+/// The default policy allows common formatting tags, and removes scripts,
+/// styles and event handler attributes. LiveView bindings such as `phx-click`
+/// and `data-phx-*` attributes are always removed, even if allowed by the
+/// policy, so user content can't trigger events.
+///
+/// # Example
+///
+/// ```
+/// let sanitizer = Sanitizer::new()
+///     .rm_tags(["img"])
+///     .add_tag_attributes("span", ["title"]);
+///
+/// html! {
+///     div.comment { (sanitizer.sanitize(&comment.body)) }
+/// }
+/// ```
+pub struct Sanitizer {
+    builder: ammonia::Builder<'static>,
+}
+
+impl Sanitizer {
+    /// Creates a sanitizer with the default policy.
+    pub fn new() -> Self {
+        let mut builder = ammonia::Builder::default();
+        builder.attribute_filter(|_, attribute, value| {
+            if is_live_view_binding(attribute) {
+                None
+            } else {
+                Some(Cow::Owned(value.to_string()))
+            }
+        });
+        Sanitizer { builder }
+    }
+
+    /// Allows additional tags.
+    pub fn add_tags(mut self, tags: impl IntoIterator<Item = &'static str>) -> Self {
+        self.builder.add_tags(tags);
+        self
+    }
+
+    /// Removes tags, such as `img`, from the allowed tags.
+    pub fn rm_tags(mut self, tags: impl IntoIterator<Item = &'static str>) -> Self {
+        self.builder.rm_tags(tags);
+        self
+    }
+
+    /// Allows additional attributes on a tag.
+    pub fn add_tag_attributes(
+        mut self,
+        tag: &'static str,
+        attributes: impl IntoIterator<Item = &'static str>,
+    ) -> Self {
+        self.builder.add_tag_attributes(tag, attributes);
+        self
+    }
+
+    /// Allows additional attributes on all tags.
+    pub fn add_generic_attributes(
+        mut self,
+        attributes: impl IntoIterator<Item = &'static str>,
+    ) -> Self {
+        self.builder.add_generic_attributes(attributes);
+        self
+    }
+
+    /// Allows additional URL schemes in links and images.
+    pub fn add_url_schemes(mut self, schemes: impl IntoIterator<Item = &'static str>) -> Self {
+        self.builder.add_url_schemes(schemes);
+        self
+    }
+
+    /// Sets the `rel` attribute added to links.
+    ///
+    /// Defaults to `noopener noreferrer`.
+    pub fn link_rel(mut self, rel: Option<&'static str>) -> Self {
+        self.builder.link_rel(rel);
+        self
+    }
+
+    /// Sanitizes untrusted HTML.
+    pub fn sanitize(&self, html: &str) -> PreEscaped<String> {
+        PreEscaped(self.builder.clean(html).to_string())
+    }
+}
+
+impl Default for Sanitizer {
+    fn default() -> Self {
+        Sanitizer::new()
+    }
+}
+
+fn is_live_view_binding(attribute: &str) -> bool {
+    let attribute = attribute.to_ascii_lowercase();
+    attribute.starts_with("phx-") || attribute.starts_with("data-phx-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{html_sanitize, Sanitizer};
+
+    #[test]
+    fn scripts_and_handlers_are_removed() {
+        let html = html_sanitize(
+            r#"<p onclick="steal()">Hi <b>there</b><script>steal()</script></p><a href="javascript:steal()">x</a>"#,
+        );
+        assert_eq!(
+            html.0,
+            r#"<p>Hi <b>there</b></p><a rel="noopener noreferrer">x</a>"#
+        );
+    }
+
+    #[test]
+    fn live_view_bindings_are_removed() {
+        let sanitizer = Sanitizer::new()
+            .add_generic_attributes(["phx-click", "data-phx-component", "title"])
+            .rm_tags(["b"]);
+        let html = sanitizer.sanitize(
+            r#"<span phx-click="delete" data-phx-component="1" title="hi"><b>x</b></span>"#,
+        );
+        assert_eq!(html.0, r#"<span title="hi">x</span>"#);
+    }
+}
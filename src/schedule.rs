@@ -0,0 +1,278 @@
+//! Schedules for recurring updates, at intervals or cron expressions.
+
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use lunatic::serializer::Json;
+use lunatic::Process;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// How far ahead a cron expression is searched before giving up, for
+/// expressions such as `0 0 31 2 *` which never match.
+const MAX_SEARCH_DAYS: u64 = 366 * 5;
+
+/// When a recurring update runs.
+///
+/// # Example
+///
+/// ```
+/// // Every second.
+/// let schedule = Schedule::every(Duration::from_secs(1));
+/// // At 9:30 UTC on weekdays.
+/// let schedule: Schedule = "30 9 * * 1-5".parse()?;
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Schedule {
+    /// Runs repeatedly, waiting the interval between each run.
+    Every(Duration),
+    /// Runs at the times matching a cron expression, in UTC.
+    Cron(Cron),
+}
+
+/// A parsed cron expression, with minute, hour, day of month, month and day
+/// of week fields.
+///
+/// Fields accept `*`, numbers, ranges such as `1-5`, steps such as `*/15`
+/// and lists such as `0,30`. Days of the week start with 0 as Sunday.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cron {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+/// A running scheduler, started with
+/// [`Store::schedule`](crate::Store::schedule).
+#[derive(Serialize, Deserialize)]
+pub struct Scheduled {
+    pub(crate) process: Process<(), Json>,
+}
+
+/// Error parsing a cron expression.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("invalid cron expression `{expression}`: {reason}")]
+pub struct CronError {
+    expression: String,
+    reason: String,
+}
+
+impl Schedule {
+    /// Creates a schedule running at an interval.
+    pub fn every(interval: Duration) -> Self {
+        Schedule::Every(interval)
+    }
+
+    /// Parses a schedule running at the times of a cron expression.
+    pub fn cron(expression: &str) -> Result<Self, CronError> {
+        expression.parse().map(Schedule::Cron)
+    }
+
+    /// Returns how long to wait from a time until the next run, or `None` if
+    /// it never runs again.
+    pub fn wait_from(&self, now: SystemTime) -> Option<Duration> {
+        match self {
+            Schedule::Every(interval) => Some(*interval),
+            Schedule::Cron(cron) => {
+                let next = cron.next_after(now)?;
+                Some(next.duration_since(now).unwrap_or_default())
+            }
+        }
+    }
+}
+
+impl Scheduled {
+    /// Stops the scheduler.
+    pub fn cancel(self) {
+        self.process.kill();
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = CronError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        Schedule::cron(expression)
+    }
+}
+
+impl Cron {
+    /// Returns the first time after `time` matching the expression.
+    pub fn next_after(&self, time: SystemTime) -> Option<SystemTime> {
+        let secs = time.duration_since(UNIX_EPOCH).ok()?.as_secs();
+        // Start at the beginning of the next minute.
+        let mut minute = secs / 60 + 1;
+        let limit = minute + MAX_SEARCH_DAYS * 24 * 60;
+        while minute < limit {
+            let days = minute / (24 * 60);
+            let (_, month, day) = civil_from_days(days);
+            let weekday = (days + 4) % 7;
+            if !self.matches_day(month, day, weekday) {
+                minute = (days + 1) * 24 * 60;
+                continue;
+            }
+            let hour = minute / 60 % 24;
+            if !matches(self.hours, hour) {
+                minute = (minute / 60 + 1) * 60;
+                continue;
+            }
+            if !matches(self.minutes, minute % 60) {
+                minute += 1;
+                continue;
+            }
+            return Some(UNIX_EPOCH + Duration::from_secs(minute * 60));
+        }
+        None
+    }
+
+    /// Matches days like cron: if both the day of month and day of week are
+    /// restricted, either can match.
+    fn matches_day(&self, month: u64, day: u64, weekday: u64) -> bool {
+        if !matches(self.months, month) {
+            return false;
+        }
+        let day_matches = matches(self.days, day);
+        let weekday_matches = matches(self.weekdays, weekday);
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday_matches,
+            (false, true) => day_matches,
+            (false, false) => day_matches || weekday_matches,
+        }
+    }
+}
+
+impl FromStr for Cron {
+    type Err = CronError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let error = |reason: String| CronError {
+            expression: expression.to_string(),
+            reason,
+        };
+        let fields: Vec<_> = expression.split_whitespace().collect();
+        let [minutes, hours, days, months, weekdays] = fields[..] else {
+            return Err(error(format!("expected 5 fields, found {}", fields.len())));
+        };
+        let mut weekday_set = parse_field(weekdays, 0, 7).map_err(error)?;
+        // Sunday can be written as 7.
+        if matches(weekday_set, 7) {
+            weekday_set |= 1;
+        }
+        Ok(Cron {
+            expression: expression.to_string(),
+            minutes: parse_field(minutes, 0, 59).map_err(error)?,
+            hours: parse_field(hours, 0, 23).map_err(error)?,
+            days: parse_field(days, 1, 31).map_err(error)?,
+            months: parse_field(months, 1, 12).map_err(error)?,
+            weekdays: weekday_set,
+            any_day: days == "*",
+            any_weekday: weekdays == "*",
+        })
+    }
+}
+
+/// Parses a cron field into a bit set of the values it matches.
+fn parse_field(field: &str, min: u64, max: u64) -> Result<u64, String> {
+    let mut set = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u64 = step.parse().map_err(|_| format!("invalid step `{step}`"))?;
+                if step == 0 {
+                    return Err("step must be greater than 0".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            range => match range.split_once('-') {
+                Some((start, end)) => (parse_value(start, min, max)?, parse_value(end, min, max)?),
+                None => {
+                    let value = parse_value(range, min, max)?;
+                    // `5/10` starts at 5 and steps to the end of the range.
+                    (value, if step > 1 { max } else { value })
+                }
+            },
+        };
+        if start > end {
+            return Err(format!("invalid range `{range}`"));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+fn parse_value(value: &str, min: u64, max: u64) -> Result<u64, String> {
+    match value.parse() {
+        Ok(value) if (min..=max).contains(&value) => Ok(value),
+        _ => Err(format!("`{value}` is not between {min} and {max}")),
+    }
+}
+
+fn matches(set: u64, value: u64) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Converts days since the unix epoch to a year, month and day.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    // Howard Hinnant's `civil_from_days`, for dates after the epoch.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{Cron, Schedule};
+
+    // 2023-06-15 12:34:56 UTC, a Thursday.
+    const NOW: u64 = 1_686_832_496;
+
+    fn next(expression: &str) -> Option<u64> {
+        let cron: Cron = expression.parse().unwrap();
+        cron.next_after(UNIX_EPOCH + Duration::from_secs(NOW))
+            .map(|time| time.duration_since(UNIX_EPOCH).unwrap().as_secs())
+    }
+
+    #[test]
+    fn next_cron_times() {
+        // 12:35 the same day.
+        assert_eq!(next("* * * * *"), Some(1_686_832_500));
+        // 12:45 the same day.
+        assert_eq!(next("*/15 * * * *"), Some(1_686_833_100));
+        // 09:30 on Friday 2023-06-16.
+        assert_eq!(next("30 9 * * 1-5"), Some(1_686_907_800));
+        // 00:00 on Sunday 2023-06-18.
+        assert_eq!(next("0 0 * * 7"), Some(1_687_046_400));
+        // 00:00 on 2023-07-01.
+        assert_eq!(next("0 0 1 * *"), Some(1_688_169_600));
+        assert_eq!(next("0 0 31 2 *"), None);
+    }
+
+    #[test]
+    fn invalid_cron_expressions() {
+        assert!(Schedule::cron("* * * *").is_err());
+        assert!(Schedule::cron("60 * * * *").is_err());
+        assert!(Schedule::cron("*/0 * * * *").is_err());
+        assert!(Schedule::cron("5-1 * * * *").is_err());
+    }
+}
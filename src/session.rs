@@ -0,0 +1,164 @@
+//! Sessions of connected LiveViews, and sessions revoked by rotation.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use lunatic::serializer::Json;
+use lunatic::{Mailbox, Process, Tag};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::maud::Session;
+
+const REVOKED_SESSIONS_PROCESS_ID: &str = "9d0e4b7a-3c61-4f2e-8a5d-2b7f1c6e0a93";
+
+/// How long a session can be joined with after it's issued.
+pub(crate) const SESSION_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+thread_local! {
+    /// Session the LiveView in the current process joined with, updated when
+    /// it's rotated.
+    static SESSION: RefCell<Option<Session>> = const { RefCell::new(None) };
+}
+
+/// Process holding the ids of sessions replaced by
+/// [`Socket::rotate_session`](crate::socket::Socket::rotate_session), shared by
+/// all handlers.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(crate) struct RevokedSessions {
+    process: Process<RevokedMessage, Json>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum RevokedMessage {
+    /// Revokes the session with an id, issued at a time.
+    Revoke(String, u64),
+    IsRevoked(Process<bool, Json>, Tag, String),
+}
+
+impl RevokedSessions {
+    /// Starts the process if it's not already running.
+    pub(crate) fn start() {
+        if Self::lookup().is_none() {
+            let process = Process::spawn_link((), revoked_sessions);
+            process.register(&REVOKED_SESSIONS_PROCESS_ID);
+        }
+    }
+
+    pub(crate) fn lookup() -> Option<Self> {
+        Process::lookup(&REVOKED_SESSIONS_PROCESS_ID).map(|process| RevokedSessions { process })
+    }
+
+    /// Revokes a session, so it can no longer be joined with.
+    pub(crate) fn revoke(&self, session: &Session) {
+        self.process.send(RevokedMessage::Revoke(
+            session.id.clone(),
+            session.issued_at,
+        ));
+    }
+
+    /// Returns whether a session has been revoked.
+    pub(crate) fn is_revoked(&self, id: String) -> bool {
+        let tag = Tag::new();
+        self.process.send(RevokedMessage::IsRevoked(
+            unsafe { Process::this() },
+            tag,
+            id,
+        ));
+        let mailbox: Mailbox<bool, Json> = unsafe { Mailbox::new() };
+        mailbox.tag_receive(&[tag])
+    }
+}
+
+/// Returns the claims of the session the LiveView joined with, set when it
+/// was last rotated with
+/// [`Socket::rotate_session`](crate::socket::Socket::rotate_session).
+///
+/// Returns `None` if the session was never rotated, or its claims aren't a
+/// `T`. Claims are only available from within the LiveView's process, such as
+/// in `mount`, event handlers and `render`.
+pub fn session_claims<T>() -> Option<T>
+where
+    T: DeserializeOwned,
+{
+    SESSION.with(|session| {
+        let session = session.borrow();
+        let claims = session.as_ref()?.claims.clone();
+        serde_json::from_value(claims).ok()
+    })
+}
+
+/// Returns the id of the session the LiveView in the current process joined
+/// with, or was last rotated to.
+pub(crate) fn session_id() -> Option<String> {
+    SESSION.with(|session| Some(session.borrow().as_ref()?.id.clone()))
+}
+
+/// Sets the session of the LiveView in the current process, returning the
+/// session it replaces.
+pub(crate) fn set_session(new_session: Session) -> Option<Session> {
+    SESSION.with(|session| session.borrow_mut().replace(new_session))
+}
+
+/// Issues a session with new claims replacing the session of the LiveView in
+/// the current process, which is revoked, returning the new session and its
+/// signed encoding.
+pub(crate) fn rotate(claims: Value) -> (Session, String) {
+    let (session, signed) = Session::issue(claims);
+    if let Some(previous) = set_session(session.clone()) {
+        RevokedSessions::lookup()
+            .expect("RevokedSessions should be started")
+            .revoke(&previous);
+    }
+    (session, signed)
+}
+
+fn revoked_sessions(_: (), mailbox: Mailbox<RevokedMessage, Json>) {
+    // Revoked ids by when their sessions were issued, dropped once the
+    // sessions expire, since expired sessions can't be joined with anyway.
+    let mut revoked: HashMap<String, u64> = HashMap::new();
+    loop {
+        match mailbox.receive() {
+            RevokedMessage::Revoke(id, issued_at) => {
+                revoked.retain(|_, issued_at| !Session::is_expired(*issued_at));
+                revoked.insert(id, issued_at);
+            }
+            RevokedMessage::IsRevoked(parent, tag, id) => {
+                parent.tag_send(tag, revoked.contains_key(&id));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{rotate, session_claims, set_session, RevokedSessions, SESSION_MAX_AGE};
+    use crate::maud::{verify_session, Session};
+
+    #[lunatic::test]
+    fn rotated_sessions_are_revoked() {
+        RevokedSessions::start();
+        let (session, signed) = Session::issue(Value::Null);
+        assert_eq!(verify_session(&signed), Some(session.clone()));
+        set_session(session);
+        assert_eq!(session_claims::<u64>(), None);
+
+        let (rotated, rotated_signed) = rotate(json!(42));
+        assert!(verify_session(&signed).is_none());
+        assert_eq!(verify_session(&rotated_signed), Some(rotated));
+        assert_eq!(session_claims::<u64>(), Some(42));
+    }
+
+    #[test]
+    fn sessions_expire_after_their_max_age() {
+        let (session, _) = Session::issue(Value::Null);
+        assert!(!Session::is_expired(session.issued_at));
+        assert!(Session::is_expired(
+            session.issued_at - SESSION_MAX_AGE.as_secs() - 1
+        ));
+    }
+}
@@ -0,0 +1,269 @@
+use std::any::type_name;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use maud_live_view::Render;
+use serde::de::{self, DeserializeOwned, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::Sha256;
+
+use crate::maud::{secret, secrets};
+
+/// Prefix mixed into the signature, so signed values can't be confused with
+/// other data signed with the app secret.
+const SIGNATURE_CONTEXT: &[u8] = b"submillisecond-live-view:signed-value:";
+
+/// How long a signed value stays valid after it's rendered.
+const MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Granularity of the time a value is signed at, so a value rendered again
+/// has the same token and isn't resent with every diff.
+const ISSUED_AT_GRANULARITY: u64 = 60 * 60;
+
+/// A value signed with the app secret, so it can't be altered by the client.
+///
+/// Values embedded in the DOM with `:name=(value)` can be modified by the
+/// client before being sent back with an event. Wrapping the value in `Signed`
+/// renders it with an HMAC signature, which is verified when the event is
+/// deserialized. Events containing a tampered value fail to deserialize and
+/// are not handled.
+///
+/// The signature covers the name of the type `T`, so a value signed for one
+/// field can't be submitted for a field of another type. Fields sharing a type,
+/// such as a price and a product id, should wrap their values in distinct
+/// newtypes. Signed values expire a day after they're rendered, unless the
+/// LiveView renders them again in the meantime.
+///
+/// The value is not encrypted, and can still be read by the client.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Serialize, Deserialize)]
+/// struct Buy {
+///     product_id: Signed<u64>,
+/// }
+///
+/// html! {
+///     button :product_id=(Signed(product.id)) @click=(Buy) { "Buy" }
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Signed<T>(pub T);
+
+impl<T> Signed<T> {
+    /// Returns the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Signed<T>
+where
+    T: Serialize,
+{
+    /// Encodes the value along with the time it's signed at and its signature.
+    pub fn token(&self) -> String {
+        let payload = serde_json::to_vec(&self.0).expect("failed to serialize signed value");
+        let payload = general_purpose::URL_SAFE_NO_PAD.encode(payload);
+        let issued_at = unix_time() / ISSUED_AT_GRANULARITY * ISSUED_AT_GRANULARITY;
+        let signature = mac::<T>(&secret(), issued_at, payload.as_bytes())
+            .finalize()
+            .into_bytes();
+        let signature = general_purpose::URL_SAFE_NO_PAD.encode(signature);
+        format!("{payload}.{issued_at}.{signature}")
+    }
+}
+
+impl<T> Signed<T>
+where
+    T: DeserializeOwned,
+{
+    /// Decodes a token created with [`Signed::token`], returning `None` if the
+    /// signature is invalid, it was signed for another type, or it has
+    /// expired.
+    pub fn verify(token: &str) -> Option<Self> {
+        Self::verify_max_age(token, MAX_AGE)
+    }
+
+    /// Decodes a token, expiring it after `max_age` instead of a day.
+    pub(crate) fn verify_max_age(token: &str, max_age: Duration) -> Option<Self> {
+        let mut parts = token.splitn(3, '.');
+        let (payload, issued_at, signature) = (parts.next()?, parts.next()?, parts.next()?);
+        let issued_at: u64 = issued_at.parse().ok()?;
+        if unix_time().saturating_sub(issued_at) > max_age.as_secs() {
+            return None;
+        }
+        let signature = general_purpose::URL_SAFE_NO_PAD.decode(signature).ok()?;
+        secrets()
+            .iter()
+            .any(|secret| {
+                mac::<T>(secret, issued_at, payload.as_bytes())
+                    .verify_slice(&signature)
+                    .is_ok()
+            })
+            .then_some(())?;
+        let payload = general_purpose::URL_SAFE_NO_PAD.decode(payload).ok()?;
+        serde_json::from_slice(&payload).ok().map(Signed)
+    }
+}
+
+impl<T> Deref for Signed<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Signed<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> Render for Signed<T>
+where
+    T: Serialize,
+{
+    fn render_to(&self, buffer: &mut String) {
+        // Tokens only contain url-safe base64 and a `.`, so need no escaping.
+        buffer.push_str(&self.token());
+    }
+}
+
+impl<T> Serialize for Signed<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.token())
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Signed<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TokenVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> Visitor<'de> for TokenVisitor<T>
+        where
+            T: DeserializeOwned,
+        {
+            type Value = Signed<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a signed value")
+            }
+
+            fn visit_str<E>(self, token: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Signed::verify(token).ok_or_else(|| E::custom("invalid signed value"))
+            }
+        }
+
+        deserializer.deserialize_str(TokenVisitor(PhantomData))
+    }
+}
+
+fn mac<T>(secret: &[u8], issued_at: u64, payload: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("unable to encode secret");
+    mac.update(SIGNATURE_CONTEXT);
+    mac.update(type_name::<T>().as_bytes());
+    mac.update(b":");
+    mac.update(&issued_at.to_be_bytes());
+    mac.update(payload);
+    mac
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::engine::general_purpose;
+    use base64::Engine;
+    use hmac::Mac;
+    use serde::{Deserialize, Serialize};
+
+    use super::{mac, unix_time, Signed, MAX_AGE};
+    use crate::maud::secret;
+
+    #[derive(Debug, PartialEq, Eq, Deserialize)]
+    struct Buy {
+        product_id: Signed<u64>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct Price(u64);
+
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct ProductId(u64);
+
+    #[test]
+    fn signed_value_round_trips() {
+        let token = Signed(42u64).token();
+        let event: Buy =
+            serde_json::from_value(serde_json::json!({ "product_id": token })).unwrap();
+        assert_eq!(event.product_id, Signed(42));
+    }
+
+    #[test]
+    fn tampered_value_is_rejected() {
+        let token = Signed(42u64).token();
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = format!(
+            "{}.{signature}",
+            Signed(1u64).token().split_once('.').unwrap().0
+        );
+        assert!(Signed::<u64>::verify(&forged).is_none());
+        assert!(
+            serde_json::from_value::<Buy>(serde_json::json!({ "product_id": forged })).is_err()
+        );
+        assert!(Signed::<u64>::verify("42").is_none());
+    }
+
+    #[test]
+    fn values_signed_for_another_type_are_rejected() {
+        let price = Signed(Price(5)).token();
+        assert_eq!(Signed::<Price>::verify(&price), Some(Signed(Price(5))));
+        assert!(Signed::<ProductId>::verify(&price).is_none());
+        assert!(Signed::<u64>::verify(&price).is_none());
+    }
+
+    #[test]
+    fn expired_values_are_rejected() {
+        let token = Signed(42u64).token();
+        let (payload, _) = token.split_once('.').unwrap();
+        let sign = |issued_at: u64| {
+            let signature = mac::<u64>(&secret(), issued_at, payload.as_bytes())
+                .finalize()
+                .into_bytes();
+            let signature = general_purpose::URL_SAFE_NO_PAD.encode(signature);
+            format!("{payload}.{issued_at}.{signature}")
+        };
+        let recent = unix_time() - MAX_AGE.as_secs() + 60;
+        assert_eq!(Signed::<u64>::verify(&sign(recent)), Some(Signed(42)));
+        let expired = unix_time() - MAX_AGE.as_secs() - 1;
+        assert!(Signed::<u64>::verify(&sign(expired)).is_none());
+    }
+}
@@ -1,15 +1,63 @@
 //! WebSocket functionality.
 
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
 use std::convert::{TryFrom, TryInto};
-use std::mem;
+use std::marker::PhantomData;
+use std::time::Duration;
+use std::{io, mem};
 
-use lunatic::{Mailbox, Process};
+use lunatic::serializer::Json;
+use lunatic::{Mailbox, MessageSignal, Process};
+use lunatic_log::{error, warn};
+use percent_encoding::percent_decode_str;
+use serde::de::{self, DeserializeOwned};
+#[cfg(feature = "msgpack")]
+use serde::ser;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use submillisecond::http::Uri;
 use submillisecond::websocket::WebSocketConnection;
 use thiserror::Error;
+use tungstenite::protocol::frame::coding::CloseCode;
+use tungstenite::protocol::CloseFrame;
 
-use crate::event_handler::{EventHandler, EventHandlerError};
+use crate::auth::{self, Identity};
+use crate::download::Download;
+use crate::event_handler::{EventHandler, EventHandlerError, EventHandlerMessage};
+use crate::job::{self, Job, JobProgress};
+use crate::navigation::Navigation;
+use crate::timings::{self, Phase};
+use crate::upload::UPLOAD_TOPIC_PREFIX;
+use crate::{flags, session, LiveView, TerminateReason};
+
+/// Name of the event pushed to the client with a rotated session.
+const SESSION_EVENT: &str = "live_view:session";
+/// Name of the event pushed to the client to show a notification.
+const NOTIFY_EVENT: &str = "live_view:notify";
+/// Name of the event pushed to the client to download a file.
+const DOWNLOAD_EVENT: &str = "live_view:download";
+
+/// Kind of a binary message pushed by the client.
+const BINARY_PUSH: u8 = 0;
+/// First byte of a frame encoded with MessagePack, an array of five items.
+#[cfg(feature = "msgpack")]
+const MSGPACK_FRAME: u8 = 0x95;
+
+/// Name of the cookie holding the browser's timezone, read by dead renders.
+pub(crate) const TIMEZONE_COOKIE: &str = "_live_view_timezone";
+
+thread_local! {
+    /// URI of the LiveView in the current process, updated when patched.
+    static URI: RefCell<Option<Uri>> = const { RefCell::new(None) };
+    /// Timezone reported by the browser of the LiveView in the current process.
+    static TIMEZONE: RefCell<Option<String>> = const { RefCell::new(None) };
+    /// Values assigned to the connection of the LiveView in the current process.
+    static ASSIGNS: RefCell<HashMap<String, Value>> = RefCell::new(HashMap::new());
+    /// Events pushed to the client by the LiveView in the current process,
+    /// sent with its next diff.
+    static PUSHED_EVENTS: RefCell<Vec<Value>> = const { RefCell::new(Vec::new()) };
+}
 
 /// Wrapper around a websocket connection to handle phoenix channels.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -19,6 +67,18 @@ pub struct Socket {
     pub(crate) socket: RawSocket,
 }
 
+/// Sends messages to a LiveView from another process, handled by
+/// [`LiveView::handle_info`].
+///
+/// Created with [`Socket::info_sender`], and can be sent to other processes.
+/// Messages sent after the LiveView stopped are dropped.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct InfoSender<M> {
+    event_handler: Process<EventHandlerMessage, Json>,
+    message: PhantomData<M>,
+}
+
 /// A raw event from the socket.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Event {
@@ -30,6 +90,24 @@ pub struct Event {
     pub ty: String,
     /// Event value.
     pub value: Value,
+    /// Id of the component the event targets, if any, set with `phx-target`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cid: Option<u32>,
+}
+
+/// Level of a notification shown with [`Socket::notify`], used by the client
+/// to style it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyLevel {
+    /// General information.
+    Info,
+    /// An action succeeded.
+    Success,
+    /// Something may need attention.
+    Warning,
+    /// An action failed.
+    Error,
 }
 
 /// Wrapper around a websocket connection to handle phoenix channels.
@@ -38,6 +116,53 @@ pub(crate) struct RawSocket {
     pub(crate) conn: WebSocketConnection,
     pub(crate) ref1: Option<String>,
     pub(crate) topic: String,
+    options: SocketOptions,
+    /// Format of the frames sent to the client, negotiated when joining.
+    serializer: Serializer,
+    /// Process writing to the connection, shared by every handle of it.
+    writer: Process<WriterMessage>,
+}
+
+/// Messages of a connection's writer process.
+#[derive(Debug, Serialize, Deserialize)]
+enum WriterMessage {
+    Frame(Frame),
+    /// Closes the connection with a close code and reason.
+    Close(u16, String),
+    /// Sets the event handler shut down if the client stops reading.
+    Attach(Process<EventHandlerMessage, Json>),
+}
+
+/// A frame encoded in the negotiated wire format.
+#[derive(Debug, Serialize, Deserialize)]
+enum Frame {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+/// Timeouts and limits of the websocket connection.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(crate) struct SocketOptions {
+    /// How long a write can block before the message is left buffered.
+    pub(crate) write_timeout: Option<Duration>,
+    /// Maximum bytes queued or left buffered before the connection is closed.
+    pub(crate) max_buffered_bytes: usize,
+    /// How long to wait for the client to join after connecting.
+    pub(crate) join_timeout: Option<Duration>,
+    /// How long the client can go without sending a message, such as a
+    /// heartbeat, before the connection is closed.
+    pub(crate) heartbeat_timeout: Option<Duration>,
+}
+
+/// Wire format of the frames sent to the client.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Serializer {
+    /// JSON text frames.
+    #[default]
+    Json,
+    /// MessagePack binary frames, with the same structure as JSON frames.
+    #[cfg(feature = "msgpack")]
+    MessagePack,
 }
 
 /// Protocol-reserved events.
@@ -67,6 +192,37 @@ pub(crate) enum ProtocolEvent {
     /// Reply to a message sent by the client.
     #[serde(rename = "phx_reply")]
     Reply,
+    /// The client stored a refreshed auth token.
+    #[serde(rename = "auth_ack")]
+    AuthAck,
+    /// The URL was patched, by the client following a `data-phx-link="patch"`
+    /// link or by the server with [`Socket::push_patch`].
+    #[serde(rename = "live_patch")]
+    LivePatch,
+    /// The server navigated the client to another LiveView with
+    /// [`Socket::push_navigate`]. (Non-receivable)
+    #[serde(rename = "live_redirect")]
+    LiveRedirect,
+    /// The server reloads the client's page, such as after the template
+    /// changed. (Non-receivable)
+    #[serde(rename = "redirect")]
+    Redirect,
+    /// The client requests to upload files.
+    #[serde(rename = "allow_upload")]
+    AllowUpload,
+    /// The client reports the progress of an upload.
+    #[serde(rename = "progress")]
+    Progress,
+    /// A chunk of an uploaded file, sent as a binary message.
+    #[serde(rename = "chunk")]
+    Chunk,
+    /// Components were removed from the page, and will be destroyed unless
+    /// they're added back.
+    #[serde(rename = "cids_will_destroy")]
+    CidsWillDestroy,
+    /// Components removed from the page can be destroyed.
+    #[serde(rename = "cids_destroyed")]
+    CidsDestroyed,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -86,6 +242,9 @@ pub(crate) struct JoinEvent {
     pub session: String,
     #[serde(rename = "static")]
     pub static_token: Option<String>,
+    /// CSRF cookie sent with the websocket upgrade request, set by the server.
+    #[serde(default, rename = "_csrf_cookie")]
+    pub csrf_cookie: Option<String>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -96,6 +255,17 @@ pub(crate) struct JoinEventParams {
     pub mounts: u32,
     #[serde(rename = "_track_static", default)]
     pub track_static: Vec<String>,
+    #[serde(rename = "_auth_token", default)]
+    pub auth_token: Option<String>,
+    #[serde(rename = "_timezone", default)]
+    pub timezone: Option<String>,
+    /// Fingerprint of the statics the client was last joined with, if it's
+    /// rejoining.
+    #[serde(rename = "_statics", default)]
+    pub statics: Option<String>,
+    /// Wire format requested by the client, such as `"msgpack"`.
+    #[serde(rename = "_serializer", default)]
+    pub serializer: Option<String>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -107,6 +277,8 @@ pub(crate) enum Status {
 
 pub(crate) enum SocketMessage {
     Event(Message),
+    /// A message with a binary payload, such as a chunk of an upload.
+    Binary(Message, Vec<u8>),
     Close,
     Ping(Vec<u8>),
     Pong(Vec<u8>),
@@ -127,6 +299,311 @@ struct Response<T> {
 }
 
 impl Socket {
+    /// Returns the identity authenticated with [`TokenAuth`](crate::TokenAuth),
+    /// if the token hasn't expired.
+    ///
+    /// The identity is only available from within the LiveView's process, such
+    /// as in `mount`, event handlers and `render`.
+    pub fn identity(&self) -> Option<Identity> {
+        auth::identity()
+    }
+
+    /// Returns the current URI of the LiveView, including patches made since
+    /// it was mounted.
+    ///
+    /// Like [`Socket::identity`], the URI is only available from within the
+    /// LiveView's process.
+    pub fn uri(&self) -> Option<Uri> {
+        URI.with(|uri| uri.borrow().clone())
+    }
+
+    /// Returns the IANA name of the browser's timezone, such as
+    /// `Europe/Paris`, if it was reported.
+    ///
+    /// The timezone is sent when the live socket joins, and in a cookie for
+    /// later page loads. Like [`Socket::identity`], it's only available from
+    /// within the LiveView's process.
+    pub fn timezone(&self) -> Option<String> {
+        timezone()
+    }
+
+    /// Assigns a value to the connection under `key`, replacing any previous
+    /// value.
+    ///
+    /// Assigns share connection-scoped data, such as the current user or
+    /// locale, between `mount`, event handlers and helpers without storing it
+    /// in the LiveView. Like [`Socket::identity`], they're kept by the
+    /// LiveView's process, and start empty when navigating to another
+    /// LiveView.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// fn mount(_uri: Uri, socket: Option<Socket>) -> Result<Self, MountError> {
+    ///     if let Some(mut socket) = socket {
+    ///         socket.assign("locale", "fr")?;
+    ///     }
+    ///     // ...
+    /// }
+    ///
+    /// let locale: Option<String> = socket.get_assign("locale");
+    /// ```
+    pub fn assign<T>(&mut self, key: &str, value: T) -> Result<(), serde_json::Error>
+    where
+        T: Serialize,
+    {
+        let value = serde_json::to_value(value)?;
+        ASSIGNS.with(|assigns| assigns.borrow_mut().insert(key.to_string(), value));
+        Ok(())
+    }
+
+    /// Returns the value assigned to the connection under `key`, if any and
+    /// if it deserializes to `T`.
+    pub fn get_assign<T>(&self, key: &str) -> Option<T>
+    where
+        T: DeserializeOwned,
+    {
+        let value = ASSIGNS.with(|assigns| assigns.borrow().get(key).cloned())?;
+        serde_json::from_value(value).ok()
+    }
+
+    /// Returns a sender of messages to the LiveView `T`, which must be the
+    /// LiveView of this socket.
+    pub fn info_sender<T>(&self) -> InfoSender<T::Message>
+    where
+        T: LiveView,
+    {
+        InfoSender {
+            event_handler: self.event_handler.process(),
+            message: PhantomData,
+        }
+    }
+
+    /// Returns whether a feature flag is enabled, as with
+    /// [`feature_enabled`](crate::feature_enabled).
+    pub fn feature_enabled(&self, name: &str) -> bool {
+        flags::feature_enabled(name)
+    }
+
+    /// Returns the decoded segments of the current URI's path.
+    ///
+    /// For example, `/posts/my%20post` has the segments `["posts", "my post"]`.
+    pub fn path_segments(&self) -> Vec<String> {
+        self.uri()
+            .map(|uri| {
+                uri.path()
+                    .split('/')
+                    .filter(|segment| !segment.is_empty())
+                    .map(|segment| percent_decode_str(segment).decode_utf8_lossy().into_owned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Deserializes the query string of the current URI.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #[derive(Deserialize)]
+    /// struct Pagination {
+    ///     page: Option<u32>,
+    /// }
+    ///
+    /// let page = socket.query::<Pagination>()?.page.unwrap_or(1);
+    /// ```
+    pub fn query<Q>(&self) -> Result<Q, serde_qs::Error>
+    where
+        Q: DeserializeOwned,
+    {
+        let uri = self.uri();
+        let query = uri.as_ref().and_then(Uri::query).unwrap_or_default();
+        serde_qs::from_str(query)
+    }
+
+    /// Patches the client's URL without remounting, adding a history entry.
+    ///
+    /// The LiveView's
+    /// [`LiveView::handle_params`](crate::LiveView::handle_params)
+    /// is invoked with the new URI, and the client is sent the URL together
+    /// with the re-rendered diff. When called from an event handler, the patch
+    /// is applied once the event has been handled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// impl LiveViewEvent<Search> for Posts {
+    ///     fn handle(state: &mut Self, event: Search) {
+    ///         if let Some(socket) = &state.socket {
+    ///             let query = serde_qs::to_string(&event).unwrap();
+    ///             let _ = socket.push_patch(&format!("/posts?{query}"));
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn push_patch(&self, url: &str) -> Result<(), EventHandlerError> {
+        url.parse::<Uri>()
+            .map_err(|_| EventHandlerError::InvalidUrl)?;
+        self.event_handler.push_patch(url.to_string());
+        Ok(())
+    }
+
+    /// Navigates the client to another LiveView, mounting it over the same
+    /// websocket instead of loading the page.
+    ///
+    /// The current LiveView is stopped, and `L` is mounted with the URL as if
+    /// the page had been loaded, including the mount hooks of its handler. If
+    /// mounting fails, the client loads the page instead. `L` must be served
+    /// by a handler of the application, and the URL must be one of its routes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// impl LiveViewEvent<Open> for Posts {
+    ///     fn handle(state: &mut Self, event: Open) {
+    ///         if let Some(socket) = &state.socket {
+    ///             let _ = socket.push_navigate::<Post>(&format!("/posts/{}", event.id));
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn push_navigate<L>(&self, url: &str) -> Result<(), EventHandlerError>
+    where
+        L: LiveView,
+    {
+        url.parse::<Uri>()
+            .map_err(|_| EventHandlerError::InvalidUrl)?;
+        let navigation = Navigation::to::<L>().ok_or(EventHandlerError::UnknownLiveView)?;
+        self.event_handler.navigate(navigation, url.to_string());
+        Ok(())
+    }
+
+    /// Re-issues the signed session and CSRF token with new claims, and pushes
+    /// them to the client to use when rejoining.
+    ///
+    /// Call this when a user logs in or their privileges change, so a session
+    /// planted by an attacker before the change isn't carried over. The
+    /// previous session is revoked, and the claims are returned by
+    /// [`session_claims`](crate::session_claims) once rejoined. With
+    /// [`CsrfProtection::Cookie`](crate::handler::CsrfProtection::Cookie) the
+    /// CSRF cookie is kept, since rejoins are checked against the cookie
+    /// sent when connecting.
+    ///
+    /// Must be called from within the LiveView's process, such as in an event
+    /// handler.
+    pub fn rotate_session(&mut self, claims: impl Serialize) -> Result<(), EventHandlerError> {
+        let claims = serde_json::to_value(claims).map_err(|_| EventHandlerError::SerializeEvent)?;
+        let (session, signed) = session::rotate(claims);
+        self.push_client_event(
+            SESSION_EVENT,
+            json!({ "session": signed, "csrf_token": session.csrf_token }),
+        )
+    }
+
+    /// Shows a transient notification on the client.
+    ///
+    /// The notification is dismissed after the timeout, or stays until closed
+    /// by the user if there is none. Notifications can be sent from event
+    /// handlers, or from any process holding a clone of the socket.
+    ///
+    /// Notifications are rendered by the client into a `#live-view-toasts`
+    /// element, which is created if the layout doesn't have one, as
+    /// `div.toast.toast-{level}` elements.
+    pub fn notify(
+        &mut self,
+        level: NotifyLevel,
+        message: impl Into<String>,
+        timeout: Option<Duration>,
+    ) -> Result<(), EventHandlerError> {
+        self.push_client_event(
+            NOTIFY_EVENT,
+            json!({
+                "level": level,
+                "message": message.into(),
+                "timeout": timeout.map(|timeout| timeout.as_millis() as u64),
+            }),
+        )
+    }
+
+    /// Makes the client download a file.
+    ///
+    /// The download can hold its content, such as a generated CSV export, or
+    /// link to a URL serving it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// impl LiveViewEvent<Export> for Orders {
+    ///     fn handle(state: &mut Self, _event: Export) {
+    ///         let csv = state.to_csv();
+    ///         if let Some(socket) = &mut state.socket {
+    ///             let _ = socket.download(Download::content("orders.csv", "text/csv", csv));
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn download(&mut self, download: Download) -> Result<(), EventHandlerError> {
+        let payload =
+            serde_json::to_value(download).map_err(|_| EventHandlerError::SerializeEvent)?;
+        self.push_client_event(DOWNLOAD_EVENT, payload)
+    }
+
+    /// Runs a long-running job in a background process, returning its
+    /// progress state.
+    ///
+    /// The job receives the context and a [`JobProgress`] to report progress
+    /// with, which the LiveView receives as [`JobUpdated`] events, followed by
+    /// a final event once the job completes or fails. The job is stopped if
+    /// the LiveView's connection closes.
+    ///
+    /// [`JobUpdated`]: crate::JobUpdated
+    pub fn track_job<C>(&self, context: C, job: fn(C, JobProgress) -> Result<(), String>) -> Job
+    where
+        C: Serialize + DeserializeOwned,
+    {
+        job::track(self.clone(), context, job)
+    }
+
+    /// Pushes an event to the client, such as to scroll, focus or update a
+    /// chart.
+    ///
+    /// The event is received by hooks registered with
+    /// `this.handleEvent(name, callback)`, and dispatched on the window as
+    /// `phx:{name}`. It's sent with the next diff, so hooks see the DOM the
+    /// event handler rendered, or on its own if nothing is rendered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// impl LiveViewEvent<AddPoint> for Chart {
+    ///     fn handle(state: &mut Self, event: AddPoint) {
+    ///         if let Some(socket) = &mut state.socket {
+    ///             let _ = socket.push_event("points", json!({ "points": [event.point] }));
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn push_event<T>(&mut self, name: &str, payload: T) -> Result<(), EventHandlerError>
+    where
+        T: Serialize,
+    {
+        let payload =
+            serde_json::to_value(payload).map_err(|_| EventHandlerError::SerializeEvent)?;
+        self.push_client_event(name, payload)
+    }
+
+    /// Pushes an event handled by the client script, queued until the
+    /// LiveView's next diff.
+    fn push_client_event(&mut self, event: &str, payload: Value) -> Result<(), EventHandlerError> {
+        if lunatic::host::process_id() == self.event_handler.process().id() {
+            queue_client_event(event.to_string(), payload);
+        } else {
+            self.event_handler
+                .push_client_event(event.to_string(), payload);
+        }
+        Ok(())
+    }
+
     /// Sends an event and wait for it to be sent to the socket.
     ///
     /// If you intend on sending an event from an event handler, use
@@ -158,6 +635,16 @@ impl Socket {
         // });
     }
 
+    /// Sends an event already serialized under the type name of its event,
+    /// and wait for it to be sent to the socket.
+    pub(crate) fn send_event_value(
+        &mut self,
+        name: String,
+        value: Value,
+    ) -> Result<(), EventHandlerError> {
+        Self::_send_event_value(name, value, &self.event_handler, &mut self.socket)
+    }
+
     fn _send_event<E>(
         event: E,
         event_handler: &EventHandler,
@@ -167,10 +654,21 @@ impl Socket {
         E: Serialize,
     {
         let value = serde_json::to_value(event).map_err(|_| EventHandlerError::SerializeEvent)?;
+        let name = std::any::type_name::<E>().to_string();
+        Self::_send_event_value(name, value, event_handler, socket)
+    }
+
+    fn _send_event_value(
+        name: String,
+        value: Value,
+        event_handler: &EventHandler,
+        socket: &mut RawSocket,
+    ) -> Result<(), EventHandlerError> {
         let reply = event_handler.handle_event(Event {
-            name: std::any::type_name::<E>().to_string(),
+            name,
             ty: "internal".to_string(),
             value,
+            cid: None,
         })?;
         let msg = match reply {
             Some(reply) => reply,
@@ -182,7 +680,60 @@ impl Socket {
     }
 }
 
+impl Default for SocketOptions {
+    fn default() -> Self {
+        SocketOptions {
+            write_timeout: Some(Duration::from_secs(30)),
+            max_buffered_bytes: 1024 * 1024,
+            join_timeout: Some(Duration::from_secs(30)),
+            // Clients send a heartbeat every 30 seconds.
+            heartbeat_timeout: Some(Duration::from_secs(60)),
+        }
+    }
+}
+
 impl RawSocket {
+    /// Wraps a connection, starting its writer process, which stops once the
+    /// calling process exits.
+    pub fn new(
+        conn: WebSocketConnection,
+        ref1: Option<String>,
+        topic: String,
+        options: SocketOptions,
+    ) -> Self {
+        let connection = unsafe { Process::<()>::this() };
+        let writer = Process::spawn((conn.clone(), options, connection), writer);
+        RawSocket {
+            conn,
+            ref1,
+            topic,
+            options,
+            serializer: Serializer::Json,
+            writer,
+        }
+    }
+
+    /// Returns a socket for a channel joined again on the same connection,
+    /// written by the same writer process.
+    pub(crate) fn rejoin(&self, ref1: Option<String>, topic: String) -> Self {
+        RawSocket {
+            ref1,
+            topic,
+            serializer: Serializer::Json,
+            ..self.clone()
+        }
+    }
+
+    /// Uses the wire format requested by the client when joining, if it's
+    /// supported, and JSON otherwise.
+    pub(crate) fn negotiate_serializer(&mut self, requested: Option<&str>) {
+        self.serializer = match requested {
+            #[cfg(feature = "msgpack")]
+            Some("msgpack") => Serializer::MessagePack,
+            _ => Serializer::Json,
+        };
+    }
+
     // pub fn receive(&mut self) -> Result<SocketMessage, SocketError> {
     //     Self::receive_from_conn(&mut self.conn)
     // }
@@ -196,21 +747,242 @@ impl RawSocket {
     where
         T: Serialize,
     {
-        let protocol_event = serde_json::to_value(event)?;
-        let text = serde_json::to_string(&json!([
-            &self.ref1,
-            &None::<()>,
-            &self.topic,
-            &protocol_event,
-            value,
-        ]))?;
-
-        Ok(self.conn.write_message(tungstenite::Message::Text(text))?)
+        let frame = timings::measure(Phase::Serialize, || {
+            let protocol_event = serde_json::to_value(event)?;
+            self.encode(&json!([
+                &self.ref1,
+                &None::<()>,
+                &self.topic,
+                &protocol_event,
+                value,
+            ]))
+        })?;
+        self.writer.send(WriterMessage::Frame(frame));
+        Ok(())
     }
 
     pub fn send_reply(&mut self, message: &Message) -> Result<(), SocketError> {
-        let text = serde_json::to_string(&message.to_tuple())?;
-        Ok(self.conn.write_message(tungstenite::Message::Text(text))?)
+        let frame = timings::measure(Phase::Serialize, || self.encode(&message.to_tuple()))?;
+        self.writer.send(WriterMessage::Frame(frame));
+        Ok(())
+    }
+
+    /// Closes the connection once the frames sent before are written.
+    pub(crate) fn close(&self, code: CloseCode, reason: &str) {
+        self.writer
+            .send(WriterMessage::Close(code.into(), reason.to_string()));
+    }
+
+    /// Shuts down the event handler if the client stops reading, replacing
+    /// the event handler of the LiveView it navigated from.
+    pub(crate) fn attach(&self, event_handler: Process<EventHandlerMessage, Json>) {
+        self.writer.send(WriterMessage::Attach(event_handler));
+    }
+
+    /// Encodes a frame in the negotiated wire format.
+    fn encode<T>(&self, frame: &T) -> Result<Frame, serde_json::Error>
+    where
+        T: Serialize,
+    {
+        match self.serializer {
+            Serializer::Json => serde_json::to_string(frame).map(Frame::Text),
+            // Encoded from a JSON value, so enums and maps have the same
+            // structure as in JSON frames.
+            #[cfg(feature = "msgpack")]
+            Serializer::MessagePack => rmp_serde::to_vec(&serde_json::to_value(frame)?)
+                .map(Frame::Binary)
+                .map_err(ser::Error::custom),
+        }
+    }
+}
+
+impl Frame {
+    fn len(&self) -> usize {
+        match self {
+            Frame::Text(text) => text.len(),
+            Frame::Binary(bytes) => bytes.len(),
+        }
+    }
+}
+
+impl From<Frame> for tungstenite::Message {
+    fn from(frame: Frame) -> Self {
+        match frame {
+            Frame::Text(text) => tungstenite::Message::Text(text),
+            Frame::Binary(bytes) => tungstenite::Message::Binary(bytes),
+        }
+    }
+}
+
+/// Writes the frames of a connection in the order they're sent, so handles of
+/// the connection in other processes never interleave their writes.
+///
+/// Frames sent while a write blocks are taken from the mailbox before the next
+/// write, and frames of writes which timed out stay buffered until the next
+/// write, both counting towards the buffer limit. A client which doesn't read
+/// them before the limit is reached has its LiveView shut down and its
+/// remaining frames dropped, since a close frame would never be read either.
+fn writer(
+    (mut conn, options, connection): (WebSocketConnection, SocketOptions, Process<()>),
+    mailbox: Mailbox<WriterMessage>,
+) {
+    let mailbox = mailbox.monitorable();
+    // Frames sent by the connection's process are written before it exits.
+    mailbox.monitor(connection);
+    // The timeout applies to every handle of the connection, which only the
+    // writer writes to.
+    conn.get_mut()
+        .set_write_timeout(options.write_timeout)
+        .expect("failed to set write timeout");
+    let mut event_handler: Option<Process<EventHandlerMessage, Json>> = None;
+    // Frames and closes waiting to be written, and the bytes of their frames.
+    let mut pending = VecDeque::new();
+    let mut queued = 0;
+    // Bytes of writes which timed out, left in the connection's buffer.
+    let mut buffered = 0;
+    let mut closed = false;
+    loop {
+        let message = if pending.is_empty() && !closed {
+            Some(mailbox.receive())
+        } else {
+            mailbox.receive_timeout(Duration::ZERO).ok()
+        };
+        match message {
+            Some(MessageSignal::Message(WriterMessage::Frame(frame))) => {
+                queued += frame.len();
+                pending.push_back(WriterMessage::Frame(frame));
+            }
+            Some(MessageSignal::Message(close @ WriterMessage::Close(..))) => {
+                pending.push_back(close);
+            }
+            Some(MessageSignal::Message(WriterMessage::Attach(process))) => {
+                event_handler = Some(process);
+            }
+            Some(MessageSignal::Signal(_)) => closed = true,
+            None => match pending.pop_front() {
+                Some(WriterMessage::Frame(frame)) => {
+                    let len = frame.len();
+                    queued -= len;
+                    match conn.write_message(frame.into()) {
+                        Ok(()) => buffered = 0,
+                        Err(tungstenite::Error::Io(err))
+                            if matches!(
+                                err.kind(),
+                                io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+                            ) =>
+                        {
+                            buffered += len;
+                        }
+                        Err(err) => warn!("write message failed: {err}"),
+                    }
+                }
+                Some(WriterMessage::Close(code, reason)) => {
+                    let frame = CloseFrame {
+                        code: code.into(),
+                        reason: reason.into(),
+                    };
+                    if let Err(err) = conn.close(Some(frame)) {
+                        warn!("close failed: {err}");
+                    }
+                }
+                Some(WriterMessage::Attach(_)) => {}
+                None => return,
+            },
+        }
+        if queued + buffered > options.max_buffered_bytes {
+            error!(
+                "closing connection to slow client with {} bytes buffered",
+                queued + buffered
+            );
+            if let Some(event_handler) = event_handler {
+                event_handler.send(EventHandlerMessage::Shutdown(TerminateReason::Timeout));
+            }
+            return;
+        }
+    }
+}
+
+impl<M> InfoSender<M>
+where
+    M: Serialize,
+{
+    /// Sends a message to the LiveView.
+    pub fn send(&self, msg: M) {
+        match serde_json::to_value(msg) {
+            Ok(msg) => self.event_handler.send(EventHandlerMessage::Info(msg)),
+            Err(err) => error!("failed to serialize message: {err}"),
+        }
+    }
+
+    /// Sends a message to the LiveView after a delay, such as for a timer.
+    pub fn send_after(&self, msg: M, delay: Duration) {
+        match serde_json::to_value(msg) {
+            Ok(msg) => {
+                self.event_handler
+                    .send_after(EventHandlerMessage::Info(msg), delay);
+            }
+            Err(err) => error!("failed to serialize message: {err}"),
+        }
+    }
+}
+
+impl<M> Clone for InfoSender<M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M> Copy for InfoSender<M> {}
+
+/// Sets the URI of the LiveView in the current process.
+pub(crate) fn set_uri(new_uri: Uri) {
+    URI.with(|uri| *uri.borrow_mut() = Some(new_uri));
+}
+
+/// Returns the URI of the LiveView in the current process.
+pub(crate) fn uri() -> Option<Uri> {
+    URI.with(|uri| uri.borrow().clone())
+}
+
+/// Queues an event pushed to the client by the LiveView in the current
+/// process.
+pub(crate) fn queue_client_event(event: String, payload: Value) {
+    PUSHED_EVENTS.with(|events| events.borrow_mut().push(json!([event, payload])));
+}
+
+/// Takes the events pushed to the client since the last diff, if any.
+pub(crate) fn take_pushed_events() -> Option<Value> {
+    let events = PUSHED_EVENTS.with(|events| mem::take(&mut *events.borrow_mut()));
+    (!events.is_empty()).then_some(Value::Array(events))
+}
+
+/// Returns the browser's timezone of the LiveView in the current process.
+pub(crate) fn timezone() -> Option<String> {
+    TIMEZONE.with(|timezone| timezone.borrow().clone())
+}
+
+/// Sets the browser's timezone of the LiveView in the current process.
+pub(crate) fn set_timezone(new_timezone: Option<String>) {
+    TIMEZONE.with(|timezone| *timezone.borrow_mut() = new_timezone);
+}
+
+impl Event {
+    /// Returns whether the event is of the event type with the given type
+    /// name.
+    ///
+    /// Events pushed by client hooks can also be named by the type's name
+    /// alone, without its module path, such as `"ChartClicked"`. Events
+    /// rendered with [`Dispatch`](crate::Dispatch) are named by the type's name
+    /// followed by `#` and the variant.
+    pub fn matches(&self, type_name: &str) -> bool {
+        match self.name.strip_prefix(type_name) {
+            Some("") => return true,
+            Some(variant) if variant.starts_with('#') => return true,
+            _ => {}
+        }
+        self.ty == "hook"
+            && !self.name.contains("::")
+            && type_name.rsplit("::").next() == Some(self.name.as_str())
     }
 }
 
@@ -228,18 +1000,38 @@ impl Message {
         self
     }
 
-    // pub fn reply_err<T>(&mut self, response: T) -> &mut Self
-    // where
-    //     T: Serialize,
-    // {
-    //     self.event = ProtocolEvent::Reply;
-    //     self.payload = serde_json::to_value(Response {
-    //         status: Status::Error,
-    //         response,
-    //     })
-    //     .unwrap();
-    //     self
-    // }
+    pub fn reply_err<T>(&mut self, response: T) -> &mut Self
+    where
+        T: Serialize,
+    {
+        self.event = ProtocolEvent::Reply;
+        self.payload = serde_json::to_value(Response {
+            status: Status::Error,
+            response,
+        })
+        .unwrap();
+        self
+    }
+
+    /// Returns true if the client does not expect a reply to this message.
+    ///
+    /// This is set for events bound on elements with the `phx-no-reply`
+    /// attribute.
+    pub fn is_no_reply(&self) -> bool {
+        self.payload
+            .get("no_reply")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    /// Returns whether the message was sent on the channel of an upload.
+    pub fn is_upload(&self) -> bool {
+        self.topic.starts_with(UPLOAD_TOPIC_PREFIX)
+    }
+
+    pub fn optimistic_ref(&self) -> Option<u64> {
+        self.payload.get("optimistic_ref").and_then(Value::as_u64)
+    }
 
     pub fn take_event(&mut self) -> Result<Event, serde_json::Error> {
         serde_json::from_value(mem::take(&mut self.payload))
@@ -286,6 +1078,28 @@ impl Message {
     }
 }
 
+/// Decodes a binary message pushed by the client, with a header holding the
+/// lengths of its refs, topic and event, followed by the payload.
+fn decode_binary_push(bytes: &[u8]) -> Option<SocketMessage> {
+    let header = bytes.get(1..5)?;
+    let mut offset = 5;
+    let mut fields = Vec::with_capacity(4);
+    for len in header {
+        let field = bytes.get(offset..offset + *len as usize)?;
+        fields.push(String::from_utf8(field.to_vec()).ok()?);
+        offset += *len as usize;
+    }
+    let [ref1, ref2, topic, event]: [String; 4] = fields.try_into().ok()?;
+    let message = Message {
+        ref1: Some(ref1),
+        ref2: Some(ref2),
+        topic,
+        event: serde_json::from_value(Value::String(event)).ok()?,
+        payload: Value::Null,
+    };
+    Some(SocketMessage::Binary(message, bytes[offset..].to_vec()))
+}
+
 impl JoinEvent {
     pub fn url(&self) -> Option<&String> {
         self.url.as_ref().or(self.redirect.as_ref())
@@ -301,6 +1115,19 @@ impl TryFrom<tungstenite::Message> for SocketMessage {
                 let items = serde_json::from_str(&text)?;
                 Ok(SocketMessage::Event(Message::from_tuple(items)))
             }
+            tungstenite::Message::Binary(bytes) if bytes.first() == Some(&BINARY_PUSH) => {
+                decode_binary_push(&bytes).ok_or_else(|| {
+                    SocketError::DeserializeError(de::Error::custom("invalid binary message"))
+                })
+            }
+            #[cfg(feature = "msgpack")]
+            tungstenite::Message::Binary(bytes) if bytes.first() == Some(&MSGPACK_FRAME) => {
+                let items: Value = rmp_serde::from_slice(&bytes)
+                    .map_err(<serde_json::Error as de::Error>::custom)?;
+                Ok(SocketMessage::Event(Message::from_tuple(
+                    serde_json::from_value(items)?,
+                )))
+            }
             tungstenite::Message::Binary(bytes) => {
                 let items = serde_json::from_slice(&bytes)?;
                 Ok(SocketMessage::Event(Message::from_tuple(items)))
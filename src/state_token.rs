@@ -0,0 +1,219 @@
+//! Encrypted snapshots of LiveView state.
+//!
+//! Snapshots returned by [`LiveView::snapshot`](crate::LiveView::snapshot) are
+//! encrypted into the page's `data-phx-static` attribute, and sent back by the
+//! client when joining. This lets a reconnect to any node restore the state
+//! without a shared store.
+//!
+//! Tokens also hold a fingerprint of the LiveView's templates, so a reconnect
+//! after deploying changed templates remounts from scratch instead of restoring
+//! state the new templates may not expect. They're bound to the session and
+//! LiveView they were issued for, so a token can't be replayed by another
+//! session or restored into another LiveView.
+
+use std::any::type_name;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose;
+use base64::Engine;
+use lunatic_log::{info, warn};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::maud::{secret, secrets};
+
+/// Context mixed into the encryption key, so it differs from keys used to sign
+/// other data with the app secret.
+const KEY_CONTEXT: &[u8] = b"submillisecond-live-view:state-token:";
+
+const NONCE_LEN: usize = 12;
+
+/// Limits on state tokens.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(crate) struct StateTokenOptions {
+    /// How long a token can be used to restore state after being issued.
+    pub(crate) max_age: Duration,
+    /// Maximum length of an encoded token. Larger snapshots are not sent.
+    pub(crate) max_len: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Claims {
+    exp: u64,
+    state: Value,
+    #[serde(default)]
+    fingerprint: String,
+    /// Id of the session the token was issued to.
+    #[serde(default)]
+    session: String,
+    /// Type name of the LiveView the snapshot was taken of.
+    #[serde(default)]
+    live_view: String,
+}
+
+impl Default for StateTokenOptions {
+    fn default() -> Self {
+        StateTokenOptions {
+            max_age: Duration::from_secs(60 * 60),
+            max_len: 4096,
+        }
+    }
+}
+
+impl StateTokenOptions {
+    /// Encrypts a snapshot of the LiveView `T` for a session, returning `None`
+    /// if the token would exceed the maximum length.
+    pub(crate) fn encrypt<T>(
+        &self,
+        snapshot: Value,
+        fingerprint: &str,
+        session: &str,
+    ) -> Option<String> {
+        let claims = Claims {
+            exp: unix_time() + self.max_age.as_secs(),
+            state: snapshot,
+            fingerprint: fingerprint.to_string(),
+            session: session.to_string(),
+            live_view: type_name::<T>().to_string(),
+        };
+        let plaintext = serde_json::to_vec(&claims).ok()?;
+
+        let mut nonce = [0; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = cipher(&secret())
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+            .ok()?;
+
+        let token = general_purpose::URL_SAFE_NO_PAD.encode([&nonce[..], &ciphertext].concat());
+        if token.len() > self.max_len {
+            warn!(
+                "state snapshot of {} bytes exceeds the limit of {} bytes and will not be restored",
+                token.len(),
+                self.max_len
+            );
+            return None;
+        }
+
+        Some(token)
+    }
+
+    /// Decrypts a token of the LiveView `T`, returning `None` if it is invalid
+    /// or expired, or was issued to another session, LiveView or templates
+    /// with another fingerprint.
+    pub(crate) fn decrypt<T>(
+        &self,
+        token: &str,
+        fingerprint: &str,
+        session: &str,
+    ) -> Option<Value> {
+        if token.len() > self.max_len {
+            return None;
+        }
+
+        let bytes = general_purpose::URL_SAFE_NO_PAD.decode(token).ok()?;
+        if bytes.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let plaintext = secrets().iter().find_map(|secret| {
+            cipher(secret)
+                .decrypt(Nonce::from_slice(nonce), ciphertext)
+                .ok()
+        })?;
+
+        let claims: Claims = serde_json::from_slice(&plaintext).ok()?;
+        if claims.exp <= unix_time() {
+            return None;
+        }
+        if claims.session != session || claims.live_view != type_name::<T>() {
+            warn!("state token issued to another session or LiveView");
+            return None;
+        }
+        if claims.fingerprint != fingerprint {
+            info!("templates changed since the state snapshot was taken, remounting");
+            return None;
+        }
+        Some(claims.state)
+    }
+}
+
+fn cipher(secret: &[u8]) -> Aes256Gcm {
+    let key = Sha256::new()
+        .chain_update(KEY_CONTEXT)
+        .chain_update(secret)
+        .finalize();
+    Aes256Gcm::new(&key)
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::StateTokenOptions;
+
+    struct Counter;
+
+    struct Other;
+
+    #[test]
+    fn state_token_round_trips() {
+        let options = StateTokenOptions::default();
+        let token = options
+            .encrypt::<Counter>(json!({ "count": 3 }), "v1", "session")
+            .unwrap();
+        assert_eq!(
+            options.decrypt::<Counter>(&token, "v1", "session"),
+            Some(json!({ "count": 3 }))
+        );
+        assert!(!token.contains("count"));
+        assert_eq!(options.decrypt::<Counter>(&token, "v2", "session"), None);
+        assert_eq!(options.decrypt::<Counter>(&token, "v1", "other"), None);
+        assert_eq!(options.decrypt::<Other>(&token, "v1", "session"), None);
+    }
+
+    #[test]
+    fn invalid_state_tokens_are_rejected() {
+        let options = StateTokenOptions::default();
+        let token = options
+            .encrypt::<Counter>(json!({ "count": 3 }), "v1", "session")
+            .unwrap();
+        let mut tampered = token.into_bytes();
+        let last = tampered.len() - 1;
+        tampered[last] = if tampered[last] == b'A' { b'B' } else { b'A' };
+        assert_eq!(
+            options.decrypt::<Counter>(&String::from_utf8(tampered).unwrap(), "v1", "session"),
+            None
+        );
+
+        let expired = StateTokenOptions {
+            max_age: Duration::ZERO,
+            ..options
+        };
+        let token = expired
+            .encrypt::<Counter>(json!({ "count": 3 }), "v1", "session")
+            .unwrap();
+        assert_eq!(expired.decrypt::<Counter>(&token, "v1", "session"), None);
+
+        let small = StateTokenOptions {
+            max_len: 8,
+            ..options
+        };
+        assert_eq!(
+            small.encrypt::<Counter>(json!({ "count": 3 }), "v1", "session"),
+            None
+        );
+    }
+}
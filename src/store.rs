@@ -0,0 +1,286 @@
+//! Shared state which re-renders subscribed LiveViews when changed.
+
+use std::any::type_name;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
+
+use lunatic::function::FuncRef;
+use lunatic::serializer::Json;
+use lunatic::{Mailbox, MessageSignal, Process, ProcessDiedSignal, Tag};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::{Schedule, Scheduled};
+use crate::socket::Socket;
+
+const STORE_PROCESS_ID: &str = "4a7e1c93-0f2d-4b6a-9e85-c3d1f7a2b049";
+
+/// Shared state held by a named process, which re-renders subscribed LiveViews
+/// when any process changes it.
+///
+/// Each state type has a single store, started once with [`Store::start`].
+/// LiveViews subscribe with [`Store::subscribe`], typically in
+/// [`LiveView::after_join`](crate::LiveView::after_join), and receive a
+/// [`StoreChanged`] event with the new value after each change.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct Count(i32);
+///
+/// impl LiveView for Counter {
+///     type Events = (Increment, StoreChanged<Count>);
+///
+///     fn mount(_uri: Uri, _socket: Option<Socket>) -> Result<Self, MountError> {
+///         Ok(Counter {
+///             count: Store::<Count>::get().value(),
+///         })
+///     }
+///
+///     fn after_join(&mut self, socket: &mut Socket) {
+///         Store::<Count>::get().subscribe(socket);
+///     }
+///     // ...
+/// }
+///
+/// impl LiveViewEvent<Increment> for Counter {
+///     fn handle(_state: &mut Self, _event: Increment) {
+///         Store::<Count>::get().update(|Count(count)| Count(count + 1));
+///     }
+/// }
+///
+/// impl LiveViewEvent<StoreChanged<Count>> for Counter {
+///     fn handle(state: &mut Self, event: StoreChanged<Count>) {
+///         state.count = event.value;
+///     }
+/// }
+/// ```
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Store<T> {
+    process: Process<StoreMessage<T>, Json>,
+    phantom: PhantomData<T>,
+}
+
+/// Event sent to subscribed LiveViews when a [`Store`] changes.
+///
+/// LiveViews handle it like any other event, by including it in
+/// [`LiveView::Events`](crate::LiveView::Events).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoreChanged<T> {
+    /// The new value of the store.
+    pub value: T,
+}
+
+/// Function applied to the value by the store's process.
+type Update<T> = FuncRef<fn(T) -> T>;
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "T: Serialize + DeserializeOwned")]
+enum StoreMessage<T> {
+    Value(Process<T, Json>, Tag),
+    Set(T),
+    Update(Update<T>),
+    Subscribe(Box<Socket>),
+    Watch(Process<T, Json>),
+}
+
+impl<T> Store<T>
+where
+    T: Clone + Serialize + DeserializeOwned,
+{
+    /// Starts the store with its initial value, replacing a store of the same
+    /// type if one is already running.
+    ///
+    /// The store is linked to the current process.
+    pub fn start(value: T) -> Self {
+        let process = Process::spawn_link(value, store::<T>);
+        process.register(&Self::process_name());
+        Store {
+            process,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the running store of the state type, if any.
+    pub fn lookup() -> Option<Self> {
+        Process::lookup(&Self::process_name()).map(|process| Store {
+            process,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns the running store of the state type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the store hasn't been started.
+    pub fn get() -> Self {
+        Self::lookup().unwrap_or_else(|| {
+            panic!(
+                "Store<{}> should be started with Store::start",
+                type_name::<T>()
+            )
+        })
+    }
+
+    /// Returns the current value of the store.
+    pub fn value(&self) -> T {
+        let tag = Tag::new();
+        self.process
+            .send(StoreMessage::Value(unsafe { Process::this() }, tag));
+        let mailbox: Mailbox<T, Json> = unsafe { Mailbox::new() };
+        mailbox.tag_receive(&[tag])
+    }
+
+    /// Replaces the value of the store, notifying subscribers.
+    pub fn set(&self, value: T) {
+        self.process.send(StoreMessage::Set(value));
+    }
+
+    /// Updates the value of the store, notifying subscribers.
+    ///
+    /// The update is applied by the store's process, so concurrent updates
+    /// aren't lost.
+    pub fn update(&self, update: fn(T) -> T) {
+        self.process
+            .send(StoreMessage::Update(FuncRef::new(update)));
+    }
+
+    /// Subscribes a LiveView to changes, until its connection closes.
+    pub fn subscribe(&self, socket: &Socket) {
+        self.process
+            .send(StoreMessage::Subscribe(Box::new(socket.clone())));
+    }
+
+    /// Sends changes to a process, until it stops.
+    pub(crate) fn watch(&self, process: Process<T, Json>) {
+        self.process.send(StoreMessage::Watch(process));
+    }
+
+    /// Updates the value on a schedule, notifying subscribers.
+    ///
+    /// A single scheduler updates every subscribed LiveView, such as a clock
+    /// or dashboard, rather than each connection running its own timer. The
+    /// scheduler is linked to the current process.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let store = Store::start(Now(SystemTime::now()));
+    /// store.schedule(Schedule::every(Duration::from_secs(1)), |_| Now(SystemTime::now()));
+    /// ```
+    pub fn schedule(&self, schedule: Schedule, update: fn(T) -> T) -> Scheduled {
+        let process = Process::spawn_link((schedule, *self, FuncRef::new(update)), scheduler::<T>);
+        Scheduled { process }
+    }
+
+    fn process_name() -> String {
+        format!("{STORE_PROCESS_ID}-{}", type_name::<T>())
+    }
+}
+
+impl<T> Clone for Store<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Store<T> {}
+
+fn store<T>(mut value: T, mailbox: Mailbox<StoreMessage<T>, Json>)
+where
+    T: Clone + Serialize + DeserializeOwned,
+{
+    let mailbox = mailbox.monitorable();
+    // Forwarding processes of subscribers, by the id of their event handler,
+    // and watching processes by their own id.
+    let mut subscribers: HashMap<u64, Process<T, Json>> = HashMap::new();
+
+    loop {
+        match mailbox.receive() {
+            MessageSignal::Message(StoreMessage::Value(parent, tag)) => {
+                parent.tag_send(tag, value.clone());
+            }
+            MessageSignal::Message(StoreMessage::Set(new_value)) => {
+                value = new_value;
+                notify(&subscribers, &value);
+            }
+            MessageSignal::Message(StoreMessage::Update(update)) => {
+                value = (update.get())(value);
+                notify(&subscribers, &value);
+            }
+            MessageSignal::Message(StoreMessage::Subscribe(socket)) => {
+                let event_handler = socket.event_handler.process();
+                subscribers.entry(event_handler.id()).or_insert_with(|| {
+                    mailbox.monitor(event_handler);
+                    Process::spawn(*socket, forward::<T>)
+                });
+            }
+            MessageSignal::Message(StoreMessage::Watch(process)) => {
+                subscribers.entry(process.id()).or_insert_with(|| {
+                    mailbox.monitor(process);
+                    process
+                });
+            }
+            MessageSignal::Signal(ProcessDiedSignal(id)) => {
+                if let Some(forwarder) = subscribers.remove(&id) {
+                    forwarder.kill();
+                }
+            }
+        }
+    }
+}
+
+fn notify<T>(subscribers: &HashMap<u64, Process<T, Json>>, value: &T)
+where
+    T: Clone + Serialize + DeserializeOwned,
+{
+    for forwarder in subscribers.values() {
+        forwarder.send(value.clone());
+    }
+}
+
+fn scheduler<T>((schedule, store, update): (Schedule, Store<T>, Update<T>), _: Mailbox<(), Json>)
+where
+    T: Clone + Serialize + DeserializeOwned,
+{
+    while let Some(wait) = schedule.wait_from(SystemTime::now()) {
+        lunatic::sleep(wait);
+        store.process.send(StoreMessage::Update(update));
+    }
+}
+
+/// Sends changes to a subscriber in order, without blocking the store while
+/// the LiveView re-renders.
+fn forward<T>(mut socket: Socket, mailbox: Mailbox<T, Json>)
+where
+    T: Serialize + DeserializeOwned,
+{
+    loop {
+        let mut value = mailbox.receive();
+        // Skip values replaced while the LiveView was busy.
+        while let Ok(newer) = mailbox.receive_timeout(Duration::ZERO) {
+            value = newer;
+        }
+        if socket.send_event(StoreChanged { value }).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Store;
+
+    #[lunatic::test]
+    fn updates_are_applied_in_order() {
+        let store = Store::start(1u32);
+        store.update(|count| count * 10);
+        store.set(3);
+        store.update(|count| count + 1);
+        assert_eq!(Store::<u32>::get().value(), 4);
+    }
+}
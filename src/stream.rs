@@ -0,0 +1,149 @@
+//! Large collections sent to the client without being kept by the LiveView.
+
+use std::cell::RefCell;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A collection whose items are sent to the client once, and inserted,
+/// updated or deleted there by their DOM id.
+///
+/// Lists kept in a LiveView are held in memory and diffed on every render,
+/// and the whole list is sent again whenever it changes. A stream only holds
+/// the items inserted or deleted since the last render, which are taken when
+/// rendering, so collections of thousands of items cost nothing once sent.
+///
+/// Items are rendered with the DOM id returned by [`Stream::take`], in a
+/// container with `phx-update="append"` or `phx-update="prepend"`, where the
+/// client adds new items and updates existing ones with the same id. Deleted
+/// items are removed by the client from the ids in the container's
+/// `data-stream-deleted` attribute, which must be rendered before the items.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct Inbox {
+///     messages: Stream<Message>,
+/// }
+///
+/// impl LiveView for Inbox {
+///     fn mount(_uri: Uri, _socket: Option<Socket>) -> Self {
+///         let mut messages = Stream::new("messages");
+///         for message in load_messages() {
+///             messages.insert(message.id, message);
+///         }
+///         Inbox { messages }
+///     }
+///
+///     fn render(&self) -> Rendered {
+///         html! {
+///             ul #messages phx-update="append" data-stream-deleted=(self.messages.deleted()) {
+///                 @for (id, message) in self.messages.take() {
+///                     li id=(id) { (message.text) }
+///                 }
+///             }
+///         }
+///     }
+///     // ...
+/// }
+///
+/// impl LiveViewEvent<Archive> for Inbox {
+///     fn handle(state: &mut Self, event: Archive) {
+///         state.messages.delete(event.id);
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Stream<T> {
+    name: String,
+    pending: RefCell<Pending<T>>,
+}
+
+/// Changes to a stream since the last render.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Pending<T> {
+    /// Items inserted or updated, by DOM id.
+    inserts: Vec<(String, T)>,
+    /// DOM ids of deleted items.
+    deletes: Vec<String>,
+}
+
+impl<T> Stream<T> {
+    /// Creates an empty stream, whose DOM ids are prefixed with `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Stream {
+            name: name.into(),
+            pending: RefCell::new(Pending {
+                inserts: Vec::new(),
+                deletes: Vec::new(),
+            }),
+        }
+    }
+
+    /// Returns the DOM id of an item, as `{name}-{id}`.
+    pub fn dom_id(&self, id: impl fmt::Display) -> String {
+        format!("{}-{id}", self.name)
+    }
+
+    /// Inserts an item, or updates it if the client already shows an item
+    /// with the same id.
+    pub fn insert(&mut self, id: impl fmt::Display, item: T) {
+        let dom_id = self.dom_id(id);
+        let pending = self.pending.get_mut();
+        pending.deletes.retain(|deleted| *deleted != dom_id);
+        match pending.inserts.iter_mut().find(|(id, _)| *id == dom_id) {
+            Some((_, inserted)) => *inserted = item,
+            None => pending.inserts.push((dom_id, item)),
+        }
+    }
+
+    /// Deletes an item from the client.
+    pub fn delete(&mut self, id: impl fmt::Display) {
+        let dom_id = self.dom_id(id);
+        let pending = self.pending.get_mut();
+        pending.inserts.retain(|(inserted, _)| *inserted != dom_id);
+        if !pending.deletes.contains(&dom_id) {
+            pending.deletes.push(dom_id);
+        }
+    }
+
+    /// Returns the DOM ids of the items deleted since the last render,
+    /// separated by spaces, for the container's `data-stream-deleted`
+    /// attribute.
+    pub fn deleted(&self) -> String {
+        self.pending.borrow().deletes.join(" ")
+    }
+
+    /// Takes the items inserted since the last render with their DOM ids, to
+    /// render them, clearing the changes of the stream.
+    pub fn take(&self) -> Vec<(String, T)> {
+        let mut pending = self.pending.borrow_mut();
+        pending.deletes.clear();
+        std::mem::take(&mut pending.inserts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stream;
+
+    #[test]
+    fn changes_are_cleared_once_rendered() {
+        let mut stream = Stream::new("messages");
+        stream.insert(1, "hello");
+        stream.insert(2, "world");
+        stream.insert(1, "hi");
+        stream.delete(2);
+        stream.delete(3);
+        assert_eq!(stream.deleted(), "messages-2 messages-3");
+        assert_eq!(stream.take(), [("messages-1".to_string(), "hi")]);
+        assert_eq!(stream.deleted(), "");
+        assert!(stream.take().is_empty());
+
+        stream.delete(1);
+        stream.insert(1, "back");
+        assert_eq!(stream.deleted(), "");
+        assert_eq!(stream.take(), [("messages-1".to_string(), "back")]);
+    }
+}
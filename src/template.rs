@@ -1,16 +1,29 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+#[cfg(debug_assertions)]
+use std::time::Duration;
 use std::{fs, io};
 
-use hmac::{Hmac, Mac};
-use jwt::SignWithKey;
-use lunatic::abstract_process;
 use lunatic::ap::{AbstractProcess, Config, ProcessRef};
+use lunatic::serializer::Json;
+#[cfg(debug_assertions)]
+use lunatic::Mailbox;
+use lunatic::{abstract_process, Process};
+#[cfg(debug_assertions)]
+use lunatic_log::warn;
+use lunatic_log::{error, info};
+use maud_live_view::Render;
 use nipper::Document;
 use rand::distributions::Alphanumeric;
 use rand::Rng;
-use sha2::Sha256;
+use serde::{Deserialize, Serialize};
 
-use crate::csrf::CsrfToken;
-use crate::maud::{secret, Session};
+use crate::csp::script_hash;
+use crate::event_handler::EventHandlerMessage;
+use crate::handler::CsrfProtection;
+use crate::maud::{hash, Session};
+use crate::EventConfig;
 
 const TEMPLATE_PROCESS_ID: &str = "e6cdcfeb-8552-4de2-8e8b-484724380248";
 
@@ -22,14 +35,142 @@ const LIVEVIEW_JS: &str = include_str!("../dist/liveview-release.js");
 
 const HTML_SEPARATOR: &str = "<!-- SUBMILLISECOND_LIVE_VIEW_SEPARATOR -->";
 
+thread_local! {
+    /// Templates requested by the current process, by path and selector.
+    static TEMPLATES: RefCell<HashMap<(String, String), Rc<Template>>> = RefCell::new(HashMap::new());
+}
+
 pub struct TemplateProcess {
+    template: Template,
+    selector: String,
+    /// Event handlers of the LiveViews rendered with the template, reloaded
+    /// when it changes.
+    subscribers: Vec<Process<EventHandlerMessage, Json>>,
+}
+
+/// The layout of a LiveView handler, with its template process.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Layout {
+    path: String,
+    selector: String,
+    process: ProcessRef<TemplateProcess>,
+}
+
+/// A template split around the LiveView container, shared with request
+/// handlers so dead renders don't have to go through the template process.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Template {
     html_parts: [String; 3],
+    fingerprint: String,
+    /// Hashes of the inline scripts, allowed by the Content Security Policy.
+    script_hashes: Vec<String>,
 }
 
 #[abstract_process(visibility = pub)]
 impl TemplateProcess {
     #[init]
     fn init(_: Config<Self>, (html, selector): (String, String)) -> Result<Self, ()> {
+        let template = Template::parse(&html, &selector).unwrap_or_else(|err| panic!("{err}"));
+        Ok(TemplateProcess {
+            template,
+            selector,
+            subscribers: Vec::new(),
+        })
+    }
+
+    #[handle_request]
+    fn template(&self) -> Template {
+        self.template.clone()
+    }
+
+    /// Replaces the template, reloading the pages of the subscribed LiveViews.
+    ///
+    /// The template is kept if the new one is invalid.
+    #[handle_message]
+    fn reload(&mut self, html: String) {
+        match Template::parse(&html, &self.selector) {
+            Ok(template) => {
+                info!("template changed, reloading connected LiveViews");
+                self.template = template;
+                // Reloaded pages subscribe again when they rejoin.
+                for subscriber in self.subscribers.drain(..) {
+                    subscriber.send(EventHandlerMessage::Reload);
+                }
+            }
+            Err(err) => error!("failed to reload template: {err}"),
+        }
+    }
+
+    /// Subscribes a LiveView's event handler to template changes.
+    ///
+    /// Event handlers which have exited since the last subscription are
+    /// dropped, so the list only holds the connected LiveViews.
+    #[handle_message]
+    fn subscribe(&mut self, event_handler: Process<EventHandlerMessage, Json>) {
+        self.subscribers.retain(Process::is_alive);
+        self.subscribers.push(event_handler);
+    }
+
+    pub fn start(path: &str, selector: &str) -> io::Result<ProcessRef<Self>> {
+        let name = Self::process_name(path, selector);
+        let template = fs::read_to_string(path)?;
+        let process = Self::start_as(&name, (template.clone(), selector.to_string())).unwrap();
+        process.link();
+        // Templates are reloaded when edited during development.
+        #[cfg(debug_assertions)]
+        Process::spawn_link((path.to_string(), template, process), watch_template);
+        Ok(process)
+    }
+
+    pub fn lookup(path: &str, selector: &str) -> Option<ProcessRef<Self>> {
+        let name = Self::process_name(path, selector);
+        ProcessRef::lookup(&name)
+    }
+
+    fn process_name(path: &str, selector: &str) -> String {
+        format!("{TEMPLATE_PROCESS_ID}-{path}-{selector}")
+    }
+}
+
+impl Layout {
+    /// Looks up the layout's template process, started by the handler's init.
+    pub fn lookup(path: &str, selector: &str) -> Option<Self> {
+        TemplateProcess::lookup(path, selector).map(|process| Layout {
+            path: path.to_string(),
+            selector: selector.to_string(),
+            process,
+        })
+    }
+
+    /// Returns the template process, which reloads pages when the template is
+    /// edited during development.
+    pub fn process(&self) -> ProcessRef<TemplateProcess> {
+        self.process
+    }
+
+    /// Returns the template, split and hashed once by the template process,
+    /// so dead renders don't parse it again.
+    ///
+    /// Release builds request it once per process, while debug builds request
+    /// it every time, since it's reloaded when edited.
+    pub fn template(&self) -> Rc<Template> {
+        if cfg!(debug_assertions) {
+            return Rc::new(self.process.template());
+        }
+        TEMPLATES.with(|templates| {
+            templates
+                .borrow_mut()
+                .entry((self.path.clone(), self.selector.clone()))
+                .or_insert_with(|| Rc::new(self.process.template()))
+                .clone()
+        })
+    }
+}
+
+impl Template {
+    /// Parses a template, splitting it around the LiveView container matching
+    /// `selector`.
+    fn parse(html: &str, selector: &str) -> Result<Template, String> {
         let document = Document::from(&html.replace(0x0 as char, ""));
         #[cfg(feature = "liveview_js")]
         document.select("head").append_html(format!(
@@ -39,25 +180,66 @@ impl TemplateProcess {
         document
             .select("head")
             .append_html(format!(r#"{HTML_SEPARATOR}"#,));
-        let mut selection = document.select(&selector);
+        let mut selection = document.select(selector);
         if !selection.exists() {
-            panic!("selector '{selector}' does not exist");
+            return Err(format!("selector '{selector}' does not exist"));
         }
         selection.append_html(HTML_SEPARATOR);
-        let html_parts = document
+        let script_hashes = document
+            .select("script")
+            .nodes()
+            .iter()
+            .filter(|script| script.attr("src").is_none())
+            .map(|script| script_hash(&script.text()))
+            .collect();
+        let html_parts: [String; 3] = document
             .html()
             .to_string()
             .splitn(3, HTML_SEPARATOR)
             .map(|s| s.to_string())
             .collect::<Vec<_>>()
             .try_into()
-            .unwrap();
-        Ok(TemplateProcess { html_parts })
+            .map_err(|_| "template has no head".to_string())?;
+        let fingerprint = hash(html_parts.iter().map(String::as_bytes));
+        Ok(Template {
+            html_parts,
+            fingerprint,
+            script_hashes,
+        })
     }
 
-    #[handle_request]
-    fn render(&self, content: String) -> String {
+    /// Returns a hash of the template, used to invalidate cached dead renders
+    /// when the template changes.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// Returns the hashes of the template's inline scripts.
+    pub fn script_hashes(&self) -> &[String] {
+        &self.script_hashes
+    }
+
+    /// Renders the template with the LiveView's content, a state token, the
+    /// session and its signed encoding, the LiveView's event defaults, and its
+    /// title and head content, if any.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &self,
+        content: &str,
+        static_token: &str,
+        (session, session_str): (Session, String),
+        csrf: CsrfProtection,
+        events: EventConfig,
+        title: Option<&str>,
+        head: Option<&str>,
+    ) -> String {
         let mut html_parts = self.html_parts.clone();
+        if let Some(title) = title {
+            replace_title(&mut html_parts[0], title);
+        }
+        if let Some(head) = head {
+            html_parts[0].push_str(head);
+        }
 
         let mut rng = rand::thread_rng();
         let id: String = (&mut rng)
@@ -66,38 +248,95 @@ impl TemplateProcess {
             .map(char::from)
             .collect();
 
-        let key: Hmac<Sha256> = Hmac::new_from_slice(&secret()).expect("unable to encode secret");
-        let csrf_token = CsrfToken::generate().masked;
-        let session = Session {
-            csrf_token: csrf_token.clone(),
-        };
-        let session_str = session.sign_with_key(&key).expect("failed to sign session");
+        let csrf_token = session.csrf_token;
 
-        html_parts[0].push_str(&format!(
-            r#"<meta name="csrf-token" content="{csrf_token}" />"#
-        ));
+        if csrf == CsrfProtection::MetaTag {
+            html_parts[0].push_str(&format!(
+                r#"<meta name="csrf-token" content="{csrf_token}" />"#
+            ));
+        }
 
         html_parts[1].push_str(&format!(
-            r#"<div data-phx-main="true" data-phx-static="" data-phx-session={session_str} id={id}>{content}</div>"#
+            r#"<div data-phx-main="true" data-phx-static="{static_token}" data-phx-session={session_str} id={id}{}>{content}</div>"#,
+            events.container_attributes()
         ));
 
         html_parts.into_iter().collect()
     }
 
-    pub fn start(path: &str, selector: &str) -> io::Result<ProcessRef<Self>> {
-        let name = Self::process_name(path, selector);
-        let template = fs::read_to_string(path)?;
-        let process = Self::start_as(&name, (template, selector.to_string())).unwrap();
-        process.link();
-        Ok(process)
+    /// Renders the template with content which isn't a LiveView, such as an
+    /// error page, so the client doesn't connect a live socket.
+    pub fn render_static(&self, content: &str) -> String {
+        let mut html_parts = self.html_parts.clone();
+        html_parts[1].push_str(&format!("<div>{content}</div>"));
+        html_parts.into_iter().collect()
     }
+}
 
-    pub fn lookup(path: &str, selector: &str) -> Option<ProcessRef<Self>> {
-        let name = Self::process_name(path, selector);
-        ProcessRef::lookup(&name)
+/// Reloads the template whenever its file changes, checking it every half
+/// second.
+#[cfg(debug_assertions)]
+fn watch_template(
+    (path, mut html, process): (String, String, ProcessRef<TemplateProcess>),
+    _: Mailbox<()>,
+) {
+    loop {
+        lunatic::sleep(Duration::from_millis(500));
+        match fs::read_to_string(&path) {
+            Ok(new_html) if new_html != html => {
+                html = new_html;
+                process.reload(html.clone());
+            }
+            Ok(_) => {}
+            Err(err) => warn!("failed to read template {path}: {err}"),
+        }
     }
+}
 
-    fn process_name(path: &str, selector: &str) -> String {
-        format!("{TEMPLATE_PROCESS_ID}-{path}-{selector}")
+/// Replaces the content of the `title` element of a page's head, adding one
+/// if there's none.
+fn replace_title(head: &mut String, title: &str) {
+    let mut escaped = String::new();
+    title.render_to(&mut escaped);
+    let start = head
+        .find("<title")
+        .and_then(|start| head[start..].find('>').map(|end| start + end + 1));
+    match start.zip(head.find("</title>")) {
+        Some((start, end)) if start <= end => head.replace_range(start..end, &escaped),
+        _ => head.push_str(&format!("<title>{escaped}</title>")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    /// Compares parsing the template, as every request did, with requesting
+    /// it from the template process.
+    #[lunatic::test]
+    #[ignore = "benchmark, run with `cargo test --release -- --ignored`"]
+    fn requesting_the_template_is_cheaper_than_parsing() {
+        const RUNS: u32 = 100;
+        let html = include_str!("../examples/todos.html");
+
+        let start = Instant::now();
+        for _ in 0..RUNS {
+            Template::parse(html, "#app").unwrap();
+        }
+        let parse = start.elapsed() / RUNS;
+
+        let name = TemplateProcess::process_name("bench.html", "#app");
+        let process =
+            TemplateProcess::start_as(&name, (html.to_string(), "#app".to_string())).unwrap();
+        let start = Instant::now();
+        for _ in 0..RUNS {
+            process.template();
+        }
+        let request = start.elapsed() / RUNS;
+
+        println!("parse: {parse:?}, request: {request:?}");
+        assert!(request < parse);
     }
 }
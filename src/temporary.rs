@@ -0,0 +1,89 @@
+use std::cell::RefCell;
+
+use serde::{Deserialize, Serialize};
+
+/// A field reset to its default each time the LiveView renders.
+///
+/// LiveViews keep their state between renders, so a chat keeping every
+/// message holds them all in memory and diffs them on each render. Wrapping
+/// the messages in a `TemporaryAssign` empties them once they're rendered,
+/// leaving only the new messages in the next render. Rendered in a container
+/// with `phx-update="append"`, the client adds them after the messages it
+/// already shows.
+///
+/// The value is taken with [`TemporaryAssign::take`] while rendering, which is
+/// possible from `&self`.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct Chat {
+///     messages: TemporaryAssign<Vec<ChatMessage>>,
+/// }
+///
+/// impl LiveView for Chat {
+///     fn render(&self) -> Rendered {
+///         html! {
+///             ul #messages phx-update="append" {
+///                 @for message in self.messages.take() {
+///                     li id=(message.id) { (message.text) }
+///                 }
+///             }
+///         }
+///     }
+///     // ...
+/// }
+///
+/// impl LiveViewEvent<Broadcast<ChatMessage>> for Chat {
+///     fn handle(state: &mut Self, event: Broadcast<ChatMessage>) {
+///         state.messages.get_mut().push(event.message);
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TemporaryAssign<T>(RefCell<T>);
+
+impl<T> TemporaryAssign<T> {
+    /// Creates a temporary assign, holding `value` until the next render.
+    pub fn new(value: T) -> Self {
+        TemporaryAssign(RefCell::new(value))
+    }
+
+    /// Replaces the value.
+    pub fn set(&mut self, value: T) {
+        *self.0.get_mut() = value;
+    }
+
+    /// Returns a mutable reference to the value, such as to add items to it.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.0.get_mut()
+    }
+}
+
+impl<T> TemporaryAssign<T>
+where
+    T: Default,
+{
+    /// Takes the value to render it, resetting it to its default.
+    pub fn take(&self) -> T {
+        self.0.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TemporaryAssign;
+
+    #[test]
+    fn values_are_reset_once_taken() {
+        let mut messages = TemporaryAssign::new(vec!["hello"]);
+        messages.get_mut().push("world");
+        assert_eq!(messages.take(), ["hello", "world"]);
+        assert!(messages.take().is_empty());
+
+        messages.set(vec!["again"]);
+        assert_eq!(messages.take(), ["again"]);
+    }
+}
@@ -0,0 +1,38 @@
+//! Rendering times in the viewer's timezone.
+//!
+//! Only available with the `timezone` feature enabled.
+
+use chrono::{DateTime, TimeZone};
+use chrono_tz::Tz;
+
+use crate::socket;
+
+/// Returns the timezone of the viewer's browser, or UTC if it's unknown.
+///
+/// The timezone is known when the live socket joins, and for page loads once
+/// the browser has connected before. Like
+/// [`Socket::timezone`](crate::socket::Socket::timezone), it's only available
+/// from within the LiveView's process, such as in `mount` and `render`.
+pub fn viewer_timezone() -> Tz {
+    socket::timezone()
+        .and_then(|timezone| timezone.parse().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+/// Converts a time to the viewer's timezone, for formatting in `html!`.
+///
+/// # Example
+///
+/// ```
+/// html! {
+///     time datetime=(self.posted_at.to_rfc3339()) {
+///         (local_time(&self.posted_at).format("%b %e, %H:%M"))
+///     }
+/// }
+/// ```
+pub fn local_time<T>(time: &DateTime<T>) -> DateTime<Tz>
+where
+    T: TimeZone,
+{
+    time.with_timezone(&viewer_timezone())
+}
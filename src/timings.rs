@@ -0,0 +1,108 @@
+//! Timing instrumentation for the phases of handling an event.
+//!
+//! Durations are accumulated per process while an event is handled, and
+//! reported to the callback registered with
+//! [`LiveViewHandler::on_timings`](crate::handler::LiveViewHandler::on_timings).
+//!
+//! Timings are only recorded when the `timings` feature is enabled.
+
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+use lunatic::function::FuncRef;
+use serde::{Deserialize, Serialize};
+
+thread_local! {
+    static TIMINGS: RefCell<Timings> = RefCell::new(Timings::default());
+}
+
+/// Time spent in each phase of handling events.
+///
+/// Events received together are handled as a batch with a single render, and
+/// are reported together. Messages are written to the socket by a separate
+/// process after they're handed to it, so the time spent writing them isn't
+/// included.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timings {
+    /// Names of the events handled.
+    pub events: Vec<String>,
+    /// Time spent in the LiveView's event handlers.
+    pub handle: Duration,
+    /// Time spent in [`LiveView::render`](crate::LiveView::render).
+    pub render: Duration,
+    /// Time spent diffing the render against the previous render.
+    pub diff: Duration,
+    /// Time spent serializing messages sent on the socket.
+    ///
+    /// Events sent with
+    /// [`Socket::send_event`](crate::socket::Socket::send_event)
+    /// are written by the sending process, and so do not include this phase.
+    pub serialize: Duration,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Phase {
+    Handle,
+    Render,
+    Diff,
+    Serialize,
+}
+
+/// Runs `f`, adding its duration to the current process' timings.
+pub(crate) fn measure<R>(phase: Phase, f: impl FnOnce() -> R) -> R {
+    let _timer = start(phase);
+    f()
+}
+
+/// Starts timing `phase`, which is recorded when the returned timer is dropped.
+pub(crate) fn start(phase: Phase) -> Timer {
+    Timer {
+        phase,
+        start: cfg!(feature = "timings").then(Instant::now),
+    }
+}
+
+pub(crate) struct Timer {
+    phase: Phase,
+    start: Option<Instant>,
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+
+        let elapsed = start.elapsed();
+        TIMINGS.with(|timings| {
+            let mut timings = timings.borrow_mut();
+            let duration = match self.phase {
+                Phase::Handle => &mut timings.handle,
+                Phase::Render => &mut timings.render,
+                Phase::Diff => &mut timings.diff,
+                Phase::Serialize => &mut timings.serialize,
+            };
+            *duration += elapsed;
+        });
+    }
+}
+
+/// Discards timings recorded outside of handling events.
+pub(crate) fn reset() {
+    if cfg!(feature = "timings") {
+        TIMINGS.with(|timings| timings.take());
+    }
+}
+
+/// Reports and resets the timings recorded for `events`.
+pub(crate) fn report(callback: Option<FuncRef<fn(Timings)>>, events: Vec<String>) {
+    if !cfg!(feature = "timings") {
+        return;
+    }
+
+    let mut timings = TIMINGS.with(|timings| timings.take());
+    if let Some(callback) = callback {
+        timings.events = events;
+        (callback.get())(timings);
+    }
+}
@@ -0,0 +1,744 @@
+//! File uploads over the live socket.
+
+use std::cell::RefCell;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+use crate::csrf::generate_token;
+use crate::rendered::Rendered;
+use crate::{self as submillisecond_live_view, html};
+
+/// Prefix of the topics uploads are sent on, followed by the entry's ref.
+pub(crate) const UPLOAD_TOPIC_PREFIX: &str = "lvu:";
+
+const DEFAULT_MAX_ENTRIES: usize = 1;
+const DEFAULT_MAX_FILE_SIZE: u64 = 8_000_000;
+const DEFAULT_CHUNK_SIZE: usize = 64_000;
+
+thread_local! {
+    /// Uploads allowed by the LiveView in the current process.
+    static UPLOADS: RefCell<Vec<Upload>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Configuration of an upload, allowed with [`allow_upload`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadConfig {
+    accept: Vec<String>,
+    max_entries: usize,
+    max_file_size: u64,
+    chunk_size: usize,
+    auto_upload: bool,
+    external: bool,
+}
+
+/// A file selected for an upload.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadEntry {
+    /// Reference of the entry, assigned by the client.
+    pub entry_ref: String,
+    /// Name of the file.
+    pub name: String,
+    /// MIME type of the file, as reported by the browser.
+    pub content_type: String,
+    /// Size of the file in bytes, as reported by the browser.
+    pub size: u64,
+    /// Percentage of the file uploaded, from 0 to 100.
+    pub progress: u8,
+    /// Whether the file has been uploaded.
+    pub done: bool,
+    /// Why the file was rejected, in which case it isn't uploaded.
+    pub error: Option<UploadEntryError>,
+    /// Metadata of an external upload, returned by
+    /// [`LiveView::presign_upload`](crate::LiveView::presign_upload).
+    pub meta: Option<Value>,
+}
+
+/// Reason a file selected for an upload was rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadEntryError {
+    /// The file is larger than [`UploadConfig::max_file_size`].
+    #[error("file is too large")]
+    TooLarge,
+    /// The file's type isn't one of [`UploadConfig::accept`].
+    #[error("file type is not accepted")]
+    NotAccepted,
+    /// More files were selected than [`UploadConfig::max_entries`].
+    #[error("too many files")]
+    TooManyFiles,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum UploadError {
+    #[error("unknown upload entry")]
+    UnknownEntry,
+    #[error("file too large")]
+    TooLarge,
+}
+
+struct Upload {
+    name: String,
+    config: UploadConfig,
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    entry: UploadEntry,
+    /// Token the entry's upload joins with, set once the client is allowed to
+    /// upload it.
+    token: Option<String>,
+    data: Vec<u8>,
+}
+
+/// A file from the client, as sent with form changes and upload requests.
+#[derive(Deserialize)]
+struct ClientEntry {
+    #[serde(rename = "ref")]
+    entry_ref: String,
+    name: String,
+    #[serde(rename = "type", default)]
+    content_type: String,
+    size: u64,
+}
+
+impl UploadConfig {
+    /// Creates a configuration accepting a single file of any type, up to
+    /// 8MB.
+    pub fn new() -> Self {
+        UploadConfig::default()
+    }
+
+    /// Sets the file extensions or MIME types accepted, such as `.png` or
+    /// `image/*`.
+    pub fn accept<I, S>(mut self, accept: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.accept = accept.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the maximum number of files selected at once, allowing multiple
+    /// files when greater than 1.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Sets the maximum size of each file in bytes.
+    pub fn max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Sets the size of the chunks files are sent in, 64KB by default.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// Uploads files as soon as they are selected, rather than when the form
+    /// is submitted.
+    pub fn auto_upload(mut self, auto_upload: bool) -> Self {
+        self.auto_upload = auto_upload;
+        self
+    }
+
+    /// Uploads files directly from the client to an external service, such as
+    /// to S3 with a presigned URL, rather than over the live socket.
+    ///
+    /// The metadata of each file is returned by
+    /// [`LiveView::presign_upload`](crate::LiveView::presign_upload), and its
+    /// `uploader` names the client uploader which sends the file, registered
+    /// with `liveSocket.uploaders`. Only the progress of the files is reported
+    /// to the server, and consumed files have no contents.
+    pub fn external(mut self, external: bool) -> Self {
+        self.external = external;
+        self
+    }
+
+    /// Returns whether a file is accepted by its extension or MIME type.
+    fn accepts(&self, name: &str, content_type: &str) -> bool {
+        let name = name.to_lowercase();
+        self.accept.is_empty()
+            || self.accept.iter().any(|accept| {
+                let accept = accept.to_lowercase();
+                if accept.starts_with('.') {
+                    name.ends_with(&accept)
+                } else if let Some(kind) = accept.strip_suffix("/*") {
+                    content_type.split('/').next() == Some(kind)
+                } else {
+                    content_type == accept
+                }
+            })
+    }
+}
+
+impl Default for UploadConfig {
+    fn default() -> Self {
+        UploadConfig {
+            accept: Vec::new(),
+            max_entries: DEFAULT_MAX_ENTRIES,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            auto_upload: false,
+            external: false,
+        }
+    }
+}
+
+impl Upload {
+    /// Reference of the upload, shared with the client.
+    fn upload_ref(&self) -> String {
+        upload_ref(&self.name)
+    }
+
+    fn refs(&self, filter: impl Fn(&Entry) -> bool) -> String {
+        self.entries
+            .iter()
+            .filter(|entry| filter(entry))
+            .map(|entry| entry.entry.entry_ref.as_str())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn entry_mut(&mut self, entry_ref: &str) -> Option<&mut Entry> {
+        self.entries
+            .iter_mut()
+            .find(|entry| entry.entry.entry_ref == entry_ref)
+    }
+
+    /// Adds an entry selected by the client, if it's not already tracked.
+    fn put_entry(&mut self, client: ClientEntry) -> &mut Entry {
+        let index = match self
+            .entries
+            .iter()
+            .position(|entry| entry.entry.entry_ref == client.entry_ref)
+        {
+            Some(index) => index,
+            None => {
+                self.entries.push(Entry {
+                    entry: UploadEntry {
+                        entry_ref: client.entry_ref,
+                        name: client.name,
+                        content_type: client.content_type,
+                        size: client.size,
+                        progress: 0,
+                        done: false,
+                        error: None,
+                        meta: None,
+                    },
+                    token: None,
+                    data: Vec::new(),
+                });
+                self.entries.len() - 1
+            }
+        };
+        &mut self.entries[index]
+    }
+
+    /// Rejects entries which are too large, aren't accepted, or exceed the
+    /// maximum number of entries.
+    fn validate(&mut self) {
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            let UploadEntry {
+                name,
+                content_type,
+                size,
+                ..
+            } = &entry.entry;
+            entry.entry.error = if index >= self.config.max_entries {
+                Some(UploadEntryError::TooManyFiles)
+            } else if *size > self.config.max_file_size {
+                Some(UploadEntryError::TooLarge)
+            } else if !self.config.accepts(name, content_type) {
+                Some(UploadEntryError::NotAccepted)
+            } else {
+                None
+            };
+            if entry.entry.error.is_some() {
+                entry.token = None;
+                entry.entry.meta = None;
+                entry.data.clear();
+            }
+        }
+    }
+}
+
+/// Allows files to be uploaded under a name, rendered with
+/// [`live_file_input`].
+///
+/// Uploads are allowed when mounting, and are only available from within the
+/// LiveView's process. The input must be in a form with a `@change` event, so
+/// selected files are sent to the server. Files are received in chunks while
+/// the form is submitted, or as soon as they are selected with
+/// [`UploadConfig::auto_upload`], and are consumed with
+/// [`consume_uploaded_entries`].
+///
+/// # Example
+///
+/// ```
+/// impl LiveView for Profile {
+///     type Events = (Validate, Save);
+///
+///     fn mount(_uri: Uri, _socket: Option<Socket>) -> Result<Self, MountError> {
+///         allow_upload("avatar", UploadConfig::new().accept([".jpg", ".png"]));
+///         Ok(Profile::default())
+///     }
+///
+///     fn render(&self) -> Rendered {
+///         html! {
+///             form @change=(Validate) @submit=(Save) {
+///                 @(live_file_input("avatar"))
+///                 @for entry in uploaded_entries("avatar") {
+///                     progress value=(entry.progress) max="100" { (entry.name) }
+///                 }
+///                 button type="submit" { "Save" }
+///             }
+///         }
+///     }
+///     // ...
+/// }
+///
+/// impl LiveViewEvent<Save> for Profile {
+///     fn handle(state: &mut Self, _event: Save) {
+///         state.avatars = consume_uploaded_entries("avatar", |entry, data| {
+///             store_avatar(&entry.name, data)
+///         });
+///     }
+/// }
+/// ```
+pub fn allow_upload(name: impl Into<String>, config: UploadConfig) {
+    let name = name.into();
+    UPLOADS.with(|uploads| {
+        let mut uploads = uploads.borrow_mut();
+        uploads.retain(|upload| upload.name != name);
+        uploads.push(Upload {
+            name,
+            config,
+            entries: Vec::new(),
+        });
+    });
+}
+
+/// Renders the file input of an upload allowed with [`allow_upload`].
+///
+/// Nothing is rendered if the upload isn't allowed.
+pub fn live_file_input(name: &str) -> Rendered {
+    let input = with_upload(name, |upload| {
+        (
+            upload.upload_ref(),
+            upload.config.accept.join(","),
+            upload.config.max_entries > 1,
+            upload.config.auto_upload,
+            upload.refs(|_| true),
+            upload.refs(|entry| entry.entry.done),
+            upload.refs(|entry| entry.token.is_some() || entry.entry.meta.is_some()),
+        )
+    });
+    let Some((upload_ref, accept, multiple, auto_upload, active, done, preflighted)) = input else {
+        return html! {};
+    };
+    let accept = (!accept.is_empty()).then_some(accept);
+    html! {
+        input
+            type="file"
+            id=(upload_ref)
+            name=(name)
+            accept=[accept]
+            multiple[multiple]
+            phx-hook="Phoenix.LiveFileUpload"
+            data-phx-update="ignore"
+            data-phx-upload-ref=(upload_ref)
+            data-phx-active-refs=(active)
+            data-phx-done-refs=(done)
+            data-phx-preflighted-refs=(preflighted)
+            data-phx-auto-upload[auto_upload];
+    }
+}
+
+/// Returns the files selected for an upload, including those still being
+/// uploaded and those rejected.
+pub fn uploaded_entries(name: &str) -> Vec<UploadEntry> {
+    with_upload(name, |upload| {
+        upload
+            .entries
+            .iter()
+            .map(|entry| entry.entry.clone())
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Consumes the files of an upload which have been uploaded, passing each to
+/// `consume` with its contents.
+///
+/// Files uploaded externally have no contents, and are identified by their
+/// [`UploadEntry::meta`].
+///
+/// Consumed files are removed from the upload, and files still being uploaded
+/// are kept.
+pub fn consume_uploaded_entries<R>(
+    name: &str,
+    mut consume: impl FnMut(&UploadEntry, Vec<u8>) -> R,
+) -> Vec<R> {
+    let done = with_upload(name, |upload| {
+        let (done, pending) = upload
+            .entries
+            .drain(..)
+            .partition::<Vec<_>, _>(|entry| entry.entry.done);
+        upload.entries = pending;
+        done
+    })
+    .unwrap_or_default();
+    done.into_iter()
+        .map(|entry| consume(&entry.entry, entry.data))
+        .collect()
+}
+
+/// Cancels the upload of a file, removing it from the selected files.
+///
+/// Chunks of the file still being sent are rejected, which stops its upload
+/// on the client.
+///
+/// # Example
+///
+/// ```
+/// impl LiveViewEvent<CancelUpload> for Profile {
+///     fn handle(_state: &mut Self, event: CancelUpload) {
+///         cancel_upload("avatar", &event.entry_ref);
+///     }
+/// }
+/// ```
+pub fn cancel_upload(name: &str, entry_ref: &str) {
+    with_upload(name, |upload| {
+        upload
+            .entries
+            .retain(|entry| entry.entry.entry_ref != entry_ref);
+        upload.validate();
+    });
+}
+
+/// Tracks the files selected for uploads, sent with form changes.
+pub(crate) fn track(uploads: &Value) {
+    let Some(uploads) = uploads.as_object() else {
+        return;
+    };
+    for (upload_ref, entries) in uploads {
+        let entries: Vec<ClientEntry> = serde_json::from_value(entries.clone()).unwrap_or_default();
+        with_upload_ref(upload_ref, |upload| {
+            for entry in entries {
+                upload.put_entry(entry);
+            }
+            upload.validate();
+        });
+    }
+}
+
+/// Allows the client to upload files, replying with the tokens their uploads
+/// join with, or the metadata returned by `presign` for external uploads.
+///
+/// Files are validated before any of them is allowed, replying with the first
+/// rejected file instead.
+pub(crate) fn preflight(
+    payload: &Value,
+    mut presign: impl FnMut(&str, &UploadEntry) -> Result<Value, String>,
+) -> Value {
+    let upload_ref = payload
+        .get("ref")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let entries: Vec<ClientEntry> = payload
+        .get("entries")
+        .cloned()
+        .and_then(|entries| serde_json::from_value(entries).ok())
+        .unwrap_or_default();
+    let allowed = with_upload_ref(upload_ref, |upload| {
+        let entry_refs: Vec<_> = entries
+            .into_iter()
+            .map(|entry| upload.put_entry(entry).entry.entry_ref.clone())
+            .collect();
+        upload.validate();
+        let rejected = upload.entries.iter().find_map(|entry| {
+            let error = entry.entry.error?;
+            entry_refs
+                .contains(&entry.entry.entry_ref)
+                .then(|| json!([entry.entry.entry_ref, error]))
+        });
+        if let Some(error) = rejected {
+            return Err(error);
+        }
+        let entries: Vec<_> = upload
+            .entries
+            .iter()
+            .filter(|entry| entry_refs.contains(&entry.entry.entry_ref))
+            .map(|entry| entry.entry.clone())
+            .collect();
+        Ok((upload.name.clone(), upload.config.clone(), entries))
+    });
+    let (name, config, entries) = match allowed {
+        Some(Ok(allowed)) => allowed,
+        Some(Err(error)) => return json!({ "ref": upload_ref, "error": error }),
+        None => return json!({ "ref": upload_ref, "error": [upload_ref, "not allowed"] }),
+    };
+    // Presigned outside of the uploads, which the LiveView may read.
+    let mut metas = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let meta = if config.external {
+            match presign(&name, &entry) {
+                Ok(meta) => Some(meta),
+                Err(reason) => {
+                    return json!({ "ref": upload_ref, "error": [entry.entry_ref, reason] })
+                }
+            }
+        } else {
+            None
+        };
+        metas.push((entry.entry_ref, meta));
+    }
+    let entries = with_upload_ref(upload_ref, |upload| {
+        let mut entries = Map::new();
+        for (entry_ref, meta) in metas {
+            let Some(entry) = upload.entry_mut(&entry_ref) else {
+                continue;
+            };
+            let meta = match meta {
+                Some(meta) => entry.entry.meta.insert(meta).clone(),
+                None => Value::String(entry.token.get_or_insert_with(generate_token).clone()),
+            };
+            entries.insert(entry_ref, meta);
+        }
+        entries
+    });
+    json!({
+        "ref": upload_ref,
+        "config": { "chunk_size": config.chunk_size },
+        "entries": entries.unwrap_or_default(),
+    })
+}
+
+/// Checks the token an entry's upload joins with.
+pub(crate) fn join(topic: &str, token: &str) -> Result<(), UploadError> {
+    with_entry(topic, |_, entry| {
+        if entry.token.as_deref() == Some(token) {
+            Ok(())
+        } else {
+            Err(UploadError::UnknownEntry)
+        }
+    })
+}
+
+/// Receives a chunk of an entry's file.
+///
+/// Chunks beyond the file's reported size, or the upload's maximum size, are
+/// rejected.
+pub(crate) fn chunk(topic: &str, chunk: Vec<u8>) -> Result<(), UploadError> {
+    with_entry(topic, |config, entry| {
+        let size = (entry.data.len() + chunk.len()) as u64;
+        if size > entry.entry.size.min(config.max_file_size) {
+            return Err(UploadError::TooLarge);
+        }
+        entry.data.extend(chunk);
+        Ok(())
+    })
+}
+
+/// Updates the progress of an entry, reported by the client, returning the
+/// upload's name and the entry.
+pub(crate) fn progress(payload: &Value) -> Option<(String, UploadEntry)> {
+    let upload_ref = payload
+        .get("ref")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let entry_ref = payload
+        .get("entry_ref")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let progress = payload.get("progress").and_then(Value::as_u64);
+    with_upload_ref(upload_ref, |upload| match progress {
+        Some(progress) => {
+            let external = upload.config.external;
+            let entry = upload.entry_mut(entry_ref)?;
+            let received = external || entry.data.len() as u64 == entry.entry.size;
+            entry.entry.progress = progress.min(100) as u8;
+            entry.entry.done = entry.entry.progress == 100 && received;
+            let entry = entry.entry.clone();
+            Some((upload.name.clone(), entry))
+        }
+        // The upload failed on the client.
+        None => {
+            upload
+                .entries
+                .retain(|entry| entry.entry.entry_ref != entry_ref);
+            None
+        }
+    })
+    .flatten()
+}
+
+fn upload_ref(name: &str) -> String {
+    format!("phx-upload-{name}")
+}
+
+fn with_upload<R>(name: &str, f: impl FnOnce(&mut Upload) -> R) -> Option<R> {
+    UPLOADS.with(|uploads| {
+        uploads
+            .borrow_mut()
+            .iter_mut()
+            .find(|upload| upload.name == name)
+            .map(f)
+    })
+}
+
+fn with_upload_ref<R>(upload_ref: &str, f: impl FnOnce(&mut Upload) -> R) -> Option<R> {
+    UPLOADS.with(|uploads| {
+        uploads
+            .borrow_mut()
+            .iter_mut()
+            .find(|upload| upload.upload_ref() == upload_ref)
+            .map(f)
+    })
+}
+
+/// Runs `f` with the preflighted entry uploaded on a topic, and the
+/// configuration of its upload.
+fn with_entry<R>(
+    topic: &str,
+    f: impl FnOnce(&UploadConfig, &mut Entry) -> Result<R, UploadError>,
+) -> Result<R, UploadError> {
+    let entry_ref = topic
+        .strip_prefix(UPLOAD_TOPIC_PREFIX)
+        .ok_or(UploadError::UnknownEntry)?;
+    UPLOADS.with(|uploads| {
+        let mut uploads = uploads.borrow_mut();
+        let (upload, index) = uploads
+            .iter_mut()
+            .find_map(|upload| {
+                let index = upload.entries.iter().position(|entry| {
+                    entry.entry.entry_ref == entry_ref
+                        && entry.token.is_some()
+                        && entry.entry.error.is_none()
+                })?;
+                Some((upload, index))
+            })
+            .ok_or(UploadError::UnknownEntry)?;
+        f(&upload.config, &mut upload.entries[index])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        allow_upload, cancel_upload, chunk, consume_uploaded_entries, join, preflight, progress,
+        track, uploaded_entries, UploadConfig, UploadEntryError,
+    };
+
+    #[test]
+    fn entries_are_uploaded_in_chunks() {
+        allow_upload("avatar", UploadConfig::new());
+        track(&json!({
+            "phx-upload-avatar": [{ "ref": "0", "name": "me.png", "type": "image/png", "size": 5 }]
+        }));
+        let reply = preflight(
+            &json!({
+                "ref": "phx-upload-avatar",
+                "entries": [{ "ref": "0", "name": "me.png", "type": "image/png", "size": 5 }]
+            }),
+            |_, _| unreachable!(),
+        );
+        let token = reply["entries"]["0"].as_str().unwrap();
+        assert!(join("lvu:0", "wrong").is_err());
+        join("lvu:0", token).unwrap();
+
+        chunk("lvu:0", b"hel".to_vec()).unwrap();
+        assert!(chunk("lvu:0", b"lo!!".to_vec()).is_err());
+        chunk("lvu:0", b"lo".to_vec()).unwrap();
+        progress(&json!({ "ref": "phx-upload-avatar", "entry_ref": "0", "progress": 100 }));
+
+        let consumed = consume_uploaded_entries("avatar", |entry, data| (entry.name.clone(), data));
+        assert_eq!(consumed, [("me.png".to_string(), b"hello".to_vec())]);
+        assert!(uploaded_entries("avatar").is_empty());
+    }
+
+    #[test]
+    fn invalid_entries_are_rejected() {
+        let config = UploadConfig::new()
+            .accept([".png", "image/jpeg"])
+            .max_entries(2)
+            .max_file_size(10);
+        allow_upload("photos", config);
+        let reply = preflight(
+            &json!({
+                "ref": "phx-upload-photos",
+                "entries": [
+                    { "ref": "0", "name": "a.PNG", "type": "image/png", "size": 5 },
+                    { "ref": "1", "name": "b.gif", "type": "image/gif", "size": 5 },
+                    { "ref": "2", "name": "c.jpg", "type": "image/jpeg", "size": 5 },
+                ]
+            }),
+            |_, _| unreachable!(),
+        );
+        assert_eq!(reply["error"], json!(["1", "not_accepted"]));
+        let errors: Vec<_> = uploaded_entries("photos")
+            .into_iter()
+            .map(|entry| entry.error)
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                None,
+                Some(UploadEntryError::NotAccepted),
+                Some(UploadEntryError::TooManyFiles)
+            ]
+        );
+
+        cancel_upload("photos", "1");
+        cancel_upload("photos", "2");
+        let reply = preflight(
+            &json!({
+                "ref": "phx-upload-photos",
+                "entries": [{ "ref": "3", "name": "d.png", "type": "image/png", "size": 11 }]
+            }),
+            |_, _| unreachable!(),
+        );
+        assert_eq!(reply["error"], json!(["3", "too_large"]));
+
+        cancel_upload("photos", "3");
+        let reply = preflight(
+            &json!({
+                "ref": "phx-upload-photos",
+                "entries": [{ "ref": "0", "name": "a.PNG", "type": "image/png", "size": 5 }]
+            }),
+            |_, _| unreachable!(),
+        );
+        assert!(reply["entries"]["0"].is_string());
+        assert!(join("lvu:0", "").is_err());
+    }
+
+    #[test]
+    fn external_entries_are_presigned() {
+        allow_upload("video", UploadConfig::new().external(true));
+        let entries = json!({
+            "ref": "phx-upload-video",
+            "entries": [{ "ref": "0", "name": "clip.mp4", "type": "video/mp4", "size": 5 }]
+        });
+        let reply = preflight(&entries, |_, _| Err("bucket unavailable".to_string()));
+        assert_eq!(reply["error"], json!(["0", "bucket unavailable"]));
+        let reply = preflight(&entries, |name, entry| {
+            Ok(json!({ "uploader": "S3", "key": format!("{name}/{}", entry.name) }))
+        });
+        let meta = json!({ "uploader": "S3", "key": "video/clip.mp4" });
+        assert_eq!(reply["entries"]["0"], meta);
+        assert!(join("lvu:0", "").is_err());
+        assert!(chunk("lvu:0", b"hello".to_vec()).is_err());
+
+        progress(&json!({ "ref": "phx-upload-video", "entry_ref": "0", "progress": 100 }));
+        let consumed = consume_uploaded_entries("video", |entry, data| (entry.meta.clone(), data));
+        assert_eq!(consumed, [(Some(meta), Vec::new())]);
+    }
+}
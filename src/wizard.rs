@@ -0,0 +1,204 @@
+//! Multi-step flows, with a step per URL.
+
+use serde::{Deserialize, Serialize};
+use submillisecond::http::Uri;
+
+use crate::rendered::Rendered;
+use crate::{self as submillisecond_live_view, html};
+
+/// Query parameter holding the current step, starting from 1.
+const STEP_PARAM: &str = "step";
+
+/// Ordered steps of a multi-step flow, such as a checkout or signup form.
+///
+/// Each step has its own URL, with a `?step=` query parameter, which the
+/// back and next links of [`Wizard::render_nav`] patch to. The LiveView moves
+/// between steps in [`LiveView::handle_params`](crate::LiveView::handle_params)
+/// with [`Wizard::handle_params`], which only moves forward past steps that
+/// are valid.
+///
+/// # Example
+///
+/// ```
+/// impl LiveView for Checkout {
+///     fn mount(uri: Uri, _socket: Option<Socket>) -> Result<Self, MountError> {
+///         let mut wizard = Wizard::new(["Address", "Shipping", "Payment"]);
+///         let order = Order::default();
+///         wizard.handle_params(&uri, |step| order.is_step_valid(step));
+///         Ok(Checkout { wizard, order })
+///     }
+///
+///     fn handle_params(&mut self, uri: Uri) {
+///         let order = &self.order;
+///         self.wizard.handle_params(&uri, |step| order.is_step_valid(step));
+///     }
+///
+///     fn render(&self) -> Rendered {
+///         html! {
+///             @(self.wizard.render_progress())
+///             @if self.wizard.step() == 0 {
+///                 @(self.render_address())
+///             }
+///             // ...
+///             @(self.wizard.render_nav())
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Wizard {
+    steps: Vec<String>,
+    step: usize,
+    reached: usize,
+}
+
+impl Wizard {
+    /// Creates a wizard on its first step, with the titles of its steps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no steps.
+    pub fn new<I>(steps: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let steps: Vec<String> = steps.into_iter().map(Into::into).collect();
+        assert!(!steps.is_empty(), "a wizard needs at least one step");
+        Wizard {
+            steps,
+            step: 0,
+            reached: 0,
+        }
+    }
+
+    /// Returns the index of the current step, starting from 0.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Returns the title of the current step.
+    pub fn title(&self) -> &str {
+        &self.steps[self.step]
+    }
+
+    /// Returns the titles of the steps.
+    pub fn steps(&self) -> &[String] {
+        &self.steps
+    }
+
+    /// Returns whether the current step is the first.
+    pub fn is_first(&self) -> bool {
+        self.step == 0
+    }
+
+    /// Returns whether the current step is the last.
+    pub fn is_last(&self) -> bool {
+        self.step == self.steps.len() - 1
+    }
+
+    /// Moves to the step in the URI's query, returning the step moved to.
+    ///
+    /// Earlier steps can always be returned to. Moving forward checks each
+    /// step being left with `is_valid`, stopping at the first invalid step.
+    pub fn handle_params<F>(&mut self, uri: &Uri, mut is_valid: F) -> usize
+    where
+        F: FnMut(usize) -> bool,
+    {
+        let requested = uri
+            .query()
+            .and_then(step_from_query)
+            .map(|step| step.clamp(1, self.steps.len()) - 1)
+            .unwrap_or(0);
+        if requested <= self.step {
+            self.step = requested;
+        } else {
+            while self.step < requested && is_valid(self.step) {
+                self.step += 1;
+            }
+        }
+        self.reached = self.reached.max(self.step);
+        self.step
+    }
+
+    /// Returns the relative URL of a step, starting from 0.
+    pub fn href(&self, step: usize) -> String {
+        format!("?{STEP_PARAM}={}", step + 1)
+    }
+
+    /// Renders the steps as an ordered list, with links to the steps already
+    /// reached.
+    pub fn render_progress(&self) -> Rendered {
+        let steps = self.steps.iter().enumerate().map(|(i, title)| {
+            let class = match i.cmp(&self.step) {
+                std::cmp::Ordering::Less => "wizard-step complete",
+                std::cmp::Ordering::Equal => "wizard-step current",
+                std::cmp::Ordering::Greater => "wizard-step",
+            };
+            (i, title, class, self.href(i))
+        });
+        html! {
+            ol.wizard-steps {
+                @for (i, title, class, href) in steps {
+                    li class=(class) {
+                        @if i <= self.reached && i != self.step {
+                            a href=(href) data-phx-link="patch" data-phx-link-state="push" {
+                                (title)
+                            }
+                        } @else {
+                            span { (title) }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Renders back and next links, which patch the URL to the previous and
+    /// next steps.
+    pub fn render_nav(&self) -> Rendered {
+        let back = self.href(self.step.saturating_sub(1));
+        let next = self.href(self.step + 1);
+        html! {
+            nav.wizard-nav {
+                @if !self.is_first() {
+                    a.wizard-back href=(back) data-phx-link="patch" data-phx-link-state="push" {
+                        "Back"
+                    }
+                }
+                @if !self.is_last() {
+                    a.wizard-next href=(next) data-phx-link="patch" data-phx-link-state="push" {
+                        "Next"
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn step_from_query(query: &str) -> Option<usize> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(name, _)| *name == STEP_PARAM)
+        .and_then(|(_, step)| step.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use submillisecond::http::Uri;
+
+    use super::Wizard;
+
+    #[test]
+    fn handle_params_stops_at_invalid_steps() {
+        let mut wizard = Wizard::new(["Address", "Shipping", "Payment"]);
+        let uri = |query: &str| format!("/checkout?{query}").parse::<Uri>().unwrap();
+
+        assert_eq!(wizard.handle_params(&uri("step=3"), |step| step != 1), 1);
+        assert_eq!(wizard.handle_params(&uri("step=1"), |_| false), 0);
+        assert_eq!(wizard.handle_params(&uri("step=9"), |_| true), 2);
+        assert!(wizard.is_last());
+        assert_eq!(wizard.handle_params(&uri("other=1"), |_| true), 0);
+    }
+}
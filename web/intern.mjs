@@ -0,0 +1,36 @@
+// Strings repeated within a render are sent once in an `i` table on the root
+// of the diff or join render, and referenced with `{i: index}` within it.
+// Replies and pushed events, under `r` and `e`, are never interned, so they're
+// left as sent even if they hold `i` keys of their own.
+function expandInterned(value, table) {
+  if (Array.isArray(value)) {
+    return value.map((item) => expandInterned(item, table));
+  }
+  if (value === null || typeof value !== "object") {
+    return value;
+  }
+  if (typeof value.i === "number" && Object.keys(value).length === 1) {
+    return table[value.i];
+  }
+  for (const key in value) {
+    value[key] = expandInterned(value[key], table);
+  }
+  return value;
+}
+
+export function expandInternedMessage(msg) {
+  const payload = msg.payload || {};
+  const response = payload.response || {};
+  const render = msg.event === "diff" ? payload : response.diff || response.rendered;
+  if (!render || !Array.isArray(render.i)) {
+    return msg;
+  }
+  const table = render.i;
+  delete render.i;
+  for (const key in render) {
+    if (key !== "r" && key !== "e") {
+      render[key] = expandInterned(render[key], table);
+    }
+  }
+  return msg;
+}
@@ -0,0 +1,47 @@
+import assert from "node:assert/strict";
+import { test } from "node:test";
+
+import { expandInternedMessage } from "./intern.mjs";
+
+test("expands interned strings of a diff", () => {
+  const msg = {
+    event: "diff",
+    payload: { 0: { d: [[{ i: 0 }, "Buy milk"], [{ i: 0 }, "Walk dog"]] }, i: ["done"] },
+  };
+  assert.deepEqual(expandInternedMessage(msg).payload, {
+    0: { d: [["done", "Buy milk"], ["done", "Walk dog"]] },
+  });
+});
+
+test("leaves replies and events holding `i` keys as sent", () => {
+  const msg = {
+    event: "phx_reply",
+    payload: {
+      status: "ok",
+      response: {
+        diff: {
+          0: { i: 0 },
+          i: ["done"],
+          r: { i: 1, items: [{ i: 0 }], table: { i: ["kept"] } },
+          e: [["scroll", { i: 2 }]],
+        },
+      },
+    },
+  };
+  assert.deepEqual(expandInternedMessage(msg).payload.response.diff, {
+    0: "done",
+    r: { i: 1, items: [{ i: 0 }], table: { i: ["kept"] } },
+    e: [["scroll", { i: 2 }]],
+  });
+});
+
+test("leaves replies without a render as sent", () => {
+  const msg = {
+    event: "phx_reply",
+    payload: { status: "ok", response: { i: ["a"], value: { i: 0 } } },
+  };
+  assert.deepEqual(expandInternedMessage(msg).payload, {
+    status: "ok",
+    response: { i: ["a"], value: { i: 0 } },
+  });
+});